@@ -0,0 +1,11 @@
+#![no_main]
+
+use blackjack::{history::History, stats_import};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|contents: &str| {
+    let history = History::parse(contents);
+    assert_eq!(History::parse(&history.to_csv()).hands.len(), history.hands.len());
+
+    let _ = stats_import::parse_summary_csv(contents);
+});
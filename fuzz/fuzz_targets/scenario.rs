@@ -0,0 +1,10 @@
+#![no_main]
+
+use blackjack::scenario::Scenario;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|contents: &str| {
+    if let Ok(scenario) = Scenario::parse(contents) {
+        let _ = scenario.play();
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use blackjack::{config::Config, profile::Profile};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|contents: &str| {
+    let config = Config::parse(contents);
+    // what's written back must read the same
+    assert_eq!(Config::parse(&config.to_file_contents()), config);
+
+    let _ = Profile::parse(contents);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use blackjack::main_state;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let _ = main_state::parse_stats(line);
+});
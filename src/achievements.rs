@@ -0,0 +1,97 @@
+//! Achievements for milestones of the whole play history. Once earned they are kept in the
+//! profile, so they stay even if the history is cleared.
+
+use crate::history::History;
+use crate::playtime::HOUR;
+use crate::profile::Profile;
+
+/// What achievements are checked against.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Progress {
+    pub wins: u32,
+    pub hands: usize,
+    pub longest_streak: u32,
+    /// Tips given to the dealer
+    pub tips: u32,
+    /// Seconds played, see playtime.rs
+    pub playtime: u64,
+}
+
+impl Progress {
+    pub fn new(wins: u32, history: &History, profile: &Profile) -> Self {
+        Progress {
+            wins,
+            hands: history.hands.len(),
+            longest_streak: history.longest_win_streak(),
+            tips: profile.tips,
+            playtime: profile.playtime,
+        }
+    }
+}
+
+pub struct Achievement {
+    /// Stored in the profile
+    pub id: &'static str,
+    pub description: &'static str,
+    pub earned: fn(&Progress) -> bool,
+    /// Not described until earned
+    pub hidden: bool,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "first_win", description: "Win a hand", earned: |progress| progress.wins >= 1, hidden: false },
+    Achievement { id: "ten_wins", description: "Win 10 hands", earned: |progress| progress.wins >= 10, hidden: false },
+    Achievement { id: "fifty_wins", description: "Win 50 hands", earned: |progress| progress.wins >= 50, hidden: false },
+    Achievement { id: "five_in_a_row", description: "Win 5 hands in a row", earned: |progress| progress.longest_streak >= 5, hidden: false },
+    Achievement { id: "hundred_hands", description: "Play 100 hands", earned: |progress| progress.hands >= 100, hidden: false },
+    Achievement { id: "one_hour", description: "Play for an hour", earned: |progress| progress.playtime >= HOUR, hidden: false },
+    Achievement { id: "ten_hours", description: "Play for 10 hours", earned: |progress| progress.playtime >= 10 * HOUR, hidden: false },
+    Achievement { id: "generous", description: "Tip the dealer 5 times", earned: |progress| progress.tips >= 5, hidden: true },
+];
+
+pub fn find(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+/// Achievements the progress earns that aren't in `earned` yet.
+pub fn newly_earned(progress: &Progress, earned: &[String]) -> Vec<&'static Achievement> {
+    ACHIEVEMENTS.iter().
+        filter(|achievement| (achievement.earned)(progress) && !earned.iter().any(|id| id == achievement.id)).
+        collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_new_achievements_are_returned() {
+        let progress = Progress { wins: 12, hands: 30, longest_streak: 2, ..Progress::default() };
+        let ids: Vec<&str> = newly_earned(&progress, &[String::from("first_win")]).iter().map(|achievement| achievement.id).collect();
+
+        assert_eq!(ids, ["ten_wins"]);
+    }
+
+    #[test]
+    fn tips_earn_the_hidden_one() {
+        let progress = Progress { tips: 5, ..Progress::default() };
+        let earned = newly_earned(&progress, &[]);
+
+        assert_eq!(earned.len(), 1);
+        assert!(earned[0].hidden);
+    }
+
+    #[test]
+    fn playtime_earns_by_the_hour() {
+        let progress = Progress { playtime: 10 * HOUR + 1, ..Progress::default() };
+        let ids: Vec<&str> = newly_earned(&progress, &[]).iter().map(|achievement| achievement.id).collect();
+
+        assert_eq!(ids, ["one_hour", "ten_hours"]);
+    }
+
+    #[test]
+    fn nothing_for_a_new_player() {
+        assert!(newly_earned(&Progress::default(), &[]).is_empty());
+    }
+}
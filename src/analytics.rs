@@ -0,0 +1,292 @@
+use crate::game_engine::Outcome;
+use crate::history::History;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+/// The win rate line gets at most this many points, however long the history is
+pub const MAX_LINE_POINTS: usize = 60;
+
+const AXIS_WIDTH: f32 = 2.0;
+const LINE_WIDTH: f32 = 3.0;
+const BAR_GAP: f32 = 30.0;
+const HEATMAP_CELL_GAP: f32 = 2.0;
+
+/// Rows of the heatmap: what the first two cards can be worth
+pub const STARTING_TOTALS: std::ops::RangeInclusive<u32> = 4..=21;
+/// Columns of the heatmap: the dealer's first card, ace counting 11
+pub const UP_CARDS: std::ops::RangeInclusive<u32> = 2..=11;
+
+/// Hands that started the same way.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeatmapCell {
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl HeatmapCell {
+    /// Wins minus losses per hand, from -1 to 1. None without hands.
+    pub fn net(&self) -> Option<f32> {
+        if self.hands == 0 {
+            return None;
+        }
+
+        Some((self.wins as f32 - self.losses as f32) / self.hands as f32)
+    }
+}
+
+/// Outcomes by starting total(rows) and dealer up-card(columns).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    pub cells: Vec<Vec<HeatmapCell>>,
+}
+
+impl Heatmap {
+    pub fn cell(&self, starting_total: u32, up_card: u32) -> HeatmapCell {
+        self.cells[(starting_total - STARTING_TOTALS.start()) as usize][(up_card - UP_CARDS.start()) as usize]
+    }
+
+    /// The situations that went worst, with at least `min_hands` hands so one bad beat
+    /// doesn't top the list. Worst first, as (starting total, up-card, cell).
+    pub fn worst(&self, min_hands: u32, count: usize) -> Vec<(u32, u32, HeatmapCell)> {
+        let mut situations: Vec<(u32, u32, HeatmapCell)> = STARTING_TOTALS.
+            flat_map(|total| UP_CARDS.map(move |up_card| (total, up_card))).
+            map(|(total, up_card)| (total, up_card, self.cell(total, up_card))).
+            filter(|(_, _, cell)| cell.hands >= min_hands).
+            collect();
+
+        situations.sort_by(|a, b| a.2.net().partial_cmp(&b.2.net()).unwrap_or(std::cmp::Ordering::Equal));
+        situations.truncate(count);
+
+        situations
+    }
+}
+
+/// Counts the hands that know their starting hand, older hands are left out.
+pub fn outcome_heatmap(history: &History) -> Heatmap {
+    let row = vec![HeatmapCell::default(); UP_CARDS.count()];
+    let mut heatmap = Heatmap { cells: vec![row; STARTING_TOTALS.count()] };
+
+    for hand in &history.hands {
+        let (total, up_card) = match (hand.player_start, hand.dealer_up_card) {
+            (Some(total), Some(up_card)) if STARTING_TOTALS.contains(&total) && UP_CARDS.contains(&up_card) => (total, up_card),
+            _ => continue,
+        };
+
+        let cell = &mut heatmap.cells[(total - STARTING_TOTALS.start()) as usize][(up_card - UP_CARDS.start()) as usize];
+        cell.hands += 1;
+        match hand.outcome {
+            Outcome::Win => cell.wins += 1,
+            Outcome::Lose => cell.losses += 1,
+            _ => (),
+        }
+    }
+
+    heatmap
+}
+
+/// Share of wins among all hands played so far, after every hand. Long histories are
+/// sampled down to `max_points` evenly spread hands(the last one is always included).
+pub fn win_rate_over_time(history: &History, max_points: usize) -> Vec<f32> {
+    let mut wins = 0;
+    let rates: Vec<f32> = history.hands.iter().
+        enumerate().
+        map(|(i, hand)| {
+            if hand.outcome == Outcome::Win {
+                wins += 1;
+            }
+            wins as f32 / (i + 1) as f32
+        }).
+        collect();
+
+    if rates.len() <= max_points || max_points < 2 {
+        return rates;
+    }
+
+    (0..max_points).
+        map(|i| rates[i * (rates.len() - 1) / (max_points - 1)]).
+        collect()
+}
+
+/// Number of wins, draws and losses.
+pub fn outcome_counts(history: &History) -> [(Outcome, usize); 3] {
+    [Outcome::Win, Outcome::Draw, Outcome::Lose].map(|outcome| {
+        let count = history.count(&outcome);
+        (outcome, count)
+    })
+}
+
+/// Average final hand value of the player and of the dealer.
+pub fn average_hand_values(history: &History) -> Option<(f32, f32)> {
+    if history.hands.is_empty() {
+        return None;
+    }
+
+    let hands = history.hands.len() as f32;
+    let player: u32 = history.hands.iter().map(|hand| hand.player_score).sum();
+    let dealer: u32 = history.hands.iter().map(|hand| hand.dealer_score).sum();
+
+    Some((player as f32 / hands, dealer as f32 / hands))
+}
+
+fn axes(mesh: &mut graphics::MeshBuilder, area: graphics::Rect, color: graphics::Color) -> GameResult<()> {
+    let bottom_left = Point2 { x: area.x, y: area.y + area.h };
+
+    mesh.line(&[Point2 { x: area.x, y: area.y }, bottom_left], AXIS_WIDTH, color)?;
+    mesh.line(&[bottom_left, Point2 { x: area.x + area.w, y: area.y + area.h }], AXIS_WIDTH, color)?;
+
+    Ok(())
+}
+
+/// Draws `values`(0.0 to `max`) as a line over the width of `area`.
+pub fn draw_line_chart(ctx: &mut Context, area: graphics::Rect, values: &[f32], max: f32, color: graphics::Color) -> GameResult<()> {
+    let mut mesh = graphics::MeshBuilder::new();
+    axes(&mut mesh, area, graphics::Color::WHITE)?;
+
+    // a line needs two points
+    if values.len() >= 2 {
+        let step = area.w / (values.len() - 1) as f32;
+        let points: Vec<Point2<f32>> = values.iter().
+            enumerate().
+            map(|(i, value)| Point2 { x: area.x + i as f32 * step, y: area.y + area.h * (1.0 - value / max) }).
+            collect();
+
+        mesh.line(&points, LINE_WIDTH, color)?;
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+/// Draws one bar per value(0.0 to `max`), side by side in `area`.
+pub fn draw_bar_chart(ctx: &mut Context, area: graphics::Rect, values: &[(f32, graphics::Color)], max: f32) -> GameResult<()> {
+    let mut mesh = graphics::MeshBuilder::new();
+    axes(&mut mesh, area, graphics::Color::WHITE)?;
+
+    let bar_width = (area.w - BAR_GAP * (values.len() + 1) as f32) / values.len().max(1) as f32;
+    for (i, (value, color)) in values.iter().enumerate() {
+        let height = if max > 0.0 { area.h * value / max } else { 0.0 };
+        if height <= 0.0 {
+            continue;
+        }
+
+        let x = area.x + BAR_GAP + i as f32 * (bar_width + BAR_GAP);
+        mesh.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(x, area.y + area.h - height, bar_width, height), *color)?;
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+/// Red for situations that lose, green for ones that win, grey without hands.
+fn heat_color(net: Option<f32>) -> graphics::Color {
+    match net {
+        None => graphics::Color::from_rgb(70, 70, 70),
+        Some(net) => {
+            let net = net.clamp(-1.0, 1.0);
+            let red = if net < 0.0 { 1.0 } else { 1.0 - net };
+            let green = if net > 0.0 { 1.0 } else { 1.0 + net };
+
+            graphics::Color::new(red * 0.8, green * 0.8, 0.1, 1.0)
+        },
+    }
+}
+
+/// Draws the heatmap in `area`, a cell per starting total(top to bottom) and up-card(left to right).
+pub fn draw_heatmap(ctx: &mut Context, area: graphics::Rect, heatmap: &Heatmap) -> GameResult<()> {
+    let mut mesh = graphics::MeshBuilder::new();
+
+    let rows = heatmap.cells.len();
+    let columns = heatmap.cells.first().map(|row| row.len()).unwrap_or(0);
+    let cell_width = area.w / columns.max(1) as f32;
+    let cell_height = area.h / rows.max(1) as f32;
+
+    for (row, cells) in heatmap.cells.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let rect = graphics::Rect::new(area.x + column as f32 * cell_width,
+                                           area.y + row as f32 * cell_height,
+                                           cell_width - HEATMAP_CELL_GAP,
+                                           cell_height - HEATMAP_CELL_GAP);
+            mesh.rectangle(graphics::DrawMode::fill(), rect, heat_color(cell.net()))?;
+        }
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HandRecord;
+
+    fn history(outcomes: &[Outcome]) -> History {
+        History {
+            hands: outcomes.iter().
+                enumerate().
+                map(|(i, outcome)| HandRecord {
+                    id: i as u64 + 1,
+                    timestamp: 0,
+                    outcome: outcome.clone(),
+                    player_score: 20,
+                    dealer_score: if *outcome == Outcome::Win { 18 } else { 21 },
+                    hint_used: false,
+                    handicap_used: false,
+                    player_start: None,
+                    dealer_up_card: None,
+                    would_have_drawn: None,
+                }).
+                collect(),
+        }
+    }
+
+    #[test]
+    fn win_rate_is_cumulative() {
+        let history = history(&[Outcome::Win, Outcome::Lose, Outcome::Win, Outcome::Win]);
+
+        assert_eq!(win_rate_over_time(&history, MAX_LINE_POINTS), vec![1.0, 0.5, 2.0 / 3.0, 0.75]);
+    }
+
+    #[test]
+    fn long_histories_are_sampled() {
+        let history = history(&vec![Outcome::Lose; 100]);
+        let rates = win_rate_over_time(&history, 10);
+
+        assert_eq!(rates.len(), 10);
+        assert!(win_rate_over_time(&History::default(), 10).is_empty());
+    }
+
+    #[test]
+    fn counts_and_averages() {
+        let history = history(&[Outcome::Win, Outcome::Lose, Outcome::Draw, Outcome::Lose]);
+
+        assert_eq!(outcome_counts(&history), [(Outcome::Win, 1), (Outcome::Draw, 1), (Outcome::Lose, 2)]);
+        assert_eq!(average_hand_values(&history), Some((20.0, 20.25)));
+        assert_eq!(average_hand_values(&History::default()), None);
+    }
+
+    #[test]
+    fn heatmap_counts_known_starts() {
+        let mut history = history(&[Outcome::Lose, Outcome::Lose, Outcome::Win, Outcome::Win, Outcome::Draw]);
+        let starts = [(Some(16), Some(10)), (Some(16), Some(10)), (Some(16), Some(10)), (Some(11), Some(6)), (None, None)];
+        for (hand, (player_start, dealer_up_card)) in history.hands.iter_mut().zip(starts) {
+            hand.player_start = player_start;
+            hand.dealer_up_card = dealer_up_card;
+        }
+
+        let heatmap = outcome_heatmap(&history);
+        assert_eq!(heatmap.cell(16, 10), HeatmapCell { hands: 3, wins: 1, losses: 2 });
+        assert_eq!(heatmap.cell(11, 6).net(), Some(1.0));
+        assert_eq!(heatmap.cell(4, 2).net(), None);
+
+        let worst = heatmap.worst(2, 3);
+        assert_eq!(worst.len(), 1);
+        assert_eq!((worst[0].0, worst[0].1), (16, 10));
+    }
+}
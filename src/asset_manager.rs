@@ -0,0 +1,228 @@
+//! Card images and sounds, loaded once and kept for as long as the card set is used. The files are
+//! read on a background thread while the menu is up and turned into images a few per update, so
+//! pressing PLAY never waits for the disk. A file that isn't in yet is loaded when first needed.
+//! In GPU-light mode the images are kept at half their resolution, for old integrated GPUs.
+
+use ggez::{
+    Context,
+    GameError,
+    GameResult,
+    graphics,
+    audio,
+};
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// Files turned into images or sounds per update, so the menu doesn't stutter
+const DECODED_PER_UPDATE: usize = 4;
+/// Images are this many times smaller each way in GPU-light mode
+pub const LOW_RESOLUTION_FACTOR: f32 = 2.0;
+
+/// A file to read in the background: the path the game asks for it by, and the files on disk
+/// it may be in, the first one that exists is read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetFile {
+    pub key: String,
+    pub files: Vec<PathBuf>,
+}
+
+fn is_sound(key: &str) -> bool {
+    key.ends_with(".wav") || key.ends_with(".ogg")
+}
+
+#[derive(Debug, Default)]
+pub struct AssetManager {
+    images: HashMap<String, graphics::Image>,
+    sounds: HashMap<String, audio::SoundData>,
+    /// Files read by the background thread, waiting to be decoded
+    receiver: Option<Receiver<(String, Vec<u8>)>>,
+    preloading_started: bool,
+    /// Images are loaded at half their resolution
+    low_resolution: bool,
+}
+
+/// `pixels` of a `width` by `height` RGBA image at half the size each way, every pixel the
+/// average of four. An odd last row or column is left out.
+pub fn halve_rgba(width: u32, height: u32, pixels: &[u8]) -> (u32, u32, Vec<u8>) {
+    let (half_width, half_height) = ((width / 2).max(1), (height / 2).max(1));
+    let mut halved = Vec::with_capacity((half_width * half_height * 4) as usize);
+
+    for y in 0..half_height {
+        for x in 0..half_width {
+            // the four pixels, or fewer for an image a pixel wide or high
+            let corners = [(2 * x, 2 * y), (2 * x + 1, 2 * y), (2 * x, 2 * y + 1), (2 * x + 1, 2 * y + 1)];
+            let inside: Vec<usize> = corners.iter().
+                filter(|(x, y)| *x < width && *y < height).
+                map(|(x, y)| ((y * width + x) * 4) as usize).
+                collect();
+
+            for channel in 0..4 {
+                let sum: u32 = inside.iter().map(|i| pixels[i + channel] as u32).sum();
+                halved.push((sum / inside.len() as u32) as u8);
+            }
+        }
+    }
+
+    (half_width, half_height, halved)
+}
+
+/// Decodes an image file, at half its resolution when `low_resolution`. The halving is done on
+/// the pixels before the image goes to the GPU, which only ever gets the small one.
+fn decode(ctx: &mut Context, bytes: &[u8], low_resolution: bool) -> GameResult<graphics::Image> {
+    if !low_resolution {
+        return graphics::Image::from_bytes(ctx, bytes);
+    }
+
+    let decoded = image::load_from_memory(bytes).
+        map_err(|e| GameError::ResourceLoadError(e.to_string()))?.
+        to_rgba8();
+    let (width, height, halved) = halve_rgba(decoded.width(), decoded.height(), decoded.as_raw());
+
+    graphics::Image::from_rgba8(ctx, width as u16, height as u16, &halved)
+}
+
+impl AssetManager {
+    /// Loads the images at half their resolution from now on. The ones loaded so far are
+    /// dropped when it changes, so they're loaded again the new way.
+    pub fn set_low_resolution(&mut self, low_resolution: bool) {
+        if self.low_resolution != low_resolution {
+            self.low_resolution = low_resolution;
+            self.images.clear();
+        }
+    }
+
+    /// Starts reading `files` in the background, once.
+    pub fn preload(&mut self, files: Vec<AssetFile>) {
+        if self.preloading_started {
+            return;
+        }
+        self.preloading_started = true;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for file in files {
+                if let Some(bytes) = file.files.iter().find_map(|path| fs::read(path).ok()) {
+                    // the card set was changed in the meantime
+                    if sender.send((file.key, bytes)).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        self.receiver = Some(receiver);
+    }
+
+    pub fn preloading(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Decodes a few of the files read so far, call it every update while waiting on the menu.
+    pub fn update(&mut self, ctx: &mut Context) {
+        for _ in 0..DECODED_PER_UPDATE {
+            let (key, bytes) = match self.receiver.as_ref().map(|receiver| receiver.try_recv()) {
+                Some(Ok(file)) => file,
+                Some(Err(TryRecvError::Disconnected)) => {
+                    self.receiver = None;
+                    return;
+                },
+                _ => return,
+            };
+
+            if is_sound(&key) {
+                self.sounds.entry(key).or_insert_with(|| audio::SoundData::from_bytes(&bytes));
+            } else if let Entry::Vacant(entry) = self.images.entry(key) {
+                // a broken file is loaded again when needed, which reports the error
+                if let Ok(image) = decode(ctx, &bytes, self.low_resolution) {
+                    entry.insert(image);
+                }
+            }
+        }
+    }
+
+    /// The image for `key`, decoded from the file `read` gives the first time.
+    pub fn image<F>(&mut self, ctx: &mut Context, key: &str, read: F) -> GameResult<graphics::Image>
+        where F: FnOnce(&mut Context) -> GameResult<Vec<u8>> {
+        if let Some(image) = self.images.get(key) {
+            return Ok(image.clone());
+        }
+
+        let bytes = read(ctx)?;
+        let image = decode(ctx, &bytes, self.low_resolution)?;
+        self.images.insert(key.to_string(), image.clone());
+
+        Ok(image)
+    }
+
+    /// Decodes a changed image again from `read`. The one loaded before stays when the new file
+    /// doesn't load, and an image that wasn't loaded yet is left for when it's needed.
+    pub fn reload_image<F>(&mut self, ctx: &mut Context, key: &str, read: F) -> GameResult<()>
+        where F: FnOnce(&mut Context) -> GameResult<Vec<u8>> {
+        if self.images.contains_key(key) {
+            let bytes = read(ctx)?;
+            let image = decode(ctx, &bytes, self.low_resolution)?;
+            self.images.insert(key.to_string(), image);
+        }
+
+        Ok(())
+    }
+
+    /// A new source of the sound for `key`, from `load` while the sound isn't preloaded.
+    pub fn sound<F>(&mut self, ctx: &mut Context, key: &str, load: F) -> GameResult<audio::Source>
+        where F: FnOnce(&mut Context) -> GameResult<audio::Source> {
+        match self.sounds.get(key) {
+            Some(data) => audio::Source::from_data(ctx, data.clone()),
+            None => load(ctx),
+        }
+    }
+
+    /// Drops a changed sound, so it's loaded again.
+    pub fn forget_sound(&mut self, key: &str) {
+        self.sounds.remove(key);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_are_told_by_extension() {
+        assert!(is_sound("/sfx/card_deal.wav"));
+        assert!(!is_sound("/card_images/ace_of_spades.png"));
+    }
+
+    #[test]
+    fn halving_averages_four_pixels() {
+        // a 2x2 of black, white and two greys, with a third column that's left out
+        let pixels = [
+            0, 0, 0, 255,   255, 255, 255, 255,   9, 9, 9, 9,
+            100, 100, 100, 255,   45, 45, 45, 255,   9, 9, 9, 9,
+        ];
+
+        assert_eq!(halve_rgba(3, 2, &pixels), (1, 1, vec![100, 100, 100, 255]));
+        // a pixel high image stays a pixel high
+        assert_eq!(halve_rgba(2, 1, &[0, 0, 0, 0, 200, 200, 200, 200]), (1, 1, vec![100, 100, 100, 100]));
+    }
+
+    #[test]
+    fn preloading_starts_once() {
+        let mut assets = AssetManager::default();
+        let missing = AssetFile { key: String::from("/sfx/missing.wav"), files: vec![PathBuf::from("no/such/file.wav")] };
+
+        assets.preload(vec![missing.clone()]);
+        assert!(assets.preloading());
+
+        let receiver = assets.receiver.take().unwrap();
+        assets.preload(vec![missing]);
+        assert!(assets.receiver.is_none());
+
+        // nothing to read, the thread just ends
+        assert!(receiver.recv().is_err());
+    }
+}
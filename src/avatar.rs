@@ -0,0 +1,105 @@
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+const HEAD_RADIUS: f32 = 40.0;
+const EYE_RADIUS: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Headwear {
+    None,
+    Visor,
+    Hat,
+    Antenna,
+}
+
+/// A face for the dealer, drawn next to the dealer's cards.
+#[derive(Debug)]
+pub struct DealerAvatar {
+    pub name: &'static str,
+    pub face: (u8, u8, u8),
+    /// Robots get a square head
+    pub square: bool,
+    pub headwear: Headwear,
+}
+
+pub const STYLES: &[DealerAvatar] = &[
+    DealerAvatar { name: "none", face: (0, 0, 0), square: false, headwear: Headwear::None },
+    DealerAvatar { name: "croupier", face: (236, 200, 160), square: false, headwear: Headwear::Visor },
+    DealerAvatar { name: "cowboy", face: (214, 170, 120), square: false, headwear: Headwear::Hat },
+    DealerAvatar { name: "robot", face: (170, 178, 190), square: true, headwear: Headwear::Antenna },
+];
+
+pub fn find(name: &str) -> &'static DealerAvatar {
+    STYLES.iter().find(|style| style.name == name).unwrap_or(&STYLES[0])
+}
+
+fn color((r, g, b): (u8, u8, u8)) -> graphics::Color {
+    graphics::Color::from_rgb(r, g, b)
+}
+
+impl DealerAvatar {
+    /// Draws the avatar centered on `center`. "none" draws nothing.
+    pub fn draw(&self, ctx: &mut Context, center: Point2<f32>) -> GameResult<()> {
+        if self.name == STYLES[0].name {
+            return Ok(());
+        }
+
+        let dark = graphics::Color::from_rgb(30, 30, 30);
+        let mut mesh = graphics::MeshBuilder::new();
+
+        if self.square {
+            let head = graphics::Rect::new(center.x - HEAD_RADIUS, center.y - HEAD_RADIUS, HEAD_RADIUS * 2.0, HEAD_RADIUS * 2.0);
+            mesh.rectangle(graphics::DrawMode::fill(), head, color(self.face))?;
+        } else {
+            mesh.circle(graphics::DrawMode::fill(), center, HEAD_RADIUS, 0.5, color(self.face))?;
+        }
+
+        for side in [-1.0, 1.0] {
+            let eye = Point2 { x: center.x + side * HEAD_RADIUS * 0.4, y: center.y - HEAD_RADIUS * 0.15 };
+            mesh.circle(graphics::DrawMode::fill(), eye, EYE_RADIUS, 0.5, dark)?;
+        }
+        mesh.line(&[Point2 { x: center.x - HEAD_RADIUS * 0.35, y: center.y + HEAD_RADIUS * 0.4 },
+                    Point2 { x: center.x + HEAD_RADIUS * 0.35, y: center.y + HEAD_RADIUS * 0.4 }],
+                  3.0,
+                  dark)?;
+
+        let top = center.y - HEAD_RADIUS;
+        match self.headwear {
+            Headwear::None => (),
+            Headwear::Visor => {
+                let visor = graphics::Rect::new(center.x - HEAD_RADIUS, top - 4.0, HEAD_RADIUS * 2.0, 14.0);
+                mesh.rectangle(graphics::DrawMode::fill(), visor, graphics::Color::from_rgb(30, 120, 60))?;
+            },
+            Headwear::Hat => {
+                let brim = graphics::Rect::new(center.x - HEAD_RADIUS * 1.4, top - 2.0, HEAD_RADIUS * 2.8, 10.0);
+                let crown = graphics::Rect::new(center.x - HEAD_RADIUS * 0.7, top - 32.0, HEAD_RADIUS * 1.4, 32.0);
+                let brown = graphics::Color::from_rgb(110, 70, 35);
+                mesh.rectangle(graphics::DrawMode::fill(), brim, brown)?;
+                mesh.rectangle(graphics::DrawMode::fill(), crown, brown)?;
+            },
+            Headwear::Antenna => {
+                mesh.line(&[Point2 { x: center.x, y: top }, Point2 { x: center.x, y: top - 24.0 }], 3.0, dark)?;
+                mesh.circle(graphics::DrawMode::fill(), Point2 { x: center.x, y: top - 28.0 }, 6.0, 0.5, graphics::Color::from_rgb(204, 0, 0))?;
+            },
+        }
+
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_unknown_avatar_gives_none() {
+        assert_eq!(find("no such dealer").name, "none");
+        assert_eq!(find("robot").headwear, Headwear::Antenna);
+    }
+}
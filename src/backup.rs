@@ -0,0 +1,241 @@
+//! Backups of the save files. Every backup is a folder under "backups" named after the time it
+//! was made, holding a copy of each save file that existed then. One is made when the game
+//! starts(unless nothing changed since the last one) and whenever the player asks for one, and
+//! only the newest MAX_BACKUPS are kept.
+
+use crate::config;
+use crate::gallery;
+use crate::history;
+use crate::profile;
+use crate::trainer;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_DIR: &str = "backups";
+pub const MAX_BACKUPS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    /// Seconds since the epoch, also the folder name
+    pub timestamp: u64,
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// "2022-04-15 05:20 UTC", for the settings screen
+    pub fn describe(&self) -> String {
+        format_timestamp(self.timestamp)
+    }
+}
+
+/// Every file the game saves to, `stats_file` being the one the game was started with.
+pub fn save_files(stats_file: &str) -> Vec<String> {
+    vec![
+        stats_file.to_string(),
+        config::CONFIG_FILE_NAME.to_string(),
+        profile::PROFILE_FILE_NAME.to_string(),
+        history::HISTORY_FILE_NAME.to_string(),
+        trainer::TRAINER_STATS_FILE_NAME.to_string(),
+        gallery::GALLERY_FILE_NAME.to_string(),
+    ]
+}
+
+/// Backup folder for a save file, by its name without the folders.
+fn backup_name(file: &str) -> Option<&std::ffi::OsStr> {
+    Path::new(file).file_name()
+}
+
+/// The backups in `dir`, newest first. Folders that aren't named after a time are ignored.
+pub fn list(dir: &Path) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = match fs::read_dir(dir) {
+        Ok(entries) => entries.
+            filter_map(|entry| entry.ok()).
+            filter(|entry| entry.path().is_dir()).
+            filter_map(|entry| {
+                let timestamp = entry.file_name().to_str()?.parse().ok()?;
+                Some(Backup { timestamp, path: entry.path() })
+            }).
+            collect(),
+        Err(_) => Vec::new(),
+    };
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.timestamp));
+    backups
+}
+
+/// Copies the save files that exist into a new backup.
+pub fn create(dir: &Path, files: &[String], timestamp: u64) -> io::Result<Backup> {
+    let path = dir.join(timestamp.to_string());
+    fs::create_dir_all(&path)?;
+
+    for file in files {
+        if let (true, Some(name)) = (Path::new(file).is_file(), backup_name(file)) {
+            fs::copy(file, path.join(name))?;
+        }
+    }
+
+    Ok(Backup { timestamp, path })
+}
+
+/// Removes the oldest backups so that only `keep` are left.
+pub fn prune(dir: &Path, keep: usize) -> io::Result<()> {
+    for backup in list(dir).iter().skip(keep) {
+        fs::remove_dir_all(&backup.path)?;
+    }
+
+    Ok(())
+}
+
+/// Whether the save files are the same as in the backup
+fn unchanged_since(backup: &Backup, files: &[String]) -> bool {
+    files.iter().all(|file| {
+        let saved = backup_name(file).and_then(|name| fs::read(backup.path.join(name)).ok());
+
+        saved == fs::read(file).ok()
+    })
+}
+
+/// A backup on start. Nothing is made when the newest backup already has the same saves,
+/// so starting the game over and over doesn't push the older backups out.
+pub fn autosave(dir: &Path, files: &[String], timestamp: u64) -> io::Result<Option<Backup>> {
+    if let Some(newest) = list(dir).first() {
+        if unchanged_since(newest, files) {
+            return Ok(None);
+        }
+    }
+
+    let backup = create(dir, files, timestamp)?;
+    prune(dir, MAX_BACKUPS)?;
+
+    Ok(Some(backup))
+}
+
+/// Puts the saves from `backup` back. The current saves are backed up first, so a restore
+/// can be taken back by restoring that one. Save files the backup doesn't have are removed,
+/// as they didn't exist yet when it was made.
+pub fn restore(dir: &Path, backup: &Backup, files: &[String], timestamp: u64) -> io::Result<()> {
+    // read before backing up, since pruning can remove the backup being restored
+    let mut saved = Vec::new();
+    for file in files {
+        let contents = match backup_name(file).map(|name| backup.path.join(name)) {
+            Some(path) if path.is_file() => Some(fs::read(path)?),
+            _ => None,
+        };
+        saved.push((file, contents));
+    }
+
+    create(dir, files, timestamp)?;
+    prune(dir, MAX_BACKUPS)?;
+
+    for (file, contents) in saved {
+        match contents {
+            Some(contents) => fs::write(file, contents)?,
+            None if Path::new(file).is_file() => fs::remove_file(file)?,
+            None => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Date and time(UTC) of seconds since the epoch.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // days to a civil date, counting in 400 year eras that start on the 1st of March
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, seconds / 3_600, seconds % 3_600 / 60)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blackjack_backup_test_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn save_file(dir: &Path, name: &str, contents: &str) -> String {
+        let file = dir.join(name);
+        fs::write(&file, contents).unwrap();
+
+        file.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn timestamps_are_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_timestamp(1_650_000_000), "2022-04-15 05:20 UTC");
+        assert_eq!(format_timestamp(951_825_600), "2000-02-29 12:00 UTC");
+    }
+
+    #[test]
+    fn only_the_newest_are_kept() {
+        let root = temp_dir("rotate");
+        let backups = root.join(BACKUP_DIR);
+        let files = vec![save_file(&root, "stats.txt", "0 0 0")];
+
+        for timestamp in 1..=MAX_BACKUPS as u64 + 2 {
+            fs::write(&files[0], timestamp.to_string()).unwrap();
+            autosave(&backups, &files, timestamp).unwrap();
+        }
+
+        let timestamps: Vec<u64> = list(&backups).iter().map(|backup| backup.timestamp).collect();
+        assert_eq!(timestamps, (3..=MAX_BACKUPS as u64 + 2).rev().collect::<Vec<u64>>());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn unchanged_saves_are_not_backed_up_again() {
+        let root = temp_dir("unchanged");
+        let backups = root.join(BACKUP_DIR);
+        let files = vec![save_file(&root, "stats.txt", "3 1 0")];
+
+        assert!(autosave(&backups, &files, 10).unwrap().is_some());
+        assert!(autosave(&backups, &files, 20).unwrap().is_none());
+        assert_eq!(list(&backups).len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn restore_puts_the_saves_back_and_can_be_undone() {
+        let root = temp_dir("restore");
+        let backups = root.join(BACKUP_DIR);
+        let stats = save_file(&root, "stats.txt", "3 1 0");
+        let files = vec![stats.clone(), root.join("profile.txt").to_string_lossy().to_string()];
+
+        let old = create(&backups, &files, 10).unwrap();
+        fs::write(&stats, "").unwrap();
+        let profile = save_file(&root, "profile.txt", "name = Kris");
+
+        restore(&backups, &old, &files, 20).unwrap();
+
+        assert_eq!(fs::read_to_string(&stats).unwrap(), "3 1 0");
+        assert!(!Path::new(&profile).exists());
+
+        let undo = &list(&backups)[0];
+        assert_eq!(undo.timestamp, 20);
+        assert_eq!(fs::read_to_string(undo.path.join("profile.txt")).unwrap(), "name = Kris");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+//! The banner over the table once a hand is over, as big as the hand was. A loss, a push and a
+//! plain win get the usual text. A blackjack or a five card 21 washes the whole table in gold,
+//! and the win that reaches a milestone of wins gets a ribbon across the table saying so. The
+//! game doesn't pay out, so the hand's cards and the wins it brings are what make it big.
+
+use crate::card::Card;
+use crate::game_engine::{self, Outcome};
+
+use ggez::graphics;
+
+/// Wins worth a ribbon, and every MILESTONE_STEP wins after the last of them
+const MILESTONES: &[u32] = &[10, 25, 50, 100, 250, 500];
+const MILESTONE_STEP: u32 = 500;
+
+/// Cards of a 21 that counts as big without being a blackjack
+const FIVE_CARDS: usize = 5;
+
+const GOLD: graphics::Color = graphics::Color { r: 212.0 / 255.0, g: 175.0 / 255.0, b: 55.0 / 255.0, a: 1.0 };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Banner {
+    Lose,
+    Draw,
+    Win,
+    /// A win with a blackjack or a five card 21
+    BigWin,
+    /// The win that brought the wins up to this milestone
+    Milestone(u32),
+}
+
+pub fn is_milestone(wins: u32) -> bool {
+    MILESTONES.contains(&wins) || (wins > MILESTONES[MILESTONES.len() - 1] && wins / MILESTONE_STEP * MILESTONE_STEP == wins)
+}
+
+impl Banner {
+    /// The banner of a finished hand. `wins` is what the wins come to if the hand is a counted
+    /// win, None for practice and replays.
+    pub fn of(outcome: &Outcome, player_cards: &[Card], wins: Option<u32>) -> Option<Self> {
+        let banner = match outcome {
+            Outcome::Lose => Banner::Lose,
+            Outcome::Draw => Banner::Draw,
+            Outcome::Undecided => return None,
+            Outcome::Win => match wins.filter(|wins| is_milestone(*wins)) {
+                Some(wins) => Banner::Milestone(wins),
+                None if game_engine::hand_value(player_cards) == 21 && (player_cards.len() == 2 || player_cards.len() >= FIVE_CARDS) => Banner::BigWin,
+                None => Banner::Win,
+            },
+        };
+
+        Some(banner)
+    }
+
+    pub fn color(&self) -> graphics::Color {
+        match self {
+            Banner::Lose => graphics::Color::from_rgb(204, 0, 0),
+            Banner::Draw => graphics::Color::WHITE,
+            Banner::Win => graphics::Color::from_rgb(255, 163, 26),
+            Banner::BigWin | Banner::Milestone(_) => GOLD,
+        }
+    }
+
+    /// Size of the text compared to the usual one
+    pub fn scale(&self) -> f32 {
+        match self {
+            Banner::Lose | Banner::Draw | Banner::Win => 1.0,
+            Banner::BigWin => 1.4,
+            Banner::Milestone(_) => 1.2,
+        }
+    }
+
+    /// Drawn over the whole table, under the text
+    pub fn wash(&self) -> Option<graphics::Color> {
+        match self {
+            Banner::BigWin => Some(graphics::Color::new(GOLD.r, GOLD.g, GOLD.b, 0.3)),
+            _ => None,
+        }
+    }
+
+    /// The line under the text, for a milestone
+    pub fn subtitle(&self) -> Option<String> {
+        match self {
+            Banner::Milestone(wins) => Some(format!("{} wins!", wins)),
+            _ => None,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn milestones_come_less_often() {
+        let milestones: Vec<u32> = (1..=2000).filter(|wins| is_milestone(*wins)).collect();
+
+        assert_eq!(milestones, vec![10, 25, 50, 100, 250, 500, 1000, 1500, 2000]);
+    }
+
+    #[test]
+    fn bigger_hands_get_bigger_banners() {
+        let twenty = cards(&["king_of_clubs", "queen_of_hearts"]);
+        let blackjack = cards(&["ace_of_clubs", "king_of_hearts"]);
+        let five_card = cards(&["2_of_clubs", "3_of_hearts", "4_of_spades", "5_of_diamonds", "7_of_clubs"]);
+
+        assert_eq!(Banner::of(&Outcome::Win, &twenty, Some(3)), Some(Banner::Win));
+        assert_eq!(Banner::of(&Outcome::Win, &blackjack, Some(3)), Some(Banner::BigWin));
+        assert_eq!(Banner::of(&Outcome::Win, &five_card, None), Some(Banner::BigWin));
+        assert_eq!(Banner::of(&Outcome::Draw, &blackjack, Some(3)), Some(Banner::Draw));
+        assert_eq!(Banner::of(&Outcome::Undecided, &twenty, None), None);
+
+        // the milestone wins over the blackjack, and practice reaches none
+        assert_eq!(Banner::of(&Outcome::Win, &blackjack, Some(50)), Some(Banner::Milestone(50)));
+        assert_eq!(Banner::of(&Outcome::Win, &twenty, None), Some(Banner::Win));
+    }
+}
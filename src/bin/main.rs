@@ -1,46 +1,96 @@
 use ggez::{
     ContextBuilder,
-    conf::{Conf, WindowMode},
+    conf::Conf,
     event,
     filesystem,
 };
 
-use std::io::{BufReader, Write, BufWriter};
 use std::fs::File;
-use std::fs::OpenOptions;
 
 use std::env;
 use std::path;
+use std::process;
 
-use blackjack::main_state;
+use blackjack::{backup, config, history, main_state, migration, profile, resources, snapshot, trainer, window, window_title};
+use blackjack::migration::SaveFile;
+use blackjack::scenario::Scenario;
+use blackjack::scene::{self, Scene};
 
 const FILE_NAME: &str = "stats.txt";
-const ICON_PATH: &str = "\\icons\\black-jack.png";
 
-fn create_file(file_name: &str) -> File {
-    {
-        let _ = File::create(file_name).unwrap();
-    }
-    {
-        let f = OpenOptions::new().write(true).open(file_name).unwrap();
-        let mut writer = BufWriter::new(f);
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+
+    args.iter().
+        position(|arg| arg == flag).
+        and_then(|i| args.get(i + 1)).
+        cloned()
+}
+
+/// `--render-scene <name> --out <png>`: the scene to render instead of starting the game
+fn render_scene_args() -> Option<(Scene, path::PathBuf)> {
+    let name = arg_value("--render-scene")?;
+    let scene = match Scene::from_name(&name) {
+        Some(scene) => scene,
+        None => {
+            let names: Vec<&str> = scene::SCENES.iter().map(|scene| scene.name()).collect();
+            eprintln!("Unknown scene \"{}\", expected one of: {}", name, names.join(", "));
+            process::exit(2);
+        },
+    };
+    let out = match arg_value("--out") {
+        Some(out) => path::PathBuf::from(out),
+        None => path::PathBuf::from(format!("{}.png", scene.name())),
+    };
+
+    Some((scene, out))
+}
 
-        writer.write(b"0 0 0").unwrap();
-        writer.flush().unwrap();
+fn render_scene(ctx: &mut ggez::Context, scene: Scene, out: &path::Path) {
+    let frame = main_state::MainState::for_scene(ctx, scene).
+        and_then(|mut state| state.render_frame(ctx));
+
+    let result = match frame {
+        Ok(frame) => frame.save(out).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(()) => println!("Rendered {} to {}", scene.name(), out.display()),
+        Err(e) => {
+            eprintln!("Could not render {}: {}", scene.name(), e);
+            process::exit(1);
+        },
     }
+}
+
+/// `--scenario <file>`: a hand to replay right away
+fn scenario_arg() -> Option<Scenario> {
+    let file_name = arg_value("--scenario")?;
 
-    File::open(file_name).unwrap()
+    match Scenario::load(&file_name) {
+        Ok(scenario) => Some(scenario),
+        Err(e) => {
+            eprintln!("Could not load the scenario {}: {}", file_name, e);
+            process::exit(2);
+        },
+    }
 }
 
 fn main() {
+    let render_scene_args = render_scene_args();
+    let scenario = scenario_arg();
+
+    // the window opens as it was left, scenes are always rendered at the layout's size
+    let config = config::Config::load(config::CONFIG_FILE_NAME);
+    let window_mode = match render_scene_args {
+        Some(_) => window::window_mode(&config::Config::default(), false),
+        None => window::window_mode(&config, true),
+    };
+
     let mut conf = Conf::new().
-        window_mode(WindowMode {
-            width: 1900.0,
-            height: 900.0,
-            ..Default::default()
-        });
+        window_mode(window_mode);
       
-    conf.window_setup = conf.window_setup.title("Blackjack").icon(ICON_PATH);    
+    conf.window_setup = conf.window_setup.title(window_title::GAME_NAME);    
 
     let (mut ctx, event_loop) = ContextBuilder::new("BlackJack", "Kris").
         default_conf(conf.clone()).
@@ -53,16 +103,44 @@ fn main() {
         filesystem::mount(&mut ctx, &path, true);
     }
 
-    let f;
-    let f_unwrapped = File::open(FILE_NAME);
+    if let Some((scene, out)) = render_scene_args {
+        render_scene(&mut ctx, scene, &out);
+        return;
+    }
+
+    snapshot::add_to_panic_reports();
+    window::restore(&ctx, &config);
 
-    match f_unwrapped {
-        Ok(file) => f = file,
-        Err(_) => f = create_file(FILE_NAME),
+    if let Err(e) = resources::set_window_icon(&mut ctx) {
+        eprintln!("Could not set the window icon: {}", e);
     }
-    let reader = BufReader::new(f);
 
-    let state = main_state::MainState::new(&mut ctx, reader, FILE_NAME).unwrap();
+    // no stats file yet, the setup wizard writes it once the player is through
+    let first_run = File::open(FILE_NAME).is_err();
+    // before anything is read, so a save damaged in this session can still be restored
+    if let Err(e) = backup::autosave(path::Path::new(backup::BACKUP_DIR), &backup::save_files(FILE_NAME), history::now()) {
+        eprintln!("Could not back up the saves: {}", e);
+    }
+    // upgraded from older releases if needed
+    let stats = if first_run { String::from("0 0 0") } else { migration::read(FILE_NAME, SaveFile::Stats).unwrap_or_default() };
+    let reader = stats.as_bytes();
+
+    let profile = profile::Profile::load(profile::PROFILE_FILE_NAME);
+    let history = history::History::load(history::HISTORY_FILE_NAME);
+    let trainer_stats = trainer::TrainerStats::load(trainer::TRAINER_STATS_FILE_NAME);
+    let mut state = main_state::MainState::new(&mut ctx, reader, FILE_NAME, config, profile, history, trainer_stats).unwrap();
+
+    if first_run {
+        state.start_setup();
+    }
+    if let Some(scenario) = scenario {
+        state.start_scenario(scenario);
+    }
+
+    #[cfg(feature = "hot-reload")]
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        state.watch_resources(&path::Path::new(&manifest_dir).join("resources"));
+    }
 
     event::run(ctx, event_loop, state);
 }
\ No newline at end of file
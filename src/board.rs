@@ -1,19 +1,75 @@
-use crate::card::{self, Card};
+use crate::burn::{self, BurnCard};
+use crate::card::{self, Card, CardValue};
+use crate::card_back::BackAnimation;
+use crate::card_set::CardSet;
+use crate::game_engine;
+use crate::layout::{Anchor, Layout};
+use crate::practice;
+use crate::stamp::{self, Stamp};
+use crate::resources;
+use crate::what_if::Shoe;
 use ggez::{
     Context, 
+    GameError,
     GameResult, 
     graphics,
     audio,
     mint::{Point2, Vector2}
 };
-use rand::seq::SliceRandom;
+use rand::{Rng, seq::SliceRandom};
 
 
-pub const DECK_POSITION: Point2<f32> = Point2 { x: 100.0, y: 160.0 };
-const PLAYER_FIRST_POSITION: Point2<f32> = Point2 { x: 100.0, y: 770.0 };
-const DEALER_FIRST_POSITION: Point2<f32> = Point2 { x: 100.0, y: 475.0 };
 const MOVING_CARD_STEP: f32 = 1.0 / 75.0;
 const CARD_SPACING: f32 = 170.0;
+/// How far down a card the row under it starts, see CardRows
+const ROW_OVERLAP: f32 = 0.5;
+/// Space between a row's last card and the badge with the hand's value
+const BADGE_GAP: f32 = 16.0;
+const BADGE_PADDING: f32 = 8.0;
+/// Height of a card's edge in the side of the shoe
+const SHOE_PIXELS_PER_CARD: f32 = 1.0;
+/// The cut card goes in this many cards from the bottom, unless the player places it
+pub const CUT_CARD_FROM_BOTTOM: usize = 18;
+/// Where the player may place the cut card, in cards from the bottom: at least a quarter of
+/// the deck is dealt, and what's left is more than any hand takes
+pub const CUT_RANGE: std::ops::RangeInclusive<usize> = CUT_CARD_FROM_BOTTOM..=39;
+const SHOE_MARGIN: f32 = 12.0;
+
+/// How the cards of a hand go from its first one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CardRows {
+    /// From one card of a row to the next, negative on the mirrored table
+    spacing: f32,
+    /// Cards in a row before the hand goes on in a row under it, None for a single row
+    per_row: Option<usize>,
+    /// From one row to the next, less than a card so the rows overlap
+    row_spacing: f32,
+}
+
+impl CardRows {
+    fn new(layout: &Layout) -> Self {
+        CardRows {
+            spacing: CARD_SPACING * layout.card_size() * layout.row_direction(),
+            per_row: layout.cards_per_row(),
+            row_spacing: card::CARD_DIMENSION_Y * layout.card_size() * ROW_OVERLAP,
+        }
+    }
+
+    /// Middle of the card `index` of a hand whose first card is at `first`
+    fn position(&self, first: Point2<f32>, index: usize) -> Point2<f32> {
+        let (row, column) = match self.per_row {
+            Some(per_row) => (index / per_row, index % per_row),
+            None => (0, index),
+        };
+
+        Point2 { x: first.x + column as f32 * self.spacing, y: first.y + row as f32 * self.row_spacing }
+    }
+
+    /// Cards in the widest row of a hand of `cards`
+    fn widest(&self, cards: usize) -> usize {
+        self.per_row.map_or(cards, |per_row| cards.min(per_row))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum Turn {
@@ -24,52 +80,165 @@ pub enum Turn {
 #[derive(Debug)]
 pub struct Deck {
     cards: Vec<Card>,
+    /// The cut card, in cards from the bottom
+    cut: usize,
+    /// Whether the player placed the cut card, the deck is then dealt down to it over several
+    /// hands instead of being shuffled for each one
+    cut_placed: bool,
 }
 
 impl Deck {
-    pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-        
+    /// Shuffled with `rng`, the gameplay stream in the game(see GameRng).
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
         let mut vec = card::all();
-        vec.shuffle(&mut rng);
+        vec.shuffle(rng);
 
         Deck {
             cards: vec,
+            cut: CUT_CARD_FROM_BOTTOM,
+            cut_placed: false,
         }
     }
 
-    pub fn deal_card(&mut self, ctx: &mut Context) -> GameResult<Card> {
-        let mut card = self.cards.pop().unwrap();
+    /// A shuffled deck of any cards, `counts[i]` of practice::RANKS[i]. A small composition is
+    /// repeated up to a full deck's worth, which is more than one hand can take.
+    pub fn with_composition<R: Rng>(rng: &mut R, counts: &[usize]) -> Self {
+        let cards = practice::cards_of(counts);
+        let full_deck = card::all().len();
+        let mut vec: Vec<Card> = cards.iter().cycle().take(full_deck.max(cards.len())).cloned().collect();
+        vec.shuffle(rng);
+
+        Deck {
+            cards: vec,
+            cut: CUT_CARD_FROM_BOTTOM,
+            cut_placed: false,
+        }
+    }
+
+    /// A shuffled deck with the given cards on top, dealt in the given order.
+    pub fn stacked<R: Rng>(rng: &mut R, top_cards: &[String]) -> Self {
+        let mut deck = Deck::new(rng);
+
+        deck.cards.retain(|card| !top_cards.contains(&card.name));
+        deck.cards.extend(top_cards.iter().rev().map(|name| Card::new(name)));
+
+        deck
+    }
+
+    pub fn deal_card(&mut self, ctx: &mut Context, card_set: &CardSet) -> GameResult<Card> {
+        let card = self.cards.pop().unwrap();
+
+        card_set.load_card(ctx, &card.name)?;
+
+        Ok(card)
+    }
 
-        card.load(ctx)?;
+    /// Takes a specific card out of the deck, for setting up fixed tables.
+    pub fn take_card(&mut self, ctx: &mut Context, card_set: &CardSet, card_name: &str) -> GameResult<Card> {
+        let index = self.cards.iter().
+            position(|card| card.name == card_name).
+            ok_or_else(|| GameError::CustomError(format!("{} is not in the deck", card_name)))?;
+        let card = self.cards.remove(index);
 
-        card.position = DECK_POSITION;
+        card_set.load_card(ctx, &card.name)?;
 
         Ok(card)
     }
 
-    pub fn get_top_card(&self) -> Card {
-        self.cards.last().unwrap().clone()
+    // The deck has two views. Anyone may count what's left. Only the game itself may peek at
+    // the next card(the hint does), so peek is crate-only and the cards stay private.
+
+    /// Cards still in the deck
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// How many cards of each rank are left, see practice::composition.
+    pub fn composition(&self) -> Vec<(&'static str, usize)> {
+        practice::composition(&self.cards)
+    }
+
+    /// What's left by points, for the what-if odds.
+    pub fn shoe(&self) -> Shoe {
+        Shoe::of(&self.cards)
+    }
+
+    /// Puts the cut card `from_bottom` cards from the bottom, kept in CUT_RANGE, and returns
+    /// where it went.
+    pub fn place_cut(&mut self, from_bottom: usize) -> usize {
+        self.cut = from_bottom.clamp(*CUT_RANGE.start(), *CUT_RANGE.end());
+        self.cut_placed = true;
+
+        self.cut
+    }
+
+    pub fn cut_placed(&self) -> bool {
+        self.cut_placed
+    }
+
+    /// Whether the next hand is dealt from this deck too: the player placed the cut card and it
+    /// hasn't come out yet.
+    pub fn carries_over(&self) -> bool {
+        self.cut_placed && self.cards.len() > self.cut
+    }
+
+    /// The number of the shoe the next hand is dealt from, this deck's `index` while it carries over.
+    pub fn next_shoe(&self, index: u32) -> u32 {
+        if self.carries_over() { index } else { index + 1 }
+    }
+
+    /// Moves a card still in the deck to the top, so it's dealt next. False when it was dealt already.
+    pub fn put_on_top(&mut self, card_name: &str) -> bool {
+        match self.cards.iter().position(|card| card.name == card_name) {
+            Some(index) => {
+                let card = self.cards.remove(index);
+                self.cards.push(card);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// The card dealt next. Never show it to the player as it is.
+    pub(crate) fn peek(&self) -> CardValue {
+        self.cards.last().unwrap().value().unwrap()
+    }
+
+    /// The card on top, for telling the player what a hit would have brought once it no
+    /// longer matters(see hindsight.rs).
+    pub(crate) fn top_card(&self) -> Option<&Card> {
+        self.cards.last()
+    }
+
+    /// The cards left, the one dealt next first, for the ghost's hand(see ghost.rs).
+    pub(crate) fn cards_from_top(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter().rev()
     }
 }
 
 pub struct Assets {
     pub deck_image: graphics::Image,
+    pub deck_scale: f32,
     pub card_deal_sound: Box<dyn audio::SoundSource>,
     pub card_flip_sound: Box<dyn audio::SoundSource>,
+    /// Before each of the dealer's cards in sweat mode, see sweat.rs
+    pub heartbeat_sound: Box<dyn audio::SoundSource>,
 }
 
 impl Assets {
-    pub fn new(ctx: &mut Context) -> GameResult<Assets> { 
-        let deck_image = graphics::Image::new(ctx, "\\card_images\\card_back.png")?;
-        let card_deal_sound = audio::Source::new(ctx, "\\sfx\\card_deal.wav")?;
-        let card_flip_sound = audio::Source::new(ctx, "\\sfx\\card_flip.wav")?;
+    pub fn new(ctx: &mut Context, card_set: &CardSet) -> GameResult<Assets> { 
+        let deck_image = card_set.back_image(ctx)?;
+        let card_deal_sound = card_set.sound(ctx, resources::CARD_DEAL_SOUND_PATH)?;
+        let card_flip_sound = card_set.sound(ctx, resources::CARD_FLIP_SOUND_PATH)?;
+        let heartbeat_sound = card_set.sound(ctx, resources::HEARTBEAT_SOUND_PATH)?;
 
         Ok (
             Assets {
                 deck_image, 
+                deck_scale: card_set.back_scale(),
                 card_deal_sound: Box::new(card_deal_sound), 
                 card_flip_sound: Box::new(card_flip_sound),
+                heartbeat_sound: Box::new(heartbeat_sound),
             }
         )
     }
@@ -81,75 +250,174 @@ pub struct Board {
     pub dealed_cards_player: Vec<Card>,
     pub dealed_cards_dealer: Vec<Card>,
     pub assets: Assets,
+    pub card_set: CardSet,
+    pub back_animation: BackAnimation,
     pub calculate_result: bool,
     pub card_moving: bool,
+    /// BUST or BLACKJACK over the player's and the dealer's hand
+    stamps: (Option<Stamp>, Option<Stamp>),
+    /// The card burned after the shuffle and how much longer it stays on the table
+    burned: Option<(Card, f32)>,
+    burn_position: Point2<f32>,
+    /// Whether the deck was shuffled for this hand, rather than carried over from the last one
+    shuffled: bool,
+    rows: CardRows,
+    /// Where the deck and the first card of each row are, from the layout
+    pub deck_position: Point2<f32>,
+    player_first_position: Point2<f32>,
+    dealer_first_position: Point2<f32>,
     next_card_position_player: Point2<f32>,
     next_card_position_dealer: Point2<f32>,
     translation: Vector2<f32>,
 }
 
 impl Board {
-    fn get_translating_vector(next_pos: Point2<f32>) -> Vector2<f32> {
-        let vec_x: f32 = (next_pos.x - DECK_POSITION.x) * MOVING_CARD_STEP;
-        let vec_y: f32 = (next_pos.y - DECK_POSITION.y) * MOVING_CARD_STEP;
+    fn get_translating_vector(&self, next_pos: Point2<f32>) -> Vector2<f32> {
+        let vec_x: f32 = (next_pos.x - self.deck_position.x) * MOVING_CARD_STEP;
+        let vec_y: f32 = (next_pos.y - self.deck_position.y) * MOVING_CARD_STEP;
 
         Vector2 { x: vec_x, y: vec_y }
     }
 
-    fn draw_deck(&self, ctx: &mut Context) -> GameResult<()> {
+    fn draw_deck(&self, ctx: &mut Context, locked: bool) -> GameResult<()> {
+        self.draw_shoe(ctx)?;
+
+        let color = if locked { graphics::Color::from_rgb(120, 120, 120) } else { graphics::Color::WHITE };
         let draw_params = graphics::DrawParam::default().
-            dest(DECK_POSITION).
+            src(self.back_animation.src()).
+            dest(self.deck_position).
+            color(color).
             offset(Point2 { x: 0.5, y: 0.5 }).
             scale(Vector2 {
-                x: card::CARD_SCALE,
-                y: card::CARD_SCALE,
+                x: self.assets.deck_scale,
+                y: self.assets.deck_scale,
             });
         graphics::draw(ctx, &self.assets.deck_image, draw_params)?;
 
         Ok(())
     }
 
-    pub fn new(ctx: &mut Context) -> GameResult<Board> {
-        let assets = Assets::new(ctx)?;
-
-        Ok(
-            Board {
-                deck: Deck::new(),
-                turn: Turn::Player,
-                dealed_cards_player: Vec::new(),
-                dealed_cards_dealer: Vec::new(),
-                assets,
-                calculate_result: false,
-                next_card_position_player: PLAYER_FIRST_POSITION,
-                next_card_position_dealer: DEALER_FIRST_POSITION,
-                translation: Self::get_translating_vector(PLAYER_FIRST_POSITION),
-                card_moving: false,
-            }
-        )   
+    /// The shoe around the deck: the side of the cards left below the top card, as high as
+    /// there are cards, with the cut card in red.
+    fn draw_shoe(&self, ctx: &mut Context) -> GameResult<()> {
+        let remaining = self.deck.remaining();
+        let (card_width, card_height) = self.card_set.card_dimensions();
+        let card_left = self.deck_position.x - card_width / 2.0;
+        let card_bottom = self.deck_position.y + card_height / 2.0;
+        let height = shoe_height(remaining);
+
+        let mut mesh = graphics::MeshBuilder::new();
+        let shoe = graphics::Rect::new(card_left - SHOE_MARGIN,
+                                       self.deck_position.y - card_height / 2.0 - SHOE_MARGIN,
+                                       card_width + 2.0 * SHOE_MARGIN,
+                                       card_height + height + 2.0 * SHOE_MARGIN);
+        mesh.rounded_rectangle(graphics::DrawMode::fill(), shoe, 10.0, graphics::Color::from_rgb(60, 40, 25))?;
+
+        if height > 0.0 {
+            let side = graphics::Rect::new(card_left, card_bottom, card_width, height);
+            mesh.rectangle(graphics::DrawMode::fill(), side, graphics::Color::from_rgb(235, 235, 225))?;
+        }
+        if let Some(depth) = cut_card_depth(remaining, self.deck.cut) {
+            let y = card_bottom + depth;
+            mesh.line(&[Point2 { x: card_left, y }, Point2 { x: card_left + card_width, y }], 2.0, graphics::Color::from_rgb(204, 0, 0))?;
+        }
+
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    pub fn new<R: Rng>(ctx: &mut Context, card_set: CardSet, rng: &mut R, layout: &Layout) -> GameResult<Board> {
+        let card_set = card_set.with_size(layout.card_size());
+        card_set.preload();
+        let assets = Assets::new(ctx, &card_set)?;
+        let player_first_position = layout.at(Anchor::PlayerFirstCard);
+
+        let mut board = Board {
+            deck: Deck::new(rng),
+            turn: Turn::Player,
+            dealed_cards_player: Vec::new(),
+            dealed_cards_dealer: Vec::new(),
+            assets,
+            back_animation: BackAnimation::new(card_set.back_style()),
+            card_set,
+            calculate_result: false,
+            deck_position: layout.at(Anchor::Deck),
+            player_first_position,
+            dealer_first_position: layout.at(Anchor::DealerFirstCard),
+            next_card_position_player: player_first_position,
+            next_card_position_dealer: layout.at(Anchor::DealerFirstCard),
+            translation: Vector2 { x: 0.0, y: 0.0 },
+            card_moving: false,
+            stamps: (None, None),
+            burned: None,
+            burn_position: layout.at(Anchor::BurnCard),
+            shuffled: true,
+            rows: CardRows::new(layout),
+        };
+        board.translation = board.get_translating_vector(player_first_position);
+
+        Ok(board)
     }
 
     fn change_next_position(&mut self) {
         match self.turn {
             Turn::Player => {
-                self.next_card_position_player.x += CARD_SPACING;
+                self.next_card_position_player = self.rows.position(self.player_first_position, self.dealed_cards_player.len());
             },
             Turn::Dealer => {
-                self.next_card_position_dealer.x += CARD_SPACING;
+                self.next_card_position_dealer = self.rows.position(self.dealer_first_position, self.dealed_cards_dealer.len());
             },
         }
     }
 
+    /// Takes the positions and the size of the cards from `layout` again, for a window that
+    /// went between the compact table and the full one. The cards on the table land in their
+    /// new places right away.
+    pub fn relayout(&mut self, layout: &Layout) {
+        self.card_set = self.card_set.clone().with_size(layout.card_size());
+        self.assets.deck_scale = self.card_set.back_scale();
+        self.rows = CardRows::new(layout);
+        self.deck_position = layout.at(Anchor::Deck);
+        self.burn_position = layout.at(Anchor::BurnCard);
+        self.player_first_position = layout.at(Anchor::PlayerFirstCard);
+        self.dealer_first_position = layout.at(Anchor::DealerFirstCard);
+
+        if let Some((card, _)) = &mut self.burned {
+            let flip_state = card.flip_state.clone();
+            card.place(self.burn_position);
+            card.flip_state = flip_state;
+        }
+        let rows = self.rows;
+        for (cards, first) in [(&mut self.dealed_cards_player, self.player_first_position), (&mut self.dealed_cards_dealer, self.dealer_first_position)] {
+            for (i, card) in cards.iter_mut().enumerate() {
+                card.place(rows.position(first, i));
+            }
+        }
+        // a card that was flying has landed, the next one goes after it
+        self.next_card_position_player = self.rows.position(self.player_first_position, self.dealed_cards_player.len());
+        self.next_card_position_dealer = self.rows.position(self.dealer_first_position, self.dealed_cards_dealer.len());
+        self.change_translating_vector();
+    }
+
     fn change_translating_vector(&mut self) {
         match self.turn {
             Turn::Player => {
-                self.translation = Self::get_translating_vector(self.next_card_position_player);
+                self.translation = self.get_translating_vector(self.next_card_position_player);
             },
             Turn::Dealer => {
-                self.translation = Self::get_translating_vector(self.next_card_position_dealer);
+                self.translation = self.get_translating_vector(self.next_card_position_dealer);
             }
         }
     }
 
+    /// The top card of the deck, lying on the deck to move from there.
+    pub fn deal_card(&mut self, ctx: &mut Context) -> GameResult<Card> {
+        let mut card = self.deck.deal_card(ctx, &self.card_set)?;
+        card.position = self.deck_position;
+
+        Ok(card)
+    }
+
     pub fn set_card(&mut self, dealed_card: Card) {
         match self.turn {
             Turn::Player => self.dealed_cards_player.push(dealed_card),
@@ -157,23 +425,94 @@ impl Board {
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context, time_delta: f32) {
+    /// Puts the given card face up in the current turn's row, without dealing it.
+    pub fn place_card(&mut self, ctx: &mut Context, card_name: &str) -> GameResult<()> {
+        let mut card = self.deck.take_card(ctx, &self.card_set, card_name)?;
+
+        match self.turn {
+            Turn::Player => card.place(self.next_card_position_player),
+            Turn::Dealer => card.place(self.next_card_position_dealer),
+        }
+        self.set_card(card);
+        self.change_next_position();
+        self.change_translating_vector();
+
+        Ok(())
+    }
+
+    /// Deals this hand from the last hand's deck, see Deck::carries_over.
+    pub fn continue_shoe(&mut self, deck: Deck) {
+        self.deck = deck;
+        self.shuffled = false;
+    }
+
+    /// The cut card can be placed after a shuffle, until the hand's first card is dealt.
+    pub fn can_place_cut(&self) -> bool {
+        self.shuffled && self.dealed_cards_player.is_empty() && self.dealed_cards_dealer.is_empty()
+    }
+
+    /// Where in the deck a click on the side of the shoe puts the cut card, in cards from the
+    /// bottom. None for a click elsewhere.
+    pub fn cut_at(&self, position: Point2<f32>) -> Option<usize> {
+        let (card_width, card_height) = self.card_set.card_dimensions();
+        let card_left = self.deck_position.x - card_width / 2.0;
+        let card_bottom = self.deck_position.y + card_height / 2.0;
+        let height = shoe_height(self.deck.remaining());
+
+        let inside = position.x >= card_left && position.x <= card_left + card_width &&
+                     position.y >= card_bottom && position.y <= card_bottom + height + SHOE_MARGIN;
+
+        if inside { Some(cut_at_depth(self.deck.remaining(), position.y - card_bottom)) } else { None }
+    }
+
+    /// Burns the top card of a freshly shuffled deck, shown face up or face down for a moment.
+    /// A deck something was dealt from is left alone, so coming back to a hand burns nothing.
+    pub fn burn_card(&mut self, ctx: &mut Context, burn: BurnCard) -> GameResult<()> {
+        if !burn.burns() || self.deck.remaining() < card::all().len() {
+            return Ok(());
+        }
+
+        let mut card = self.deck.deal_card(ctx, &self.card_set)?;
+        card.place(self.burn_position);
+        if burn == BurnCard::FaceDown {
+            card.flip_state = card::CardFlipState::Back;
+        }
+        self.burned = Some((card, burn::BURN_SECONDS));
+
+        Ok(())
+    }
+
+    /// `animation_speed` is how many times faster than normal the cards move and flip.
+    pub fn update(&mut self, ctx: &mut Context, time_delta: f32, animation_speed: f32) {
         let mut is_moving: bool = false;
         let mut is_flipping: bool = false;
 
+        // GPU-light mode keeps the backs on their first frame
+        if !self.card_set.light() {
+            self.back_animation.update(time_delta);
+        }
+        if let Some((_, time_left)) = &mut self.burned {
+            *time_left -= time_delta;
+            if *time_left <= 0.0 {
+                self.burned = None;
+            }
+        }
+        stamp::update(&mut self.stamps.0, landed(&self.dealed_cards_player), time_delta);
+        stamp::update(&mut self.stamps.1, landed(&self.dealed_cards_dealer), time_delta);
+
         for card in &mut self.dealed_cards_player {
             let mut vec = Vector2{ x: 0.0, y: 0.0 };
             
             if matches!(card.move_state, card::CardMoveState::Moving) {
                 is_moving = true;
-                vec = self.translation.clone();
+                vec = Vector2 { x: self.translation.x * animation_speed, y: self.translation.y * animation_speed };
             }
             
             if !matches!(card.animation.state, card::FlipAnimationState::Stopped) {
                 is_flipping = true;
             }
 
-            card.update(time_delta, vec, self.next_card_position_player);
+            card.update(time_delta * animation_speed, vec, self.next_card_position_player);
         }
 
         for card in &mut self.dealed_cards_dealer {
@@ -181,14 +520,14 @@ impl Board {
             
             if matches!(card.move_state, card::CardMoveState::Moving) {
                 is_moving = true;
-                vec = self.translation.clone();
+                vec = Vector2 { x: self.translation.x * animation_speed, y: self.translation.y * animation_speed };
             }
             
             if !matches!(card.animation.state, card::FlipAnimationState::Stopped) {
                 is_flipping = true;
             }
             
-            card.update(time_delta, vec, self.next_card_position_dealer);
+            card.update(time_delta * animation_speed, vec, self.next_card_position_dealer);
         }
 
         if is_moving && !self.card_moving {
@@ -213,32 +552,245 @@ impl Board {
         // в другите два случая не правим нищо
     }
 
-    pub fn draw(&self,  ctx: &mut Context) -> GameResult<()> {
-        self.draw_deck(ctx)?;
+    /// Reloads a changed asset(given by its ggez path) everywhere it's currently used. Cards take
+    /// their images from the card set when drawn, so they pick up the new one by themselves.
+    /// A file that doesn't load leaves the asset loaded before in place.
+    pub fn reload_asset(&mut self, ctx: &mut Context, path: &str) -> GameResult<()> {
+        let is_back = path == resources::CARD_BACK_PATH || self.card_set.back_style().path == Some(path);
+        self.card_set.reload_image(ctx, path)?;
+
+        if is_back {
+            self.assets.deck_image = self.card_set.back_image(ctx)?;
+        } else if path == resources::CARD_DEAL_SOUND_PATH {
+            self.assets.card_deal_sound = Box::new(resources::load_sound(ctx, path)?);
+        } else if path == resources::CARD_FLIP_SOUND_PATH {
+            self.assets.card_flip_sound = Box::new(resources::load_sound(ctx, path)?);
+        } else if path == resources::HEARTBEAT_SOUND_PATH {
+            self.assets.heartbeat_sound = Box::new(resources::load_sound(ctx, path)?);
+        }
+        // the sound loaded, so the next boards take it too
+        self.card_set.assets().forget_sound(path);
+
+        Ok(())
+    }
+
+    /// `deck_locked` greys the deck out, when clicking it does nothing.
+    pub fn draw(&self,  ctx: &mut Context, deck_locked: bool) -> GameResult<()> {
+        self.draw_deck(ctx, deck_locked)?;
+
+        let back_src = self.back_animation.src();
+
+        if let Some((card, _)) = &self.burned {
+            card.draw(ctx, &self.card_set, back_src)?;
+        }
 
         for card in &self.dealed_cards_player {
-            card.draw(ctx)?;
+            card.draw(ctx, &self.card_set, back_src)?;
         }
 
         for card in &self.dealed_cards_dealer {
-            card.draw(ctx)?;
+            card.draw(ctx, &self.card_set, back_src)?;
+        }
+
+        Ok(())
+    }
+
+    /// A badge with the hand's value next to each row, "7/17" for a soft hand. Only the cards
+    /// that have landed count, so it changes as a card arrives.
+    pub fn draw_value_badges(&self, ctx: &mut Context, font: graphics::Font, size: f32) -> GameResult<()> {
+        let card_width = self.card_set.card_dimensions().0;
+
+        draw_value_badge(ctx, &self.dealed_cards_player, self.player_first_position, &self.rows, card_width, font, size)?;
+        draw_value_badge(ctx, &self.dealed_cards_dealer, self.dealer_first_position, &self.rows, card_width, font, size)
+    }
+
+    /// The stamps over the middle of the hands they belong to.
+    pub fn draw_stamps(&self, ctx: &mut Context, font: graphics::Font, size: f32) -> GameResult<()> {
+        let hands = [
+            (&self.stamps.0, &self.dealed_cards_player, self.player_first_position),
+            (&self.stamps.1, &self.dealed_cards_dealer, self.dealer_first_position),
+        ];
+
+        for (stamp, cards, first_position) in hands {
+            if let Some(stamp) = stamp {
+                // the middle of the first row
+                let last = self.rows.position(first_position, self.rows.widest(landed(cards).len()) - 1);
+                let middle = Point2 { x: (first_position.x + last.x) / 2.0, y: first_position.y };
+                stamp::draw(ctx, stamp, middle, font, size)?;
+            }
         }
 
         Ok(())
     }
 }
 
+/// How high the side of the shoe is with `remaining` cards, the top card being drawn whole.
+pub fn shoe_height(remaining: usize) -> f32 {
+    remaining.saturating_sub(1) as f32 * SHOE_PIXELS_PER_CARD
+}
+
+/// How far below the top card the cut card `cut` cards from the bottom is, None once it's
+/// been reached.
+pub fn cut_card_depth(remaining: usize, cut: usize) -> Option<f32> {
+    if remaining <= cut {
+        return None;
+    }
+
+    Some((remaining - cut) as f32 * SHOE_PIXELS_PER_CARD)
+}
+
+/// Where a cut card placed `depth` below the top card of `remaining` goes, in cards from the
+/// bottom.
+pub fn cut_at_depth(remaining: usize, depth: f32) -> usize {
+    let below_top = (depth / SHOE_PIXELS_PER_CARD).round().max(0.0) as usize;
+
+    remaining.saturating_sub(below_top)
+}
+
+/// The cards of a row that have landed, which is all but one that's still flying to it.
+fn landed(cards: &[Card]) -> &[Card] {
+    // cards land in the order they're dealt
+    let landed_count = cards.iter().
+        take_while(|card| matches!(card.move_state, card::CardMoveState::Stopped)).
+        count();
+
+    &cards[..landed_count]
+}
+
+fn draw_value_badge(ctx: &mut Context, cards: &[Card], first_position: Point2<f32>, rows: &CardRows, card_width: f32, font: graphics::Font, size: f32) -> GameResult<()> {
+    let landed = landed(cards);
+    if landed.is_empty() {
+        return Ok(());
+    }
+
+    let value = match game_engine::hand_totals(landed) {
+        (hard, Some(soft)) => format!("{}/{}", hard, soft),
+        (hard, None) => hard.to_string(),
+    };
+    let mut text = graphics::Text::new(value);
+    text.set_font(font, graphics::PxScale::from(size));
+    let dimensions = text.dimensions(ctx);
+
+    // after the last card, cards being drawn around their middle
+    let last_middle = rows.position(first_position, landed.len() - 1);
+    let width = dimensions.w + 2.0 * BADGE_PADDING;
+    let x = if rows.spacing > 0.0 {
+        last_middle.x + card_width / 2.0 + BADGE_GAP
+    } else {
+        last_middle.x - card_width / 2.0 - BADGE_GAP - width
+    };
+    let rect = graphics::Rect::new(x,
+                                   last_middle.y - dimensions.h / 2.0 - BADGE_PADDING,
+                                   width,
+                                   dimensions.h + 2.0 * BADGE_PADDING);
+    let badge = graphics::Mesh::new_rounded_rectangle(ctx, graphics::DrawMode::fill(), rect, rect.h / 2.0, graphics::Color::new(0.0, 0.0, 0.0, 0.6))?;
+
+    graphics::draw(ctx, &badge, graphics::DrawParam::default())?;
+    graphics::draw(ctx, &text, graphics::DrawParam::default().dest(Point2 { x: rect.x + BADGE_PADDING, y: rect.y + BADGE_PADDING }))
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn deck_get_top_card_returns_top_card() {
-        let deck = Deck::new();
-        let card = deck.get_top_card();
+    fn stacked_deck_deals_top_cards_in_order() {
+        let mut deck = Deck::stacked(&mut rand::thread_rng(), &[String::from("ace_of_spades"), String::from("2_of_hearts")]);
 
-        assert_eq!(card.name, deck.cards.last().unwrap().name);
         assert_eq!(deck.cards.len(), 52);
+        assert_eq!(deck.cards.pop().unwrap().name, "ace_of_spades");
+        assert_eq!(deck.cards.pop().unwrap().name, "2_of_hearts");
+    }
+
+    #[test]
+    fn composed_deck_has_only_the_ranks_asked_for() {
+        // only aces, three of them
+        let mut counts = vec![0; practice::RANKS.len()];
+        counts[0] = 3;
+        let deck = Deck::with_composition(&mut rand::thread_rng(), &counts);
+
+        assert_eq!(deck.remaining(), 52);
+        assert!(deck.cards.iter().all(Card::is_an_ace));
+        assert_eq!(Deck::with_composition(&mut rand::thread_rng(), &[8; 13]).remaining(), 104);
+    }
+
+    #[test]
+    fn long_hands_go_on_in_a_second_row_on_the_compact_table() {
+        let mut layout = Layout::default();
+        let first = Point2 { x: 100.0, y: 500.0 };
+        assert_eq!(CardRows::new(&layout).position(first, 5), Point2 { x: 100.0 + 5.0 * CARD_SPACING, y: 500.0 });
+
+        layout.compact = true;
+        let rows = CardRows::new(&layout);
+        assert_eq!(rows.position(first, 4), Point2 { x: 100.0, y: 500.0 + rows.row_spacing });
+        assert!(rows.row_spacing < card::CARD_DIMENSION_Y * layout.card_size());
+        assert_eq!(rows.widest(6), 4);
+    }
+
+    #[test]
+    fn shoe_shortens_as_cards_go() {
+        assert!(shoe_height(40) < shoe_height(52));
+        assert_eq!(shoe_height(1), 0.0);
+
+        assert!(cut_card_depth(30, CUT_CARD_FROM_BOTTOM).unwrap() < cut_card_depth(52, CUT_CARD_FROM_BOTTOM).unwrap());
+        assert_eq!(cut_card_depth(CUT_CARD_FROM_BOTTOM, CUT_CARD_FROM_BOTTOM), None);
+    }
+
+    #[test]
+    fn placed_cut_card_keeps_the_deck() {
+        let mut deck = Deck::new(&mut rand::thread_rng());
+        assert!(!deck.carries_over());
+
+        // a click past the bottom of the shoe puts it as deep as it may go
+        assert_eq!(deck.place_cut(cut_at_depth(52, 60.0)), *CUT_RANGE.start());
+        assert_eq!(deck.place_cut(cut_at_depth(52, 22.0)), 30);
+        assert_eq!(cut_card_depth(52, 30), Some(22.0));
+
+        deck.cards.truncate(31);
+        assert!(deck.carries_over());
+        deck.cards.pop();
+        assert!(!deck.carries_over());
+    }
+
+    #[test]
+    fn hands_on_one_code_dont_depend_on_the_hits_taken() {
+        let rng = crate::rng::GameRng::seeded(0xC0DE);
+        // the first four cards of each hand, taking `hits` more cards a hand
+        let play = |hits: &[usize]| -> Vec<Vec<String>> {
+            let mut shoe = 0;
+            hits.iter().map(|hits| {
+                let mut deck = Deck::new(&mut rng.shoe(shoe));
+                let dealt = deck.cards_from_top().take(4).map(|card| card.name.clone()).collect();
+                deck.cards.truncate(deck.cards.len() - 4 - hits);
+                shoe = deck.next_shoe(shoe);
+                dealt
+            }).collect()
+        };
+        assert_eq!(play(&[0, 3, 1, 5]), play(&[2, 0, 4, 0]));
+
+        // a cut card would deal the next hand from what the hits left, so challenges don't allow it
+        let mut deck = Deck::new(&mut rng.shoe(0));
+        deck.place_cut(*CUT_RANGE.start());
+        deck.cards.truncate(45);
+        assert_eq!(deck.next_shoe(0), 0);
+    }
+
+    #[test]
+    fn deck_peek_returns_top_card() {
+        let deck = Deck::new(&mut rand::thread_rng());
+        let card = deck.peek();
+
+        assert_eq!(card, deck.cards.last().unwrap().value().unwrap());
+        assert_eq!(deck.cards.len(), 52);
+    }
+
+    #[test]
+    fn public_view_only_counts() {
+        let deck = Deck::stacked(&mut rand::thread_rng(), &[String::from("ace_of_spades")]);
+
+        assert_eq!(deck.remaining(), 52);
+        assert_eq!(deck.composition().iter().map(|(_, left)| left).sum::<usize>(), 52);
+        assert!(deck.composition().iter().all(|(_, left)| *left == 4));
     }
 }
@@ -0,0 +1,70 @@
+//! A speech bubble: rounded, as big as its text, with a tail down to whoever is talking. Long
+//! lines wrap at MAX_TEXT_WIDTH so the bubble never runs off the table, and the bubble can open
+//! to either side of its tail.
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+const MAX_TEXT_WIDTH: f32 = 360.0;
+const PADDING: f32 = 14.0;
+const TAIL_HEIGHT: f32 = 24.0;
+const TAIL_WIDTH: f32 = 22.0;
+/// How far the tail's root is from the bubble's edge on the tail's side
+const TAIL_INSET: f32 = 30.0;
+
+/// The bubble around a text of this size whose tail ends at `tail`, the bubble above and to
+/// the left of it, or to the right when `opens_right`.
+pub fn bubble_rect(tail: Point2<f32>, text_width: f32, text_height: f32, opens_right: bool) -> graphics::Rect {
+    let width = text_width + 2.0 * PADDING;
+    let height = text_height + 2.0 * PADDING;
+    let x = if opens_right { tail.x - TAIL_INSET } else { tail.x + TAIL_INSET - width };
+
+    graphics::Rect::new(x, tail.y - TAIL_HEIGHT - height, width, height)
+}
+
+pub fn draw(ctx: &mut Context, font: graphics::Font, size: f32, tail: Point2<f32>, opens_right: bool, line: &str) -> GameResult<()> {
+    let mut text = graphics::Text::new(graphics::TextFragment::new(line).color(graphics::Color::BLACK));
+    text.set_font(font, graphics::PxScale::from(size));
+    text.set_bounds(Point2 { x: MAX_TEXT_WIDTH, y: f32::INFINITY }, graphics::Align::Left);
+
+    let dimensions = text.dimensions(ctx);
+    let rect = bubble_rect(tail, dimensions.w, dimensions.h, opens_right);
+    let bottom = rect.y + rect.h;
+    // the root's edge nearest the tail, and the other one toward the bubble's middle
+    let (root, toward_middle) = if opens_right { (rect.x + TAIL_INSET, TAIL_WIDTH) } else { (rect.x + rect.w - TAIL_INSET, -TAIL_WIDTH) };
+
+    let mut mesh = graphics::MeshBuilder::new();
+    mesh.rounded_rectangle(graphics::DrawMode::fill(), rect, 12.0, graphics::Color::WHITE)?;
+    // the tail overlaps the bubble a little, so no seam shows
+    mesh.polygon(graphics::DrawMode::fill(), &[Point2 { x: root + toward_middle, y: bottom - 1.0 }, Point2 { x: root, y: bottom - 1.0 }, tail], graphics::Color::WHITE)?;
+    let bubble = mesh.build(ctx)?;
+
+    graphics::draw(ctx, &bubble, graphics::DrawParam::default())?;
+    graphics::draw(ctx, &text, graphics::DrawParam::default().dest(Point2 { x: rect.x + PADDING, y: rect.y + PADDING }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_with_the_text_away_from_the_tail() {
+        let tail = Point2 { x: 1000.0, y: 500.0 };
+        let short = bubble_rect(tail, 100.0, 30.0, false);
+        let long = bubble_rect(tail, 300.0, 60.0, false);
+
+        assert_eq!((short.w, short.h), (100.0 + 2.0 * PADDING, 30.0 + 2.0 * PADDING));
+        assert_eq!(short.x + short.w, long.x + long.w);
+        assert_eq!(short.y + short.h, tail.y - TAIL_HEIGHT);
+        assert!(long.y < short.y);
+
+        let right = bubble_rect(tail, 300.0, 60.0, true);
+        assert_eq!(right.x, long.x + long.w - 2.0 * TAIL_INSET);
+        assert_eq!(right.w, long.w);
+    }
+}
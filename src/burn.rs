@@ -0,0 +1,56 @@
+//! The burn card: after the shuffle the top card goes to the discards before the first hand,
+//! like at a casino table. Shown face up for a moment it's a card a counter gets to count,
+//! face down it's simply gone from the deck.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BurnCard {
+    #[default]
+    Off,
+    FaceDown,
+    Shown,
+}
+
+pub const BURN_CARDS: &[BurnCard] = &[BurnCard::Off, BurnCard::FaceDown, BurnCard::Shown];
+
+/// How long the burned card stays on the table
+pub const BURN_SECONDS: f32 = 1.5;
+
+impl BurnCard {
+    /// Also the value in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            BurnCard::Off => "off",
+            BurnCard::FaceDown => "face down",
+            BurnCard::Shown => "shown",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        BURN_CARDS.iter().find(|burn| burn.name() == name).copied()
+    }
+
+    pub fn next(&self, step: i32) -> Self {
+        let current = BURN_CARDS.iter().position(|burn| burn == self).unwrap_or(0) as i32;
+
+        BURN_CARDS[(current + step).rem_euclid(BURN_CARDS.len() as i32) as usize]
+    }
+
+    pub fn burns(&self) -> bool {
+        *self != BurnCard::Off
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for burn in BURN_CARDS {
+            assert_eq!(BurnCard::from_name(burn.name()), Some(*burn));
+        }
+        assert_eq!(BurnCard::from_name("sideways"), None);
+        assert_eq!(BurnCard::Shown.next(1), BurnCard::Off);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::chips::{Chips, DENOMINATIONS};
+use crate::probability::{self, HandOdds, Rules};
+use crate::simulation::{BettingStrategy, Simulation, STRATEGIES};
+
+const DEFAULT_BET: u32 = 10;
+const DEFAULT_BANKROLL: u32 = 1000;
+const BET_STEP: u32 = 5;
+const BANKROLL_STEP: u32 = 100;
+/// Typed numbers stop growing at this many digits
+const MAX_DIGITS: u32 = 7;
+/// Bets of the last simulations offered to pick again
+pub const RECENT_BETS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalculatorRow {
+    Bet,
+    Bankroll,
+    Handicap,
+    Strategy,
+    Simulate,
+}
+
+pub const CALCULATOR_ROWS: &[CalculatorRow] = &[
+    CalculatorRow::Bet,
+    CalculatorRow::Bankroll,
+    CalculatorRow::Handicap,
+    CalculatorRow::Strategy,
+    CalculatorRow::Simulate,
+];
+
+/// Family mode has no bets, so only the rules can be changed.
+pub const FAMILY_CALCULATOR_ROWS: &[CalculatorRow] = &[
+    CalculatorRow::Handicap,
+];
+
+/// Inputs of the risk calculator screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskCalculator {
+    pub bet: u32,
+    pub bankroll: u32,
+    pub rules: Rules,
+    pub row: usize,
+    /// Index in simulation::STRATEGIES
+    pub strategy: usize,
+    /// The last simulated sessions
+    pub simulation: Option<Simulation>,
+    /// Odds in points instead of a bet and a bankroll, see Config::family_mode
+    pub family_mode: bool,
+    /// See Config::audit_chips
+    pub audit_chips: bool,
+    /// Bets of the last simulations, newest first and each once
+    pub recent_bets: Vec<u32>,
+}
+
+impl Default for RiskCalculator {
+    fn default() -> Self {
+        RiskCalculator {
+            bet: DEFAULT_BET,
+            bankroll: DEFAULT_BANKROLL,
+            rules: Rules::default(),
+            row: 0,
+            strategy: 0,
+            simulation: None,
+            family_mode: false,
+            audit_chips: false,
+            recent_bets: Vec::new(),
+        }
+    }
+}
+
+impl RiskCalculator {
+    pub fn rows(&self) -> &'static [CalculatorRow] {
+        if self.family_mode { FAMILY_CALCULATOR_ROWS } else { CALCULATOR_ROWS }
+    }
+
+    pub fn selected(&self) -> CalculatorRow {
+        self.rows()[self.row]
+    }
+
+    pub fn select(&mut self, step: i32) {
+        let rows = self.rows().len() as i32;
+        self.row = (self.row as i32 + step).rem_euclid(rows) as usize;
+    }
+
+    /// Left/Right on the selected row.
+    pub fn change(&mut self, step: i32) {
+        let add = |value: u32, amount: u32| {
+            if step > 0 { value.saturating_add(amount) } else { value.saturating_sub(amount) }
+        };
+
+        match self.selected() {
+            CalculatorRow::Bet => self.bet = add(self.bet, BET_STEP).max(1),
+            CalculatorRow::Bankroll => self.bankroll = add(self.bankroll, BANKROLL_STEP),
+            CalculatorRow::Handicap => self.rules.dealer_handicap = !self.rules.dealer_handicap,
+            CalculatorRow::Strategy => {
+                self.strategy = (self.strategy as i32 + step).rem_euclid(STRATEGIES.len() as i32) as usize;
+            },
+            CalculatorRow::Simulate => (),
+        }
+    }
+
+    pub fn betting_strategy(&self) -> BettingStrategy {
+        STRATEGIES[self.strategy]
+    }
+
+    /// Plays sessions of hands automatically with the chosen betting strategy.
+    pub fn simulate(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        self.simulation = Some(Simulation::run(&mut rng, self.betting_strategy(), self.bet, self.bankroll, self.rules, self.audit_chips));
+        self.remember_bet();
+    }
+
+    fn remember_bet(&mut self) {
+        let bet = self.bet;
+
+        self.recent_bets.retain(|recent| *recent != bet);
+        self.recent_bets.insert(0, bet);
+        self.recent_bets.truncate(RECENT_BETS);
+    }
+
+    /// Bets one of the recent bets again.
+    pub fn use_recent_bet(&mut self, index: usize) {
+        if let Some(bet) = self.recent_bets.get(index) {
+            self.bet = *bet;
+        }
+    }
+
+    fn selected_number(&mut self) -> Option<&mut u32> {
+        match self.selected() {
+            CalculatorRow::Bet => Some(&mut self.bet),
+            CalculatorRow::Bankroll => Some(&mut self.bankroll),
+            _ => None,
+        }
+    }
+
+    /// Typing a digit appends it to the selected number.
+    pub fn type_digit(&mut self, digit: u32) {
+        if let Some(number) = self.selected_number() {
+            if *number < 10u32.pow(MAX_DIGITS - 1) {
+                *number = *number * 10 + digit;
+            }
+        }
+    }
+
+    /// A click on a chip of the tray puts it on the bet.
+    pub fn add_chip(&mut self, denomination: usize) {
+        self.bet = self.bet.saturating_add(DENOMINATIONS[denomination]).min(10u32.pow(MAX_DIGITS) - 1);
+    }
+
+    /// A right click takes it back, the bet stays at least 1.
+    pub fn remove_chip(&mut self, denomination: usize) {
+        self.bet = self.bet.saturating_sub(DENOMINATIONS[denomination]).max(1);
+    }
+
+    /// The bet as chips, the fewest that make it up
+    pub fn bet_chips(&self) -> Chips {
+        Chips::make_change(self.bet)
+    }
+
+    /// Backspace removes the last digit of the selected number.
+    pub fn erase_digit(&mut self) {
+        if let Some(number) = self.selected_number() {
+            *number /= 10;
+        }
+    }
+
+    pub fn odds(&self) -> HandOdds {
+        probability::best_play_odds(self.rules)
+    }
+
+    /// Result lines: the odds of a hand, expected value and risk of ruin. Family mode counts
+    /// a point for a win and takes one for a loss instead.
+    pub fn results(&self) -> Vec<String> {
+        let odds = self.odds();
+        let odds_line = format!("Best play wins {:.1}%, draws {:.1}% and loses {:.1}% of the hands",
+                                odds.win * 100.0, odds.draw * 100.0, odds.lose * 100.0);
+
+        if self.family_mode {
+            return vec![
+                odds_line,
+                format!("Expected score: {:+.1} points per 100 hands(+1 for a win, -1 for a loss)", odds.expected_value() * 100.0),
+            ];
+        }
+
+        let expected_value = odds.expected_value() * self.bet as f64;
+        let mut lines = vec![
+            odds_line,
+            format!("Expected value: {:+.2} per hand, {:+.0} per 100 hands", expected_value, expected_value * 100.0),
+            // wins pay 1:1, so the payout is the bet again
+            format!("A win pays {}: {}", self.bet, Chips::make_change(self.bet).describe()),
+            format!("Risk of ruin: {:.1}%", probability::risk_of_ruin(&odds, self.bet, self.bankroll) * 100.0),
+        ];
+        if let Some(hands) = probability::expected_hands_to_ruin(&odds, self.bet, self.bankroll) {
+            lines.push(format!("On average the bankroll lasts about {:.0} hands", hands));
+        }
+
+        lines
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_edits_the_selected_number() {
+        let mut calculator = RiskCalculator::default();
+
+        calculator.erase_digit();
+        calculator.erase_digit();
+        calculator.type_digit(2);
+        calculator.type_digit(5);
+        assert_eq!(calculator.bet, 25);
+
+        calculator.select(2);
+        calculator.type_digit(1);
+        assert_eq!((calculator.bet, calculator.bankroll), (25, 1000));
+    }
+
+    #[test]
+    fn numbers_stay_in_range() {
+        let mut calculator = RiskCalculator { bet: 3, ..RiskCalculator::default() };
+
+        calculator.change(-1);
+        assert_eq!(calculator.bet, 1);
+
+        calculator.bet = 9_999_999;
+        calculator.type_digit(9);
+        assert_eq!(calculator.bet, 9_999_999);
+    }
+
+    #[test]
+    fn chips_change_the_bet() {
+        let mut calculator = RiskCalculator { bet: 10, ..RiskCalculator::default() };
+
+        calculator.add_chip(2);
+        calculator.add_chip(0);
+        assert_eq!(calculator.bet, 36);
+        assert_eq!(calculator.bet_chips().counts, [1, 2, 1, 0, 0]);
+
+        calculator.remove_chip(4);
+        assert_eq!(calculator.bet, 1);
+    }
+
+    #[test]
+    fn last_three_bets_are_kept() {
+        let mut calculator = RiskCalculator::default();
+
+        for bet in [10, 25, 10, 100, 5] {
+            calculator.bet = bet;
+            calculator.remember_bet();
+        }
+        assert_eq!(calculator.recent_bets, [5, 100, 10]);
+
+        calculator.use_recent_bet(1);
+        assert_eq!(calculator.bet, 100);
+        calculator.use_recent_bet(RECENT_BETS);
+        assert_eq!(calculator.bet, 100);
+    }
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut calculator = RiskCalculator::default();
+
+        calculator.select(-1);
+        assert_eq!(calculator.selected(), CalculatorRow::Simulate);
+
+        calculator.select(-1);
+        calculator.change(-1);
+        assert_eq!(calculator.betting_strategy(), BettingStrategy::Kelly);
+    }
+
+    #[test]
+    fn family_mode_has_no_bets() {
+        let mut calculator = RiskCalculator { family_mode: true, ..RiskCalculator::default() };
+
+        calculator.select(1);
+        calculator.type_digit(5);
+        assert_eq!(calculator.selected(), CalculatorRow::Handicap);
+        assert_eq!(calculator.bet, DEFAULT_BET);
+
+        let results = calculator.results().join(" ").to_lowercase();
+        assert!(results.contains("points"));
+        assert!(!results.contains("bankroll") && !results.contains("ruin"));
+    }
+}
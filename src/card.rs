@@ -1,3 +1,5 @@
+use crate::card_set::CardSet;
+
 use ggez::{Context, GameResult, graphics};
 use ggez::mint::{Point2, Vector2};
 
@@ -46,6 +48,67 @@ impl Error for CardNameError {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Spades => '♠',
+        }
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rank {
+    Number(u32),
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// "2".."10", "J", "Q", "K" or "A"
+    pub fn short_name(&self) -> String {
+        match self {
+            Rank::Number(n) => n.to_string(),
+            Rank::Jack => String::from("J"),
+            Rank::Queen => String::from("Q"),
+            Rank::King => String::from("K"),
+            Rank::Ace => String::from("A"),
+        }
+    }
+
+    /// Points in blackjack, an ace counting 11
+    pub fn points(&self) -> u32 {
+        match self {
+            Rank::Number(n) => *n,
+            Rank::Jack | Rank::Queen | Rank::King => 10,
+            Rank::Ace => 11,
+        }
+    }
+}
+
+/// What a card is, without anything for drawing it. Cheap to copy around.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardValue {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
 #[derive(Debug, Clone)]
 pub struct Card {
     pub flip_state: CardFlipState,
@@ -53,8 +116,6 @@ pub struct Card {
     pub name: String,
     pub position: Point2<f32>,
     pub animation: FlipAnimation,
-    image_front: Option<graphics::Image>,
-    image_back: Option<graphics::Image>,
     flipped: bool,
 }
 
@@ -67,18 +128,15 @@ impl Card {
             position: Point2 { x: 0.0, y: 0.0 },
             animation: FlipAnimation::new(FLIP_DURATION),
             flipped: false,
-            image_back: None,
-            image_front: None,  
         }
     }
 
-    pub fn load(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let path = format!("\\card_images\\{}.png", self.name);
-
-        self.image_front = Some(graphics::Image::new(ctx, path)?);
-        self.image_back  = Some(graphics::Image::new(ctx, "\\card_images\\card_back.png")?);
-
-        Ok(())
+    /// Puts the card face up at its final position, skipping the deal and flip animations.
+    pub fn place(&mut self, position: Point2<f32>) {
+        self.position = position;
+        self.move_state = CardMoveState::Stopped;
+        self.flip_state = CardFlipState::Front;
+        self.flipped = true;
     }
 
     pub fn update(&mut self, time_delta: f32, translation: Vector2<f32>, dest_point: Point2<f32>) {
@@ -105,19 +163,23 @@ impl Card {
         }
     }
 
-    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
-        if let Some(image) = self.get_visible_image() {
-            let draw_params = graphics::DrawParam::default().
-                dest(self.position).
-                offset(Point2 { x: 0.5, y: 0.5 }).
-                scale(Vector2 {
-                    x: self.animation.scale_x * CARD_SCALE,
-                    y: CARD_SCALE,
-                });
-            graphics::draw(ctx, image, draw_params)?;
-        }
-
-        Ok(())
+    /// The images come from `card_set`, which keeps them for every card.
+    /// `back_src` is the frame of the(possibly animated) card back to show while face down.
+    pub fn draw(&self, ctx: &mut Context, card_set: &CardSet, back_src: graphics::Rect) -> GameResult<()> {
+        let (image, scale, src) = match self.flip_state {
+            CardFlipState::Front => (card_set.front_image(ctx, &self.name)?, card_set.front_scale(), graphics::Rect::one()),
+            CardFlipState::Back  => (card_set.back_image(ctx)?, card_set.back_scale(), back_src),
+        };
+
+        let draw_params = graphics::DrawParam::default().
+            src(src).
+            dest(self.position).
+            offset(Point2 { x: 0.5, y: 0.5 }).
+            scale(Vector2 {
+                x: self.animation.scale_x * scale,
+                y: scale,
+            });
+        graphics::draw(ctx, &image, draw_params)
     }
 
     pub fn get_points(&self) -> Result<u32, CardNameError> {
@@ -140,18 +202,48 @@ impl Card {
         }
     }
     
+    /// Rank from the first part of the name("king" in "king_of_spades").
+    pub fn rank(&self) -> Result<Rank, CardNameError> {
+        match self.name.split('_').next().unwrap_or_default() {
+            "jack" => Ok(Rank::Jack),
+            "queen" => Ok(Rank::Queen),
+            "king" => Ok(Rank::King),
+            "ace" => Ok(Rank::Ace),
+            number => match number.parse() {
+                Ok(n) if (2..=10).contains(&n) => Ok(Rank::Number(n)),
+                _ => Err(CardNameError::new("Invalid card name!")),
+            },
+        }
+    }
+
+    /// Suit from the last part of the name("spades" in "king_of_spades").
+    pub fn suit(&self) -> Result<Suit, CardNameError> {
+        match self.name.rsplit('_').next().unwrap_or_default() {
+            "clubs" => Ok(Suit::Clubs),
+            "diamonds" => Ok(Suit::Diamonds),
+            "hearts" => Ok(Suit::Hearts),
+            "spades" => Ok(Suit::Spades),
+            _ => Err(CardNameError::new("Invalid card name!")),
+        }
+    }
+
+    pub fn value(&self) -> Result<CardValue, CardNameError> {
+        Ok(CardValue { rank: self.rank()?, suit: self.suit()? })
+    }
+
+    /// Rank and suit symbol like "K♦", falls back to the name for unknown cards
+    pub fn short_name(&self) -> String {
+        match (self.rank(), self.suit()) {
+            (Ok(rank), Ok(suit)) => rank.short_name() + suit.symbol().to_string().as_str(),
+            _ => self.name.clone(),
+        }
+    }
+
     pub fn is_an_ace(&self) -> bool {
         let c: char = self.name.chars().next().unwrap();
         
         c == 'a'
     }
-
-    fn get_visible_image(&self) -> Option<&graphics::Image> {
-        match self.flip_state {
-            CardFlipState::Front => self.image_front.as_ref(),
-            CardFlipState::Back  => self.image_back.as_ref(),
-        }
-    }
 }
 
 #[derive(Debug, Clone)]
@@ -423,4 +515,33 @@ mod tests {
     fn all_should_return_52_cards() {
         assert_eq!(all().len(), 52);
     }
+
+    #[test]
+    fn every_card_has_rank_and_suit() {
+        for card in all() {
+            assert!(card.rank().is_ok() && card.suit().is_ok(), "{} should parse", card.name);
+        }
+    }
+
+    #[test]
+    fn short_name_uses_suit_symbol() {
+        assert_eq!(Card::new("king_of_diamonds").short_name(), "K♦");
+        assert_eq!(Card::new("10_of_spades").short_name(), "10♠");
+        assert!(Suit::Hearts.is_red());
+    }
+
+    #[test]
+    fn rank_of_unknown_card_fails() {
+        assert!(Card::new("1_of_clubs").rank().is_err());
+        assert!(Card::new("2_of_something").suit().is_err());
+    }
+
+    #[test]
+    fn values_give_the_same_points() {
+        for card in all() {
+            assert_eq!(card.value().unwrap().rank.points(), card.get_points().unwrap(), "{}", card.name);
+        }
+
+        assert_eq!(Card::new("queen_of_hearts").value().unwrap(), CardValue { rank: Rank::Queen, suit: Suit::Hearts });
+    }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use ggez::graphics::Rect;
+
+/// A look for the back of the cards(and the deck). Animated backs are sprite sheets
+/// with all frames side by side in a single row.
+#[derive(Debug)]
+pub struct CardBackStyle {
+    pub name: &'static str,
+
+    /// Sprite sheet in the resources folder, None uses the back of the active card set
+    pub path: Option<&'static str>,
+
+    /// Width in pixels of a single frame
+    pub frame_width: u32,
+
+    pub frames: u32,
+
+    /// Seconds each frame stays on screen
+    pub frame_duration: f32,
+}
+
+pub const CLASSIC: CardBackStyle = CardBackStyle {
+    name: "classic",
+    path: None,
+    frame_width: 0,
+    frames: 1,
+    frame_duration: 0.0,
+};
+
+pub const STYLES: &[CardBackStyle] = &[
+    CLASSIC,
+    CardBackStyle {
+        name: "shimmer",
+        path: Some("/card_backs/shimmer.png"),
+        frame_width: 165,
+        frames: 8,
+        frame_duration: 0.12,
+    },
+];
+
+pub fn find(name: &str) -> &'static CardBackStyle {
+    STYLES.iter().find(|style| style.name == name).unwrap_or(&STYLES[0])
+}
+
+/// Shared clock for animated backs, so every face-down card and the deck show the same frame.
+#[derive(Debug, Clone)]
+pub struct BackAnimation {
+    frames: u32,
+    frame_duration: f32,
+    frame: u32,
+    elapsed: f32,
+}
+
+impl BackAnimation {
+    pub fn new(style: &CardBackStyle) -> Self {
+        BackAnimation {
+            frames: style.frames.max(1),
+            frame_duration: style.frame_duration,
+            frame: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        if self.frames == 1 || self.frame_duration <= 0.0 {
+            return;
+        }
+
+        self.elapsed += time_delta;
+        while self.elapsed >= self.frame_duration {
+            self.elapsed -= self.frame_duration;
+            self.frame = (self.frame + 1) % self.frames;
+        }
+    }
+
+    /// Part of the sprite sheet to draw(in the 0..1 coordinates ggez uses for `src`)
+    pub fn src(&self) -> Rect {
+        let frame_width = 1.0 / self.frames as f32;
+
+        Rect::new(self.frame as f32 * frame_width, 0.0, frame_width, 1.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_unknown_style_gives_classic() {
+        assert_eq!(find("no such back").name, CLASSIC.name);
+        assert_eq!(find("shimmer").name, "shimmer");
+    }
+
+    #[test]
+    fn static_back_uses_whole_image() {
+        let mut animation = BackAnimation::new(&CLASSIC);
+        animation.update(10.0);
+
+        assert_eq!(animation.src(), Rect::new(0.0, 0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn animated_back_advances_and_wraps() {
+        let style = find("shimmer");
+        let mut animation = BackAnimation::new(style);
+
+        animation.update(style.frame_duration * 1.5);
+        assert_eq!(animation.frame, 1);
+
+        animation.update(style.frame_duration * style.frames as f32);
+        assert_eq!(animation.frame, 1);
+        assert_eq!(animation.src().w, 1.0 / style.frames as f32);
+    }
+}
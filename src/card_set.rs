@@ -0,0 +1,375 @@
+use crate::asset_manager::{self, AssetFile, AssetManager};
+use crate::card;
+use crate::card_back::{self, CardBackStyle};
+use crate::resources;
+
+use ggez::{Context, GameResult, graphics, audio};
+
+use std::cell::{RefCell, RefMut};
+use std::fmt;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub const CARD_SETS_DIR: &str = "cardsets";
+pub const DEFAULT_CARD_SET: &str = "default";
+
+// dimensions of the images in the default set, custom sets are scaled to match them on screen
+const DEFAULT_FRONT_WIDTH: u32 = 500;
+const DEFAULT_FRONT_HEIGHT: u32 = 726;
+const DEFAULT_BACK_WIDTH: u32 = 660;
+const DEFAULT_BACK_HEIGHT: u32 = 865;
+
+/// How far(relative) a custom card's aspect ratio may be from the default one
+const ASPECT_RATIO_TOLERANCE: f32 = 0.15;
+
+const CARD_BACK_NAME: &str = "card_back";
+
+#[derive(Debug, Clone)]
+pub struct CardSetError {
+    details: String,
+}
+
+impl CardSetError {
+    fn new(msg: &str) -> Self {
+        CardSetError {
+            details: msg.to_string()
+        }
+    }
+}
+
+impl fmt::Display for CardSetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.details)
+    }
+}
+
+impl Error for CardSetError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// Where card images come from: the bundled resources or a folder in "cardsets"
+/// holding the 52 cards plus "card_back.png", named like the bundled ones.
+#[derive(Debug, Clone)]
+pub struct CardSet {
+    pub name: String,
+    dir: Option<PathBuf>,
+    back_style: &'static CardBackStyle,
+    front_scale: f32,
+    back_scale: f32,
+    /// How big the cards are drawn, 1 being their usual size
+    size: f32,
+    /// GPU-light mode: the images are at half resolution and drawn twice as big, the backs
+    /// hold still
+    light: bool,
+    /// The images and sounds loaded so far, shared by the boards using this set
+    assets: Rc<RefCell<AssetManager>>,
+}
+
+impl CardSet {
+    pub fn default_set() -> Self {
+        CardSet {
+            name: String::from(DEFAULT_CARD_SET),
+            dir: None,
+            back_style: &card_back::CLASSIC,
+            front_scale: card::CARD_SCALE,
+            back_scale: card::CARD_SCALE,
+            size: 1.0,
+            light: false,
+            assets: Rc::default(),
+        }
+    }
+
+    /// Opens and validates a custom set. Every image must be a PNG, all fronts must have
+    /// the same dimensions and roughly the shape of a playing card.
+    pub fn load(name: &str) -> Result<Self, CardSetError> {
+        if name == DEFAULT_CARD_SET {
+            return Ok(Self::default_set());
+        }
+
+        let dir = Path::new(CARD_SETS_DIR).join(name);
+        if !dir.is_dir() {
+            return Err(CardSetError::new(&format!("Card set \"{}\" was not found", name)));
+        }
+
+        let mut front_size: Option<(u32, u32)> = None;
+        for card in card::all() {
+            let size = read_png_size(&image_file(&dir, &card.name))?;
+
+            match front_size {
+                None => front_size = Some(size),
+                Some(first) if first != size => {
+                    return Err(CardSetError::new(&format!(
+                        "{}.png is {}x{}, but the other cards are {}x{}", card.name, size.0, size.1, first.0, first.1)));
+                },
+                _ => (),
+            }
+        }
+        let (front_width, front_height) = front_size.unwrap();
+        if !card_shaped((front_width, front_height), (DEFAULT_FRONT_WIDTH, DEFAULT_FRONT_HEIGHT)) {
+            return Err(CardSetError::new(&format!(
+                "Cards are {}x{}, which is not the shape of a playing card", front_width, front_height)));
+        }
+
+        // only its width is scaled to the default one, so it has to be the default back's shape
+        let (back_width, back_height) = read_png_size(&image_file(&dir, CARD_BACK_NAME))?;
+        if !card_shaped((back_width, back_height), (DEFAULT_BACK_WIDTH, DEFAULT_BACK_HEIGHT)) {
+            return Err(CardSetError::new(&format!(
+                "{}.png is {}x{}, which is not the shape of the card back", CARD_BACK_NAME, back_width, back_height)));
+        }
+
+        Ok(
+            CardSet {
+                name: name.to_string(),
+                dir: Some(dir),
+                back_style: &card_back::CLASSIC,
+                front_scale: card::CARD_SCALE * DEFAULT_FRONT_WIDTH as f32 / front_width as f32,
+                back_scale: card::CARD_SCALE * DEFAULT_BACK_WIDTH as f32 / back_width as f32,
+                size: 1.0,
+                light: false,
+                assets: Rc::default(),
+            }
+        )
+    }
+
+    /// Loads the set with the given name, using the default set if it's missing or incomplete.
+    pub fn load_or_default(name: &str) -> (Self, Option<CardSetError>) {
+        match Self::load(name) {
+            Ok(card_set) => (card_set, None),
+            Err(e) => (Self::default_set(), Some(e)),
+        }
+    }
+
+    /// Uses the given back instead of the set's own "card_back.png"(unless it's the classic one).
+    pub fn with_back_style(mut self, back_style: &'static CardBackStyle) -> Self {
+        self.back_style = back_style;
+        self
+    }
+
+    /// Draws the cards `size` times their usual size, for the compact table(see layout.rs).
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// GPU-light mode or not, see Config::gpu_light. The images loaded so far are loaded again.
+    pub fn with_light(mut self, light: bool) -> Self {
+        self.light = light;
+        self.assets().set_low_resolution(light);
+        self
+    }
+
+    pub fn light(&self) -> bool {
+        self.light
+    }
+
+    /// The images' scale times the cards' size, and twice that for the half resolution images
+    fn drawn_scale(&self, scale: f32) -> f32 {
+        let resolution = if self.light { asset_manager::LOW_RESOLUTION_FACTOR } else { 1.0 };
+
+        scale * self.size * resolution
+    }
+
+    /// Width and height of a card as drawn
+    pub fn card_dimensions(&self) -> (f32, f32) {
+        (card::CARD_DIMENSION_X * self.size, card::CARD_DIMENSION_Y * self.size)
+    }
+
+    pub fn back_style(&self) -> &'static CardBackStyle {
+        self.back_style
+    }
+
+    pub fn assets(&self) -> RefMut<'_, AssetManager> {
+        self.assets.borrow_mut()
+    }
+
+    /// Where the front of a card is, a file on disk for custom sets or a ggez path.
+    fn front_path(&self, card_name: &str) -> String {
+        match &self.dir {
+            Some(dir) => image_file(dir, card_name).to_string_lossy().into_owned(),
+            None => resources::card_image_path(card_name),
+        }
+    }
+
+    fn back_path(&self) -> String {
+        match (self.back_style.path, &self.dir) {
+            (Some(path), _) => path.to_string(),
+            (None, Some(dir)) => image_file(dir, CARD_BACK_NAME).to_string_lossy().into_owned(),
+            (None, None) => resources::CARD_BACK_PATH.to_string(),
+        }
+    }
+
+    /// Reads a ggez path from the resources, anything else from disk.
+    fn read_image(ctx: &mut Context, path: &str) -> GameResult<Vec<u8>> {
+        if path.starts_with('/') {
+            resources::read(ctx, path)
+        } else {
+            Ok(fs::read(path)?)
+        }
+    }
+
+    fn asset_file(path: &str) -> Option<AssetFile> {
+        if path.starts_with('/') {
+            resources::asset_file(path)
+        } else {
+            Some(AssetFile { key: path.to_string(), files: vec![PathBuf::from(path)] })
+        }
+    }
+
+    /// Starts reading the set's images and the sounds in the background, see AssetManager.
+    pub fn preload(&self) {
+        let paths = card::all().iter().
+            map(|card| self.front_path(&card.name)).
+            chain([self.back_path(), resources::CARD_DEAL_SOUND_PATH.to_string(), resources::CARD_FLIP_SOUND_PATH.to_string(), resources::HEARTBEAT_SOUND_PATH.to_string()]).
+            collect::<Vec<String>>();
+
+        self.assets().preload(paths.iter().filter_map(|path| Self::asset_file(path)).collect());
+    }
+
+    pub fn front_image(&self, ctx: &mut Context, card_name: &str) -> GameResult<graphics::Image> {
+        let path = self.front_path(card_name);
+
+        self.assets().image(ctx, &path, |ctx| Self::read_image(ctx, &path))
+    }
+
+    pub fn back_image(&self, ctx: &mut Context) -> GameResult<graphics::Image> {
+        let path = self.back_path();
+
+        self.assets().image(ctx, &path, |ctx| Self::read_image(ctx, &path))
+    }
+
+    /// Loads the image at `path` again after it changed on disk, see AssetManager::reload_image.
+    pub fn reload_image(&self, ctx: &mut Context, path: &str) -> GameResult<()> {
+        self.assets().reload_image(ctx, path, |ctx| Self::read_image(ctx, path))
+    }
+
+    /// Makes sure both sides of a card are loaded, so drawing it doesn't wait for the disk.
+    pub fn load_card(&self, ctx: &mut Context, card_name: &str) -> GameResult<()> {
+        self.front_image(ctx, card_name)?;
+        self.back_image(ctx)?;
+
+        Ok(())
+    }
+
+    pub fn sound(&self, ctx: &mut Context, path: &str) -> GameResult<audio::Source> {
+        self.assets().sound(ctx, path, |ctx| resources::load_sound(ctx, path))
+    }
+
+    pub fn front_scale(&self) -> f32 {
+        self.drawn_scale(self.front_scale)
+    }
+
+    pub fn back_scale(&self) -> f32 {
+        let scale = match self.back_style.path {
+            Some(_) => card::CARD_SCALE * DEFAULT_BACK_WIDTH as f32 / self.back_style.frame_width as f32,
+            None => self.back_scale,
+        };
+
+        self.drawn_scale(scale)
+    }
+}
+
+/// Names of all sets the player can choose from, the default one first.
+pub fn available() -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(CARD_SETS_DIR) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_CARD_SET.to_string());
+
+    names
+}
+
+fn image_file(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.png", name))
+}
+
+/// Whether an image of `size` is within ASPECT_RATIO_TOLERANCE of the shape of `default`.
+fn card_shaped(size: (u32, u32), default: (u32, u32)) -> bool {
+    let default_ratio = default.0 as f32 / default.1 as f32;
+    let ratio = size.0 as f32 / size.1 as f32;
+
+    (ratio - default_ratio).abs() <= default_ratio * ASPECT_RATIO_TOLERANCE
+}
+
+fn read_png_size(file: &Path) -> Result<(u32, u32), CardSetError> {
+    let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+    let bytes = fs::read(file).
+        map_err(|_| CardSetError::new(&format!("{} is missing", name)))?;
+
+    png_size(&bytes).ok_or_else(|| CardSetError::new(&format!("{} is not a valid PNG image", name)))
+}
+
+/// Reads the width and height from a PNG header without decoding the image.
+fn png_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    if bytes.len() < 24 || &bytes[0..8] != SIGNATURE || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    Some((width, height))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn png_size_reads_header() {
+        assert_eq!(png_size(&png_header(500, 726)), Some((500, 726)));
+    }
+
+    #[test]
+    fn png_size_rejects_other_files() {
+        assert_eq!(png_size(b"GIF89a not a png at all"), None);
+        assert_eq!(png_size(&png_header(0, 726)), None);
+    }
+
+    #[test]
+    fn backs_are_checked_against_the_default_back() {
+        assert!(card_shaped((330, 432), (DEFAULT_BACK_WIDTH, DEFAULT_BACK_HEIGHT)));
+        // a square back would come out shorter than the fronts
+        assert!(!card_shaped((660, 660), (DEFAULT_BACK_WIDTH, DEFAULT_BACK_HEIGHT)));
+        assert!(!card_shaped((660, 1400), (DEFAULT_BACK_WIDTH, DEFAULT_BACK_HEIGHT)));
+    }
+
+    #[test]
+    fn load_missing_set_fails() {
+        assert!(CardSet::load("a set that does not exist").is_err());
+    }
+
+    #[test]
+    fn load_or_default_falls_back() {
+        let (card_set, error) = CardSet::load_or_default("a set that does not exist");
+
+        assert_eq!(card_set.name, DEFAULT_CARD_SET);
+        assert!(error.is_some());
+    }
+}
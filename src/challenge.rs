@@ -0,0 +1,215 @@
+//! Challenge a friend: the session's seed and the one rule that changes which cards come make a
+//! code. A friend who types the code gets the same shoes in the same order(see GameRng::shoe), so
+//! the two play the same hands. Each exports a log of the counted hands to the "challenges"
+//! folder, and a friend's log copied in there is compared hand for hand with one's own. ggez has
+//! no clipboard, so the code is shown on the challenge screen to be copied by hand.
+
+use crate::burn::BurnCard;
+use crate::config;
+use crate::game_engine::Outcome;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const CHALLENGE_DIR: &str = "challenges";
+/// The longest code that can be typed
+pub const CODE_MAX_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Challenge {
+    pub seed: u64,
+    /// The only rule of the config that changes which cards are dealt
+    pub burn_card: BurnCard,
+}
+
+impl Challenge {
+    /// "1F2E3D4C5B6A7988/face down"
+    pub fn code(&self) -> String {
+        format!("{:016X}/{}", self.seed, self.burn_card.name())
+    }
+
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let (seed, burn_card) = code.trim().split_once('/').
+            ok_or_else(|| String::from("a code looks like \"1F2E3D4C5B6A7988/off\""))?;
+        let seed = u64::from_str_radix(seed.trim(), 16).
+            map_err(|_| format!("\"{}\" isn't a seed", seed.trim()))?;
+        let burn_card = BurnCard::from_name(&burn_card.trim().to_lowercase()).
+            ok_or_else(|| format!("\"{}\" isn't a burn card rule", burn_card.trim()))?;
+
+        Ok(Challenge { seed, burn_card })
+    }
+}
+
+/// How a counted hand ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeHand {
+    pub outcome: Outcome,
+    pub player_score: u32,
+    pub dealer_score: u32,
+}
+
+impl ChallengeHand {
+    fn describe(&self) -> String {
+        format!("{} {}-{}", self.outcome.name(), self.player_score, self.dealer_score)
+    }
+}
+
+/// A player's counted hands on a challenge, in the order they were played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeLog {
+    pub player: String,
+    pub challenge: Challenge,
+    pub hands: Vec<ChallengeHand>,
+}
+
+impl ChallengeLog {
+    pub fn new(player: &str, challenge: Challenge) -> Self {
+        ChallengeLog { player: player.to_string(), challenge, hands: Vec::new() }
+    }
+
+    pub fn wins(&self) -> usize {
+        self.hands.iter().filter(|hand| matches!(hand.outcome, Outcome::Win)).count()
+    }
+
+    /// "code = ..." and "player = ..." and then a "hand = <outcome> <player> <dealer>" line a hand
+    pub fn to_file_contents(&self) -> String {
+        let hands: String = self.hands.iter().
+            map(|hand| format!("hand = {} {} {}\n", hand.outcome.name(), hand.player_score, hand.dealer_score)).
+            collect();
+
+        format!("code = {}\nplayer = {}\n{}", self.challenge.code(), self.player, hands)
+    }
+
+    pub fn parse(contents: &str) -> Option<Self> {
+        let mut challenge = None;
+        let mut player = None;
+        let mut hands = Vec::new();
+
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            match key {
+                "code" => challenge = Challenge::parse(value).ok(),
+                "player" => player = Some(value.to_string()),
+                "hand" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    if let [outcome, player_score, dealer_score] = parts[..] {
+                        hands.push(ChallengeHand {
+                            outcome: Outcome::from_name(outcome)?,
+                            player_score: player_score.parse().ok()?,
+                            dealer_score: dealer_score.parse().ok()?,
+                        });
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Some(ChallengeLog { player: player?, challenge: challenge?, hands })
+    }
+
+    /// Each player's log of a challenge has a file of its own.
+    fn file_name(&self) -> String {
+        let player: String = self.player.chars().filter(|c| c.is_alphanumeric()).collect();
+        format!("{:016X}_{}.txt", self.challenge.seed, player)
+    }
+
+    /// Writes the log into `dir`, returning the file.
+    pub fn export(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        fs::write(&path, self.to_file_contents())?;
+
+        Ok(path)
+    }
+}
+
+/// The other players' logs in `dir` with the same code as `mine`.
+pub fn load_friends(dir: &Path, mine: &ChallengeLog) -> Vec<ChallengeLog> {
+    let mut logs: Vec<ChallengeLog> = match fs::read_dir(dir) {
+        Ok(entries) => entries.
+            filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok()).
+            filter_map(|contents| ChallengeLog::parse(&contents)).
+            filter(|log| log.challenge == mine.challenge && log.player != mine.player).
+            collect(),
+        Err(_) => Vec::new(),
+    };
+    logs.sort_by(|a, b| a.player.cmp(&b.player));
+
+    logs
+}
+
+/// The two logs side by side, a line a hand after a line with the wins.
+pub fn compare(mine: &ChallengeLog, theirs: &ChallengeLog) -> Vec<String> {
+    let describe = |log: &ChallengeLog, i: usize| log.hands.get(i).map_or(String::from("-"), ChallengeHand::describe);
+
+    let mut lines = vec![
+        format!("You won {} of {} hands, {} won {} of {}", mine.wins(), mine.hands.len(), theirs.player, theirs.wins(), theirs.hands.len()),
+        format!("{:<6} {:<16} {}", "HAND", "YOU", theirs.player.to_uppercase()),
+    ];
+    for i in 0..mine.hands.len().max(theirs.hands.len()) {
+        lines.push(format!("{:<6} {:<16} {}", i + 1, describe(mine, i), describe(theirs, i)));
+    }
+
+    lines
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(player: &str, outcomes: &[Outcome]) -> ChallengeLog {
+        let mut log = ChallengeLog::new(player, Challenge { seed: 0xBEEF, burn_card: BurnCard::FaceDown });
+        log.hands = outcomes.iter().
+            map(|outcome| ChallengeHand { outcome: outcome.clone(), player_score: 20, dealer_score: 18 }).
+            collect();
+
+        log
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        let challenge = Challenge { seed: u64::MAX - 5, burn_card: BurnCard::FaceDown };
+
+        assert_eq!(Challenge::parse(&challenge.code()), Ok(challenge));
+        assert_eq!(Challenge::parse(" 00000000000000ff / SHOWN "), Ok(Challenge { seed: 255, burn_card: BurnCard::Shown }));
+        assert!(Challenge::parse("00000000000000ff").is_err());
+        assert!(Challenge::parse("seed/off").is_err());
+        assert!(Challenge::parse("ff/sideways").is_err());
+    }
+
+    #[test]
+    fn logs_round_trip() {
+        let log = log("Kris", &[Outcome::Win, Outcome::Lose, Outcome::Draw]);
+
+        assert_eq!(ChallengeLog::parse(&log.to_file_contents()), Some(log));
+        assert_eq!(ChallengeLog::parse("player = Kris\nhand = win 20 18\n"), None);
+    }
+
+    #[test]
+    fn compares_hand_for_hand() {
+        let lines = compare(&log("Kris", &[Outcome::Win, Outcome::Lose]), &log("Ana", &[Outcome::Win]));
+
+        assert_eq!(lines[0], "You won 1 of 2 hands, Ana won 1 of 1");
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].contains("win 20-18") && lines[3].ends_with(" -"));
+    }
+
+    #[test]
+    fn finds_the_friends_on_the_same_code() {
+        let dir = std::env::temp_dir().join(format!("blackjack_challenge_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mine = log("Kris", &[Outcome::Win]);
+        mine.export(&dir).unwrap();
+        log("Ana", &[Outcome::Lose]).export(&dir).unwrap();
+        let mut other_code = log("Bo", &[]);
+        other_code.challenge.seed = 1;
+        other_code.export(&dir).unwrap();
+
+        let friends = load_friends(&dir, &mine);
+        assert_eq!(friends.iter().map(|log| log.player.as_str()).collect::<Vec<&str>>(), vec!["Ana"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+//! Casino chips for the bets of the risk calculator. An amount is always shown as the fewest
+//! chips that make it up, biggest first, the way a dealer pays out, and a tray of one chip of
+//! each denomination builds the bet with clicks.
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+/// Chip values, smallest first
+pub const DENOMINATIONS: [u32; 5] = [1, 5, 25, 100, 500];
+
+pub const CHIP_RADIUS: f32 = 32.0;
+/// Space between the middles of two chips in the tray or two stacks
+pub const CHIP_SPACING: f32 = 90.0;
+/// How much higher each chip in a stack is drawn
+const CHIP_THICKNESS: f32 = 8.0;
+/// Bigger stacks are drawn this high, the count is still right
+const MAX_DRAWN_CHIPS: u32 = 25;
+const EDGE_WIDTH: f32 = 3.0;
+const TOLERANCE: f32 = 0.5;
+
+/// The usual colors: white, red, green, black and purple.
+pub fn chip_color(denomination: usize) -> graphics::Color {
+    match denomination {
+        0 => graphics::Color::from_rgb(235, 235, 235),
+        1 => graphics::Color::from_rgb(200, 30, 30),
+        2 => graphics::Color::from_rgb(30, 140, 60),
+        3 => graphics::Color::from_rgb(25, 25, 25),
+        _ => graphics::Color::from_rgb(120, 50, 160),
+    }
+}
+
+/// How many chips of each denomination, indexed like DENOMINATIONS.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Chips {
+    pub counts: [u32; 5],
+}
+
+impl Chips {
+    /// The fewest chips worth `amount`. Taking the biggest chip that fits is enough for these
+    /// denominations, since each one is a multiple of the one before.
+    pub fn make_change(amount: u32) -> Self {
+        let mut chips = Chips::default();
+        let mut left = amount;
+
+        for (i, denomination) in DENOMINATIONS.iter().enumerate().rev() {
+            chips.counts[i] = left / denomination;
+            left %= denomination;
+        }
+
+        chips
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().zip(DENOMINATIONS.iter()).map(|(count, denomination)| count * denomination).sum()
+    }
+
+    /// "2 x 25 + 3 x 1", biggest first
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = self.counts.iter().
+            zip(DENOMINATIONS.iter()).
+            rev().
+            filter(|(count, _)| **count > 0).
+            map(|(count, denomination)| format!("{} x {}", count, denomination)).
+            collect();
+
+        if parts.is_empty() { "no chips".to_string() } else { parts.join(" + ") }
+    }
+}
+
+/// Middle of a chip in the tray, the first one being at `position`.
+fn tray_chip_position(position: Point2<f32>, denomination: usize) -> Point2<f32> {
+    Point2 { x: position.x + denomination as f32 * CHIP_SPACING, y: position.y }
+}
+
+/// The tray chip under the mouse, as an index in DENOMINATIONS.
+pub fn tray_chip_at(position: Point2<f32>, mouse_position: Point2<f32>) -> Option<usize> {
+    (0..DENOMINATIONS.len()).find(|denomination| {
+        let middle = tray_chip_position(position, *denomination);
+        let (dx, dy) = (mouse_position.x - middle.x, mouse_position.y - middle.y);
+
+        dx * dx + dy * dy <= CHIP_RADIUS * CHIP_RADIUS
+    })
+}
+
+/// One chip of each denomination in a row, the values are written on them by the caller.
+pub fn draw_tray(ctx: &mut Context, position: Point2<f32>) -> GameResult<()> {
+    let mut mesh = graphics::MeshBuilder::new();
+
+    for denomination in 0..DENOMINATIONS.len() {
+        let middle = tray_chip_position(position, denomination);
+        mesh.circle(graphics::DrawMode::fill(), middle, CHIP_RADIUS, TOLERANCE, chip_color(denomination))?;
+        mesh.circle(graphics::DrawMode::stroke(EDGE_WIDTH), middle, CHIP_RADIUS, TOLERANCE, graphics::Color::WHITE)?;
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+/// A stack per denomination, side by side from the bottom middle of the first one at
+/// `position`, each as high as it has chips.
+pub fn draw_stacks(ctx: &mut Context, position: Point2<f32>, chips: &Chips) -> GameResult<()> {
+    if chips.total() == 0 {
+        return Ok(());
+    }
+
+    let mut mesh = graphics::MeshBuilder::new();
+
+    for (denomination, count) in chips.counts.iter().enumerate() {
+        let x = position.x + denomination as f32 * CHIP_SPACING;
+
+        // chips seen from the side, lower ones first so the ones above cover them
+        for chip in 0..(*count).min(MAX_DRAWN_CHIPS) {
+            let middle = Point2 { x, y: position.y - chip as f32 * CHIP_THICKNESS };
+            mesh.ellipse(graphics::DrawMode::fill(), middle, CHIP_RADIUS, CHIP_RADIUS / 3.0, TOLERANCE, chip_color(denomination))?;
+            mesh.ellipse(graphics::DrawMode::stroke(1.0), middle, CHIP_RADIUS, CHIP_RADIUS / 3.0, TOLERANCE, graphics::Color::WHITE)?;
+        }
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_uses_the_fewest_chips() {
+        let chips = Chips::make_change(638);
+
+        assert_eq!(chips.counts, [3, 2, 1, 1, 1]);
+        assert_eq!(chips.total(), 638);
+        assert_eq!(chips.describe(), "1 x 500 + 1 x 100 + 1 x 25 + 2 x 5 + 3 x 1");
+        assert_eq!(Chips::make_change(0).describe(), "no chips");
+    }
+
+    #[test]
+    fn clicks_find_the_chip() {
+        let tray = Point2 { x: 100.0, y: 800.0 };
+
+        assert_eq!(tray_chip_at(tray, Point2 { x: 100.0, y: 800.0 }), Some(0));
+        assert_eq!(tray_chip_at(tray, Point2 { x: 100.0 + 4.0 * CHIP_SPACING + 10.0, y: 790.0 }), Some(4));
+        assert_eq!(tray_chip_at(tray, Point2 { x: 100.0 + CHIP_SPACING / 2.0, y: 800.0 }), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Coach mode: a read-only view of the hand for someone teaching the player, kept up to date
+//! in a file a second window can show(an editor that reloads, a browser or an OBS text
+//! source). Under the cards it says what the best play for the game's rules is and what
+//! hitting and standing are each worth.
+
+use crate::card::Card;
+use crate::probability::{self, BestPlay, Rules};
+use crate::review;
+use crate::scenario::Action;
+use crate::stream_overlay::HandState;
+
+use std::fs;
+use std::io;
+
+pub const COACH_FILE_NAME: &str = "coach.txt";
+
+/// The best play on the player's hand, with the expected results in bets per hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Annotation {
+    /// Best value of the hand
+    pub value: u32,
+    pub recommended: Action,
+    pub hit: f64,
+    pub stand: f64,
+}
+
+impl Annotation {
+    pub fn new(best: &BestPlay, cards: &[Card]) -> Self {
+        let (hard, has_ace) = review::hard_total(cards);
+        let (hit, stand) = best.expected_values(hard, has_ace);
+
+        Annotation {
+            value: probability::best_value(hard, has_ace),
+            recommended: if best.should_draw(hard, has_ace) { Action::Hit } else { Action::Stand },
+            hit,
+            stand,
+        }
+    }
+
+    /// The lines under the hand, in points per 100 hands like the mistake review
+    pub fn describe(&self) -> String {
+        format!("Best play on {}: {}\n  hit: {:+.1} points per 100 hands\n  stand: {:+.1} points per 100 hands\n",
+                self.value,
+                review::action_name(self.recommended),
+                self.hit * 100.0,
+                self.stand * 100.0)
+    }
+}
+
+/// The whole file: the hand as the stream overlay has it, then the annotation when the player
+/// has a decision to make.
+pub fn coach_text(hand: &HandState, annotation: Option<&Annotation>) -> String {
+    let advice = match annotation {
+        Some(annotation) => annotation.describe(),
+        None => String::from("Nothing to decide right now\n"),
+    };
+
+    format!("COACH VIEW\n{}\n{}", hand.to_text(), advice)
+}
+
+/// Keeps the coach's file up to date, touching the disk only when it changed.
+#[derive(Debug, Default)]
+pub struct CoachView {
+    best: Option<(Rules, BestPlay)>,
+    last_written: Option<String>,
+}
+
+impl CoachView {
+    pub fn annotate(&mut self, cards: &[Card], rules: Rules) -> Annotation {
+        let best = match &self.best {
+            Some((known, best)) if *known == rules => best,
+            _ => &self.best.insert((rules, probability::best_play(rules))).1,
+        };
+
+        Annotation::new(best, cards)
+    }
+
+    pub fn write(&mut self, text: String) -> io::Result<()> {
+        if self.last_written.as_ref() == Some(&text) {
+            return Ok(());
+        }
+
+        fs::write(COACH_FILE_NAME, &text)?;
+        self.last_written = Some(text);
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn twenty_stands() {
+        let mut coach = CoachView::default();
+        let annotation = coach.annotate(&cards(&["king_of_clubs", "queen_of_hearts"]), Rules::default());
+
+        assert_eq!(annotation.value, 20);
+        assert_eq!(annotation.recommended, Action::Stand);
+        assert!(annotation.stand > annotation.hit);
+        assert!(annotation.describe().starts_with("Best play on 20: stand\n"));
+    }
+
+    #[test]
+    fn soft_hands_count_the_ace_as_eleven() {
+        let mut coach = CoachView::default();
+        let annotation = coach.annotate(&cards(&["ace_of_clubs", "2_of_hearts"]), Rules::default());
+
+        assert_eq!(annotation.value, 13);
+        assert_eq!(annotation.recommended, Action::Hit);
+    }
+
+    #[test]
+    fn text_has_the_hand_and_the_advice() {
+        let hand = HandState {
+            player: String::from("Kris"),
+            player_score: 12,
+            dealer_score: 0,
+            player_cards: vec![String::from("5 of clubs"), String::from("7 of hearts")],
+            dealer_cards: Vec::new(),
+            status: String::from("player"),
+            wins: 0,
+        };
+        let annotation = CoachView::default().annotate(&cards(&["5_of_clubs", "7_of_hearts"]), Rules::default());
+
+        let text = coach_text(&hand, Some(&annotation));
+        assert!(text.contains("Kris: 5 of clubs, 7 of hearts\n"));
+        assert!(text.contains("Best play on 12: "));
+        assert!(coach_text(&hand, None).ends_with("Nothing to decide right now\n"));
+    }
+}
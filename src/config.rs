@@ -0,0 +1,407 @@
+use crate::burn::{self, BurnCard};
+use crate::card_back;
+use crate::i18n::Language;
+use crate::keymap::{KeyMap, KEY_ACTIONS};
+use crate::migration::{self, SaveFile};
+use crate::speed::{self, GameSpeed};
+use crate::table_talk::{self, Chattiness};
+use crate::window;
+
+use std::fs;
+use std::io;
+
+pub const CONFIG_FILE_NAME: &str = "config.txt";
+
+/// User settings, stored as simple "key = value" lines.
+/// Unknown keys and malformed lines are ignored, missing keys keep their defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Name of the folder in "cardsets" to take card images from, or "default"
+    pub card_set: String,
+
+    /// One of the styles in card_back::STYLES
+    pub card_back: String,
+
+    /// Font file in the "fonts" folder for regular text, or "default"
+    pub font: String,
+
+    /// Code of the UI language, see i18n::LANGUAGES
+    pub language: String,
+
+    /// "http://host[:port]/path" of a leaderboard server, empty keeps it offline
+    pub leaderboard_url: String,
+
+    /// Signs stats.txt so scores edited by hand aren't sent to the leaderboard, see signature.rs
+    pub sign_saves: bool,
+
+    /// Writes the hand to files for stream overlays and enlarges the outcome text
+    pub stream_mode: bool,
+
+    /// Whether stream mode also hides the hint and the power-up counts from viewers
+    pub stream_hide_power_ups: bool,
+
+    /// Nothing that looks like gambling: no bet sizes, no bankroll and neutral wording.
+    /// Only set in the file, so it can't be switched off from the game.
+    pub family_mode: bool,
+
+    /// Animation speed, dealer delays and how long finished hands stay up, see speed.rs
+    pub game_speed: GameSpeed,
+
+    /// Ends the player's turn on reaching 21
+    pub auto_stand_on_21: bool,
+
+    /// Deals the next hand once the outcome has been shown, instead of going back to the menu
+    pub auto_advance: bool,
+
+    /// Asks for a second click before hitting a hard 17 or more
+    pub warn_risky_hits: bool,
+
+    /// Shows the odds of standing and of hitting while the player decides, see what_if.rs
+    pub what_if_odds: bool,
+
+    /// Says after a stand what a hit would have brought, see hindsight.rs
+    pub show_next_card: bool,
+
+    /// Burns the first card after each shuffle, face down or shown for a moment, see burn.rs
+    pub burn_card: BurnCard,
+
+    /// Seconds the player has for each decision before standing, 0 for no limit
+    pub turn_timer_seconds: u32,
+
+    /// Minutes without input before the game saves and goes back to the menu, 0 never does.
+    /// A hand being played is left alone, see idle.rs.
+    pub idle_minutes: u32,
+
+    /// The table mirrored left to right, the deck on the right, see layout.rs
+    pub left_handed: bool,
+
+    /// Slows the dealer's draw down to a heartbeat when a lot rides on it, never in speed mode,
+    /// see sweat.rs
+    pub sweat_mode: bool,
+
+    /// How often the dealer remarks on the table in a speech bubble, see table_talk.rs
+    pub table_talk: Chattiness,
+
+    /// Keeps coach.txt up to date with the hand and the best play, for a second window
+    pub coach_mode: bool,
+
+    /// Only the cards and the scores on the table: no power-ups, wins, hints, placard or name plate
+    pub minimal_hud: bool,
+
+    /// Fewer effects for old integrated GPUs: cards at half resolution, card backs that hold
+    /// still, a plain felt and no gold wash, see CardSet::with_light
+    pub gpu_light: bool,
+
+    /// Checks the chips of every simulated hand in release builds too, debug builds always do
+    pub audit_chips: bool,
+
+    /// A window without a border or title bar
+    pub borderless: bool,
+
+    /// The window's size, position and monitor when the game last closed, see window.rs.
+    /// No position opens it in the system's default place.
+    pub window_size: (u32, u32),
+    pub window_position: Option<(i32, i32)>,
+    pub window_monitor: String,
+
+    /// Keys of the table's actions, a "key_<action>" line each
+    pub key_map: KeyMap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            card_set: String::from("default"),
+            card_back: String::from("classic"),
+            font: String::from("default"),
+            language: String::from("en"),
+            leaderboard_url: String::new(),
+            sign_saves: false,
+            stream_mode: false,
+            stream_hide_power_ups: true,
+            family_mode: false,
+            game_speed: GameSpeed::Normal,
+            auto_stand_on_21: true,
+            auto_advance: false,
+            warn_risky_hits: true,
+            what_if_odds: false,
+            show_next_card: false,
+            burn_card: BurnCard::Off,
+            turn_timer_seconds: 0,
+            idle_minutes: 10,
+            left_handed: false,
+            sweat_mode: false,
+            table_talk: Chattiness::Sometimes,
+            coach_mode: false,
+            minimal_hud: false,
+            gpu_light: false,
+            audit_chips: false,
+            borderless: false,
+            window_size: window::DEFAULT_SIZE,
+            window_position: None,
+            window_monitor: String::new(),
+            key_map: KeyMap::default(),
+        }
+    }
+}
+
+/// How a changed key reaches the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Applies {
+    /// Right away
+    Live,
+    /// The next time the game starts
+    Restart,
+    /// Only through the file, the game doesn't change it
+    FileOnly,
+}
+
+pub fn applies(key: &str) -> Applies {
+    match key {
+        // the leaderboard client is made once, when the game starts
+        "leaderboard_url" => Applies::Restart,
+        // the window is made once too, and its place is written on quitting
+        "borderless" | "window_size" | "window_position" | "window_monitor" => Applies::Restart,
+        // see Config::family_mode
+        "family_mode" => Applies::FileOnly,
+        _ => Applies::Live,
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    value.parse().map_err(|_| String::from("expected true or false"))
+}
+
+/// "expected one of casual, normal, speed"
+fn expected_one_of<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    format!("expected one of {}", names.collect::<Vec<&str>>().join(", "))
+}
+
+impl Config {
+    pub fn parse(contents: &str) -> Self {
+        let mut config = Config::default();
+
+        for (key, value) in contents.lines().filter_map(parse_line) {
+            if value.is_empty() {
+                continue;
+            }
+
+            // a bad value keeps the default
+            let _ = config.set(key, value);
+        }
+
+        config
+    }
+
+    /// Sets `key` to `value` as written in the file. A value that doesn't fit the key, or a
+    /// key that doesn't exist, is an error saying why and changes nothing.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "card_set" => self.card_set = value.to_string(),
+            "card_back" if card_back::STYLES.iter().any(|style| style.name == value) => self.card_back = value.to_string(),
+            "card_back" => return Err(expected_one_of(card_back::STYLES.iter().map(|style| style.name))),
+            "font" => self.font = value.to_string(),
+            "language" if Language::from_code(value).is_some() => self.language = value.to_string(),
+            "language" => return Err(expected_one_of(crate::i18n::LANGUAGES.iter().map(|language| language.code()))),
+            "leaderboard_url" => self.leaderboard_url = value.to_string(),
+            "sign_saves" => self.sign_saves = parse_bool(value)?,
+            "stream_mode" => self.stream_mode = parse_bool(value)?,
+            "stream_hide_power_ups" => self.stream_hide_power_ups = parse_bool(value)?,
+            "family_mode" => self.family_mode = parse_bool(value)?,
+            "game_speed" => self.game_speed = GameSpeed::from_name(value).ok_or_else(|| expected_one_of(speed::SPEEDS.iter().map(|speed| speed.name())))?,
+            "auto_stand_on_21" => self.auto_stand_on_21 = parse_bool(value)?,
+            "auto_advance" => self.auto_advance = parse_bool(value)?,
+            "warn_risky_hits" => self.warn_risky_hits = parse_bool(value)?,
+            "what_if_odds" => self.what_if_odds = parse_bool(value)?,
+            "show_next_card" => self.show_next_card = parse_bool(value)?,
+            "burn_card" => self.burn_card = BurnCard::from_name(value).ok_or_else(|| expected_one_of(burn::BURN_CARDS.iter().map(|burn| burn.name())))?,
+            "turn_timer_seconds" => self.turn_timer_seconds = value.parse().map_err(|_| String::from("expected a whole number of seconds"))?,
+            "idle_minutes" => self.idle_minutes = value.parse().map_err(|_| String::from("expected a whole number of minutes"))?,
+            "left_handed" => self.left_handed = parse_bool(value)?,
+            "sweat_mode" => self.sweat_mode = parse_bool(value)?,
+            "table_talk" => self.table_talk = Chattiness::from_name(value).ok_or_else(|| expected_one_of(table_talk::CHATTINESS.iter().map(|chattiness| chattiness.name())))?,
+            "coach_mode" => self.coach_mode = parse_bool(value)?,
+            "minimal_hud" => self.minimal_hud = parse_bool(value)?,
+            "gpu_light" => self.gpu_light = parse_bool(value)?,
+            "audit_chips" => self.audit_chips = parse_bool(value)?,
+            "borderless" => self.borderless = parse_bool(value)?,
+            "window_size" => self.window_size = window::parse_size(value)?,
+            "window_position" => self.window_position = window::parse_position(value)?,
+            "window_monitor" => self.window_monitor = value.to_string(),
+            key if self.key_map.parse_line(key, value) => (),
+            key if KEY_ACTIONS.iter().any(|action| action.config_key() == key) => {
+                return Err(String::from("expected key names separated by spaces, like \"Space S\""));
+            },
+            key => return Err(format!("there's no \"{}\" setting", key)),
+        }
+
+        Ok(())
+    }
+
+    /// Every key and its value as the file has them, in the file's order.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let entries = [
+            ("card_set", self.card_set.clone()),
+            ("card_back", self.card_back.clone()),
+            ("font", self.font.clone()),
+            ("language", self.language.clone()),
+            ("leaderboard_url", self.leaderboard_url.clone()),
+            ("sign_saves", self.sign_saves.to_string()),
+            ("stream_mode", self.stream_mode.to_string()),
+            ("stream_hide_power_ups", self.stream_hide_power_ups.to_string()),
+            ("family_mode", self.family_mode.to_string()),
+            ("game_speed", self.game_speed.name().to_string()),
+            ("auto_stand_on_21", self.auto_stand_on_21.to_string()),
+            ("auto_advance", self.auto_advance.to_string()),
+            ("warn_risky_hits", self.warn_risky_hits.to_string()),
+            ("what_if_odds", self.what_if_odds.to_string()),
+            ("show_next_card", self.show_next_card.to_string()),
+            ("burn_card", self.burn_card.name().to_string()),
+            ("turn_timer_seconds", self.turn_timer_seconds.to_string()),
+            ("idle_minutes", self.idle_minutes.to_string()),
+            ("left_handed", self.left_handed.to_string()),
+            ("sweat_mode", self.sweat_mode.to_string()),
+            ("table_talk", self.table_talk.name().to_string()),
+            ("coach_mode", self.coach_mode.to_string()),
+            ("minimal_hud", self.minimal_hud.to_string()),
+            ("gpu_light", self.gpu_light.to_string()),
+            ("audit_chips", self.audit_chips.to_string()),
+            ("borderless", self.borderless.to_string()),
+            ("window_size", window::size_name(self.window_size)),
+            ("window_position", window::position_name(self.window_position)),
+            ("window_monitor", self.window_monitor.clone()),
+        ];
+
+        entries.into_iter().
+            map(|(key, value)| (key.to_string(), value)).
+            chain(KEY_ACTIONS.iter().map(|action| (action.config_key(), self.key_map.names(*action)))).
+            collect()
+    }
+
+    pub fn load(file_name: &str) -> Self {
+        match migration::read(file_name, SaveFile::Config) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Config::default(),
+        }
+    }
+
+    pub fn to_file_contents(&self) -> String {
+        let lines: String = self.entries().iter().
+            map(|(key, value)| format!("{} = {}\n", key, value)).
+            collect();
+
+        lines + &SaveFile::Config.version_line()
+    }
+
+    pub fn save(&self, file_name: &str) -> io::Result<()> {
+        fs::write(file_name, self.to_file_contents())
+    }
+}
+
+/// Splits "key = value" into its trimmed parts, skipping empty lines and "#" comments.
+pub fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (key, value) = line.split_once('=')?;
+
+    Some((key.trim(), value.trim()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::KeyAction;
+    use ggez::event::KeyCode;
+
+    #[test]
+    fn parse_line_splits_key_and_value() {
+        assert_eq!(parse_line("  card_set =  my cards "), Some(("card_set", "my cards")));
+        assert_eq!(parse_line("# a comment"), None);
+        assert_eq!(parse_line("no separator"), None);
+    }
+
+    #[test]
+    fn parse_ignores_unknown_keys() {
+        let config = Config::parse("unknown = 5\ncard_set = neon\n");
+
+        assert_eq!(config.card_set, "neon");
+    }
+
+    #[test]
+    fn set_says_what_is_wrong() {
+        let mut config = Config::default();
+
+        assert_eq!(config.set("coach_mode", "yes"), Err(String::from("expected true or false")));
+        assert_eq!(config.set("burn_card", "up"), Err(String::from("expected one of off, face down, shown")));
+        assert!(config.set("language", "xx").unwrap_err().contains("en, bg"));
+        assert!(config.set("key_hit", "Escape").is_err());
+        assert!(config.set("volume", "3").unwrap_err().contains("volume"));
+        assert_eq!(config, Config::default());
+
+        assert_eq!(config.set("turn_timer_seconds", "20"), Ok(()));
+        assert_eq!(config.set("key_stand", "space"), Ok(()));
+        assert_eq!(config.entries().last().unwrap(), &(String::from("key_minimal_hud"), String::from("M")));
+        assert_eq!(config.turn_timer_seconds, 20);
+    }
+
+    #[test]
+    fn parse_keeps_default_for_bad_bool() {
+        assert!(Config::parse("stream_hide_power_ups = maybe\n").stream_hide_power_ups);
+    }
+
+    #[test]
+    fn parse_keeps_default_for_unknown_speed() {
+        assert_eq!(Config::parse("game_speed = ludicrous\n").game_speed, GameSpeed::Normal);
+        assert_eq!(Config::parse("game_speed = casual\n").game_speed, GameSpeed::Casual);
+    }
+
+    #[test]
+    fn parse_empty_gives_default() {
+        assert_eq!(Config::parse(""), Config::default());
+    }
+
+    #[test]
+    fn file_contents_round_trip() {
+        let mut key_map = KeyMap::default();
+        key_map.bind(KeyAction::Hit, &[KeyCode::D, KeyCode::Space]);
+
+        let config = Config {
+            card_set: String::from("neon"),
+            card_back: String::from("shimmer"),
+            font: String::from("Noto Sans.ttf"),
+            language: String::from("bg"),
+            leaderboard_url: String::from("http://localhost:8000/blackjack"),
+            sign_saves: true,
+            stream_mode: true,
+            stream_hide_power_ups: false,
+            family_mode: true,
+            game_speed: GameSpeed::Speed,
+            auto_stand_on_21: false,
+            auto_advance: true,
+            warn_risky_hits: false,
+            what_if_odds: true,
+            show_next_card: true,
+            burn_card: BurnCard::Shown,
+            turn_timer_seconds: 15,
+            idle_minutes: 0,
+            left_handed: true,
+            sweat_mode: true,
+            table_talk: Chattiness::Often,
+            coach_mode: true,
+            minimal_hud: true,
+            gpu_light: true,
+            audit_chips: true,
+            borderless: true,
+            window_size: (1280, 720),
+            window_position: Some((-1800, 40)),
+            window_monitor: String::from("HDMI-1"),
+            key_map,
+        };
+
+        assert_eq!(Config::parse(&config.to_file_contents()), config);
+    }
+}
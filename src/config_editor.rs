@@ -0,0 +1,204 @@
+//! The advanced settings: every key of config.txt on one screen, edited as the file has it.
+//! A value is checked before it's taken(see Config::set), most apply right away and the rest
+//! say when they will. The file is also watched, so editing it by hand while the game runs
+//! works the same.
+
+use crate::config::{self, Applies, Config};
+
+use std::fs;
+use std::time::SystemTime;
+
+/// How often the file is looked at for changes
+pub const SECONDS_BETWEEN_CHECKS: f32 = 1.0;
+
+/// The row picked and the value being typed into it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigEditor {
+    row: usize,
+    /// The new value, while one is typed
+    pub typed: Option<String>,
+    /// Why the last value wasn't taken
+    pub error: Option<String>,
+}
+
+impl ConfigEditor {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn select(&mut self, step: i32, rows: usize) {
+        if self.typed.is_none() && rows > 0 {
+            self.row = (self.row as i32 + step).rem_euclid(rows as i32) as usize;
+        }
+    }
+
+    /// Starts typing over the value of the row picked, with `value` to change.
+    pub fn edit(&mut self, value: &str) {
+        self.typed = Some(value.to_string());
+        self.error = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.typed = None;
+    }
+
+    pub fn type_char(&mut self, character: char) {
+        if let Some(typed) = &mut self.typed {
+            if !character.is_control() {
+                typed.push(character);
+            }
+        }
+    }
+
+    pub fn erase_char(&mut self) {
+        if let Some(typed) = &mut self.typed {
+            typed.pop();
+        }
+    }
+
+    /// Sets the key of the row picked to what was typed. The key on success, so the game can
+    /// apply it.
+    pub fn submit(&mut self, config: &mut Config) -> Option<String> {
+        let typed = self.typed.take()?;
+        let (key, _) = config.entries().into_iter().nth(self.row)?;
+
+        if config::applies(&key) == Applies::FileOnly {
+            self.error = Some(format!("{} can only be changed in {}", key, config::CONFIG_FILE_NAME));
+            return None;
+        }
+
+        match config.set(&key, typed.trim()) {
+            Ok(()) => {
+                self.error = None;
+                Some(key)
+            },
+            Err(e) => {
+                self.error = Some(format!("{}: {}", key, e));
+                self.typed = Some(typed);
+                None
+            },
+        }
+    }
+}
+
+/// "  (after a restart)" for a key that doesn't apply right away
+pub fn applies_note(key: &str) -> &'static str {
+    match config::applies(key) {
+        Applies::Live => "",
+        Applies::Restart => "  (after a restart)",
+        Applies::FileOnly => "  (config.txt only)",
+    }
+}
+
+/// The keys whose values differ between two configs.
+pub fn changed_keys(old: &Config, new: &Config) -> Vec<String> {
+    old.entries().into_iter().
+        zip(new.entries()).
+        filter(|(old, new)| old.1 != new.1).
+        map(|(old, _)| old.0).
+        collect()
+}
+
+/// Notices the config file changing on disk, the game's own saves included.
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    file_name: String,
+    modified: Option<SystemTime>,
+    time_till_check: f32,
+}
+
+impl ConfigWatcher {
+    pub fn new(file_name: &str) -> Self {
+        ConfigWatcher {
+            file_name: file_name.to_string(),
+            modified: modified(file_name),
+            time_till_check: SECONDS_BETWEEN_CHECKS,
+        }
+    }
+
+    /// True when the file was written since the last check.
+    pub fn changed(&mut self, time_delta: f32) -> bool {
+        self.time_till_check -= time_delta;
+        if self.time_till_check > 0.0 {
+            return false;
+        }
+        self.time_till_check = SECONDS_BETWEEN_CHECKS;
+
+        let modified = modified(&self.file_name);
+        if modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+        true
+    }
+}
+
+fn modified(file_name: &str) -> Option<SystemTime> {
+    fs::metadata(file_name).and_then(|metadata| metadata.modified()).ok()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_of(config: &Config, key: &str) -> usize {
+        config.entries().iter().position(|(name, _)| name == key).unwrap()
+    }
+
+    #[test]
+    fn bad_values_are_kept_to_fix() {
+        let mut config = Config::default();
+        let mut editor = ConfigEditor::default();
+        editor.select(row_of(&config, "game_speed") as i32, config.entries().len());
+
+        editor.edit("ludicrous");
+        assert_eq!(editor.submit(&mut config), None);
+        assert!(editor.error.as_ref().unwrap().contains("expected one of casual, normal, speed"));
+        assert_eq!(editor.typed.as_deref(), Some("ludicrous"));
+
+        editor.typed = Some(String::from("speed "));
+        assert_eq!(editor.submit(&mut config).as_deref(), Some("game_speed"));
+        assert_eq!(config.game_speed, crate::speed::GameSpeed::Speed);
+        assert_eq!(editor.error, None);
+    }
+
+    #[test]
+    fn family_mode_stays_in_the_file() {
+        let mut config = Config::default();
+        let mut editor = ConfigEditor::default();
+        editor.select(row_of(&config, "family_mode") as i32, config.entries().len());
+
+        editor.edit("true");
+        assert_eq!(editor.submit(&mut config), None);
+        assert!(!config.family_mode);
+        assert_eq!(applies_note("family_mode"), "  (config.txt only)");
+    }
+
+    #[test]
+    fn changes_are_listed_by_key() {
+        let old = Config::default();
+        let new = Config { coach_mode: true, turn_timer_seconds: 10, ..Config::default() };
+
+        assert_eq!(changed_keys(&old, &new), ["turn_timer_seconds", "coach_mode"]);
+    }
+
+    #[test]
+    fn watcher_sees_a_written_file() {
+        let file_name = std::env::temp_dir().join(format!("blackjack_config_watch_{}.txt", std::process::id()));
+        let file_name = file_name.to_str().unwrap();
+        let _ = fs::remove_file(file_name);
+
+        let mut watcher = ConfigWatcher::new(file_name);
+        assert!(!watcher.changed(SECONDS_BETWEEN_CHECKS));
+
+        fs::write(file_name, "coach_mode = true\n").unwrap();
+        // not before the next check
+        assert!(!watcher.changed(0.0));
+        assert!(watcher.changed(SECONDS_BETWEEN_CHECKS));
+        assert!(!watcher.changed(SECONDS_BETWEEN_CHECKS));
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+//! What the ABOUT screen shows: the version, where the bundled assets come from and the licenses.
+//! Every file in the resources folder has to be covered by one of the credits(a test checks it),
+//! so new art, sounds or fonts can't be added without saying where they're from.
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// From the "license" key in Cargo.toml, empty while none is set
+const LICENSE: &str = env!("CARGO_PKG_LICENSE");
+
+/// Not known yet, to be filled in by whoever added the asset
+const NOT_RECORDED: &str = "not recorded";
+
+pub struct Credit {
+    pub what: &'static str,
+    /// Files or folders in the resources folder this covers
+    pub paths: &'static [&'static str],
+    pub source: &'static str,
+    pub license: &'static str,
+}
+
+pub const ASSET_CREDITS: &[Credit] = &[
+    Credit {
+        what: "DejaVu Serif font",
+        paths: &["font/DejaVuSerif.ttf"],
+        source: "DejaVu fonts project",
+        license: "Bitstream Vera license, DejaVu changes in the public domain",
+    },
+    Credit { what: "Menu font", paths: &["font/FancyMenuFont.ttf"], source: NOT_RECORDED, license: NOT_RECORDED },
+    Credit { what: "Card images", paths: &["card_images/"], source: NOT_RECORDED, license: NOT_RECORDED },
+    Credit { what: "Shimmer card back", paths: &["card_backs/"], source: NOT_RECORDED, license: NOT_RECORDED },
+    Credit { what: "Card sounds", paths: &["sfx/"], source: NOT_RECORDED, license: NOT_RECORDED },
+    Credit { what: "Window icon", paths: &["icons/"], source: NOT_RECORDED, license: NOT_RECORDED },
+];
+
+pub const LIBRARY_CREDITS: &[(&str, &str)] = &[
+    ("ggez", "MIT"),
+    ("rand", "MIT or Apache-2.0"),
+    ("nalgebra", "Apache-2.0"),
+];
+
+pub fn game_license() -> &'static str {
+    if LICENSE.is_empty() { "not chosen yet" } else { LICENSE }
+}
+
+/// Lines for the ABOUT screen
+pub fn lines() -> Vec<String> {
+    let mut lines = vec![
+        format!("Blackjack {}", VERSION),
+        format!("Game license: {}", game_license()),
+        String::new(),
+        String::from("Assets"),
+    ];
+    for credit in ASSET_CREDITS {
+        lines.push(format!("   {}: source {}, license {}", credit.what, credit.source, credit.license));
+    }
+
+    lines.push(String::new());
+    lines.push(String::from("Made with"));
+    for (library, license) in LIBRARY_CREDITS {
+        lines.push(format!("   {} ({})", library, license));
+    }
+
+    lines
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn files(dir: &Path, root: &Path, found: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files(&path, root, found);
+            } else {
+                let relative = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+                found.push(relative);
+            }
+        }
+    }
+
+    #[test]
+    fn every_resource_is_credited() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources");
+        let mut found = Vec::new();
+        files(&root, &root, &mut found);
+
+        for file in found {
+            let credited = ASSET_CREDITS.iter().any(|credit| credit.paths.iter().any(|path| file.starts_with(path)));
+            assert!(credited, "{} has no entry in ASSET_CREDITS", file);
+        }
+    }
+
+    #[test]
+    fn version_comes_first() {
+        assert_eq!(lines()[0], format!("Blackjack {}", VERSION));
+    }
+}
@@ -0,0 +1,414 @@
+//! Counting drill: cards are dealt face up one after another from a shoe and at random points
+//! the player is asked for the running Hi-Lo count(2 to 6 count +1, 7 to 9 count 0, tens and
+//! aces count -1). A side panel can show what's left in the shoe, rank by rank or by Hi-Lo
+//! group, to check a count against. With the burn card on(see burn.rs) each shoe starts by
+//! burning a card, one shown face up is part of the count.
+
+use crate::burn::BurnCard;
+use crate::card::{self, Card};
+use crate::practice;
+
+use rand::{Rng, seq::SliceRandom};
+
+/// Times the player is asked for the count in a round
+pub const STOPS_PER_ROUND: u32 = 5;
+pub const MAX_DECKS: u32 = 8;
+/// How long the right count stays on screen
+pub const SECONDS_OF_FEEDBACK: f32 = 1.5;
+/// Cards dealt before each stop, picked at random in this range
+const CARDS_BETWEEN_STOPS: std::ops::RangeInclusive<usize> = 4..=15;
+/// Typed counts stop growing at this many characters(sign included)
+const MAX_TYPED: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrillSpeed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+pub const SPEEDS: &[DrillSpeed] = &[DrillSpeed::Slow, DrillSpeed::Normal, DrillSpeed::Fast];
+
+impl DrillSpeed {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DrillSpeed::Slow => "slow",
+            DrillSpeed::Normal => "normal",
+            DrillSpeed::Fast => "fast",
+        }
+    }
+
+    /// How long each card stays up
+    pub fn seconds_per_card(&self) -> f32 {
+        match self {
+            DrillSpeed::Slow => 1.5,
+            DrillSpeed::Normal => 0.9,
+            DrillSpeed::Fast => 0.45,
+        }
+    }
+}
+
+/// How the shoe panel counts what's left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShoeView {
+    /// Every rank
+    Exact,
+    /// The three Hi-Lo groups
+    Bucketed,
+}
+
+impl ShoeView {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShoeView::Exact => "by rank",
+            ShoeView::Bucketed => "by Hi-Lo group",
+        }
+    }
+}
+
+/// Difficulty of the drill, changed on the trainer screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrillSettings {
+    pub speed: DrillSpeed,
+    pub decks: u32,
+    pub shoe_view: ShoeView,
+    /// Whether the shoe panel is open, it's folded to the number of cards left otherwise
+    pub shoe_panel_open: bool,
+}
+
+impl Default for DrillSettings {
+    fn default() -> Self {
+        DrillSettings {
+            speed: DrillSpeed::Normal,
+            decks: 1,
+            shoe_view: ShoeView::Exact,
+            shoe_panel_open: false,
+        }
+    }
+}
+
+impl DrillSettings {
+    pub fn change_speed(&mut self, step: i32) {
+        let current = SPEEDS.iter().position(|speed| *speed == self.speed).unwrap_or(0) as i32;
+
+        self.speed = SPEEDS[(current + step).rem_euclid(SPEEDS.len() as i32) as usize];
+    }
+
+    pub fn change_decks(&mut self, step: i32) {
+        self.decks = (self.decks as i32 + step).clamp(1, MAX_DECKS as i32) as u32;
+    }
+
+    pub fn change_shoe_view(&mut self) {
+        self.shoe_view = match self.shoe_view {
+            ShoeView::Exact => ShoeView::Bucketed,
+            ShoeView::Bucketed => ShoeView::Exact,
+        };
+    }
+}
+
+/// Hi-Lo value of a card
+pub fn hi_lo_value(card: &Card) -> i32 {
+    match card.get_points() {
+        Ok(2..=6) => 1,
+        Ok(7..=9) => 0,
+        _ => -1,
+    }
+}
+
+/// A round of the drill.
+#[derive(Debug, Clone)]
+pub struct Drill {
+    pub settings: DrillSettings,
+    shoe: Vec<Card>,
+    /// The card face up right now, none while asking
+    pub shown: Option<Card>,
+    /// Whether the card shown is the burn card
+    pub burned: bool,
+    pub cards_dealt: u32,
+    pub running_count: i32,
+    time_till_next_card: f32,
+    cards_till_stop: usize,
+    /// Whether the player is being asked for the count
+    pub asking: bool,
+    pub typed: String,
+    /// Whether the last answer was right, shown for a moment before dealing goes on
+    pub feedback: Option<bool>,
+    feedback_time: f32,
+    pub stops: u32,
+    pub correct: u32,
+}
+
+impl Drill {
+    pub fn new<R: Rng>(rng: &mut R, settings: DrillSettings) -> Self {
+        let mut shoe: Vec<Card> = (0..settings.decks).flat_map(|_| card::all()).collect();
+        shoe.shuffle(rng);
+
+        Drill {
+            settings,
+            shoe,
+            shown: None,
+            burned: false,
+            cards_dealt: 0,
+            running_count: 0,
+            time_till_next_card: 0.0,
+            cards_till_stop: rng.gen_range(CARDS_BETWEEN_STOPS),
+            asking: false,
+            typed: String::new(),
+            feedback: None,
+            feedback_time: 0.0,
+            stops: 0,
+            correct: 0,
+        }
+    }
+
+    /// Burns the top card of the shoe. A card burned face up is shown first and counted like
+    /// the rest, one burned face down is gone without a trace.
+    pub fn burn(&mut self, burn: BurnCard) {
+        if !burn.burns() {
+            return;
+        }
+
+        if let Some(card) = self.shoe.pop() {
+            if burn == BurnCard::Shown {
+                self.running_count += hi_lo_value(&card);
+                self.shown = Some(card);
+                self.burned = true;
+                self.time_till_next_card = self.settings.speed.seconds_per_card();
+            }
+        }
+    }
+
+    /// A round ends after the last stop, or early when the shoe runs out.
+    pub fn finished(&self) -> bool {
+        !self.asking && self.feedback.is_none() && (self.stops >= STOPS_PER_ROUND || (self.stops > 0 && self.shoe.is_empty()))
+    }
+
+    /// Cards not dealt yet. A new round shuffles a full shoe.
+    pub fn cards_left(&self) -> usize {
+        self.shoe.len()
+    }
+
+    /// What's left in the shoe, a line per rank or per Hi-Lo group.
+    pub fn shoe_lines(&self, view: ShoeView) -> Vec<String> {
+        match view {
+            ShoeView::Exact => practice::composition(&self.shoe).into_iter().
+                map(|(rank, left)| format!("{}: {}", rank, left)).
+                collect(),
+            ShoeView::Bucketed => [("2-6", 1), ("7-9", 0), ("10-A", -1)].iter().
+                map(|(group, value)| {
+                    let left = self.shoe.iter().filter(|card| hi_lo_value(card) == *value).count();
+                    format!("{} ({:+}): {}", group, value, left)
+                }).
+                collect(),
+        }
+    }
+
+    fn waiting_for_answer(&self) -> bool {
+        self.asking && self.feedback.is_none()
+    }
+
+    /// A typed character of the count: digits and a leading minus.
+    pub fn type_char(&mut self, character: char) {
+        if !self.waiting_for_answer() || self.typed.len() >= MAX_TYPED {
+            return;
+        }
+
+        if character.is_ascii_digit() || (character == '-' && self.typed.is_empty()) {
+            self.typed.push(character);
+        }
+    }
+
+    pub fn erase_char(&mut self) {
+        self.typed.pop();
+    }
+
+    /// Enter checks the typed count.
+    pub fn submit(&mut self) {
+        if !self.waiting_for_answer() {
+            return;
+        }
+
+        if let Ok(count) = self.typed.parse::<i32>() {
+            let right = count == self.running_count;
+
+            self.stops += 1;
+            if right {
+                self.correct += 1;
+            }
+            self.feedback = Some(right);
+            self.feedback_time = SECONDS_OF_FEEDBACK;
+        }
+    }
+
+    /// Deals the cards as time goes by. True on the update that ends the round.
+    pub fn update<R: Rng>(&mut self, rng: &mut R, time_delta: f32) -> bool {
+        if self.finished() {
+            return false;
+        }
+
+        if self.feedback.is_some() {
+            self.feedback_time -= time_delta;
+            if self.feedback_time <= 0.0 {
+                self.feedback = None;
+                self.asking = false;
+                self.typed.clear();
+                self.cards_till_stop = rng.gen_range(CARDS_BETWEEN_STOPS);
+
+                return self.finished();
+            }
+        } else if !self.asking {
+            self.time_till_next_card -= time_delta;
+            if self.time_till_next_card <= 0.0 {
+                self.deal();
+            }
+        }
+
+        false
+    }
+
+    fn deal(&mut self) {
+        if self.cards_till_stop == 0 || self.shoe.is_empty() {
+            self.shown = None;
+            self.asking = true;
+            return;
+        }
+
+        self.burned = false;
+        if let Some(card) = self.shoe.pop() {
+            self.running_count += hi_lo_value(&card);
+            self.cards_dealt += 1;
+            self.shown = Some(card);
+        }
+        self.cards_till_stop -= 1;
+        self.time_till_next_card = self.settings.speed.seconds_per_card();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn deal_till_asked(drill: &mut Drill, rng: &mut StdRng) {
+        while !drill.asking {
+            drill.update(rng, drill.settings.speed.seconds_per_card());
+        }
+    }
+
+    #[test]
+    fn hi_lo_values() {
+        assert_eq!(hi_lo_value(&Card::new("2_of_hearts")), 1);
+        assert_eq!(hi_lo_value(&Card::new("6_of_clubs")), 1);
+        assert_eq!(hi_lo_value(&Card::new("8_of_clubs")), 0);
+        assert_eq!(hi_lo_value(&Card::new("queen_of_spades")), -1);
+        assert_eq!(hi_lo_value(&Card::new("ace_of_spades")), -1);
+    }
+
+    #[test]
+    fn whole_shoe_counts_to_zero() {
+        let shoe: Vec<Card> = (0..3).flat_map(|_| card::all()).collect();
+
+        assert_eq!(shoe.iter().map(hi_lo_value).sum::<i32>(), 0);
+    }
+
+    #[test]
+    fn typed_count_is_checked() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut drill = Drill::new(&mut rng, DrillSettings::default());
+
+        deal_till_asked(&mut drill, &mut rng);
+        assert!(drill.cards_dealt >= *CARDS_BETWEEN_STOPS.start() as u32);
+
+        for character in drill.running_count.to_string().chars() {
+            drill.type_char(character);
+        }
+        drill.submit();
+
+        assert_eq!(drill.feedback, Some(true));
+        assert_eq!((drill.stops, drill.correct), (1, 1));
+    }
+
+    #[test]
+    fn minus_only_leads() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut drill = Drill::new(&mut rng, DrillSettings::default());
+        deal_till_asked(&mut drill, &mut rng);
+
+        for character in "-3-x".chars() {
+            drill.type_char(character);
+        }
+
+        assert_eq!(drill.typed, "-3");
+    }
+
+    #[test]
+    fn round_ends_after_the_last_stop() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut drill = Drill::new(&mut rng, DrillSettings { speed: DrillSpeed::Fast, decks: 2, ..DrillSettings::default() });
+        let mut ended = 0;
+
+        while !drill.finished() {
+            deal_till_asked(&mut drill, &mut rng);
+            drill.type_char('0');
+            drill.submit();
+            if drill.update(&mut rng, SECONDS_OF_FEEDBACK + 0.1) {
+                ended += 1;
+            }
+        }
+
+        assert_eq!(drill.stops, STOPS_PER_ROUND);
+        assert_eq!(ended, 1);
+        assert!(!drill.update(&mut rng, 1.0));
+    }
+
+    #[test]
+    fn shoe_panel_follows_the_deal() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut drill = Drill::new(&mut rng, DrillSettings { decks: 2, ..DrillSettings::default() });
+        assert_eq!(drill.shoe_lines(ShoeView::Bucketed), ["2-6 (+1): 40", "7-9 (+0): 24", "10-A (-1): 40"]);
+        assert_eq!(drill.shoe_lines(ShoeView::Exact)[0], "A: 8");
+
+        deal_till_asked(&mut drill, &mut rng);
+        assert_eq!(drill.cards_left(), 104 - drill.cards_dealt as usize);
+
+        // the groups left are what the running count says
+        let left = |line: &String| line.rsplit(' ').next().unwrap().parse::<i32>().unwrap();
+        let lines = drill.shoe_lines(ShoeView::Bucketed);
+        assert_eq!(40 - left(&lines[0]) - (40 - left(&lines[2])), drill.running_count);
+    }
+
+    #[test]
+    fn only_a_shown_burn_card_counts() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut drill = Drill::new(&mut rng, DrillSettings::default());
+        drill.burn(BurnCard::FaceDown);
+        assert_eq!(drill.cards_left(), 51);
+        assert_eq!((drill.shown.is_some(), drill.running_count), (false, 0));
+
+        let mut drill = Drill::new(&mut StdRng::seed_from_u64(4), DrillSettings::default());
+        drill.burn(BurnCard::Shown);
+        let burned = drill.shown.clone().unwrap();
+        assert!(drill.burned);
+        assert_eq!(drill.running_count, hi_lo_value(&burned));
+        assert_eq!(drill.cards_dealt, 0);
+
+        // the burn card stays up for a card's time, then dealing goes on
+        drill.update(&mut rng, drill.settings.speed.seconds_per_card());
+        assert!(!drill.burned);
+        assert_eq!(drill.cards_left(), 50);
+    }
+
+    #[test]
+    fn settings_stay_in_range() {
+        let mut settings = DrillSettings::default();
+
+        settings.change_decks(-1);
+        assert_eq!(settings.decks, 1);
+        settings.change_decks(20);
+        assert_eq!(settings.decks, MAX_DECKS);
+
+        settings.change_speed(-2);
+        assert_eq!(settings.speed, DrillSpeed::Fast);
+    }
+}
@@ -0,0 +1,104 @@
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+const STRIPE_SPACING: f32 = 40.0;
+const STRIPE_WIDTH: f32 = 2.0;
+const DOT_SPACING: f32 = 60.0;
+const DOT_RADIUS: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeltPattern {
+    Plain,
+    Pinstripe,
+    Dots,
+}
+
+/// Color and pattern of the table surface.
+#[derive(Debug)]
+pub struct FeltStyle {
+    pub name: &'static str,
+    pub color: (u8, u8, u8),
+    pub pattern: FeltPattern,
+}
+
+pub const STYLES: &[FeltStyle] = &[
+    FeltStyle { name: "casino green", color: (21, 50, 30), pattern: FeltPattern::Plain },
+    FeltStyle { name: "green pinstripe", color: (21, 50, 30), pattern: FeltPattern::Pinstripe },
+    FeltStyle { name: "royal blue", color: (18, 36, 78), pattern: FeltPattern::Plain },
+    FeltStyle { name: "blue dots", color: (18, 36, 78), pattern: FeltPattern::Dots },
+    FeltStyle { name: "burgundy", color: (72, 16, 26), pattern: FeltPattern::Plain },
+    FeltStyle { name: "charcoal", color: (38, 38, 42), pattern: FeltPattern::Pinstripe },
+];
+
+/// Felts that are only earned from missions
+pub const REWARD_STYLES: &[FeltStyle] = &[
+    FeltStyle { name: "gold pinstripe", color: (74, 58, 16), pattern: FeltPattern::Pinstripe },
+    FeltStyle { name: "midnight dots", color: (12, 16, 34), pattern: FeltPattern::Dots },
+];
+
+pub fn find(name: &str) -> &'static FeltStyle {
+    STYLES.iter().chain(REWARD_STYLES).find(|style| style.name == name).unwrap_or(&STYLES[0])
+}
+
+impl FeltStyle {
+    pub fn color(&self) -> graphics::Color {
+        graphics::Color::from_rgb(self.color.0, self.color.1, self.color.2)
+    }
+
+    /// The pattern is drawn a bit lighter than the felt itself
+    fn pattern_color(&self) -> graphics::Color {
+        let lighten = |c: u8| c.saturating_add(18);
+
+        graphics::Color::from_rgb(lighten(self.color.0), lighten(self.color.1), lighten(self.color.2))
+    }
+
+    /// Clears the screen with the felt color and draws the pattern over it.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, self.color());
+
+        let graphics::Rect { w: width, h: height, .. } = graphics::screen_coordinates(ctx);
+        let color = self.pattern_color();
+        let mut mesh = graphics::MeshBuilder::new();
+
+        match self.pattern {
+            FeltPattern::Plain => return Ok(()),
+            FeltPattern::Pinstripe => {
+                let mut x = 0.0;
+                while x < width {
+                    mesh.line(&[Point2 { x, y: 0.0 }, Point2 { x, y: height }], STRIPE_WIDTH, color)?;
+                    x += STRIPE_SPACING;
+                }
+            },
+            FeltPattern::Dots => {
+                let mut y = DOT_SPACING / 2.0;
+                while y < height {
+                    let mut x = DOT_SPACING / 2.0;
+                    while x < width {
+                        mesh.circle(graphics::DrawMode::fill(), Point2 { x, y }, DOT_RADIUS, 0.5, color)?;
+                        x += DOT_SPACING;
+                    }
+                    y += DOT_SPACING;
+                }
+            },
+        }
+
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_unknown_felt_gives_casino_green() {
+        assert_eq!(find("no such felt").name, "casino green");
+        assert_eq!(find("burgundy").pattern, FeltPattern::Plain);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::resources;
+
+use ggez::{Context, GameResult, graphics};
+
+use std::fs;
+use std::path::Path;
+
+pub const FONTS_DIR: &str = "fonts";
+pub const DEFAULT_FONT: &str = "default";
+
+/// Characters drawn with the bundled font even when a user font is active,
+/// since plenty of fonts don't have the card suits.
+const FALLBACK_CHARS: &[char] = &['♠', '♥', '♦', '♣', '→'];
+
+/// Loads every font once(instead of on every frame) and picks which one draws what.
+pub struct FontManager {
+    /// Bundled font, also the glyph fallback
+    pub default: graphics::Font,
+    pub menu: graphics::Font,
+
+    /// Replaces the default font for regular text when set
+    user: Option<graphics::Font>,
+    pub user_font_name: String,
+}
+
+impl FontManager {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        Ok(
+            FontManager {
+                default: resources::load_font(ctx, resources::DEFAULT_FONT_PATH)?,
+                menu: resources::load_font(ctx, resources::MENU_FONT_PATH)?,
+                user: None,
+                user_font_name: String::from(DEFAULT_FONT),
+            }
+        )
+    }
+
+    /// Loads a changed bundled font again(hot reload), other paths are left alone.
+    pub fn reload(&mut self, ctx: &mut Context, path: &str) -> GameResult<()> {
+        if path == resources::DEFAULT_FONT_PATH {
+            self.default = resources::load_font(ctx, path)?;
+        } else if path == resources::MENU_FONT_PATH {
+            self.menu = resources::load_font(ctx, path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Switches regular text to a font file from the "fonts" folder, or back to the bundled one.
+    pub fn set_user_font(&mut self, ctx: &mut Context, name: &str) -> GameResult<()> {
+        if name == DEFAULT_FONT {
+            self.user = None;
+        } else {
+            let bytes = fs::read(Path::new(FONTS_DIR).join(name))?;
+            self.user = Some(graphics::Font::new_glyph_font_bytes(ctx, &bytes)?);
+        }
+        self.user_font_name = name.to_string();
+
+        Ok(())
+    }
+
+    /// Font for regular text
+    pub fn text_font(&self) -> graphics::Font {
+        self.user.unwrap_or(self.default)
+    }
+
+    /// Text in the regular font, with the fallback characters in the bundled one.
+    pub fn text(&self, text: &str, size: f32) -> graphics::Text {
+        self.build(text, size, false)
+    }
+
+    /// Like `text`, but hearts and diamonds are red.
+    pub fn card_text(&self, text: &str, size: f32) -> graphics::Text {
+        self.build(text, size, true)
+    }
+
+    fn build(&self, text: &str, size: f32, red_suits: bool) -> graphics::Text {
+        let mut result = graphics::Text::new("");
+
+        for (run, fallback) in split_runs(text, self.user.is_some()) {
+            let color = if red_suits && (run == "♥" || run == "♦") {
+                graphics::Color::from_rgb(204, 0, 0)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+            let font = if fallback { self.default } else { self.text_font() };
+
+            result.add(graphics::TextFragment::new(run).
+                                               color(color).
+                                               font(font).
+                                               scale(graphics::PxScale::from(size)));
+        }
+
+        result
+    }
+}
+
+/// Splits text into runs for the regular font and single fallback characters(flagged true).
+/// Without a user font everything is one run, the bundled font has all the glyphs.
+fn split_runs(text: &str, use_fallback: bool) -> Vec<(String, bool)> {
+    if !use_fallback {
+        return vec![(text.to_string(), false)];
+    }
+
+    let mut runs = Vec::new();
+    let mut current = String::new();
+
+    for c in text.chars() {
+        if FALLBACK_CHARS.contains(&c) {
+            if !current.is_empty() {
+                runs.push((std::mem::take(&mut current), false));
+            }
+            runs.push((c.to_string(), true));
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        runs.push((current, false));
+    }
+
+    runs
+}
+
+/// Font files the player can choose from, the bundled one first.
+pub fn available() -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(FONTS_DIR) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let lower = name.to_lowercase();
+            if lower.ends_with(".ttf") || lower.ends_with(".otf") {
+                names.push(name);
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_FONT.to_string());
+
+    names
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_runs_without_user_font_is_one_run() {
+        assert_eq!(split_runs("K♦ 6♥", false), vec![(String::from("K♦ 6♥"), false)]);
+    }
+
+    #[test]
+    fn split_runs_separates_suits() {
+        let runs = split_runs("K♦ (16)", true);
+
+        assert_eq!(runs, vec![
+            (String::from("K"), false),
+            (String::from("♦"), true),
+            (String::from(" (16)"), false),
+        ]);
+    }
+
+    #[test]
+    fn available_starts_with_default() {
+        assert_eq!(available()[0], DEFAULT_FONT);
+    }
+}
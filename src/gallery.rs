@@ -0,0 +1,228 @@
+//! The gallery of notable hands: a counted hand that ends in a five card 21, or in a push of 21
+//! against the dealer's 21, is kept in gallery.csv with the cards in the order they were dealt
+//! and what the player did. That is a whole scenario(see scenario.rs), so any of them can be
+//! watched again from the gallery screen. The id is the hand's id in the history.
+
+use crate::card::Card;
+use crate::game_engine::{self, Outcome};
+use crate::review;
+use crate::saver::Saver;
+use crate::scenario::{Action, Scenario};
+
+use std::fs;
+
+pub const GALLERY_FILE_NAME: &str = "gallery.csv";
+pub const CSV_HEADER: &str = "id,timestamp,kind,outcome,player_cards,dealer_cards,actions";
+
+/// Cards a 21 needs to be a five card one
+const FIVE_CARDS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notable {
+    /// 21 with five cards or more
+    FiveCardTwentyOne,
+    /// 21 against the dealer's 21
+    TwentyOnePush,
+}
+
+impl Notable {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Notable::FiveCardTwentyOne => "five_card_21",
+            Notable::TwentyOnePush => "21_push",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "five_card_21" => Some(Notable::FiveCardTwentyOne),
+            "21_push" => Some(Notable::TwentyOnePush),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Notable::FiveCardTwentyOne => "Five card 21",
+            Notable::TwentyOnePush => "21 against 21",
+        }
+    }
+}
+
+/// What makes the hand notable, if anything does.
+pub fn notable(player_cards: &[Card], dealer_cards: &[Card], outcome: &Outcome) -> Option<Notable> {
+    let player_value = game_engine::hand_value(player_cards);
+
+    if player_value == 21 && player_cards.len() >= FIVE_CARDS {
+        Some(Notable::FiveCardTwentyOne)
+    } else if player_value == 21 && *outcome == Outcome::Draw && game_engine::hand_value(dealer_cards) == 21 {
+        Some(Notable::TwentyOnePush)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GalleryHand {
+    /// The hand's id in the history
+    pub id: u64,
+    /// Seconds since the Unix epoch when the hand ended
+    pub timestamp: u64,
+    pub kind: Notable,
+    pub outcome: Outcome,
+    pub player_cards: Vec<String>,
+    pub dealer_cards: Vec<String>,
+    /// Hits, stands and power-ups, in order
+    pub actions: Vec<Action>,
+}
+
+impl GalleryHand {
+    /// The same cards and the same moves, to be watched again. It doesn't count.
+    pub fn scenario(&self) -> Scenario {
+        Scenario {
+            deck: self.player_cards.iter().chain(&self.dealer_cards).cloned().collect(),
+            actions: self.actions.clone(),
+            expected: Some(self.outcome.clone()),
+        }
+    }
+
+    pub fn to_csv_line(&self) -> String {
+        format!("{},{},{},{},{},{},{}",
+                self.id,
+                self.timestamp,
+                self.kind.name(),
+                self.outcome.name(),
+                self.player_cards.join(" "),
+                self.dealer_cards.join(" "),
+                self.actions.iter().map(|action| review::action_name(*action)).collect::<Vec<&str>>().join(" "))
+    }
+
+    pub fn from_csv_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.trim().split(',').collect();
+        if fields.len() != 7 {
+            return None;
+        }
+        let names = |field: &str| field.split_whitespace().map(String::from).collect();
+
+        Some(
+            GalleryHand {
+                id: fields[0].parse().ok()?,
+                timestamp: fields[1].parse().ok()?,
+                kind: Notable::from_name(fields[2])?,
+                outcome: Outcome::from_name(fields[3])?,
+                player_cards: names(fields[4]),
+                dealer_cards: names(fields[5]),
+                actions: fields[6].split_whitespace().map(Action::from_name).collect::<Option<Vec<Action>>>()?,
+            }
+        )
+    }
+}
+
+/// The notable hands, oldest first, and the one picked on the gallery screen.
+#[derive(Debug, Clone, Default)]
+pub struct Gallery {
+    pub hands: Vec<GalleryHand>,
+    pub selected: usize,
+}
+
+impl Gallery {
+    /// Damaged lines are skipped, the rest of the gallery still loads.
+    pub fn parse(contents: &str) -> Self {
+        let hands = contents.lines().
+            filter(|line| !line.trim().is_empty() && *line != CSV_HEADER).
+            filter_map(GalleryHand::from_csv_line).
+            collect();
+
+        Gallery { hands, selected: 0 }
+    }
+
+    pub fn load(file_name: &str) -> Self {
+        match fs::read_to_string(file_name) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Gallery::default(),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER) + "\n";
+        for hand in &self.hands {
+            csv += &hand.to_csv_line();
+            csv += "\n";
+        }
+
+        csv
+    }
+
+    /// Adds the hand and has `saver` append it to the file.
+    pub fn record(&mut self, saver: &Saver, file_name: &str, hand: GalleryHand) {
+        saver.append(file_name, Some(CSV_HEADER), hand.to_csv_line() + "\n");
+
+        self.hands.push(hand);
+    }
+
+    pub fn select(&mut self, step: i32) {
+        if self.hands.is_empty() {
+            return;
+        }
+
+        let rows = self.hands.len() as i32;
+        self.selected = (self.selected as i32 + step).rem_euclid(rows) as usize;
+    }
+
+    pub fn selected_hand(&self) -> Option<&GalleryHand> {
+        self.hands.get(self.selected)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn spots_the_notable_hands() {
+        let five_card = cards(&["2_of_clubs", "3_of_hearts", "4_of_spades", "5_of_diamonds", "7_of_clubs"]);
+        let dealer_21 = cards(&["king_of_clubs", "5_of_hearts", "6_of_spades"]);
+
+        assert_eq!(notable(&five_card, &cards(&["10_of_clubs", "8_of_hearts"]), &Outcome::Win), Some(Notable::FiveCardTwentyOne));
+        assert_eq!(notable(&cards(&["ace_of_clubs", "king_of_hearts"]), &dealer_21, &Outcome::Draw), Some(Notable::TwentyOnePush));
+        assert_eq!(notable(&cards(&["ace_of_clubs", "king_of_hearts"]), &cards(&["10_of_clubs", "9_of_hearts"]), &Outcome::Win), None);
+        assert_eq!(notable(&cards(&["10_of_clubs", "8_of_hearts"]), &cards(&["10_of_spades", "8_of_diamonds"]), &Outcome::Draw), None);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let hand = GalleryHand {
+            id: 12,
+            timestamp: 1_700_000_000,
+            kind: Notable::FiveCardTwentyOne,
+            outcome: Outcome::Win,
+            player_cards: vec![String::from("2_of_clubs"), String::from("3_of_hearts"), String::from("4_of_spades"), String::from("5_of_diamonds"), String::from("7_of_clubs")],
+            dealer_cards: vec![String::from("10_of_clubs"), String::from("8_of_hearts")],
+            actions: vec![Action::Hit, Action::Hint, Action::Hit, Action::Hit],
+        };
+        let gallery = Gallery::parse(&Gallery { hands: vec![hand.clone()], selected: 0 }.to_csv());
+
+        assert_eq!(gallery.hands, vec![hand]);
+    }
+
+    #[test]
+    fn the_scenario_deals_the_same_hand() {
+        let hand = GalleryHand::from_csv_line("3,1700000000,21_push,draw,ace_of_clubs king_of_hearts,king_of_clubs 5_of_hearts 6_of_spades,hit hit stand").unwrap();
+        let scenario = hand.scenario();
+
+        assert_eq!(scenario.deck, ["ace_of_clubs", "king_of_hearts", "king_of_clubs", "5_of_hearts", "6_of_spades"]);
+        assert_eq!(scenario.play().unwrap(), Outcome::Draw);
+    }
+
+    #[test]
+    fn damaged_lines_are_skipped() {
+        let gallery = Gallery::parse(&format!("{}\n1,2,five_card_21,win,,,\nnot a hand\n4,5,lucky,win,,,\n", CSV_HEADER));
+
+        assert_eq!(gallery.hands.len(), 1);
+    }
+}
@@ -0,0 +1,70 @@
+//! What can be done to a hand, as GameEngine::apply takes it. The engine checks that the action
+//! is allowed, changes its state and tells the caller what happened as events, so the keys, the
+//! deck click, scenarios and bots all play by the same rules. Dealing the card of a hit is left
+//! to the board, which owns the deck.
+
+use crate::scenario::Action;
+
+/// The power-ups a hand can use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerUp {
+    Hint,
+    Handicap,
+}
+
+impl PowerUp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUp::Hint => "hint",
+            PowerUp::Handicap => "handicap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameAction {
+    Hit,
+    Stand,
+    UsePowerUp(PowerUp),
+}
+
+impl From<Action> for GameAction {
+    fn from(action: Action) -> Self {
+        match action {
+            Action::Hit => GameAction::Hit,
+            Action::Stand => GameAction::Stand,
+            Action::Hint => GameAction::UsePowerUp(PowerUp::Hint),
+            Action::Handicap => GameAction::UsePowerUp(PowerUp::Handicap),
+        }
+    }
+}
+
+/// What an applied action did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    /// The player takes a card, which the board deals
+    CardRequested,
+    /// The dealer plays now
+    TurnPassed,
+    PowerUpUsed(PowerUp),
+}
+
+/// Why an action wasn't applied. Nothing changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rejection {
+    HandOver,
+    DealerTurn,
+    NoCharges(PowerUp),
+    AlreadyActive(PowerUp),
+}
+
+impl Rejection {
+    pub fn reason(&self) -> String {
+        match self {
+            Rejection::HandOver => String::from("The hand is over"),
+            Rejection::DealerTurn => String::from("It's the dealer's turn"),
+            Rejection::NoCharges(power_up) => format!("No {}s left", power_up.name()),
+            Rejection::AlreadyActive(power_up) => format!("The {} was already used this hand", power_up.name()),
+        }
+    }
+}
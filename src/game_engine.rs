@@ -1,18 +1,19 @@
 use crate::card::Card;
 use crate::board;
+use crate::game_action::{GameAction, GameEvent, PowerUp, Rejection};
+use crate::probability::Rules;
+use crate::snapshot::EngineSnapshot;
 
-use ggez::{
-    Context,
-    GameResult,
-    mint::Point2,
-    graphics,
-};
+use ggez::GameResult;
 
 
-const SCORE_SIZE: f32 = 50.0;
+/// The dealer keeps drawing below this, an ace counting 11 when it fits(so soft 17 stands too)
+pub const DEALER_STANDS_ON: u32 = 17;
+/// Points taken off the dealer's hand by the handicap power up
+pub const DEALER_HANDICAP_POINTS: u32 = 1;
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Outcome {
     Undecided,
     Win,
@@ -20,6 +21,77 @@ pub enum Outcome {
     Lose,
 }
 
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Undecided => "undecided",
+            Outcome::Win => "win",
+            Outcome::Draw => "draw",
+            Outcome::Lose => "lose",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "undecided" => Some(Outcome::Undecided),
+            "win" => Some(Outcome::Win),
+            "draw" => Some(Outcome::Draw),
+            "lose" => Some(Outcome::Lose),
+            _ => None,
+        }
+    }
+}
+
+/// Where a hand stands after a card is scored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundResult {
+    PlayerDraws,
+    /// The player can't draw any better, auto stand passes the turn to the dealer
+    PlayerOn21,
+    DealerDraws,
+    Over(Outcome),
+}
+
+/// The dealer's score as it counts: the handicap takes its points off before anything else, so
+/// 22 with the handicap is 21 and not a bust.
+pub fn effective_dealer_score(dealer_score: u32, rules: Rules) -> u32 {
+    dealer_score.saturating_sub(rules.handicap_points())
+}
+
+/// Whether the dealer takes another card: below DEALER_STANDS_ON as the hand counts, so with
+/// the handicap the dealer draws on to an effective 17.
+pub fn dealer_must_draw(dealer_score: u32, rules: Rules) -> bool {
+    effective_dealer_score(dealer_score, rules) < DEALER_STANDS_ON
+}
+
+/// Outcome of a player standing on `player_score` against a dealer who finished drawing.
+pub fn compare(player_score: u32, dealer_score: u32, rules: Rules) -> Outcome {
+    let dealer_score = effective_dealer_score(dealer_score, rules);
+
+    if player_score > 21 {
+        Outcome::Lose
+    } else if dealer_score > 21 {
+        Outcome::Win
+    } else {
+        match player_score.cmp(&dealer_score) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Less => Outcome::Lose,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        }
+    }
+}
+
+/// What the scores mean on `turn`. Nothing is changed, GameEngine::check_outcome applies it.
+pub fn resolve(player_score: u32, dealer_score: u32, turn: &board::Turn, rules: Rules) -> RoundResult {
+    match turn {
+        _ if player_score > 21 => RoundResult::Over(Outcome::Lose),
+        board::Turn::Player if player_score == 21 => RoundResult::PlayerOn21,
+        board::Turn::Player => RoundResult::PlayerDraws,
+        board::Turn::Dealer if dealer_must_draw(dealer_score, rules) => RoundResult::DealerDraws,
+        board::Turn::Dealer => RoundResult::Over(compare(player_score, dealer_score, rules)),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum HintStatus {
     Unused,
@@ -33,7 +105,42 @@ pub struct GameEngine {
     pub hint: HintStatus,
     pub dealer_handicap_active: bool,
     pub game_over: bool,
-    pub outcome: Outcome
+    pub outcome: Outcome,
+    /// Whether reaching 21 ends the player's turn, otherwise the player stands with Space
+    pub auto_stand_on_21: bool,
+}
+
+/// Points of a hand, counting one ace as 11 when that doesn't go over 21.
+pub fn hand_value(cards: &[Card]) -> u32 {
+    let mut score: u32 = 0;
+    let mut num_of_aces: u32 = 0;
+    for card in cards {
+        if card.is_an_ace() {
+            num_of_aces += 1;
+        } else {
+            score += card.get_points().unwrap();
+        }
+    }
+
+    if num_of_aces > 0 && score + 11 + (num_of_aces - 1) <= 21 {
+        score += 11 + (num_of_aces - 1);
+    } else {
+        score += num_of_aces;
+    }
+
+    score
+}
+
+/// Points of a hand with every ace as 1, and the soft total(one ace as 11) when the hand has
+/// an ace that can count 11 without going over 21.
+pub fn hand_totals(cards: &[Card]) -> (u32, Option<u32>) {
+    let hard = cards.iter().
+        map(|card| if card.is_an_ace() { 1 } else { card.get_points().unwrap_or(0) }).
+        sum::<u32>();
+    let has_ace = cards.iter().any(|card| card.is_an_ace());
+
+    let soft = if has_ace && hard + 10 <= 21 { Some(hard + 10) } else { None };
+    (hard, soft)
 }
 
 impl GameEngine {
@@ -45,67 +152,101 @@ impl GameEngine {
             dealer_handicap_active: false,
             game_over: false,
             outcome: Outcome::Undecided,
+            auto_stand_on_21: true,
         }
     }
 
+    /// The state as it is now, to compare with a later one or write out.
+    pub fn snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot::new(self.player_score, self.dealer_score, &self.hint, self.dealer_handicap_active,
+                            self.game_over, &self.outcome, self.auto_stand_on_21)
+    }
+
+    /// Rules the current hand is played by.
+    pub fn rules(&self) -> Rules {
+        Rules { dealer_handicap: self.dealer_handicap_active }
+    }
+
+    /// The dealer's score as it counts and is shown, see effective_dealer_score.
+    pub fn effective_dealer_score(&self) -> u32 {
+        effective_dealer_score(self.dealer_score, self.rules())
+    }
+
+    /// Whether the dealer takes another card, see dealer_must_draw.
+    pub fn dealer_must_draw(&self) -> bool {
+        !self.game_over && dealer_must_draw(self.dealer_score, self.rules())
+    }
+
+    /// Applies what resolve makes of the scores: the only place the scores end a hand or pass
+    /// the turn.
     pub fn check_outcome(&mut self, turn: &mut board::Turn) {
-        let handicap_addition: u32;
-        if self.dealer_handicap_active {
-            handicap_addition = 1;
-        } else {
-            handicap_addition = 0;
+        match resolve(self.player_score, self.dealer_score, turn, self.rules()) {
+            RoundResult::Over(outcome) => {
+                self.game_over = true;
+                self.outcome = outcome;
+            },
+            RoundResult::PlayerOn21 if self.auto_stand_on_21 => *turn = board::Turn::Dealer,
+            // player or dealer are still drawing
+            _ => (),
         }
+    }
 
-        if self.player_score > 21 {
-            // player has more than 21 -> player loses
-            self.game_over = true;
-            self.outcome = Outcome::Lose;
-        } else if self.dealer_score > 21 {
-            // dealer has more than 21 -> player wins
-            self.game_over = true;
-            self.outcome = Outcome::Win;
-        } else if matches!(turn, board::Turn::Dealer) 
-                && self.dealer_score >= 17 
-                && self.player_score > self.dealer_score - handicap_addition {
-            // dealer finished drawing(has >= 17) and player has more than dealer -> player wins
-            self.game_over = true;
-            self.outcome = Outcome::Win;
-        } else if matches!(turn, board::Turn::Dealer) 
-                && self.dealer_score >= 17 
-                && self.player_score < self.dealer_score - handicap_addition {
-            // dealer finished drawing(has >= 17) and player has less than dealer -> player loses  
-            self.game_over = true;
-            self.outcome = Outcome::Lose;
-        } else if matches!(turn, board::Turn::Dealer) 
-                && self.dealer_score >= 17 
-                && self.player_score == self.dealer_score - handicap_addition {
-            // dealer finished drawing(has >= 17) and player and dealer tied -> draw  
-            self.game_over = true;
-            self.outcome = Outcome::Draw;
-        } else if matches!(turn, board::Turn::Player) 
-                && self.player_score == 21{
-            // player has a blackjack -> dealers turn
-            *turn = board::Turn::Dealer;
+    /// Checks `action` against the hand and applies it, see game_action.rs. `power_ups` are the
+    /// player's hints and handicaps, None when they cost nothing(practice).
+    pub fn apply(&mut self, action: GameAction, turn: &mut board::Turn, power_ups: Option<&mut (u32, u32)>) -> Result<Vec<GameEvent>, Rejection> {
+        if self.game_over {
+            return Err(Rejection::HandOver);
         }
-        // in the other cases, player or dealer are still drawing
-    }
-
-    pub fn score(&mut self, dealed_cards: &Vec<Card>, turn: board::Turn) -> GameResult<()> {
-        let mut score: u32 = 0;
-        let mut num_of_aces: u32 = 0;
-        for card in dealed_cards {
-            if card.is_an_ace() {
-                num_of_aces += 1;
-            } else {
-                score += card.get_points().unwrap();
-            }
+        if matches!(turn, board::Turn::Dealer) {
+            return Err(Rejection::DealerTurn);
         }
-    
-        if num_of_aces > 0 && score + 11 + (num_of_aces - 1) <= 21 {
-            score += 11 + (num_of_aces - 1);
-        } else {
-            score += num_of_aces;
+
+        match action {
+            GameAction::Hit => {
+                // the hint was about this card
+                if matches!(self.hint, HintStatus::Active) {
+                    self.hint = HintStatus::Exhausted;
+                }
+
+                Ok(vec![GameEvent::CardRequested])
+            },
+            GameAction::Stand => {
+                *turn = board::Turn::Dealer;
+
+                Ok(vec![GameEvent::TurnPassed])
+            },
+            GameAction::UsePowerUp(power_up) => {
+                let used = match power_up {
+                    PowerUp::Hint => !matches!(self.hint, HintStatus::Unused),
+                    PowerUp::Handicap => self.dealer_handicap_active,
+                };
+                if used {
+                    return Err(Rejection::AlreadyActive(power_up));
+                }
+
+                if let Some(power_ups) = power_ups {
+                    let count = match power_up {
+                        PowerUp::Hint => &mut power_ups.0,
+                        PowerUp::Handicap => &mut power_ups.1,
+                    };
+                    if *count == 0 {
+                        return Err(Rejection::NoCharges(power_up));
+                    }
+                    *count -= 1;
+                }
+
+                match power_up {
+                    PowerUp::Hint => self.hint = HintStatus::Active,
+                    PowerUp::Handicap => self.dealer_handicap_active = true,
+                }
+
+                Ok(vec![GameEvent::PowerUpUsed(power_up)])
+            },
         }
+    }
+
+    pub fn score(&mut self, dealed_cards: &[Card], turn: board::Turn) -> GameResult<()> {
+        let score = hand_value(dealed_cards);
     
         match turn {
             board::Turn::Player => self.player_score = score,
@@ -114,31 +255,6 @@ impl GameEngine {
 
         Ok(())
     }
-
-    pub fn draw_score(&self, ctx: &mut Context, pos_player: Point2<f32>, pos_dealer: Point2<f32>) -> GameResult<()> {
-        let color;
-        match self.dealer_handicap_active {
-            true => color = graphics::Color::from_rgb(204, 0, 0),
-            false => color = graphics::Color::from_rgb(255, 255, 255),
-        }
-
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
-
-        let player_score_clone = self.player_score.clone();
-        let dealer_score_clone = self.dealer_score.clone();
-
-        let player_score_fragment = graphics::TextFragment::new(player_score_clone.to_string().as_str()).
-                                                            font(font).
-                                                            scale(graphics::PxScale::from(SCORE_SIZE));
-
-        let dealer_score_fragment = graphics::TextFragment::new(dealer_score_clone.to_string().as_str()).
-                                                            color(color). 
-                                                            font(font).
-                                                            scale(graphics::PxScale::from(SCORE_SIZE));
-
-        graphics::draw(ctx, &graphics::Text::new(player_score_fragment), graphics::DrawParam::default().dest(pos_player))?;
-        graphics::draw(ctx, &graphics::Text::new(dealer_score_fragment), graphics::DrawParam::default().dest(pos_dealer))
-    }
 }
 
 
@@ -199,6 +315,100 @@ mod tests {
         assert!(matches!(engine.outcome, Outcome::Draw));
     }
 
+    #[test]
+    fn check_outcome_handicap_comes_before_the_bust() {
+        let mut engine = GameEngine { dealer_handicap_active: true, ..GameEngine::new() };
+        engine.player_score = 20;
+        engine.dealer_score = 22;
+
+        engine.check_outcome(&mut board::Turn::Dealer);
+
+        assert_eq!(engine.effective_dealer_score(), 21);
+        assert!(matches!(engine.outcome, Outcome::Lose));
+
+        engine.dealer_score = 23;
+        engine.check_outcome(&mut board::Turn::Dealer);
+
+        assert!(matches!(engine.outcome, Outcome::Win));
+    }
+
+    #[test]
+    fn dealer_draws_to_an_effective_17() {
+        let mut engine = GameEngine { dealer_handicap_active: true, ..GameEngine::new() };
+        engine.player_score = 15;
+        engine.dealer_score = 17;
+
+        engine.check_outcome(&mut board::Turn::Dealer);
+
+        assert!(engine.dealer_must_draw());
+        assert!(!engine.game_over);
+
+        engine.dealer_score = 18;
+        engine.check_outcome(&mut board::Turn::Dealer);
+
+        assert!(!engine.dealer_must_draw());
+        assert!(matches!(engine.outcome, Outcome::Lose));
+    }
+
+    #[test]
+    fn resolve_changes_nothing_and_covers_every_turn() {
+        let plain = Rules::default();
+        let handicap = Rules { dealer_handicap: true };
+
+        assert_eq!(resolve(12, 0, &board::Turn::Player, plain), RoundResult::PlayerDraws);
+        assert_eq!(resolve(21, 0, &board::Turn::Player, plain), RoundResult::PlayerOn21);
+        assert_eq!(resolve(22, 0, &board::Turn::Player, plain), RoundResult::Over(Outcome::Lose));
+        assert_eq!(resolve(18, 16, &board::Turn::Dealer, plain), RoundResult::DealerDraws);
+        assert_eq!(resolve(18, 17, &board::Turn::Dealer, handicap), RoundResult::DealerDraws);
+        assert_eq!(resolve(18, 18, &board::Turn::Dealer, plain), RoundResult::Over(Outcome::Draw));
+        assert_eq!(resolve(18, 18, &board::Turn::Dealer, handicap), RoundResult::Over(Outcome::Win));
+        assert_eq!(resolve(18, 22, &board::Turn::Dealer, plain), RoundResult::Over(Outcome::Win));
+        assert_eq!(resolve(18, 22, &board::Turn::Dealer, handicap), RoundResult::Over(Outcome::Lose));
+    }
+
+    #[test]
+    fn compare_checks_busts_first() {
+        assert_eq!(compare(22, 25, Rules::default()), Outcome::Lose);
+        assert_eq!(compare(20, 23, Rules { dealer_handicap: true }), Outcome::Win);
+        assert_eq!(compare(17, 19, Rules::default()), Outcome::Lose);
+    }
+
+    #[test]
+    fn apply_checks_the_turn_first() {
+        let mut engine = GameEngine::new();
+        let mut turn = board::Turn::Player;
+
+        assert_eq!(engine.apply(GameAction::Hit, &mut turn, None), Ok(vec![GameEvent::CardRequested]));
+        assert_eq!(engine.apply(GameAction::Stand, &mut turn, None), Ok(vec![GameEvent::TurnPassed]));
+        assert!(matches!(turn, board::Turn::Dealer));
+        assert_eq!(engine.apply(GameAction::Stand, &mut turn, None), Err(Rejection::DealerTurn));
+
+        engine.game_over = true;
+        assert_eq!(engine.apply(GameAction::Hit, &mut board::Turn::Player, None), Err(Rejection::HandOver));
+    }
+
+    #[test]
+    fn apply_spends_power_ups_once() {
+        let mut engine = GameEngine::new();
+        let mut turn = board::Turn::Player;
+        let mut power_ups = (1, 0);
+
+        let hint = GameAction::UsePowerUp(PowerUp::Hint);
+        let handicap = GameAction::UsePowerUp(PowerUp::Handicap);
+        assert_eq!(engine.apply(hint, &mut turn, Some(&mut power_ups)), Ok(vec![GameEvent::PowerUpUsed(PowerUp::Hint)]));
+        assert_eq!(engine.apply(hint, &mut turn, Some(&mut power_ups)), Err(Rejection::AlreadyActive(PowerUp::Hint)));
+        assert_eq!(engine.apply(handicap, &mut turn, Some(&mut power_ups)), Err(Rejection::NoCharges(PowerUp::Handicap)));
+        assert_eq!(power_ups, (0, 0));
+        assert!(!engine.dealer_handicap_active);
+
+        // free in practice
+        assert!(engine.apply(handicap, &mut turn, None).is_ok());
+        assert!(engine.dealer_handicap_active);
+
+        engine.apply(GameAction::Hit, &mut turn, None).unwrap();
+        assert!(matches!(engine.hint, HintStatus::Exhausted));
+    }
+
     #[test]
     fn check_outcome_player_has_21_and_its_players_turn() {
         let mut engine = GameEngine::new();
@@ -210,6 +420,18 @@ mod tests {
         assert!(matches!(turn, board::Turn::Dealer));
     }
 
+    #[test]
+    fn check_outcome_player_keeps_the_turn_on_21_without_auto_stand() {
+        let mut engine = GameEngine { auto_stand_on_21: false, ..GameEngine::new() };
+        engine.player_score = 21;
+
+        let mut turn = board::Turn::Player;
+        engine.check_outcome(&mut turn);
+
+        assert!(matches!(turn, board::Turn::Player));
+        assert!(!engine.game_over);
+    }
+
     #[test]
     fn score_on_players_turn() {
         let mut engine = GameEngine::new();
@@ -271,4 +493,14 @@ mod tests {
 
         assert_eq!(engine.player_score, 18);
     }
+
+    #[test]
+    fn soft_total_only_when_the_ace_fits() {
+        let soft = vec![Card::new("ace_of_diamonds"), Card::new("6_of_hearts")];
+        let hard = vec![Card::new("ace_of_diamonds"), Card::new("6_of_hearts"), Card::new("king_of_clubs")];
+
+        assert_eq!(hand_totals(&soft), (7, Some(17)));
+        assert_eq!(hand_totals(&hard), (17, None));
+        assert_eq!(hand_totals(&hard).0, hand_value(&hard));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+//! The ghost: the best play(see probability::best_play) playing every counted hand alongside the
+//! player, on the very same cards. A hand takes its cards from the top of the deck, the player's
+//! first and then the dealer's, so the cards dealt followed by what's left in the deck are the
+//! deck as the hand found it. The ghost plays that deck after the hand, without power-ups, and
+//! the session summary compares the two(see session.rs).
+
+use crate::card::Card;
+use crate::game_engine::Outcome;
+use crate::probability::{self, BestPlay, Rules};
+use crate::simulation;
+
+/// The ghost's hands this session.
+#[derive(Debug, Clone, Default)]
+pub struct Ghost {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Worked out with the first hand
+    best: Option<BestPlay>,
+}
+
+impl Ghost {
+    /// Plays the hand dealt from `deck`, the top card first.
+    pub fn play(&mut self, deck: &[Card]) -> Outcome {
+        let rules = Rules::default();
+        let best = self.best.get_or_insert_with(|| probability::best_play(rules));
+        let outcome = play_hand(deck, best, rules);
+
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Lose => self.losses += 1,
+            Outcome::Undecided => (),
+        }
+
+        outcome
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Wins less losses, as SessionSummary::net counts the player's
+    pub fn net(&self) -> i32 {
+        self.wins as i32 - self.losses as i32
+    }
+}
+
+/// The hand the best play makes of `deck`, the top card first.
+pub fn play_hand(deck: &[Card], best: &BestPlay, rules: Rules) -> Outcome {
+    let mut cards = deck.iter();
+
+    simulation::play_hand_with(|| cards.next().map(|card| if card.is_an_ace() { 1 } else { card.get_points().unwrap_or(10) }), best, rules)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn plays_the_cards_in_order() {
+        let mut ghost = Ghost::default();
+
+        // 20 stands, the dealer makes 17
+        assert_eq!(ghost.play(&cards(&["king_of_clubs", "queen_of_hearts", "10_of_spades", "7_of_diamonds"])), Outcome::Win);
+        // 9 hits to 19, the dealer makes 19 too
+        assert_eq!(ghost.play(&cards(&["5_of_clubs", "4_of_hearts", "king_of_spades", "10_of_diamonds", "9_of_clubs"])), Outcome::Draw);
+        // 20 stands, the dealer makes 21
+        assert_eq!(ghost.play(&cards(&["king_of_clubs", "queen_of_hearts", "ace_of_spades", "jack_of_diamonds"])), Outcome::Lose);
+
+        assert_eq!((ghost.hands(), ghost.net()), (3, 0));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::png;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Screens are shrunk by this factor before comparing, which keeps the stored goldens small
+/// and smooths over tiny anti-aliasing differences between drivers.
+pub const DOWNSAMPLE: u32 = 4;
+
+/// How much a channel may differ before the pixel counts as changed
+pub const CHANNEL_TOLERANCE: u8 = 24;
+
+/// Share of changed pixels that's still accepted
+pub const MAX_CHANGED_RATIO: f32 = 0.002;
+
+/// An RGBA image, row by row from the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Averages every `factor` x `factor` block into one pixel.
+    pub fn downsample(&self, factor: u32) -> Frame {
+        let width = self.width / factor;
+        let height = self.height / factor;
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0u32; 4];
+                for dy in 0..factor {
+                    for dx in 0..factor {
+                        let i = (((y * factor + dy) * self.width + x * factor + dx) * 4) as usize;
+                        for (total, value) in sum.iter_mut().zip(&self.pixels[i..i + 4]) {
+                            *total += *value as u32;
+                        }
+                    }
+                }
+                pixels.extend(sum.iter().map(|s| (s / (factor * factor)) as u8));
+            }
+        }
+
+        Frame { width, height, pixels }
+    }
+
+    pub fn load(file: &Path) -> io::Result<Frame> {
+        let (width, height, pixels) = png::decode_rgba(&fs::read(file)?).
+            map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        Ok(Frame { width, height, pixels })
+    }
+
+    pub fn save(&self, file: &Path) -> io::Result<()> {
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(file, png::encode_rgba(self.width, self.height, &self.pixels))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    Match,
+    SizeMismatch,
+    Changed { changed_pixels: usize, total_pixels: usize },
+}
+
+pub fn compare(actual: &Frame, expected: &Frame) -> Comparison {
+    if actual.width != expected.width || actual.height != expected.height {
+        return Comparison::SizeMismatch;
+    }
+
+    let changed_pixels = actual.pixels.chunks(4).
+        zip(expected.pixels.chunks(4)).
+        filter(|(a, e)| a.iter().zip(e.iter()).any(|(a, e)| a.abs_diff(*e) > CHANNEL_TOLERANCE)).
+        count();
+    let total_pixels = (actual.width * actual.height) as usize;
+
+    if changed_pixels as f32 <= total_pixels as f32 * MAX_CHANGED_RATIO {
+        Comparison::Match
+    } else {
+        Comparison::Changed { changed_pixels, total_pixels }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> Frame {
+        Frame { width, height, pixels: vec![value; (width * height * 4) as usize] }
+    }
+
+    #[test]
+    fn downsample_averages_blocks() {
+        let mut frame = solid(4, 2, 0);
+        for i in 0..4 {
+            frame.pixels[i] = 200; // top-left pixel
+        }
+
+        let small = frame.downsample(2);
+
+        assert_eq!((small.width, small.height), (2, 1));
+        assert_eq!(&small.pixels[0..4], &[50, 50, 50, 50]);
+    }
+
+    #[test]
+    fn small_differences_match() {
+        assert_eq!(compare(&solid(10, 10, 100), &solid(10, 10, 110)), Comparison::Match);
+    }
+
+    #[test]
+    fn big_differences_fail() {
+        let expected = solid(10, 10, 100);
+        let mut actual = expected.clone();
+        actual.pixels[0] = 255;
+
+        assert_eq!(compare(&actual, &expected), Comparison::Changed { changed_pixels: 1, total_pixels: 100 });
+        assert_eq!(compare(&solid(5, 5, 0), &expected), Comparison::SizeMismatch);
+    }
+}
@@ -0,0 +1,151 @@
+//! The HELP screen's text. The keys come from the key map and the words from the language, so
+//! a key rebound in config.txt shows up here as it is.
+
+use crate::i18n::Language;
+use crate::keymap::{KeyAction, KeyMap};
+
+/// Every piece of the help text that gets translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phrase {
+    Rules,
+    Hit,
+    ClickOnDeck,
+    Or,
+    Stand,
+    UseHint,
+    UseHandicap,
+    GameSpeed,
+    Coach,
+    MinimalHud,
+    NextHand,
+    NextHandWhen,
+    BackToMenu,
+    Exit,
+    /// An action all of whose keys went to other actions
+    NoKey,
+    HintExplained,
+    HandicapExplained,
+}
+
+pub const PHRASES: &[Phrase] = &[
+    Phrase::Rules,
+    Phrase::Hit,
+    Phrase::ClickOnDeck,
+    Phrase::Or,
+    Phrase::Stand,
+    Phrase::UseHint,
+    Phrase::UseHandicap,
+    Phrase::GameSpeed,
+    Phrase::Coach,
+    Phrase::MinimalHud,
+    Phrase::NextHand,
+    Phrase::NextHandWhen,
+    Phrase::BackToMenu,
+    Phrase::Exit,
+    Phrase::NoKey,
+    Phrase::HintExplained,
+    Phrase::HandicapExplained,
+];
+
+pub fn phrase(language: Language, phrase: Phrase) -> &'static str {
+    match language {
+        Language::English => match phrase {
+            Phrase::Rules => "Standard blackjack rules.",
+            Phrase::Hit => "hit",
+            Phrase::ClickOnDeck => "Left-Mouse-Click over deck",
+            Phrase::Or => "or",
+            Phrase::Stand => "stand",
+            Phrase::UseHint => "use hint",
+            Phrase::UseHandicap => "use handicap",
+            Phrase::GameSpeed => "game speed(casual, normal, speed)",
+            Phrase::Coach => "coach view(coach.txt)",
+            Phrase::MinimalHud => "minimal HUD(only the cards and the scores)",
+            Phrase::NextHand => "next hand",
+            Phrase::NextHandWhen => "(on the menu or once a hand is over)",
+            Phrase::BackToMenu => "back to the menu once a hand is over",
+            Phrase::Exit => "exit",
+            Phrase::NoKey => "(no key)",
+            Phrase::HintExplained => "hint: gives approximation of next card's points",
+            Phrase::HandicapExplained => "handicap: dealer's score is reduced with 1 point before anything else,\nthe dealer draws to 17 of the reduced score, which is the one shown",
+        },
+        Language::Bulgarian => match phrase {
+            Phrase::Rules => "Стандартни правила на блекджек.",
+            Phrase::Hit => "карта",
+            Phrase::ClickOnDeck => "ляв бутон на мишката върху тестето",
+            Phrase::Or => "или",
+            Phrase::Stand => "стоп",
+            Phrase::UseHint => "подсказка",
+            Phrase::UseHandicap => "хендикап",
+            Phrase::GameSpeed => "скорост на играта(casual, normal, speed)",
+            Phrase::Coach => "изглед за треньор(coach.txt)",
+            Phrase::MinimalHud => "минимален екран(само картите и резултатите)",
+            Phrase::NextHand => "следваща ръка",
+            Phrase::NextHandWhen => "(в менюто или след края на ръката)",
+            Phrase::BackToMenu => "обратно към менюто след края на ръката",
+            Phrase::Exit => "изход",
+            Phrase::NoKey => "(без клавиш)",
+            Phrase::HintExplained => "подсказка: приблизителните точки на следващата карта",
+            Phrase::HandicapExplained => "хендикап: резултатът на дилъра се намалява с 1 точка преди всичко друго,\nдилърът тегли до 17 по намаления резултат, който се показва",
+        },
+    }
+}
+
+/// The help text in `language` with the keys of `key_map`, a control per line.
+pub fn text(key_map: &KeyMap, language: Language) -> String {
+    let say = |words| phrase(language, words);
+    let keys = |action| match key_map.describe(action) {
+        keys if keys.is_empty() => say(Phrase::NoKey).to_string(),
+        keys => keys,
+    };
+
+    let lines = [
+        say(Phrase::Rules).to_string(),
+        String::new(),
+        format!("{} = {} {} {}", say(Phrase::Hit), say(Phrase::ClickOnDeck), say(Phrase::Or), keys(KeyAction::Hit)),
+        format!("{} = {}", say(Phrase::Stand), keys(KeyAction::Stand)),
+        format!("{} = {}", say(Phrase::UseHint), keys(KeyAction::Hint)),
+        format!("{} = {}", say(Phrase::UseHandicap), keys(KeyAction::Handicap)),
+        format!("{} = {}", say(Phrase::GameSpeed), keys(KeyAction::GameSpeed)),
+        format!("{} = {}", say(Phrase::Coach), keys(KeyAction::Coach)),
+        format!("{} = {}", say(Phrase::MinimalHud), keys(KeyAction::MinimalHud)),
+        // R, Enter and Escape can't be rebound(see keymap.rs)
+        format!("{} = R {}", say(Phrase::NextHand), say(Phrase::NextHandWhen)),
+        format!("{} = Enter", say(Phrase::BackToMenu)),
+        format!("{} = Escape", say(Phrase::Exit)),
+        String::new(),
+        say(Phrase::HintExplained).to_string(),
+        say(Phrase::HandicapExplained).to_string(),
+    ];
+
+    lines.join("\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::event::KeyCode;
+
+    #[test]
+    fn every_phrase_is_translated() {
+        for language in crate::i18n::LANGUAGES {
+            for words in PHRASES {
+                assert!(!phrase(*language, *words).is_empty(), "{:?} is missing {:?}", language, words);
+            }
+        }
+    }
+
+    #[test]
+    fn keys_follow_the_key_map() {
+        let mut key_map = KeyMap::default();
+        assert!(text(&key_map, Language::English).contains("hit = Left-Mouse-Click over deck or H\nstand = Space / S\nuse hint = 1\n"));
+
+        // D goes to the hit, the hint loses its only key
+        key_map.bind(KeyAction::Hit, &[KeyCode::D, KeyCode::Key1]);
+        let help = text(&key_map, Language::English);
+        assert!(help.contains("over deck or D / 1\n"));
+        assert!(help.contains("use hint = (no key)\n"));
+
+        assert!(text(&key_map, Language::Bulgarian).contains("подсказка = (без клавиш)\n"));
+    }
+}
@@ -0,0 +1,41 @@
+//! What a hit would have brought: the deck is dealt in order, so after a stand the card on top
+//! is the one the player would have drawn. It's kept with the hand in the history, and shown
+//! with the outcome only when the player asks for it in the settings, since not everyone wants
+//! to know.
+
+use crate::card::Card;
+use crate::game_engine;
+
+/// "You would have drawn the 5♦ and made 21"
+pub fn describe(player_cards: &[Card], next: &Card) -> String {
+    let mut cards = player_cards.to_vec();
+    cards.push(next.clone());
+    let value = game_engine::hand_value(&cards);
+
+    let result = match value {
+        21 => String::from("and made 21"),
+        value if value > 21 => format!("and busted with {}", value),
+        value => format!("for {}", value),
+    };
+
+    format!("You would have drawn the {} {}", next.short_name(), result)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn says_where_the_hit_would_have_gone() {
+        let sixteen = cards(&["10_of_clubs", "6_of_hearts"]);
+
+        assert_eq!(describe(&sixteen, &Card::new("5_of_diamonds")), "You would have drawn the 5♦ and made 21");
+        assert_eq!(describe(&sixteen, &Card::new("king_of_spades")), "You would have drawn the K♠ and busted with 26");
+        assert_eq!(describe(&cards(&["ace_of_clubs", "6_of_hearts"]), &Card::new("queen_of_hearts")), "You would have drawn the Q♥ for 17");
+    }
+}
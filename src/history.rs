@@ -0,0 +1,267 @@
+use crate::game_engine::Outcome;
+use crate::migration::{self, SaveFile};
+use crate::saver::Saver;
+
+use std::fs;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE_NAME: &str = "history.csv";
+pub const CSV_HEADER: &str = "id,timestamp,outcome,player_score,dealer_score,hint_used,handicap_used,player_start,dealer_up_card,would_have_drawn";
+/// Columns of the first and second versions, still found in old exports
+const V1_FIELDS: usize = 7;
+const V2_FIELDS: usize = 9;
+
+/// One finished hand.
+#[derive(Debug, Clone)]
+pub struct HandRecord {
+    pub id: u64,
+
+    /// Seconds since the Unix epoch when the hand ended
+    pub timestamp: u64,
+    pub outcome: Outcome,
+    pub player_score: u32,
+    pub dealer_score: u32,
+    pub hint_used: bool,
+    pub handicap_used: bool,
+
+    /// Value of the player's first two cards, unknown for hands logged before it was
+    pub player_start: Option<u32>,
+    /// Points of the dealer's first card(2 to 11), unknown for hands logged before it was
+    pub dealer_up_card: Option<u32>,
+    /// The card a hit would have brought after the player stood(see hindsight.rs), None after
+    /// a bust and for hands logged before it was kept
+    pub would_have_drawn: Option<String>,
+}
+
+fn optional_field(value: Option<u32>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Empty is unknown, anything else has to be a number
+fn parse_optional_field(field: &str) -> Option<Option<u32>> {
+    if field.is_empty() {
+        Some(None)
+    } else {
+        field.parse().ok().map(Some)
+    }
+}
+
+impl HandRecord {
+    pub fn to_csv_line(&self) -> String {
+        format!("{},{},{},{},{},{},{},{},{},{}",
+                self.id,
+                self.timestamp,
+                self.outcome.name(),
+                self.player_score,
+                self.dealer_score,
+                self.hint_used,
+                self.handicap_used,
+                optional_field(self.player_start),
+                optional_field(self.dealer_up_card),
+                self.would_have_drawn.as_deref().unwrap_or_default())
+    }
+
+    /// Reads a line of the current log or of an old export(without the starting hands or the
+    /// card a hit would have brought).
+    pub fn from_csv_line(line: &str) -> Option<Self> {
+        let mut fields: Vec<&str> = line.trim().split(',').collect();
+        if fields.len() == V1_FIELDS {
+            fields.extend(["", ""]);
+        }
+        if fields.len() == V2_FIELDS {
+            fields.push("");
+        }
+        if fields.len() != 10 {
+            return None;
+        }
+
+        Some(
+            HandRecord {
+                id: fields[0].parse().ok()?,
+                timestamp: fields[1].parse().ok()?,
+                outcome: Outcome::from_name(fields[2])?,
+                player_score: fields[3].parse().ok()?,
+                dealer_score: fields[4].parse().ok()?,
+                hint_used: fields[5].parse().ok()?,
+                handicap_used: fields[6].parse().ok()?,
+                player_start: parse_optional_field(fields[7])?,
+                dealer_up_card: parse_optional_field(fields[8])?,
+                would_have_drawn: Some(fields[9].to_string()).filter(|name| !name.is_empty()),
+            }
+        )
+    }
+}
+
+/// Log of every hand played, kept in a CSV file so it opens in a spreadsheet as is.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub hands: Vec<HandRecord>,
+}
+
+impl History {
+    /// Reads the hands from CSV, skipping the header and any malformed lines.
+    pub fn parse(contents: &str) -> Self {
+        History {
+            hands: contents.lines().filter_map(HandRecord::from_csv_line).collect(),
+        }
+    }
+
+    pub fn load(file_name: &str) -> Self {
+        match migration::read(file_name, SaveFile::History) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => History::default(),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER) + "\n";
+        for hand in &self.hands {
+            csv += &hand.to_csv_line();
+            csv += "\n";
+        }
+
+        csv
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.hands.iter().map(|hand| hand.id + 1).max().unwrap_or(1)
+    }
+
+    /// Adds the hand and has `saver` append it to the file(without rewriting the whole log).
+    pub fn record(&mut self, saver: &Saver, file_name: &str, hand: HandRecord) {
+        saver.append(file_name, Some(CSV_HEADER), hand.to_csv_line() + "\n");
+
+        self.hands.push(hand);
+    }
+
+    pub fn save(&self, file_name: &str) -> io::Result<()> {
+        fs::write(file_name, self.to_csv())
+    }
+
+    /// Adds the hands from another log that aren't already here(same id and timestamp),
+    /// keeping everything in chronological order. Returns how many were added.
+    pub fn merge(&mut self, other: History) -> usize {
+        let before = self.hands.len();
+
+        for hand in other.hands {
+            if !self.contains(&hand) {
+                self.hands.push(hand);
+            }
+        }
+        self.hands.sort_by_key(|hand| (hand.timestamp, hand.id));
+
+        self.hands.len() - before
+    }
+
+    /// Whether the log has this hand already, by id and timestamp.
+    pub fn contains(&self, hand: &HandRecord) -> bool {
+        self.hands.iter().any(|h| h.id == hand.id && h.timestamp == hand.timestamp)
+    }
+
+    pub fn count(&self, outcome: &Outcome) -> usize {
+        self.hands.iter().filter(|hand| hand.outcome == *outcome).count()
+    }
+
+    /// Most wins in a row. Draws don't break a streak, losses do.
+    pub fn longest_win_streak(&self) -> u32 {
+        let mut longest = 0;
+        let mut current = 0;
+
+        for hand in &self.hands {
+            match hand.outcome {
+                Outcome::Win => {
+                    current += 1;
+                    longest = longest.max(current);
+                },
+                Outcome::Lose => current = 0,
+                _ => (),
+            }
+        }
+
+        longest
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A logged hand for tests, `id` also sets its timestamp.
+    pub(crate) fn hand(id: u64, outcome: Outcome) -> HandRecord {
+        HandRecord {
+            id,
+            timestamp: 1_700_000_000 + id,
+            outcome,
+            player_score: 20,
+            dealer_score: 18,
+            hint_used: true,
+            handicap_used: false,
+            player_start: Some(13),
+            dealer_up_card: Some(10),
+            would_have_drawn: Some(String::from("5_of_diamonds")),
+        }
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let history = History { hands: vec![hand(1, Outcome::Win), hand(2, Outcome::Lose)] };
+        let parsed = History::parse(&history.to_csv());
+
+        assert_eq!(parsed.hands.len(), 2);
+        assert_eq!(parsed.hands[1].to_csv_line(), history.hands[1].to_csv_line());
+    }
+
+    #[test]
+    fn parse_skips_header_and_garbage() {
+        let history = History::parse(&(CSV_HEADER.to_owned() + "\n1,5,win,21,17,false,false,,\n2,9,lose,22,18,false,false,12,x\nnot,a,hand\n"));
+
+        assert_eq!(history.hands.len(), 1);
+        assert_eq!(history.count(&Outcome::Win), 1);
+        assert_eq!(history.hands[0].player_start, None);
+    }
+
+    #[test]
+    fn old_export_lines_are_read() {
+        let hand = HandRecord::from_csv_line("3,5,draw,19,19,false,true").unwrap();
+
+        assert_eq!((hand.player_start, hand.dealer_up_card), (None, None));
+        assert!(hand.handicap_used);
+
+        let hand = HandRecord::from_csv_line("4,6,win,20,18,false,false,20,10").unwrap();
+        assert_eq!((hand.dealer_up_card, hand.would_have_drawn), (Some(10), None));
+    }
+
+    #[test]
+    fn next_id_follows_highest() {
+        let history = History { hands: vec![hand(4, Outcome::Draw), hand(2, Outcome::Win)] };
+
+        assert_eq!(history.next_id(), 5);
+        assert_eq!(History::default().next_id(), 1);
+    }
+
+    #[test]
+    fn win_streak_survives_draws() {
+        let outcomes = [Outcome::Win, Outcome::Win, Outcome::Lose, Outcome::Win, Outcome::Draw, Outcome::Win, Outcome::Win];
+        let history = History { hands: outcomes.iter().enumerate().map(|(i, o)| hand(i as u64, o.clone())).collect() };
+
+        assert_eq!(history.longest_win_streak(), 3);
+    }
+
+    #[test]
+    fn merge_skips_known_hands() {
+        let mut history = History { hands: vec![hand(1, Outcome::Win), hand(2, Outcome::Lose)] };
+        let mut other_hand = hand(1, Outcome::Draw);
+        other_hand.timestamp = 1_600_000_000;
+        let other = History { hands: vec![hand(2, Outcome::Lose), other_hand] };
+
+        assert_eq!(history.merge(other.clone()), 1);
+        assert_eq!(history.hands[0].timestamp, 1_600_000_000);
+        assert_eq!(history.merge(other), 0);
+    }
+}
@@ -0,0 +1,98 @@
+//! An "are you sure?" before hitting a hard 17 or more, which busts more often than not, so a
+//! stray click on the deck doesn't end the hand. The warning shows on the first click and a
+//! second click on the deck while it's up hits.
+
+use crate::card::Card;
+use crate::game_engine;
+
+/// How long the warning stays up, waiting for the second click
+pub const WARNING_SECONDS: f32 = 3.0;
+/// Hard totals from this one up are warned about
+pub const RISKY_HARD_TOTAL: u32 = 17;
+
+/// Whether the hand is a hard 17 or more. A soft hand can't bust with one card.
+pub fn is_risky(cards: &[Card]) -> bool {
+    let (hard, soft) = game_engine::hand_totals(cards);
+
+    soft.is_none() && hard >= RISKY_HARD_TOTAL
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HitWarning {
+    time_left: f32,
+    confirmed: bool,
+}
+
+impl HitWarning {
+    /// Whether a hit on `cards` goes ahead. On a risky hand it only does once confirmed, the
+    /// warning is shown otherwise.
+    pub fn allow_hit(&mut self, cards: &[Card]) -> bool {
+        if !is_risky(cards) || self.confirmed {
+            *self = HitWarning::default();
+            return true;
+        }
+
+        self.time_left = WARNING_SECONDS;
+        false
+    }
+
+    /// A new click on the deck while the warning is up.
+    pub fn confirm(&mut self) {
+        if self.showing() {
+            self.confirmed = true;
+        }
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        self.time_left = (self.time_left - time_delta).max(0.0);
+    }
+
+    pub fn showing(&self) -> bool {
+        self.time_left > 0.0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn only_hard_17_and_up_is_risky() {
+        assert!(is_risky(&cards(&["10_of_clubs", "7_of_hearts"])));
+        assert!(is_risky(&cards(&["ace_of_clubs", "6_of_hearts", "king_of_spades"])));
+        assert!(!is_risky(&cards(&["ace_of_clubs", "6_of_hearts"])));
+        assert!(!is_risky(&cards(&["10_of_clubs", "6_of_hearts"])));
+    }
+
+    #[test]
+    fn risky_hit_needs_a_second_click() {
+        let hand = cards(&["10_of_clubs", "8_of_hearts"]);
+        let mut warning = HitWarning::default();
+
+        // a click held down keeps asking, without confirming
+        assert!(!warning.allow_hit(&hand));
+        assert!(!warning.allow_hit(&hand));
+        assert!(warning.showing());
+
+        warning.confirm();
+        assert!(warning.allow_hit(&hand));
+        assert!(!warning.showing());
+    }
+
+    #[test]
+    fn warning_runs_out() {
+        let hand = cards(&["10_of_clubs", "8_of_hearts"]);
+        let mut warning = HitWarning::default();
+
+        warning.allow_hit(&hand);
+        warning.update(WARNING_SECONDS);
+        warning.confirm();
+
+        assert!(!warning.allow_hit(&hand));
+    }
+}
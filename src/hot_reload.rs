@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How often(in seconds) the resources folder is scanned for changes
+const POLL_INTERVAL: f32 = 0.5;
+
+/// Watches the resources folder on disk and reports which assets were modified,
+/// so they can be reloaded without restarting the game.
+/// Assets built into the binary with "embed-assets" are not affected by it.
+pub struct AssetWatcher {
+    root: PathBuf,
+    modified: HashMap<PathBuf, SystemTime>,
+    time_till_poll: f32,
+}
+
+impl AssetWatcher {
+    pub fn new(root: &Path) -> Self {
+        AssetWatcher {
+            root: root.to_path_buf(),
+            modified: scan(root),
+            time_till_poll: POLL_INTERVAL,
+        }
+    }
+
+    /// Returns the ggez paths("/card_images/...") of all files changed since the last poll.
+    pub fn update(&mut self, time_delta: f32) -> Vec<String> {
+        self.time_till_poll -= time_delta;
+        if self.time_till_poll > 0.0 {
+            return Vec::new();
+        }
+        self.time_till_poll = POLL_INTERVAL;
+
+        let current = scan(&self.root);
+        let mut changed = Vec::new();
+
+        for (file, time) in &current {
+            if self.modified.get(file) != Some(time) {
+                if let Some(asset_path) = to_asset_path(&self.root, file) {
+                    changed.push(asset_path);
+                }
+            }
+        }
+
+        self.modified = current;
+
+        changed
+    }
+}
+
+fn scan(root: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut files = HashMap::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Ok(time) = entry.metadata().and_then(|m| m.modified()) {
+                files.insert(path, time);
+            }
+        }
+    }
+
+    files
+}
+
+fn to_asset_path(root: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(root).ok()?;
+
+    let parts: Vec<String> = relative.components().
+        map(|c| c.as_os_str().to_string_lossy().into_owned()).
+        collect();
+
+    Some(format!("/{}", parts.join("/")))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn to_asset_path_is_relative_with_forward_slashes() {
+        let root = Path::new("resources");
+        let file = root.join("card_images").join("ace_of_spades.png");
+
+        assert_eq!(to_asset_path(root, &file).unwrap(), "/card_images/ace_of_spades.png");
+    }
+
+    #[test]
+    fn update_reports_modified_file() {
+        let root = std::env::temp_dir().join(format!("blackjack_hot_reload_test_{}", std::process::id()));
+        fs::create_dir_all(root.join("sfx")).unwrap();
+        let file = root.join("sfx").join("card_deal.wav");
+        File::create(&file).unwrap();
+
+        let mut watcher = AssetWatcher::new(&root);
+        assert!(watcher.update(POLL_INTERVAL).is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(&file).unwrap().set_modified(later).unwrap();
+
+        assert_eq!(watcher.update(POLL_INTERVAL), vec!["/sfx/card_deal.wav".to_string()]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! What's written around the table while a hand is played: the scores, the power ups left, the
+//! wins and the hint. Each element hangs off an anchor of the layout(the top left and top right
+//! corners of the HUD, the hint has its own), so a new one only needs a spot and its lines. The
+//! texts are kept between frames and only built again when what they say changes.
+
+use crate::layout::{Anchor, Layout};
+use crate::score_counter::ScoreCounter;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+};
+
+use std::collections::HashMap;
+
+const LABEL_SIZE: f32 = 28.0;
+const SCORE_SIZE: f32 = 50.0;
+const HINT_SIZE: f32 = 35.0;
+
+const FLASH_COLOR: graphics::Color = graphics::Color { r: 1.0, g: 163.0 / 255.0, b: 26.0 / 255.0, a: 1.0 };
+const HANDICAP_COLOR: graphics::Color = graphics::Color { r: 204.0 / 255.0, g: 0.0, b: 0.0, a: 1.0 };
+
+/// What the HUD shows this frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HudState<'a> {
+    /// The player's and the dealer's
+    pub counters: [ScoreCounter; 2],
+    pub dealer_handicap_active: bool,
+    /// Hints and handicaps left, None when they are unlimited(practice)
+    pub power_ups: Option<(u32, u32)>,
+    pub wins: u32,
+    /// Written instead of the wins, in practice
+    pub practice_label: Option<&'a str>,
+    /// The range the next card falls in, while a hint is shown
+    pub hint_range: Option<(u32, u32)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HudElement {
+    Scores,
+    PowerUps,
+    Wins,
+    Hint,
+}
+
+/// A text of an element, `offset` away from its anchor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub text: String,
+    pub offset: (f32, f32),
+    pub size: f32,
+    pub color: graphics::Color,
+}
+
+impl Line {
+    fn new(text: String, offset: (f32, f32), size: f32) -> Self {
+        Line { text, offset, size, color: graphics::Color::WHITE }
+    }
+}
+
+impl HudElement {
+    /// Everything on the play screen, in drawing order
+    pub const PLAY: [HudElement; 4] = [HudElement::Scores, HudElement::PowerUps, HudElement::Wins, HudElement::Hint];
+
+    pub fn anchor(&self) -> Anchor {
+        match self {
+            HudElement::Scores => Anchor::HudTopLeft,
+            HudElement::PowerUps | HudElement::Wins => Anchor::HudTopRight,
+            HudElement::Hint => Anchor::HintText,
+        }
+    }
+
+    /// What the element writes, nothing when there's nothing to show.
+    pub fn lines(&self, state: &HudState) -> Vec<Line> {
+        match self {
+            HudElement::Scores => {
+                // a score flashes when it counts past 17 or 21
+                let color = |counter: &ScoreCounter, normal| if counter.flashing() { FLASH_COLOR } else { normal };
+                let dealer_color = if state.dealer_handicap_active { HANDICAP_COLOR } else { graphics::Color::WHITE };
+
+                vec![
+                    Line::new(String::from("PLAYER SCORE:"), (0.0, 0.0), LABEL_SIZE),
+                    Line { color: color(&state.counters[0], graphics::Color::WHITE),
+                           ..Line::new(state.counters[0].shown().to_string(), (80.0, 50.0), SCORE_SIZE) },
+                    Line::new(String::from("DEALER SCORE:"), (395.0, 0.0), LABEL_SIZE),
+                    Line { color: color(&state.counters[1], dealer_color),
+                           ..Line::new(state.counters[1].shown().to_string(), (480.0, 50.0), SCORE_SIZE) },
+                ]
+            },
+            HudElement::PowerUps => {
+                let count = |count: u32| match state.power_ups {
+                    Some(_) => format!(" x{}", count),
+                    None => String::from(" (unlimited)"),
+                };
+                let (hints, handicaps) = state.power_ups.unwrap_or((0, 0));
+                let text = format!("AVAILABLE POWER UPS:\n1. Next card approximation{}\n2. Activate dealer handicap{}\n",
+                                   count(hints), count(handicaps));
+
+                vec![Line::new(text, (0.0, 0.0), LABEL_SIZE)]
+            },
+            HudElement::Wins => {
+                let text = match state.practice_label {
+                    Some(label) => label.to_string(),
+                    None => format!("WINS: {}", state.wins),
+                };
+
+                vec![Line::new(text, (500.0, 0.0), LABEL_SIZE)]
+            },
+            HudElement::Hint => match state.hint_range {
+                Some((begin, end)) => vec![Line::new(format!("NEXT CARD GIVES BETWEEN: {}-{}", begin, end), (0.0, 0.0), HINT_SIZE)],
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+/// A text that is built again only when its line changes.
+#[derive(Debug, Clone)]
+struct CachedText {
+    line: Line,
+    text: graphics::Text,
+}
+
+/// The texts of the elements drawn so far, by element and line.
+#[derive(Debug, Clone, Default)]
+pub struct Hud {
+    texts: HashMap<(HudElement, usize), CachedText>,
+}
+
+impl Hud {
+    pub fn draw(&mut self, ctx: &mut Context, font: graphics::Font, layout: &Layout, state: &HudState, elements: &[HudElement]) -> GameResult<()> {
+        for element in elements {
+            for (i, line) in element.lines(state).into_iter().enumerate() {
+                let position = layout.offset(element.anchor(), line.offset.0, line.offset.1);
+                let text = self.text(*element, i, line, font);
+
+                graphics::draw(ctx, text, graphics::DrawParam::default().dest(position))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, element: HudElement, index: usize, line: Line, font: graphics::Font) -> &graphics::Text {
+        let cached = self.texts.entry((element, index)).or_insert_with(|| CachedText { line: line.clone(), text: build(&line, font) });
+        if cached.line != line {
+            cached.text = build(&line, font);
+            cached.line = line;
+        }
+
+        &cached.text
+    }
+}
+
+fn build(line: &Line, font: graphics::Font) -> graphics::Text {
+    let fragment = graphics::TextFragment::new(line.text.as_str()).
+                                           color(line.color).
+                                           font(font).
+                                           scale(graphics::PxScale::from(line.size));
+
+    graphics::Text::new(fragment)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> HudState<'static> {
+        HudState {
+            counters: [ScoreCounter::default(); 2],
+            dealer_handicap_active: false,
+            power_ups: Some((2, 1)),
+            wins: 7,
+            practice_label: None,
+            hint_range: None,
+        }
+    }
+
+    #[test]
+    fn elements_write_the_state() {
+        let mut state = state();
+        assert_eq!(HudElement::Wins.lines(&state)[0].text, "WINS: 7");
+        assert!(HudElement::PowerUps.lines(&state)[0].text.contains("approximation x2"));
+        assert!(HudElement::Hint.lines(&state).is_empty());
+
+        state.practice_label = Some("PRACTICE");
+        state.power_ups = None;
+        state.hint_range = Some((3, 7));
+        assert_eq!(HudElement::Wins.lines(&state)[0].text, "PRACTICE");
+        assert!(HudElement::PowerUps.lines(&state)[0].text.contains("handicap (unlimited)"));
+        assert_eq!(HudElement::Hint.lines(&state)[0].text, "NEXT CARD GIVES BETWEEN: 3-7");
+    }
+
+    #[test]
+    fn handicap_turns_the_dealer_score_red() {
+        let mut state = state();
+        state.dealer_handicap_active = true;
+
+        let lines = HudElement::Scores.lines(&state);
+        assert_eq!(lines[1].color, graphics::Color::WHITE);
+        assert_eq!(lines[3].color, HANDICAP_COLOR);
+    }
+}
@@ -0,0 +1,526 @@
+/// Which way a language's text runs, the UI mirrors horizontal positions for right-to-left ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+    Bulgarian,
+}
+
+pub const LANGUAGES: &[Language] = &[Language::English, Language::Bulgarian];
+
+impl Language {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Bulgarian => "bg",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        LANGUAGES.iter().find(|language| language.code() == code).copied()
+    }
+
+    /// Name of the language in the language itself, for the settings screen
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Bulgarian => "Български",
+        }
+    }
+
+    pub fn direction(&self) -> TextDirection {
+        match self {
+            Language::English | Language::Bulgarian => TextDirection::LeftToRight,
+        }
+    }
+
+    /// The decorative menu font only has Latin letters, other scripts use the regular font.
+    pub fn latin_script(&self) -> bool {
+        matches!(self, Language::English)
+    }
+}
+
+/// Every translated piece of UI text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Label {
+    MenuTitle,
+    Play,
+    Settings,
+    Leaderboard,
+    History,
+    Analysis,
+    RiskCalculator,
+    Bet,
+    Bankroll,
+    Betting,
+    DealerHandicap,
+    Simulate,
+    Heatmap,
+    Gallery,
+    Challenge,
+    ExportLog,
+    Trainer,
+    Review,
+    SessionSummary,
+    Quiz,
+    CountDrill,
+    Situation,
+    Missions,
+    Unlocks,
+    DealerAvatar,
+    About,
+    Practice,
+    Tip,
+    Profile,
+    Rename,
+    ResetStats,
+    DeleteProfile,
+    Undo,
+    Help,
+    Back,
+    YouWin,
+    YouDraw,
+    YouLose,
+    WhatIf,
+    WhatIfStand,
+    WhatIfHit,
+    WhatIfWin,
+    WhatIfPush,
+    WhatIfLose,
+    WorkingOutOdds,
+    PressEnter,
+    ReadsTheFolder,
+    EveryKeyOf,
+    NoBackupsYet,
+    StatsExportedTo,
+    CouldNotExportStats,
+    HandsImported,
+    WinsImported,
+    CouldNotImportStats,
+    LeaderboardOff,
+    ScoresNotSent,
+    Updating,
+    OfflineScores,
+    LeaderboardName,
+    LeaderboardBestStreak,
+    LeaderboardWins,
+    NoHandsThisSession,
+    NoHandsYet,
+    QuizDealerShows,
+    QuizYourHand,
+    QuizTotalPrompt,
+    QuizRight,
+    QuizAnswerIs,
+    QuizPlayAgain,
+    DrillBurnCard,
+    DrillCountPrompt,
+    DrillCountIs,
+    DrillDealAgain,
+    CardSet,
+    CardBack,
+    Felt,
+    Font,
+    Name,
+    Welcome,
+    UiScale,
+    Tutorial,
+    ExportStats,
+    ImportStats,
+    BackUpNow,
+    AdvancedSettings,
+    RestoreBackup,
+    StreamMode,
+    StreamHidePowerUps,
+    GameSpeed,
+    AutoStand,
+    AutoAdvance,
+    WarnRiskyHits,
+    WhatIfOdds,
+    ShowNextCard,
+    BurnCard,
+    GpuLight,
+    Language,
+}
+
+pub const LABELS: &[Label] = &[
+    Label::MenuTitle,
+    Label::Play,
+    Label::Settings,
+    Label::Leaderboard,
+    Label::History,
+    Label::Analysis,
+    Label::RiskCalculator,
+    Label::Bet,
+    Label::Bankroll,
+    Label::Betting,
+    Label::DealerHandicap,
+    Label::Simulate,
+    Label::Heatmap,
+    Label::Gallery,
+    Label::Challenge,
+    Label::ExportLog,
+    Label::Trainer,
+    Label::Review,
+    Label::SessionSummary,
+    Label::Quiz,
+    Label::CountDrill,
+    Label::Situation,
+    Label::Missions,
+    Label::Unlocks,
+    Label::DealerAvatar,
+    Label::About,
+    Label::Practice,
+    Label::Tip,
+    Label::Profile,
+    Label::Rename,
+    Label::ResetStats,
+    Label::DeleteProfile,
+    Label::Undo,
+    Label::Help,
+    Label::Back,
+    Label::YouWin,
+    Label::YouDraw,
+    Label::YouLose,
+    Label::WhatIf,
+    Label::WhatIfStand,
+    Label::WhatIfHit,
+    Label::WhatIfWin,
+    Label::WhatIfPush,
+    Label::WhatIfLose,
+    Label::WorkingOutOdds,
+    Label::PressEnter,
+    Label::ReadsTheFolder,
+    Label::EveryKeyOf,
+    Label::NoBackupsYet,
+    Label::StatsExportedTo,
+    Label::CouldNotExportStats,
+    Label::HandsImported,
+    Label::WinsImported,
+    Label::CouldNotImportStats,
+    Label::LeaderboardOff,
+    Label::ScoresNotSent,
+    Label::Updating,
+    Label::OfflineScores,
+    Label::LeaderboardName,
+    Label::LeaderboardBestStreak,
+    Label::LeaderboardWins,
+    Label::NoHandsThisSession,
+    Label::NoHandsYet,
+    Label::QuizDealerShows,
+    Label::QuizYourHand,
+    Label::QuizTotalPrompt,
+    Label::QuizRight,
+    Label::QuizAnswerIs,
+    Label::QuizPlayAgain,
+    Label::DrillBurnCard,
+    Label::DrillCountPrompt,
+    Label::DrillCountIs,
+    Label::DrillDealAgain,
+    Label::CardSet,
+    Label::CardBack,
+    Label::Felt,
+    Label::Font,
+    Label::Name,
+    Label::Welcome,
+    Label::UiScale,
+    Label::Tutorial,
+    Label::ExportStats,
+    Label::ImportStats,
+    Label::BackUpNow,
+    Label::AdvancedSettings,
+    Label::RestoreBackup,
+    Label::StreamMode,
+    Label::StreamHidePowerUps,
+    Label::GameSpeed,
+    Label::AutoStand,
+    Label::AutoAdvance,
+    Label::WarnRiskyHits,
+    Label::WhatIfOdds,
+    Label::ShowNextCard,
+    Label::BurnCard,
+    Label::GpuLight,
+    Label::Language,
+];
+
+pub fn tr(language: Language, label: Label) -> &'static str {
+    match language {
+        Language::English => match label {
+            Label::MenuTitle => "MENU",
+            Label::Play => "PLAY",
+            Label::Settings => "SETTINGS",
+            Label::Leaderboard => "LEADERBOARD",
+            Label::History => "HISTORY",
+            Label::Analysis => "ANALYSIS",
+            Label::RiskCalculator => "RISK CALCULATOR",
+            Label::Bet => "BET",
+            Label::Bankroll => "BANKROLL",
+            Label::Betting => "BETTING",
+            Label::DealerHandicap => "DEALER HANDICAP",
+            Label::Simulate => "SIMULATE",
+            Label::Heatmap => "HEATMAP",
+            Label::Gallery => "GALLERY",
+            Label::Challenge => "CHALLENGE A FRIEND",
+            Label::ExportLog => "EXPORT LOG",
+            Label::Trainer => "TRAINER",
+            Label::Review => "REVIEW",
+            Label::SessionSummary => "SESSION SUMMARY",
+            Label::Quiz => "QUIZ",
+            Label::CountDrill => "COUNT DRILL",
+            Label::Situation => "SITUATION",
+            Label::Missions => "MISSIONS",
+            Label::Unlocks => "UNLOCKS",
+            Label::DealerAvatar => "DEALER",
+            Label::About => "ABOUT",
+            Label::Practice => "PRACTICE",
+            Label::Tip => "TIP",
+            Label::Profile => "PROFILE",
+            Label::Rename => "RENAME",
+            Label::ResetStats => "RESET STATISTICS",
+            Label::DeleteProfile => "DELETE PROFILE",
+            Label::Undo => "UNDO",
+            Label::Help => "HELP",
+            Label::Back => "BACK",
+            Label::YouWin => "YOU WIN!",
+            Label::YouDraw => "YOU DRAW!",
+            Label::YouLose => "YOU LOSE!",
+            Label::WhatIf => "WHAT IF",
+            Label::WhatIfStand => "Stand",
+            Label::WhatIfHit => "Hit",
+            Label::WhatIfWin => "win",
+            Label::WhatIfPush => "push",
+            Label::WhatIfLose => "lose",
+            Label::WorkingOutOdds => "Working out the odds...",
+            Label::PressEnter => "press Enter",
+            Label::ReadsTheFolder => "reads the folder",
+            Label::EveryKeyOf => "every key of",
+            Label::NoBackupsYet => "no backups yet",
+            Label::StatsExportedTo => "Stats exported to",
+            Label::CouldNotExportStats => "Could not export stats",
+            Label::HandsImported => "Hands imported",
+            Label::WinsImported => "wins",
+            Label::CouldNotImportStats => "Could not import stats",
+            Label::LeaderboardOff => "The online leaderboard is off, to join one set this in",
+            Label::ScoresNotSent => "Your stats file was changed outside the game, so your scores aren't sent",
+            Label::Updating => "Updating...",
+            Label::OfflineScores => "Offline, showing the last known scores",
+            Label::LeaderboardName => "NAME",
+            Label::LeaderboardBestStreak => "BEST STREAK",
+            Label::LeaderboardWins => "WINS",
+            Label::NoHandsThisSession => "No hands played yet this session.",
+            Label::NoHandsYet => "No hands played yet. Your stats show up here after a few hands.",
+            Label::QuizDealerShows => "Dealer shows",
+            Label::QuizYourHand => "Your hand",
+            Label::QuizTotalPrompt => "What is the hand worth? Type it and press Enter",
+            Label::QuizRight => "Right!",
+            Label::QuizAnswerIs => "The answer is",
+            Label::QuizPlayAgain => "Press Enter to play another round.",
+            Label::DrillBurnCard => "Burn card, it counts too",
+            Label::DrillCountPrompt => "What is the running count? Type it and press Enter",
+            Label::DrillCountIs => "The count is",
+            Label::DrillDealAgain => "Press Enter to deal another round.",
+            Label::CardSet => "CARD SET",
+            Label::CardBack => "CARD BACK",
+            Label::Felt => "FELT",
+            Label::Font => "FONT",
+            Label::Name => "NAME",
+            Label::Welcome => "WELCOME",
+            Label::UiScale => "UI SCALE",
+            Label::Tutorial => "TUTORIAL",
+            Label::ExportStats => "EXPORT STATS",
+            Label::ImportStats => "IMPORT STATS",
+            Label::BackUpNow => "BACK UP NOW",
+            Label::AdvancedSettings => "ADVANCED SETTINGS",
+            Label::RestoreBackup => "RESTORE BACKUP",
+            Label::StreamMode => "STREAM MODE",
+            Label::StreamHidePowerUps => "HIDE POWER UPS ON STREAM",
+            Label::GameSpeed => "GAME SPEED",
+            Label::AutoStand => "STAND ON 21 BY ITSELF",
+            Label::AutoAdvance => "DEAL THE NEXT HAND BY ITSELF",
+            Label::WarnRiskyHits => "ASK BEFORE HITTING A HARD 17+",
+            Label::WhatIfOdds => "SHOW THE ODDS OF HIT AND STAND",
+            Label::ShowNextCard => "SHOW WHAT A HIT WOULD HAVE BROUGHT",
+            Label::BurnCard => "BURN THE FIRST CARD",
+            Label::GpuLight => "GPU-LIGHT MODE(FOR OLD GRAPHICS)",
+            Label::Language => "LANGUAGE",
+        },
+        Language::Bulgarian => match label {
+            Label::MenuTitle => "МЕНЮ",
+            Label::Play => "ИГРАЙ",
+            Label::Settings => "НАСТРОЙКИ",
+            Label::Leaderboard => "КЛАСАЦИЯ",
+            Label::History => "ИСТОРИЯ",
+            Label::Analysis => "АНАЛИЗ",
+            Label::RiskCalculator => "КАЛКУЛАТОР НА РИСКА",
+            Label::Bet => "ЗАЛОГ",
+            Label::Bankroll => "БАНКА",
+            Label::Betting => "ЗАЛАГАНЕ",
+            Label::DealerHandicap => "ХЕНДИКАП ЗА КРУПИЕТО",
+            Label::Simulate => "СИМУЛИРАЙ",
+            Label::Heatmap => "ТОПЛИННА КАРТА",
+            Label::Gallery => "ГАЛЕРИЯ",
+            Label::Challenge => "ПРЕДИЗВИКАЙ ПРИЯТЕЛ",
+            Label::ExportLog => "ИЗНОС НА ДНЕВНИКА",
+            Label::Trainer => "ТРЕНИРОВКА",
+            Label::Review => "ПРЕГЛЕД",
+            Label::SessionSummary => "ОБОБЩЕНИЕ НА СЕСИЯТА",
+            Label::Quiz => "ВИКТОРИНА",
+            Label::CountDrill => "БРОЕНЕ НА КАРТИ",
+            Label::Situation => "СИТУАЦИЯ",
+            Label::Missions => "МИСИИ",
+            Label::Unlocks => "ОТКЛЮЧВАНИЯ",
+            Label::DealerAvatar => "КРУПИЕ",
+            Label::About => "ЗА ИГРАТА",
+            Label::Practice => "УПРАЖНЕНИЕ",
+            Label::Tip => "БАКШИШ",
+            Label::Profile => "ПРОФИЛ",
+            Label::Rename => "ПРЕИМЕНУВАЙ",
+            Label::ResetStats => "НУЛИРАЙ СТАТИСТИКАТА",
+            Label::DeleteProfile => "ИЗТРИЙ ПРОФИЛА",
+            Label::Undo => "ОТМЕНИ",
+            Label::Help => "ПОМОЩ",
+            Label::Back => "НАЗАД",
+            Label::YouWin => "ПЕЧЕЛИШ!",
+            Label::YouDraw => "РАВЕНСТВО!",
+            Label::YouLose => "ГУБИШ!",
+            Label::WhatIf => "КАКВО АКО",
+            Label::WhatIfStand => "Стоп",
+            Label::WhatIfHit => "Карта",
+            Label::WhatIfWin => "печалба",
+            Label::WhatIfPush => "равенство",
+            Label::WhatIfLose => "загуба",
+            Label::WorkingOutOdds => "Шансовете се изчисляват...",
+            Label::PressEnter => "натисни Ентър",
+            Label::ReadsTheFolder => "чете папката",
+            Label::EveryKeyOf => "всички ключове в",
+            Label::NoBackupsYet => "още няма резервни копия",
+            Label::StatsExportedTo => "Статистиката е изнесена в",
+            Label::CouldNotExportStats => "Статистиката не можа да се изнесе",
+            Label::HandsImported => "Внесени ръце",
+            Label::WinsImported => "победи",
+            Label::CouldNotImportStats => "Статистиката не можа да се внесе",
+            Label::LeaderboardOff => "Онлайн класацията е изключена, за да се включиш, задай това в",
+            Label::ScoresNotSent => "Файлът със статистиката е променян извън играта, затова резултатите ти не се изпращат",
+            Label::Updating => "Обновяване...",
+            Label::OfflineScores => "Няма връзка, показват се последните известни резултати",
+            Label::LeaderboardName => "ИМЕ",
+            Label::LeaderboardBestStreak => "НАЙ-ДЪЛГА СЕРИЯ",
+            Label::LeaderboardWins => "ПОБЕДИ",
+            Label::NoHandsThisSession => "Още няма изиграни ръце в тази сесия.",
+            Label::NoHandsYet => "Още няма изиграни ръце. Статистиката ще се появи тук след няколко ръце.",
+            Label::QuizDealerShows => "Дилърът показва",
+            Label::QuizYourHand => "Твоята ръка",
+            Label::QuizTotalPrompt => "Колко точки е ръката? Напиши ги и натисни Ентър",
+            Label::QuizRight => "Вярно!",
+            Label::QuizAnswerIs => "Отговорът е",
+            Label::QuizPlayAgain => "Натисни Ентър за още един рунд.",
+            Label::DrillBurnCard => "Изгорена карта, и тя се брои",
+            Label::DrillCountPrompt => "Колко е текущият брой? Напиши го и натисни Ентър",
+            Label::DrillCountIs => "Броят е",
+            Label::DrillDealAgain => "Натисни Ентър, за да раздадеш още един рунд.",
+            Label::CardSet => "КАРТИ",
+            Label::CardBack => "ГРЪБ НА КАРТИТЕ",
+            Label::Felt => "ПЛАТ НА МАСАТА",
+            Label::Font => "ШРИФТ",
+            Label::Name => "ИМЕ",
+            Label::Welcome => "ДОБРЕ ДОШЛИ",
+            Label::UiScale => "МАЩАБ",
+            Label::Tutorial => "ОБУЧЕНИЕ",
+            Label::ExportStats => "ИЗНОС НА СТАТИСТИКАТА",
+            Label::ImportStats => "ВНОС НА СТАТИСТИКА",
+            Label::BackUpNow => "РЕЗЕРВНО КОПИЕ СЕГА",
+            Label::AdvancedSettings => "РАЗШИРЕНИ НАСТРОЙКИ",
+            Label::RestoreBackup => "ВЪЗСТАНОВИ КОПИЕ",
+            Label::StreamMode => "РЕЖИМ ЗА СТРИЙМ",
+            Label::StreamHidePowerUps => "СКРИЙ БОНУСИТЕ В СТРИЙМА",
+            Label::GameSpeed => "СКОРОСТ НА ИГРАТА",
+            Label::AutoStand => "АВТОМАТИЧНО СТОП НА 21",
+            Label::AutoAdvance => "АВТОМАТИЧНО СЛЕДВАЩА РЪКА",
+            Label::WarnRiskyHits => "ПИТАЙ ПРЕДИ КАРТА НА ТВЪРДИ 17+",
+            Label::WhatIfOdds => "ПОКАЖИ ШАНСОВЕТЕ ПРИ КАРТА И СТОП",
+            Label::ShowNextCard => "ПОКАЖИ КАКВА КАРТА БИ ДОШЛА",
+            Label::BurnCard => "ИЗГАРЯНЕ НА ПЪРВАТА КАРТА",
+            Label::GpuLight => "ЛЕК РЕЖИМ(ЗА СТАРИ ВИДЕОКАРТИ)",
+            Label::Language => "ЕЗИК",
+        },
+    }
+}
+
+/// Family mode(see Config::family_mode) words for the labels that talk about betting.
+/// Labels that aren't here read the same in both modes.
+fn family_tr(language: Language, label: Label) -> Option<&'static str> {
+    match language {
+        Language::English => match label {
+            Label::RiskCalculator => Some("ODDS CALCULATOR"),
+            Label::Bet => Some("POINTS PER HAND"),
+            Label::Bankroll => Some("POINTS"),
+            Label::Betting => Some("SCORING"),
+            _ => None,
+        },
+        Language::Bulgarian => match label {
+            Label::RiskCalculator => Some("КАЛКУЛАТОР НА ШАНСОВЕТЕ"),
+            Label::Bet => Some("ТОЧКИ НА РЪКА"),
+            Label::Bankroll => Some("ТОЧКИ"),
+            Label::Betting => Some("ТОЧКУВАНЕ"),
+            _ => None,
+        },
+    }
+}
+
+/// The label in the wording of the mode the game is in.
+pub fn tr_mode(language: Language, family_mode: bool, label: Label) -> &'static str {
+    if family_mode {
+        family_tr(language, label).unwrap_or_else(|| tr(language, label))
+    } else {
+        tr(language, label)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for language in LANGUAGES {
+            assert_eq!(Language::from_code(language.code()), Some(*language));
+        }
+        assert_eq!(Language::from_code("xx"), None);
+    }
+
+    #[test]
+    fn every_label_is_translated() {
+        for language in LANGUAGES {
+            for label in LABELS {
+                assert!(!tr(*language, *label).is_empty(), "{:?} is missing {:?}", language, label);
+            }
+        }
+    }
+
+    #[test]
+    fn bulgarian_is_cyrillic() {
+        let cyrillic = |c: char| ('\u{0400}'..='\u{04FF}').contains(&c);
+
+        for label in LABELS {
+            assert!(tr(Language::Bulgarian, *label).chars().all(|c| cyrillic(c) || !c.is_alphabetic()));
+            assert!(tr_mode(Language::Bulgarian, true, *label).chars().all(|c| cyrillic(c) || !c.is_alphabetic()));
+        }
+    }
+
+    #[test]
+    fn family_mode_has_no_betting_words() {
+        for label in LABELS {
+            let text = tr_mode(Language::English, true, *label);
+
+            for word in ["BET", "BANKROLL", "RISK", "CHIP", "GAMBL"] {
+                assert!(!text.contains(word), "{:?} reads \"{}\" in family mode", label, text);
+            }
+        }
+        assert_eq!(tr_mode(Language::English, true, Label::Play), tr(Language::English, Label::Play));
+    }
+}
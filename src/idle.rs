@@ -0,0 +1,65 @@
+//! Leaving an idle session: after a while without a key, a click or the mouse moving, and with
+//! no hand being played, the game saves and goes back to the menu. A session left running when
+//! the machine sleeps or the player walks away then has nothing waiting to be saved.
+
+/// Counts the time since the player last did anything.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IdleTimer {
+    limit: f32,
+    idle: f32,
+}
+
+impl IdleTimer {
+    /// `minutes` without input, 0 turns it off.
+    pub fn new(minutes: u32) -> Self {
+        IdleTimer { limit: minutes as f32 * 60.0, idle: 0.0 }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.limit > 0.0
+    }
+
+    /// Any input, which starts the count again.
+    pub fn input(&mut self) {
+        self.idle = 0.0;
+    }
+
+    /// True once, when the time without input runs out.
+    pub fn update(&mut self, time_delta: f32) -> bool {
+        if !self.is_on() {
+            return false;
+        }
+
+        let was_idle = self.idle >= self.limit;
+        self.idle += time_delta;
+
+        !was_idle && self.idle >= self.limit
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_off_once_per_idle_stretch() {
+        let mut idle = IdleTimer::new(1);
+
+        assert!(!idle.update(59.0));
+        assert!(idle.update(1.0));
+        assert!(!idle.update(60.0));
+
+        idle.input();
+        assert!(!idle.update(30.0));
+        assert!(idle.update(30.0));
+    }
+
+    #[test]
+    fn zero_is_off() {
+        let mut idle = IdleTimer::new(0);
+
+        assert!(!idle.is_on());
+        assert!(!idle.update(1.0e6));
+    }
+}
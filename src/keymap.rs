@@ -0,0 +1,199 @@
+//! Keys of the table's actions. Every action can have more than one key and they can be
+//! changed in config.txt, one "key_<action> = <keys>" line per action with the key names
+//! separated by spaces, like "key_stand = Space S".
+
+use ggez::event::KeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyAction {
+    Hit,
+    Stand,
+    Hint,
+    Handicap,
+    GameSpeed,
+    Coach,
+    MinimalHud,
+}
+
+pub const KEY_ACTIONS: &[KeyAction] = &[
+    KeyAction::Hit,
+    KeyAction::Stand,
+    KeyAction::Hint,
+    KeyAction::Handicap,
+    KeyAction::GameSpeed,
+    KeyAction::Coach,
+    KeyAction::MinimalHud,
+];
+
+impl KeyAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyAction::Hit => "hit",
+            KeyAction::Stand => "stand",
+            KeyAction::Hint => "hint",
+            KeyAction::Handicap => "handicap",
+            KeyAction::GameSpeed => "game_speed",
+            KeyAction::Coach => "coach",
+            KeyAction::MinimalHud => "minimal_hud",
+        }
+    }
+
+    /// Key of the action's line in config.txt
+    pub fn config_key(&self) -> String {
+        format!("key_{}", self.name())
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            KeyAction::Hit => vec![KeyCode::H],
+            KeyAction::Stand => vec![KeyCode::Space, KeyCode::S],
+            KeyAction::Hint => vec![KeyCode::Key1],
+            KeyAction::Handicap => vec![KeyCode::Key2],
+            KeyAction::GameSpeed => vec![KeyCode::G],
+            KeyAction::Coach => vec![KeyCode::C],
+            KeyAction::MinimalHud => vec![KeyCode::M],
+        }
+    }
+}
+
+/// Keys that can be bound and their names in config.txt. R, Enter, Escape and the arrows
+/// already do something on most screens, so they're left out.
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::A, "A"), (KeyCode::B, "B"), (KeyCode::C, "C"), (KeyCode::D, "D"),
+    (KeyCode::E, "E"), (KeyCode::F, "F"), (KeyCode::G, "G"), (KeyCode::H, "H"),
+    (KeyCode::I, "I"), (KeyCode::J, "J"), (KeyCode::K, "K"), (KeyCode::L, "L"),
+    (KeyCode::M, "M"), (KeyCode::N, "N"), (KeyCode::O, "O"), (KeyCode::P, "P"),
+    (KeyCode::Q, "Q"), (KeyCode::S, "S"), (KeyCode::T, "T"), (KeyCode::U, "U"),
+    (KeyCode::V, "V"), (KeyCode::W, "W"), (KeyCode::X, "X"), (KeyCode::Y, "Y"),
+    (KeyCode::Z, "Z"),
+    (KeyCode::Key1, "1"), (KeyCode::Key2, "2"), (KeyCode::Key3, "3"), (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"), (KeyCode::Key6, "6"), (KeyCode::Key7, "7"), (KeyCode::Key8, "8"),
+    (KeyCode::Key9, "9"), (KeyCode::Key0, "0"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Tab, "Tab"),
+];
+
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES.iter().find(|(code, _)| *code == key).map(|(_, name)| *name)
+}
+
+/// Case doesn't matter, "space" works too
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|(_, key_name)| key_name.eq_ignore_ascii_case(name)).map(|(code, _)| *code)
+}
+
+fn action_index(action: KeyAction) -> usize {
+    KEY_ACTIONS.iter().position(|known| *known == action).unwrap_or(0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    /// Keys of each action, indexed like KEY_ACTIONS
+    keys: Vec<Vec<KeyCode>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap {
+            keys: KEY_ACTIONS.iter().map(KeyAction::default_keys).collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    pub fn action(&self, key: KeyCode) -> Option<KeyAction> {
+        KEY_ACTIONS.iter().
+            zip(&self.keys).
+            find(|(_, keys)| keys.contains(&key)).
+            map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: KeyAction) -> Vec<KeyCode> {
+        self.keys[action_index(action)].clone()
+    }
+
+    /// Gives the action these keys instead of its old ones. A key can only do one thing, so
+    /// other actions lose them.
+    pub fn bind(&mut self, action: KeyAction, keys: &[KeyCode]) {
+        for bound in &mut self.keys {
+            bound.retain(|key| !keys.contains(key));
+        }
+        self.keys[action_index(action)] = keys.to_vec();
+    }
+
+    /// Reads a "key_<action>" line of config.txt. False when it isn't one, or when a key name is
+    /// unknown, which keeps the action's keys.
+    pub fn parse_line(&mut self, key: &str, value: &str) -> bool {
+        let action = match KEY_ACTIONS.iter().find(|action| action.config_key() == key) {
+            Some(action) => *action,
+            None => return false,
+        };
+
+        let keys: Option<Vec<KeyCode>> = value.split_whitespace().map(key_from_name).collect();
+        match keys {
+            Some(keys) if !keys.is_empty() => {
+                self.bind(action, &keys);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// "H" or "Space / S", for showing the keys
+    pub fn describe(&self, action: KeyAction) -> String {
+        self.keys(action).iter().filter_map(|key| key_name(*key)).collect::<Vec<&str>>().join(" / ")
+    }
+
+    /// "Space S", the keys as config.txt has them
+    pub fn names(&self, action: KeyAction) -> String {
+        self.keys(action).iter().filter_map(|key| key_name(*key)).collect::<Vec<&str>>().join(" ")
+    }
+
+    /// The "key_<action> = <keys>" lines of config.txt
+    pub fn to_file_contents(&self) -> String {
+        KEY_ACTIONS.iter().
+            map(|action| format!("{} = {}\n", action.config_key(), self.names(*action))).
+            collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_play_from_the_keyboard() {
+        let keys = KeyMap::default();
+
+        assert_eq!(keys.action(KeyCode::H), Some(KeyAction::Hit));
+        assert_eq!(keys.action(KeyCode::S), Some(KeyAction::Stand));
+        assert_eq!(keys.action(KeyCode::Space), Some(KeyAction::Stand));
+        assert_eq!(keys.action(KeyCode::R), None);
+        assert_eq!(keys.describe(KeyAction::Stand), "Space / S");
+    }
+
+    #[test]
+    fn binding_takes_the_key_from_other_actions() {
+        let mut keys = KeyMap::default();
+        keys.bind(KeyAction::Hit, &[KeyCode::S]);
+
+        assert_eq!(keys.keys(KeyAction::Hit), [KeyCode::S]);
+        assert_eq!(keys.keys(KeyAction::Stand), [KeyCode::Space]);
+    }
+
+    #[test]
+    fn config_lines_round_trip() {
+        let mut keys = KeyMap::default();
+        assert!(keys.parse_line("key_hit", "space d"));
+        assert!(!keys.parse_line("key_stand", "Escape"));
+        assert!(!keys.parse_line("card_set", "neon"));
+
+        let mut parsed = KeyMap::default();
+        for (key, value) in keys.to_file_contents().lines().filter_map(|line| line.split_once(" = ")) {
+            parsed.parse_line(key, value);
+        }
+        assert_eq!(parsed.keys(KeyAction::Hit), [KeyCode::Space, KeyCode::D]);
+        assert_eq!(parsed.keys(KeyAction::Stand), [KeyCode::S]);
+    }
+}
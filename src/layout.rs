@@ -0,0 +1,473 @@
+//! Where things go on the screens. Every position has a name and a default here; a
+//! `layout.txt` next to the config can move any of them("deck = 120, 180") and scale the screen
+//! with everything on it("scale = 0.8"), so the table can be tweaked or themed without building the game again.
+//!
+//! The table can also be mirrored left to right for left-handed players(`left_handed` in
+//! config.txt): a position on the table says how far right of it its thing reaches, and is
+//! moved so the thing ends as far from the right edge as it started from the left one.
+//!
+//! A window narrower than COMPACT_BELOW_WIDTH(at the UI scale) gets the compact table, made for
+//! 1280x720 and tiled windows: smaller cards, the HUD stacked in one corner and hands going on
+//! in a second row. Its positions are fixed, layout.txt moves the full table's.
+
+use crate::config;
+use crate::window;
+use crate::i18n::TextDirection;
+
+use ggez::{graphics::Rect, mint::Point2};
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+pub const LAYOUT_FILE_NAME: &str = "layout.txt";
+
+/// Extra space around a text that still counts as clicking it
+const BUTTON_PADDING_X: f32 = 10.0;
+const BUTTON_PADDING_Y: f32 = 20.0;
+
+/// Windows narrower than this, at a UI scale of 1, get the compact table
+const COMPACT_BELOW_WIDTH: f32 = 1500.0;
+/// The width the compact table is made for
+const COMPACT_WIDTH: f32 = 1280.0;
+/// The size of the cards on the compact table
+const COMPACT_CARD_SIZE: f32 = 0.7;
+/// Cards in a row of the compact table, the hand goes on in a row under it
+const COMPACT_CARDS_PER_ROW: usize = 4;
+
+/// Declares the named positions with their defaults, in pixels of the 1900x900 window. The
+/// ones on the table have their position on the compact table after "compact", and end with
+/// "mirror" and the width of what's drawn right of them.
+macro_rules! anchors {
+    (@width) => { None };
+    (@width $width:expr) => { Some($width) };
+    (@compact) => { None };
+    (@compact $x:expr, $y:expr) => { Some(Point2 { x: $x, y: $y }) };
+    ($($(#[doc = $doc:literal])* $anchor:ident: $key:literal = ($x:expr, $y:expr) $(compact ($compact_x:expr, $compact_y:expr))? $(mirror $width:expr)?),* $(,)?) => {
+        /// A named position on one of the screens.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Anchor {
+            $($(#[doc = $doc])* $anchor),*
+        }
+
+        impl Anchor {
+            pub const ALL: &'static [Anchor] = &[$(Anchor::$anchor),*];
+
+            /// Its name in the layout file
+            pub fn key(&self) -> &'static str {
+                match self {
+                    $(Anchor::$anchor => $key),*
+                }
+            }
+
+            pub fn default_position(&self) -> Point2<f32> {
+                match self {
+                    $(Anchor::$anchor => Point2 { x: $x, y: $y }),*
+                }
+            }
+
+            pub fn from_key(key: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|anchor| anchor.key() == key)
+            }
+
+            /// How far right of the position its thing reaches, None off the table
+            pub fn table_width(&self) -> Option<f32> {
+                match self {
+                    $(Anchor::$anchor => anchors!(@width $($width)?)),*
+                }
+            }
+
+            /// Where it is on the compact table, None off the table
+            pub fn compact_position(&self) -> Option<Point2<f32>> {
+                match self {
+                    $(Anchor::$anchor => anchors!(@compact $($compact_x, $compact_y)?)),*
+                }
+            }
+        }
+    };
+}
+
+anchors! {
+    /// Middle of the deck, where dealt cards start from
+    Deck: "deck" = (100.0, 160.0) compact (80.0, 110.0) mirror 0.0,
+    /// Middle of the card burned after the shuffle, while it's on the table
+    BurnCard: "burn_card" = (1000.0, 250.0) compact (960.0, 330.0) mirror 0.0,
+    /// Middle of the first card of each row, the next ones go to the right(to the left on the
+    /// mirrored table)
+    PlayerFirstCard: "player_first_card" = (100.0, 770.0) compact (420.0, 570.0) mirror 0.0,
+    DealerFirstCard: "dealer_first_card" = (100.0, 475.0) compact (420.0, 330.0) mirror 0.0,
+    SplashTitle: "splash_title" = (560.0, 260.0),
+    /// Where the first card of the intro stops, the others follow to the right
+    SplashFirstCard: "splash_first_card" = (620.0, 480.0),
+    MenuTitle: "menu_title" = (750.0, 160.0),
+    MenuPlayText: "menu_play_text" = (790.0, 260.0),
+    MenuSettingsText: "menu_settings_text" = (790.0, 345.0),
+    MenuLeaderboardText: "menu_leaderboard_text" = (790.0, 430.0),
+    MenuHistoryText: "menu_history_text" = (790.0, 515.0),
+    MenuAnalysisText: "menu_analysis_text" = (790.0, 600.0),
+    MenuTrainerText: "menu_trainer_text" = (790.0, 685.0),
+    MenuHelpText: "menu_help_text" = (790.0, 770.0),
+    MissionsPanel: "missions_panel" = (1250.0, 300.0),
+    MenuUnlocksText: "menu_unlocks_text" = (1250.0, 770.0),
+    MenuAboutText: "menu_about_text" = (1600.0, 770.0),
+    MenuProfileText: "menu_profile_text" = (1250.0, 690.0),
+    MenuPracticeText: "menu_practice_text" = (1600.0, 690.0),
+    ShoePanel: "shoe_panel" = (1450.0, 120.0) compact (850.0, 150.0) mirror 420.0,
+    CardPicker: "card_picker" = (1450.0, 560.0) compact (850.0, 380.0) mirror 420.0,
+    ProfileTitle: "profile_title" = (800.0, 50.0),
+    ProfileSummary: "profile_summary" = (100.0, 180.0),
+    ProfileFirstRow: "profile_first_row" = (100.0, 420.0),
+    ProfilePrompt: "profile_prompt" = (100.0, 620.0),
+    ProfileUndoText: "profile_undo_text" = (100.0, 700.0),
+    AboutTitle: "about_title" = (800.0, 50.0),
+    AboutText: "about_text" = (100.0, 170.0),
+    UnlocksTitle: "unlocks_title" = (790.0, 50.0),
+    UnlocksAchievements: "unlocks_achievements" = (100.0, 180.0),
+    UnlocksItems: "unlocks_items" = (900.0, 180.0),
+    DealerAvatar: "dealer_avatar" = (1780.0, 380.0) compact (1200.0, 300.0) mirror 0.0,
+    DealerThanks: "dealer_thanks" = (1400.0, 240.0) compact (820.0, 200.0) mirror 360.0,
+    /// Where the tail of the dealer's speech bubble ends, the bubble is left of it(right of it
+    /// on the mirrored table)
+    TableTalk: "table_talk" = (1730.0, 320.0) compact (1160.0, 240.0) mirror 0.0,
+    TipText: "tip_text" = (1720.0, 460.0) compact (1150.0, 400.0) mirror 110.0,
+    Placard: "placard" = (320.0, 100.0) compact (850.0, 20.0) mirror 430.0,
+    HelpTitle: "help_title" = (800.0, 50.0),
+    HelpDescription: "help_description" = (50.0, 200.0),
+    HelpBackText: "help_back_text" = (1600.0, 800.0),
+    SettingsTitle: "settings_title" = (760.0, 50.0),
+    ConfigEditorTitle: "config_editor_title" = (660.0, 40.0),
+    ConfigEditorFirstRow: "config_editor_first_row" = (50.0, 140.0),
+    ConfigEditorControls: "config_editor_controls" = (50.0, 790.0),
+    SetupTitle: "setup_title" = (760.0, 50.0),
+    SetupFirstRow: "setup_first_row" = (300.0, 250.0),
+    SetupControls: "setup_controls" = (300.0, 700.0),
+    SettingsFirstRow: "settings_first_row" = (50.0, 150.0),
+    SettingsControls: "settings_controls" = (50.0, 820.0),
+    Toast: "toast" = (50.0, 860.0),
+    LeaderboardTitle: "leaderboard_title" = (700.0, 50.0),
+    LeaderboardFirstRow: "leaderboard_first_row" = (500.0, 200.0),
+    LeaderboardChallengeText: "leaderboard_challenge_text" = (100.0, 800.0),
+    ChallengeTitle: "challenge_title" = (640.0, 50.0),
+    ChallengeFirstRow: "challenge_first_row" = (100.0, 160.0),
+    ChallengeExportText: "challenge_export_text" = (1000.0, 800.0),
+    TranscriptTitle: "transcript_title" = (780.0, 50.0),
+    TranscriptFirstRow: "transcript_first_row" = (50.0, 170.0),
+    AnalysisTitle: "analysis_title" = (780.0, 50.0),
+    AnalysisCalculatorText: "analysis_calculator_text" = (100.0, 800.0),
+    AnalysisHeatmapText: "analysis_heatmap_text" = (800.0, 800.0),
+    AnalysisGalleryText: "analysis_gallery_text" = (1400.0, 800.0),
+    GalleryTitle: "gallery_title" = (780.0, 50.0),
+    GallerySummary: "gallery_summary" = (100.0, 160.0),
+    GalleryFirstRow: "gallery_first_row" = (100.0, 230.0),
+    GalleryControls: "gallery_controls" = (100.0, 760.0),
+    HeatmapTitle: "heatmap_title" = (780.0, 40.0),
+    HeatmapWorst: "heatmap_worst" = (1400.0, 180.0),
+    HeatmapLegend: "heatmap_legend" = (100.0, 740.0),
+    CalculatorTitle: "calculator_title" = (640.0, 50.0),
+    CalculatorFirstRow: "calculator_first_row" = (50.0, 200.0),
+    CalculatorFirstResult: "calculator_first_result" = (50.0, 480.0),
+    /// Middle of the first chip of the tray, and the bottom middle of the first stack of the bet
+    CalculatorChipTray: "calculator_chip_tray" = (100.0, 830.0),
+    CalculatorChipStacks: "calculator_chip_stacks" = (620.0, 430.0),
+    /// The recent bets, on the bet row
+    CalculatorRecentBets: "calculator_recent_bets" = (330.0, 200.0),
+    TrainerTitle: "trainer_title" = (780.0, 50.0),
+    TrainerQuizText: "trainer_quiz_text" = (100.0, 220.0),
+    TrainerDrillText: "trainer_drill_text" = (100.0, 420.0),
+    TrainerStats: "trainer_stats" = (100.0, 700.0),
+    TrainerReviewText: "trainer_review_text" = (1100.0, 800.0),
+    TrainerSituationText: "trainer_situation_text" = (1100.0, 600.0),
+    ReviewTitle: "review_title" = (800.0, 50.0),
+    ReviewSummary: "review_summary" = (100.0, 160.0),
+    ReviewFirstRow: "review_first_row" = (100.0, 230.0),
+    ReviewControls: "review_controls" = (100.0, 760.0),
+    SessionSummaryTitle: "session_summary_title" = (640.0, 50.0),
+    SessionSummaryFirstRow: "session_summary_first_row" = (300.0, 220.0),
+    SessionSummaryControls: "session_summary_controls" = (300.0, 760.0),
+    QuizTitle: "quiz_title" = (820.0, 50.0),
+    QuizStatus: "quiz_status" = (100.0, 170.0),
+    QuizDealer: "quiz_dealer" = (100.0, 260.0),
+    QuizPlayer: "quiz_player" = (100.0, 370.0),
+    QuizPrompt: "quiz_prompt" = (100.0, 500.0),
+    QuizTimer: "quiz_timer" = (100.0, 570.0),
+    QuizFeedback: "quiz_feedback" = (100.0, 620.0),
+    DrillCard: "drill_card" = (860.0, 260.0),
+    DrillShoePanel: "drill_shoe_panel" = (1450.0, 170.0),
+    NamePlate: "name_plate" = (30.0, 612.0) compact (20.0, 560.0) mirror 280.0,
+    /// Middle of the turn timer's ring, right of the name plate(left of it on the mirrored table)
+    TurnTimer: "turn_timer" = (345.0, 634.0) compact (335.0, 582.0) mirror 0.0,
+    /// Corners of the HUD over the table, the scores hang off the left one and the power ups
+    /// and the wins off the right one(see hud.rs). The mirrored table swaps their sides, the
+    /// compact one stacks them.
+    HudTopLeft: "hud_top_left" = (370.0, 50.0) compact (260.0, 20.0) mirror 600.0,
+    HudTopRight: "hud_top_right" = (1100.0, 50.0) compact (260.0, 120.0) mirror 700.0,
+    HintText: "hint_text" = (50.0, 400.0) compact (20.0, 462.0) mirror 560.0,
+    /// Under the card picker of practice, which is drawn at the same time
+    WhatIfPanel: "what_if_panel" = (1450.0, 680.0) compact (850.0, 500.0) mirror 420.0,
+    HitWarning: "hit_warning" = (30.0, 335.0) compact (850.0, 640.0) mirror 420.0,
+    /// The warning never shows while the table is locked, so they share the spot under the shoe
+    TableLock: "table_lock" = (30.0, 335.0) compact (850.0, 640.0) mirror 420.0,
+    GameOverText: "game_over_text" = (620.0, 420.0),
+    StreamGameOverText: "stream_game_over_text" = (460.0, 360.0),
+}
+
+/// The positions the screens are drawn at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    /// Positions moved by the layout file, the rest stay where they are by default
+    moved: HashMap<Anchor, Point2<f32>>,
+    /// How big everything is drawn, the screen coordinates are the window's size divided by it
+    pub scale: f32,
+    /// The table's positions mirrored left to right, for left-handed players
+    pub mirrored: bool,
+    /// The compact table, for small windows
+    pub compact: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout { moved: HashMap::new(), scale: 1.0, mirrored: false, compact: false }
+    }
+}
+
+impl Layout {
+    pub fn at(&self, anchor: Anchor) -> Point2<f32> {
+        let compact = anchor.compact_position().filter(|_| self.compact);
+        let position = compact.
+            or_else(|| self.moved.get(&anchor).copied()).
+            unwrap_or_else(|| anchor.default_position());
+        let table_width = if compact.is_some() { COMPACT_WIDTH } else { window::DEFAULT_SIZE.0 as f32 };
+        let x = match anchor.table_width() {
+            Some(width) if self.mirrored => table_width - position.x - width,
+            _ => position.x,
+        };
+
+        Point2 { x, y: position.y }
+    }
+
+    /// The screen coordinates of a window `width` by `height`, so text, cards and the space
+    /// between them grow and shrink with the positions.
+    pub fn screen_rect(&self, width: f32, height: f32) -> Rect {
+        Rect::new(0.0, 0.0, width / self.scale, height / self.scale)
+    }
+
+    /// A point in window pixels(the mouse's) in the screen coordinates.
+    pub fn to_screen(&self, point: Point2<f32>) -> Point2<f32> {
+        Point2 { x: point.x / self.scale, y: point.y / self.scale }
+    }
+
+    /// Which way a row of cards goes from its first card, 1 to the right and -1 to the left.
+    pub fn row_direction(&self) -> f32 {
+        if self.mirrored { -1.0 } else { 1.0 }
+    }
+
+    /// Whether a window `window_width` wide gets the compact table.
+    pub fn compact_for(&self, window_width: f32) -> bool {
+        window_width < COMPACT_BELOW_WIDTH * self.scale
+    }
+
+    /// How big the cards are drawn, 1 being their usual size
+    pub fn card_size(&self) -> f32 {
+        if self.compact { COMPACT_CARD_SIZE } else { 1.0 }
+    }
+
+    /// Cards in a row before a hand goes on in the next one, None for a single row.
+    pub fn cards_per_row(&self) -> Option<usize> {
+        if self.compact { Some(COMPACT_CARDS_PER_ROW) } else { None }
+    }
+
+    /// `anchor` moved by `x` and `y`, for what is drawn next to it(rows, shaking, bobbing).
+    pub fn offset(&self, anchor: Anchor, x: f32, y: f32) -> Point2<f32> {
+        let position = self.at(anchor);
+
+        Point2 { x: position.x + x, y: position.y + y }
+    }
+
+    /// Reads "name = x, y" lines and "scale = factor". Unknown names and values that aren't
+    /// numbers are left out and returned, so they can be reported.
+    pub fn parse(contents: &str) -> (Self, Vec<String>) {
+        let mut layout = Layout::default();
+        let mut skipped = Vec::new();
+
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            if key == "scale" {
+                match value.parse::<f32>() {
+                    Ok(scale) if scale > 0.0 => layout.scale = scale,
+                    _ => skipped.push(format!("{} = {}", key, value)),
+                }
+                continue;
+            }
+
+            match (Anchor::from_key(key), parse_position(value)) {
+                (Some(anchor), Some(position)) => {
+                    layout.moved.insert(anchor, position);
+                },
+                _ => skipped.push(format!("{} = {}", key, value)),
+            }
+        }
+
+        (layout, skipped)
+    }
+
+    /// The default layout when there's no file.
+    pub fn load(file_name: &str) -> Self {
+        let contents = match fs::read_to_string(file_name) {
+            Ok(contents) => contents,
+            Err(_) => return Layout::default(),
+        };
+
+        let (layout, skipped) = Self::parse(&contents);
+        for line in skipped {
+            eprintln!("Ignoring \"{}\" in {}", line, file_name);
+        }
+
+        layout
+    }
+
+    /// Every position as the file would have it, to start a layout from.
+    pub fn to_file_contents(&self) -> String {
+        let mut contents = format!("scale = {}\n", self.scale);
+        for anchor in Anchor::ALL {
+            let position = self.moved.get(anchor).copied().unwrap_or_else(|| anchor.default_position());
+            contents += &format!("{} = {}, {}\n", anchor.key(), position.x, position.y);
+        }
+
+        contents
+    }
+}
+
+/// `contents` of a layout file with its scale set to `scale`, the positions it moves kept.
+pub fn with_scale(contents: &str, scale: f32) -> String {
+    let kept: String = contents.lines().
+        filter(|line| !matches!(config::parse_line(line), Some(("scale", _)))).
+        map(|line| format!("{}\n", line)).
+        collect();
+
+    format!("scale = {}\n", scale) + &kept
+}
+
+/// Writes the scale to the layout file, which is made when there's none.
+pub fn save_scale(file_name: &str, scale: f32) -> io::Result<()> {
+    let contents = fs::read_to_string(file_name).unwrap_or_default();
+
+    fs::write(file_name, with_scale(&contents, scale))
+}
+
+/// "x, y"
+fn parse_position(value: &str) -> Option<Point2<f32>> {
+    let (x, y) = value.split_once(',')?;
+
+    Some(Point2 { x: x.trim().parse().ok()?, y: y.trim().parse().ok()? })
+}
+
+/// Positions in the code are written for left-to-right text. For right-to-left languages
+/// the text is mirrored, so it ends where a left-to-right one would start.
+pub fn text_position(position: Point2<f32>, text_width: f32, screen_width: f32, direction: TextDirection) -> Point2<f32> {
+    match direction {
+        TextDirection::LeftToRight => position,
+        TextDirection::RightToLeft => Point2 { x: screen_width - position.x - text_width, y: position.y },
+    }
+}
+
+/// Clickable area of a text button, sized from the measured text so long words(and wide
+/// glyphs) are covered completely.
+pub fn button_rect(position: Point2<f32>, text_width: f32, text_height: f32) -> Rect {
+    Rect::new(position.x - BUTTON_PADDING_X,
+              position.y - BUTTON_PADDING_Y,
+              text_width + 2.0 * BUTTON_PADDING_X,
+              text_height + 2.0 * BUTTON_PADDING_Y)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn left_to_right_keeps_position() {
+        let position = Point2 { x: 790.0, y: 400.0 };
+
+        assert_eq!(text_position(position, 200.0, 1900.0, TextDirection::LeftToRight), position);
+    }
+
+    #[test]
+    fn right_to_left_mirrors() {
+        let position = text_position(Point2 { x: 50.0, y: 200.0 }, 300.0, 1900.0, TextDirection::RightToLeft);
+
+        assert_eq!((position.x, position.y), (1550.0, 200.0));
+        // clicks land on the text where it's drawn, not where a left-to-right one would be
+        assert!(button_rect(position, 300.0, 40.0).contains(Point2 { x: 1800.0, y: 210.0 }));
+        assert!(!button_rect(position, 300.0, 40.0).contains(Point2 { x: 100.0, y: 210.0 }));
+    }
+
+    #[test]
+    fn button_rect_covers_text() {
+        let rect = button_rect(Point2 { x: 100.0, y: 100.0 }, 400.0, 60.0);
+
+        assert!(rect.contains(Point2 { x: 495.0, y: 150.0 }));
+        assert!(!rect.contains(Point2 { x: 520.0, y: 150.0 }));
+    }
+
+    #[test]
+    fn file_moves_and_scales_positions() {
+        let (layout, skipped) = Layout::parse("# the table\ndeck = 120, 180\nscale = 0.5\nnowhere = 1, 2\ntoast = left\n");
+
+        assert_eq!(layout.at(Anchor::Deck), Point2 { x: 120.0, y: 180.0 });
+        assert_eq!(layout.at(Anchor::MenuTitle), Anchor::MenuTitle.default_position());
+        // the screen shrinks with everything on it, the mouse is moved along
+        assert_eq!(layout.screen_rect(950.0, 450.0), Rect::new(0.0, 0.0, 1900.0, 900.0));
+        assert_eq!(layout.to_screen(Point2 { x: 60.0, y: 90.0 }), Point2 { x: 120.0, y: 180.0 });
+        assert_eq!(skipped, ["nowhere = 1, 2", "toast = left"]);
+    }
+
+    #[test]
+    fn scale_replaces_the_old_one() {
+        let contents = with_scale("scale = 0.5\n# the table\ndeck = 120, 180\n", 0.85);
+        let (layout, skipped) = Layout::parse(&contents);
+
+        assert!(skipped.is_empty());
+        assert_eq!(layout.scale, 0.85);
+        assert_eq!(contents.matches("scale").count(), 1);
+        assert!(contents.contains("deck = 120, 180"));
+    }
+
+    #[test]
+    fn mirroring_flips_only_the_table() {
+        let (mut layout, _) = Layout::parse("name_plate = 40, 600\n");
+        layout.mirrored = true;
+
+        assert_eq!(layout.at(Anchor::Deck), Point2 { x: 1800.0, y: 160.0 });
+        // the plate ends 40 from the right edge, as it started 40 from the left one
+        assert_eq!(layout.at(Anchor::NamePlate), Point2 { x: 1580.0, y: 600.0 });
+        assert_eq!(layout.at(Anchor::MenuTitle), Anchor::MenuTitle.default_position());
+        assert_eq!(layout.row_direction(), -1.0);
+    }
+
+    #[test]
+    fn compact_table_fits_small_windows() {
+        let (mut layout, _) = Layout::parse("deck = 120, 180\n");
+        assert!(layout.compact_for(1280.0) && !layout.compact_for(1900.0));
+
+        layout.compact = true;
+        assert_eq!(layout.at(Anchor::Deck), Point2 { x: 80.0, y: 110.0 });
+        assert_eq!(layout.at(Anchor::MenuTitle), Anchor::MenuTitle.default_position());
+        assert!(layout.card_size() < 1.0 && layout.cards_per_row().is_some());
+        // the stacked HUD
+        assert_eq!(layout.at(Anchor::HudTopLeft).x, layout.at(Anchor::HudTopRight).x);
+
+        // mirrored inside the compact table's width
+        layout.mirrored = true;
+        assert_eq!(layout.at(Anchor::NamePlate), Point2 { x: 980.0, y: 560.0 });
+        assert!(Anchor::ALL.iter().all(|anchor| anchor.compact_position().is_some() == anchor.table_width().is_some()));
+    }
+
+    #[test]
+    fn written_layout_reads_back() {
+        let (layout, _) = Layout::parse("hud_top_right = 1500, 40\n");
+        let (read, skipped) = Layout::parse(&layout.to_file_contents());
+
+        assert!(skipped.is_empty());
+        assert_eq!(read.at(Anchor::HudTopRight), Point2 { x: 1500.0, y: 40.0 });
+        assert!(Anchor::ALL.iter().all(|anchor| Anchor::from_key(anchor.key()) == Some(*anchor)));
+    }
+}
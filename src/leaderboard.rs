@@ -0,0 +1,248 @@
+//! Opt-in client for an online leaderboard, enabled by setting `leaderboard_url` in the config.
+//!
+//! The server protocol is deliberately tiny so it's easy to host one:
+//! - `POST <url>/scores` with a "name,best_streak,wins" body submits the player's best
+//! - `GET <url>/top` answers with one "name,best_streak,wins" line per entry, best first
+//!
+//! Only plain `http://` is supported. Requests run on a background thread, so a slow or
+//! missing server never stalls the game, and the last list received is cached on disk.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+pub const CACHE_FILE_NAME: &str = "leaderboard_cache.csv";
+pub const PENDING_FILE_NAME: &str = "leaderboard_pending.csv";
+pub const TOP_ENTRIES: usize = 10;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub best_streak: u32,
+    pub wins: u32,
+}
+
+impl Entry {
+    pub fn to_line(&self) -> String {
+        // commas would shift the columns, the name is only for display anyway
+        format!("{},{},{}", self.name.replace(',', " "), self.best_streak, self.wins)
+    }
+
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.trim().rsplitn(3, ',');
+        let wins = fields.next()?.trim().parse().ok()?;
+        let best_streak = fields.next()?.trim().parse().ok()?;
+        let name = fields.next()?.trim().to_string();
+
+        Some(Entry { name, best_streak, wins })
+    }
+}
+
+pub fn parse_entries(contents: &str) -> Vec<Entry> {
+    contents.lines().filter_map(Entry::from_line).take(TOP_ENTRIES).collect()
+}
+
+fn entries_to_lines(entries: &[Entry]) -> String {
+    entries.iter().map(|entry| entry.to_line() + "\n").collect()
+}
+
+/// Splits "http://host[:port]/path" into its parts.
+pub fn parse_url(url: &str) -> Option<(String, u16, String)> {
+    let rest = url.trim().strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], rest[i..].trim_end_matches('/')),
+        None => (rest, ""),
+    };
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    Some((host.to_string(), port, path.to_string()))
+}
+
+/// Sends a single HTTP/1.1 request and returns the body of a 2xx response.
+fn request(method: &str, url: &str, endpoint: &str, body: &str) -> io::Result<String> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+
+    let (host, port, path) = parse_url(url).ok_or_else(|| invalid("leaderboard_url must look like http://host[:port]/path"))?;
+    let address = (host.as_str(), port).to_socket_addrs()?.next().ok_or_else(|| invalid("could not resolve the server"))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    write!(stream,
+           "{} {}{} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+           method, path, endpoint, host, body.len(), body)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response.as_str(), ""));
+    let status: u16 = head.split_whitespace().nth(1).and_then(|code| code.parse().ok()).unwrap_or(0);
+    if !(200..300).contains(&status) {
+        return Err(io::Error::other(format!("server answered with status {}", status)));
+    }
+
+    Ok(body.to_string())
+}
+
+/// Removes the pending file only if it still holds the posted line, a score queued while
+/// the request was in flight is sent on the next sync instead of being lost.
+fn clear_pending(file: &str, posted: &str) {
+    if fs::read_to_string(file).is_ok_and(|line| line == posted) {
+        let _ = fs::remove_file(file);
+    }
+}
+
+/// Submits the pending score(if any) and fetches the top entries.
+fn sync(url: &str, pending: Option<Entry>) -> io::Result<Vec<Entry>> {
+    if let Some(entry) = pending {
+        let line = entry.to_line();
+        request("POST", url, "/scores", &line)?;
+        clear_pending(PENDING_FILE_NAME, &line);
+    }
+
+    let entries = parse_entries(&request("GET", url, "/top", "")?);
+    fs::write(CACHE_FILE_NAME, entries_to_lines(&entries))?;
+
+    Ok(entries)
+}
+
+pub struct LeaderboardClient {
+    url: String,
+    pub entries: Vec<Entry>,
+
+    /// Set when the last sync failed and `entries` come from the cache
+    pub error: Option<String>,
+    receiver: Option<Receiver<Result<Vec<Entry>, String>>>,
+}
+
+impl LeaderboardClient {
+    /// An empty url means the leaderboard is turned off.
+    pub fn new(url: &str) -> Self {
+        LeaderboardClient {
+            url: url.trim().to_string(),
+            entries: parse_entries(&fs::read_to_string(CACHE_FILE_NAME).unwrap_or_default()),
+            error: None,
+            receiver: None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.url.is_empty()
+    }
+
+    pub fn syncing(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Remembers the score to send on the next sync, so it survives being offline(and restarts).
+    pub fn queue_score(&self, entry: &Entry) {
+        if !self.enabled() {
+            return;
+        }
+
+        if let Err(e) = fs::write(PENDING_FILE_NAME, entry.to_line()) {
+            eprintln!("Could not queue the leaderboard score: {}", e);
+        }
+    }
+
+    /// Starts sending the queued score and fetching the top entries in the background.
+    pub fn sync(&mut self) {
+        if !self.enabled() || self.syncing() {
+            return;
+        }
+
+        let url = self.url.clone();
+        let pending = fs::read_to_string(PENDING_FILE_NAME).ok().and_then(|line| Entry::from_line(&line));
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let _ = sender.send(sync(&url, pending).map_err(|e| e.to_string()));
+        });
+        self.receiver = Some(receiver);
+    }
+
+    /// Picks up the result of a finished sync, call it every update.
+    pub fn update(&mut self) {
+        let result = match &self.receiver {
+            Some(receiver) => match receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => Err(String::from("the request was interrupted")),
+            },
+            None => return,
+        };
+        self.receiver = None;
+
+        match result {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            },
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_splits_parts() {
+        assert_eq!(parse_url("http://scores.example.com:8080/blackjack/"),
+                   Some((String::from("scores.example.com"), 8080, String::from("/blackjack"))));
+        assert_eq!(parse_url("http://localhost"), Some((String::from("localhost"), 80, String::new())));
+        assert_eq!(parse_url("https://secure.example.com"), None);
+        assert_eq!(parse_url("http://:80/"), None);
+    }
+
+    #[test]
+    fn entry_lines_round_trip() {
+        let entry = Entry { name: String::from("Kris, the best"), best_streak: 7, wins: 40 };
+        let parsed = Entry::from_line(&entry.to_line()).unwrap();
+
+        assert_eq!(parsed.name, "Kris  the best");
+        assert_eq!((parsed.best_streak, parsed.wins), (7, 40));
+    }
+
+    #[test]
+    fn parse_entries_skips_garbage_and_limits() {
+        let lines: String = (0..20).map(|i| format!("player{},{},{}\n", i, 20 - i, i)).collect();
+
+        assert_eq!(parse_entries(&("oops\n".to_owned() + lines.as_str())).len(), TOP_ENTRIES);
+    }
+
+    #[test]
+    fn score_queued_during_a_sync_stays_pending() {
+        let file = std::env::temp_dir().join(format!("blackjack_pending_{}.csv", std::process::id()));
+        let file = file.to_str().unwrap();
+        let sent = Entry { name: String::from("Kris"), best_streak: 3, wins: 10 }.to_line();
+        let newer = Entry { name: String::from("Kris"), best_streak: 4, wins: 11 }.to_line();
+
+        fs::write(file, &newer).unwrap();
+        clear_pending(file, &sent);
+        assert_eq!(fs::read_to_string(file).unwrap(), newer);
+
+        clear_pending(file, &newer);
+        assert!(fs::read_to_string(file).is_err());
+    }
+
+    #[test]
+    fn request_to_closed_port_fails_gracefully() {
+        assert!(request("GET", "http://127.0.0.1:1", "/top", "").is_err());
+    }
+}
@@ -1,4 +1,82 @@
+pub mod achievements;
+pub mod analytics;
+pub mod asset_manager;
+pub mod avatar;
+pub mod backup;
+pub mod banner;
 pub mod board;
+pub mod bubble;
+pub mod burn;
+pub mod calculator;
 pub mod card;
+pub mod card_back;
+pub mod card_set;
+pub mod challenge;
+pub mod chips;
+pub mod coach;
+pub mod config;
+pub mod config_editor;
+pub mod credits;
+pub mod drill;
+pub mod felt;
+pub mod fonts;
+pub mod gallery;
+pub mod game_action;
 pub mod game_engine;
+pub mod ghost;
+pub mod golden;
+pub mod help;
+pub mod hindsight;
+pub mod history;
+pub mod hit_warning;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+pub mod hud;
+pub mod i18n;
+pub mod idle;
+pub mod keymap;
+pub mod layout;
+pub mod leaderboard;
 pub mod main_state;
+pub mod migration;
+pub mod missions;
+pub mod placard;
+pub mod playtime;
+pub mod png;
+pub mod practice;
+pub mod probability;
+pub mod profile;
+pub mod profile_screen;
+pub mod quiz;
+pub mod rejection;
+pub mod resources;
+pub mod review;
+pub mod rng;
+pub mod saver;
+pub mod scenario;
+pub mod score_counter;
+pub mod scene;
+pub mod session;
+pub mod setup;
+pub mod signature;
+pub mod simulation;
+pub mod situation;
+pub mod snapshot;
+pub mod speed;
+pub mod splash;
+pub mod stamp;
+pub mod stats_export;
+pub mod stats_import;
+pub mod strategy;
+pub mod stream_overlay;
+pub mod sweat;
+pub mod table_input;
+pub mod table_talk;
+pub mod tip;
+pub mod trainer;
+pub mod transcript;
+pub mod turn_timer;
+pub mod unlocks;
+pub mod what_if;
+pub mod window;
+pub mod window_title;
@@ -1,9 +1,78 @@
+use crate::achievements::{self, Progress};
+use crate::analytics;
+use crate::avatar;
+use crate::backup::{self, Backup};
+use crate::bubble;
+use crate::banner::{self, Banner};
 use crate::board::{self, Board};
+use crate::calculator::{CalculatorRow, RiskCalculator};
 use crate::card;
-use crate::game_engine::{GameEngine, Outcome, HintStatus};
+use crate::card_back;
+use crate::card_set::{self, CardSet, CardSetError};
+use crate::challenge::{self, Challenge, ChallengeHand, ChallengeLog};
+use crate::chips;
+use crate::coach::{self, CoachView};
+use crate::config::{self, Config};
+use crate::config_editor::{self, ConfigEditor, ConfigWatcher};
+use crate::credits;
+use crate::drill::{self, Drill, DrillSettings};
+use crate::felt;
+use crate::fonts::{self, FontManager};
+use crate::gallery::{self, Gallery, GalleryHand};
+use crate::help;
+use crate::hindsight;
+use crate::history::{self, HandRecord, History};
+use crate::hit_warning::HitWarning;
+use crate::i18n::{self, Label, Language};
+use crate::idle::IdleTimer;
+use crate::keymap::KeyAction;
+use crate::layout::{self, Anchor, Layout};
+use crate::leaderboard::{self, LeaderboardClient};
+use crate::migration::{self, SaveFile};
+use crate::missions::{HandEvent, Reward};
+use crate::ghost::Ghost;
+use crate::golden::Frame;
+use crate::placard;
+use crate::playtime::{self, PlaytimeClock};
+use crate::practice::{self, CardPicker, PracticeShoe};
+use crate::profile::{self, Profile};
+use crate::profile_screen::{ProfileAction, ProfileScreen, PROFILE_ACTIONS};
+use crate::quiz::{self, QuestionKind, Quiz};
+use crate::probability::{self, Rules};
+use crate::review::SessionReview;
+use crate::rng::GameRng;
+use crate::saver::Saver;
+use crate::scenario::{Action, Scenario};
+use crate::scene::{self, Scene};
+use crate::session::SessionSummary;
+use crate::setup::{self, Setup, SetupStep};
+use crate::signature;
+use crate::situation::Situation;
+use crate::score_counter::ScoreCounter;
+use crate::snapshot;
+use crate::stats_export::{self, Summary};
+use crate::stats_import;
+use crate::stream_overlay::{self, HandState, StreamOverlay};
+use crate::sweat;
+use crate::table_input::{Lock, TableState};
+use crate::table_talk::{TableTalk, TalkEvent};
+use crate::rejection::{self, RejectionFeedback};
+use crate::tip::Tip;
+use crate::trainer::{self, TrainerStats};
+use crate::transcript::{self, Transcript};
+use crate::turn_timer::{self, TurnTimer};
+use crate::unlocks::{self, Cosmetic};
+use crate::game_action::{GameAction, GameEvent, PowerUp};
+use crate::game_engine::{self, GameEngine, Outcome, HintStatus};
+#[cfg(feature = "hot-reload")]
+use crate::hot_reload::AssetWatcher;
+use crate::hud::{Hud, HudElement, HudState};
+use crate::splash::{self, Splash};
+use crate::window_title::{self, WindowTitle};
+use crate::what_if::{self, WhatIfPanel};
+use crate::window;
 
 use rand::Rng;
-use std::str::FromStr;
 
 use ggez::{
     Context,
@@ -15,52 +84,224 @@ use ggez::{
     timer,
 };
 
-use std::io::{BufRead, Write, BufWriter};
-use std::fs::OpenOptions;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
-const MENU_TITLE_POSITION: Point2<f32> = Point2 { x: 750.0, y: 300.0 };
 const MENU_TITLE_SIZE: f32 = 80.0;
-const MENU_PLAY_TEXT_POSITION: Point2<f32> = Point2 { x: 790.0, y: 500.0 }; 
-const MENU_PLAY_TEXT_SIZE: f32 = 60.0;
-const MENU_HELP_TEXT_POSITION: Point2<f32> = Point2 { x: 790.0, y: 700.0 };
-const MENU_HELP_TEXT_SIZE: f32 = 60.0;
+const MENU_PLAY_TEXT_SIZE: f32 = 56.0;
+const MENU_SETTINGS_TEXT_SIZE: f32 = 56.0;
+const MENU_LEADERBOARD_TEXT_SIZE: f32 = 56.0;
+const MENU_HISTORY_TEXT_SIZE: f32 = 56.0;
+const MENU_ANALYSIS_TEXT_SIZE: f32 = 56.0;
+const MENU_TRAINER_TEXT_SIZE: f32 = 56.0;
+const MENU_HELP_TEXT_SIZE: f32 = 56.0;
+const MISSIONS_TITLE_SIZE: f32 = 40.0;
+const MISSIONS_TEXT_SIZE: f32 = 24.0;
+const MISSIONS_LINE_SPACING: f32 = 32.0;
+
+const SHOE_PANEL_TEXT_SIZE: f32 = 26.0;
+const SHOE_PANEL_LINE_SPACING: f32 = 30.0;
+
+const WHAT_IF_TEXT_SIZE: f32 = 24.0;
+const WHAT_IF_LINE_SPACING: f32 = 28.0;
+
+const PROFILE_TITLE_SIZE: f32 = 60.0;
+const PROFILE_TEXT_SIZE: f32 = 32.0;
+const PROFILE_LINE_SPACING: f32 = 50.0;
+const PROFILE_UNDO_TEXT_SIZE: f32 = 45.0;
+
+const ABOUT_TITLE_SIZE: f32 = 60.0;
+const ABOUT_TEXT_SIZE: f32 = 28.0;
+const ABOUT_LINE_SPACING: f32 = 36.0;
+
+const UNLOCKS_TITLE_SIZE: f32 = 60.0;
+const UNLOCKS_TEXT_SIZE: f32 = 28.0;
+const UNLOCKS_LINE_SPACING: f32 = 42.0;
+
+const DEALER_THANKS_SIZE: f32 = 30.0;
+const TIP_TEXT_SIZE: f32 = 40.0;
+const PLACARD_TEXT_SIZE: f32 = 22.0;
+const VALUE_BADGE_TEXT_SIZE: f32 = 28.0;
+const STAMP_TEXT_SIZE: f32 = 60.0;
 
-const HELP_TITLE_POSITION: Point2<f32> = Point2 { x: 800.0, y: 50.0 };
 const HELP_TITLE_SIZE: f32 = 60.0;
-const HELP_DESCRIPTION_POSITION: Point2<f32> = Point2 { x: 50.0, y: 200.0 };
 const HELP_DESCRIPTION_SIZE: f32 = 30.0;
-const HELP_BACK_TEXT_POSITION: Point2<f32> = Point2 { x: 1600.0, y: 800.0 };
 const HELP_BACK_TEXT_SIZE: f32 = 45.0;
 
-const PLAYER_SCORE_POSITION: Point2<f32> = Point2 { x: 450.0, y: 100.0 };
-const PLAYER_TEXT_SCORE_POSITION: Point2<f32> = Point2 { x: 370.0, y: 50.0 };
-const PLAYER_TEXT_SCORE_SIZE: f32 = 28.0;
-const DEALER_SCORE_POSITION: Point2<f32> = Point2 { x: 850.0, y: 100.0 };
-const DEALER_TEXT_SCORE_POSITION: Point2<f32> = Point2 { x: 765.0, y: 50.0 };
-const DEALER_TEXT_SCORE_SIZE: f32 = 28.0;
-
-const POWER_UPS_TEXT_POSITION: Point2<f32> = Point2 { x: 1100.0, y: 50.0 };
-const POWER_UPS_TEXT_SIZE: f32 = 28.0;
-
-const WINS_TEXT_POSITION:  Point2<f32> = Point2 { x: 1600.0, y: 50.0 };
-const WINS_TEXT_SIZE: f32 = 28.0;
+const SETTINGS_TITLE_SIZE: f32 = 60.0;
+const SETTINGS_ROW_SPACING: f32 = 30.0;
+const SETTINGS_TEXT_SIZE: f32 = 30.0;
+const CONFIG_EDITOR_TEXT_SIZE: f32 = 24.0;
+const CONFIG_EDITOR_ROW_SPACING: f32 = 27.0;
+
+const TOAST_TEXT_SIZE: f32 = 24.0;
+const SECONDS_TILL_TOAST_GONE: f32 = 5.0;
+
+const LEADERBOARD_TITLE_SIZE: f32 = 60.0;
+const LEADERBOARD_ROW_SPACING: f32 = 50.0;
+const LEADERBOARD_TEXT_SIZE: f32 = 32.0;
+
+const TRANSCRIPT_TITLE_SIZE: f32 = 60.0;
+const TRANSCRIPT_ROW_SPACING: f32 = 42.0;
+const TRANSCRIPT_TEXT_SIZE: f32 = 26.0;
+const TRANSCRIPT_VISIBLE_ROWS: usize = 14;
+
+const ANALYSIS_TITLE_SIZE: f32 = 60.0;
+const ANALYSIS_TEXT_SIZE: f32 = 28.0;
+const ANALYSIS_WIN_RATE_AREA: (f32, f32, f32, f32) = (100.0, 240.0, 760.0, 360.0);
+const ANALYSIS_OUTCOMES_AREA: (f32, f32, f32, f32) = (980.0, 240.0, 420.0, 360.0);
+const ANALYSIS_HAND_VALUE_AREA: (f32, f32, f32, f32) = (1480.0, 240.0, 320.0, 360.0);
+/// Top of the hand value chart, so busted hands still fit
+const ANALYSIS_MAX_HAND_VALUE: f32 = 30.0;
+const ANALYSIS_CALCULATOR_TEXT_SIZE: f32 = 45.0;
+
+const HEATMAP_TITLE_SIZE: f32 = 60.0;
+const HEATMAP_AREA: (f32, f32, f32, f32) = (220.0, 180.0, 1100.0, 540.0);
+const HEATMAP_TEXT_SIZE: f32 = 24.0;
+const HEATMAP_LINE_SPACING: f32 = 36.0;
+/// Situations played fewer times than this don't make the worst list
+const HEATMAP_MIN_HANDS: u32 = 3;
+const HEATMAP_WORST_COUNT: usize = 8;
+
+const CALCULATOR_TITLE_SIZE: f32 = 60.0;
+const CALCULATOR_ROW_SPACING: f32 = 50.0;
+const CALCULATOR_RESULT_SPACING: f32 = 42.0;
+const CALCULATOR_TEXT_SIZE: f32 = 30.0;
+const CALCULATOR_SIMULATION_AREA: (f32, f32, f32, f32) = (1100.0, 200.0, 700.0, 220.0);
+const CALCULATOR_CHIP_TEXT_SIZE: f32 = 22.0;
+const CALCULATOR_RECENT_BETS_GAP: f32 = 30.0;
+
+const TRAINER_TITLE_SIZE: f32 = 60.0;
+const TRAINER_BUTTON_TEXT_SIZE: f32 = 50.0;
+const TRAINER_DESCRIPTION_OFFSET: f32 = 70.0;
+const TRAINER_LINE_SPACING: f32 = 45.0;
+const TRAINER_TEXT_SIZE: f32 = 28.0;
+const SITUATION_MAX_LENGTH: usize = 20;
+
+const REVIEW_TITLE_SIZE: f32 = 60.0;
+const REVIEW_TEXT_SIZE: f32 = 26.0;
+const REVIEW_LINE_SPACING: f32 = 50.0;
+const REVIEW_VISIBLE_ROWS: usize = 10;
+
+const SESSION_SUMMARY_TEXT_SIZE: f32 = 36.0;
+const SESSION_SUMMARY_LINE_SPACING: f32 = 70.0;
+
+const QUIZ_TITLE_SIZE: f32 = 60.0;
+const QUIZ_CARDS_SIZE: f32 = 70.0;
+const QUIZ_TIMER_WIDTH: f32 = 800.0;
+const QUIZ_TIMER_HEIGHT: f32 = 16.0;
+const QUIZ_TEXT_SIZE: f32 = 34.0;
+
+const DRILL_CARD_SIZE: f32 = 200.0;
+
+const NAME_PLATE_WIDTH: f32 = 280.0;
+const NAME_PLATE_HEIGHT: f32 = 44.0;
+const NAME_PLATE_TEXT_SIZE: f32 = 28.0;
+const TURN_TIMER_TEXT_SIZE: f32 = 20.0;
 
 const HINT_RANGE_SIZE: u32 = 4;
-const HINT_TEXT_POSITION: Point2<f32> = Point2 { x: 50.0, y: 400.0 };
-const HINT_TEXT_SIZE: f32 = 35.0; 
-
-const GAME_OVER_TEXT_POSITION: Point2<f32> = Point2 { x: 620.0, y: 420.0 };
-const GAME_OVER_TEXT_SIZE: f32 = 100.0;
 
-const SECONDS_TILL_GAME_OVER: f32 = 4.0;
-const SECONDS_TILL_MENU: f32 = 3.0;
+const HIT_WARNING_TEXT_SIZE: f32 = 26.0;
+const TABLE_LOCK_TEXT_SIZE: f32 = 26.0;
 
+const GAME_OVER_TEXT_SIZE: f32 = 100.0;
+const STREAM_GAME_OVER_TEXT_SIZE: f32 = 180.0;
+/// Space between the milestone ribbon's edges and its text
+const BANNER_RIBBON_PADDING: f32 = 20.0;
+const HINDSIGHT_TEXT_SIZE: f32 = 36.0;
+const TABLE_TALK_TEXT_SIZE: f32 = 28.0;
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SettingsRow {
+    CardSet,
+    CardBack,
+    Felt,
+    DealerAvatar,
+    Font,
+    Name,
+    Language,
+    ExportStats,
+    ImportStats,
+    StreamMode,
+    StreamHidePowerUps,
+    GameSpeed,
+    AutoStand,
+    AutoAdvance,
+    WarnRiskyHits,
+    WhatIfOdds,
+    ShowNextCard,
+    BurnCard,
+    GpuLight,
+    BackUpNow,
+    RestoreBackup,
+    Advanced,
+}
 
+const SETTINGS_ROWS: &[SettingsRow] = &[
+    SettingsRow::CardSet,
+    SettingsRow::CardBack,
+    SettingsRow::Felt,
+    SettingsRow::DealerAvatar,
+    SettingsRow::Font,
+    SettingsRow::Name,
+    SettingsRow::Language,
+    SettingsRow::ExportStats,
+    SettingsRow::ImportStats,
+    SettingsRow::StreamMode,
+    SettingsRow::StreamHidePowerUps,
+    SettingsRow::GameSpeed,
+    SettingsRow::AutoStand,
+    SettingsRow::AutoAdvance,
+    SettingsRow::WarnRiskyHits,
+    SettingsRow::WhatIfOdds,
+    SettingsRow::ShowNextCard,
+    SettingsRow::BurnCard,
+    SettingsRow::GpuLight,
+    SettingsRow::BackUpNow,
+    SettingsRow::RestoreBackup,
+    SettingsRow::Advanced,
+];
+
+/// How the practice shoe changes, see practice::PracticeShoe
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShoeChange {
+    /// One more or one less of the rank
+    Step(i32),
+    RemoveRank,
+    Standard,
+}
 
 #[derive(Debug)]
 pub enum GameStatus {
+    /// The intro, before the menu
+    Splash,
+    /// The first-run wizard, after the intro
+    Setup,
     Menu,
     Help,
+    Settings,
+    /// Every key of config.txt, from the settings
+    ConfigEditor,
+    Leaderboard,
+    Transcript,
+    Analysis,
+    Calculator,
+    /// Notable hands from the analysis screen, see gallery.rs
+    Gallery,
+    /// The session's code and friends' logs, from the leaderboard screen
+    Challenge,
+    Heatmap,
+    Trainer,
+    Quiz,
+    Drill,
+    Unlocks,
+    About,
+    Profile,
+    Review,
+    /// Once before quitting, after a session with counted hands
+    SessionSummary,
     Play,
 }
 
@@ -72,76 +313,630 @@ pub struct MainState {
     power_ups_count: (u32, u32),
     hint_range: Option<(u32, u32)>,
     time_till_game_over: f32,
-    time_till_menu: f32,
+    /// None waits on the outcome for Enter or R, see GameSpeed::seconds_on_outcome. When it runs
+    /// out, the next hand is dealt with auto-advance and the menu comes back otherwise.
+    time_till_menu: Option<f32>,
+    time_till_dealer_card: f32,
     file_name: String,
+    config: Config,
+    language: Language,
+    profile: Profile,
+    history: History,
+    fonts: FontManager,
+    leaderboard: LeaderboardClient,
+    /// The stats file was changed outside the game, its scores stay off the leaderboard
+    scores_unverified: bool,
+    stream_overlay: StreamOverlay,
+    transcript: Transcript,
+    rng: GameRng,
+    /// Writes the saves of finished hands in the background
+    saver: Saver,
+    transcript_scroll: usize,
+    calculator: RiskCalculator,
+    quiz: Option<Quiz>,
+    drill: Option<Drill>,
+    drill_settings: DrillSettings,
+    trainer_stats: TrainerStats,
+    toast: Option<String>,
+    time_till_toast_gone: f32,
+    card_set_choice: String,
+    settings_row: usize,
+    settings_step: i32,
+    card_set_error: Option<CardSetError>,
+    profile_screen: ProfileScreen,
+    /// A practice hand: nothing is counted and the power-ups never run out
+    practice: bool,
+    /// Whether the last hand was a practice one, R deals the next hand the same way
+    last_hand_practice: bool,
+    card_picker: CardPicker,
+    /// What practice hands are dealt from, for the session
+    practice_shoe: PracticeShoe,
+    /// Typed on the trainer screen, and the situation practice hands start from
+    situation_input: String,
+    situation: Option<Situation>,
+    /// The dealer thanking for a tip
+    tip: Tip,
+    /// The dealer's remarks on the table
+    table_talk: TableTalk,
+    /// Whether the wait for the dealer's next card is a heartbeat's, see sweat.rs
+    sweat_waiting: bool,
+    /// The session's shoes dealt so far, the next fresh shoe is shuffled by GameRng::shoe of it
+    shoe_index: u32,
+    /// The counted hands since the session's seed was set, see challenge.rs
+    challenge_log: ChallengeLog,
+    /// A friend's challenge code as it's typed
+    challenge_input: String,
+    /// Friends' logs of the same challenge, from the "challenges" folder
+    challenge_friends: Vec<ChallengeLog>,
+    /// The code was shown or a friend's was taken, so the cut card stays out
+    challenge_active: bool,
+    layout: Layout,
+    rejection: RejectionFeedback,
+    /// What the player's and the dealer's scores show while they count up
+    score_counters: [ScoreCounter; 2],
+    hud: Hud,
+    splash: Splash,
+    /// The first-run wizard, while it's going
+    setup: Option<Setup>,
+    config_editor: ConfigEditor,
+    /// Picks up config.txt edited while the game runs
+    config_watcher: ConfigWatcher,
+    window_title: WindowTitle,
+    what_if: WhatIfPanel,
+    /// Asks for a second click before hitting a hard 17 or more
+    hit_warning: HitWarning,
+    /// The hand and the best play for coach.txt
+    coach: CoachView,
+    /// Stands for the player who takes too long, when config.txt sets a limit
+    turn_timer: TurnTimer,
+    idle_timer: IdleTimer,
+    playtime_clock: PlaytimeClock,
+    /// Newest first, and the one picked on the settings screen
+    backups: Vec<Backup>,
+    backup_choice: usize,
+    /// The scenario being replayed and its actions that are still to come
+    scenario: Option<Scenario>,
+    scenario_actions: VecDeque<Action>,
+    /// What the player did this hand, for the gallery
+    hand_actions: Vec<Action>,
+    gallery: Gallery,
+    /// This session's hands with a hit or a stand the best play wouldn't have made
+    review: SessionReview,
+    /// The review is shown on quitting once, the next Escape quits
+    review_shown: bool,
+    /// The card on top when the player stood, see hindsight.rs
+    would_have_drawn: Option<card::Card>,
+    session: SessionSummary,
+    ghost: Ghost,
+    summary_shown: bool,
+    #[cfg(feature = "hot-reload")]
+    asset_watcher: Option<AssetWatcher>,
 }
 
 impl MainState {
-    fn load<B: BufRead>(mut reader: B) -> (u32, u32, u32) {
+    /// The stats and the whole file, for checking its signature.
+    fn load<B: BufRead>(mut reader: B) -> ((u32, u32, u32), String) {
         let mut buffer = String::new();
-        reader.read_line(&mut buffer).unwrap();
+        // not UTF-8 leaves the buffer empty, which is handled below like any other damage
+        let _ = reader.read_to_string(&mut buffer);
+
+        let stats = parse_stats(buffer.lines().next().unwrap_or_default()).unwrap_or_else(|| {
+            eprintln!("The stats file is damaged, starting from zero");
+            (0, 0, 0)
+        });
 
-        let v: Vec<u32> = buffer.split(' ').map(|s| FromStr::from_str(s).unwrap()).collect();
+        (stats, buffer)
+    }
+
+    /// Whether the stats in `contents` stay off the leaderboard, see signature.rs.
+    fn check_signature(contents: &str, config: &Config) -> bool {
+        let unverified = signature::unverified(contents, config.sign_saves);
+        if unverified {
+            eprintln!("The stats file doesn't match its signature, its scores won't be sent to the leaderboard");
+        }
 
-        (v[0], v[1], v[2])
+        unverified
     }
 
     fn save(&self) {
-        let f = OpenOptions::new().write(true).open(self.file_name.clone()).unwrap();
-        let mut writer = BufWriter::new(f);
+        // the version goes on the second line, old releases only read the first
+        let mut contents = format!("{} {} {}\n{}", self.wins, self.power_ups_count.0, self.power_ups_count.1, SaveFile::Stats.version_line());
+        if self.scores_unverified {
+            contents += &format!("{} = true\n", signature::UNVERIFIED_KEY);
+        }
+        if self.config.sign_saves {
+            contents = signature::signed(&contents);
+        }
+
+        self.saver.write(&self.file_name, contents);
+    }
+
+    fn save_profile(&self) {
+        self.saver.write(profile::PROFILE_FILE_NAME, self.profile.to_file_contents());
+    }
+
+    fn save_history(&self) {
+        self.saver.write(history::HISTORY_FILE_NAME, self.history.to_csv());
+    }
+
+    /// The session so far, with the decisions the review checked.
+    fn session_summary(&self) -> SessionSummary {
+        SessionSummary {
+            decisions: self.review.decisions,
+            mistakes: self.review.mistakes(),
+            optimal_net: Some(self.ghost.net()).filter(|_| self.ghost.hands() > 0),
+            ..self.session.clone()
+        }
+    }
 
-        writer.write(self.wins.to_string().as_bytes()).unwrap();
-        writer.write(b" ").unwrap();
-        writer.write(self.power_ups_count.0.to_string().as_bytes()).unwrap();
-        writer.write(b" ").unwrap();
-        writer.write(self.power_ups_count.1.to_string().as_bytes()).unwrap();
+    /// Closes the session in the session log, see session.rs.
+    fn log_session(&self) {
+        if self.session.hands > 0 {
+            self.saver.append(transcript::SESSION_LOG_FILE_NAME, None, self.session_summary().log_line() + "\n");
+        }
+    }
 
-        writer.flush().unwrap();
+    /// Keeps the window's size and place for the next start, see window.rs.
+    fn remember_window(&mut self, ctx: &Context) {
+        if window::remember(ctx, &mut self.config) {
+            if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                eprintln!("Could not save the window's place: {}", e);
+            }
+        }
     }
 
-    pub fn new<B: BufRead>(ctx: &mut Context, reader: B, file: &str) -> GameResult<MainState> {
-        let board = Board::new(ctx)?;
-        let stats = Self::load(reader);
+    pub fn new<B: BufRead>(ctx: &mut Context,
+                           reader: B,
+                           file: &str,
+                           mut config: Config,
+                           mut profile: Profile,
+                           history: History,
+                           trainer_stats: TrainerStats) -> GameResult<MainState> {
+        let (stats, contents) = Self::load(reader);
+        let scores_unverified = Self::check_signature(&contents, &config);
+
+        // achievements earned before they were tracked count as well
+        for achievement in achievements::newly_earned(&Progress::new(stats.0, &history, &profile), &profile.achievements) {
+            profile.achievements.push(achievement.id.to_string());
+        }
+        // cosmetics picked before they were locked go back to the defaults until earned
+        lock_unearned_cosmetics(&mut config, &mut profile);
+
+        let (card_set, card_set_error) = CardSet::load_or_default(&config.card_set);
+        let card_set = card_set.with_back_style(card_back::find(&config.card_back)).with_light(config.gpu_light);
+        let rng = GameRng::new();
+        let mut layout = Layout::load(layout::LAYOUT_FILE_NAME);
+        layout.mirrored = config.left_handed;
+        layout.compact = layout.compact_for(config.window_size.0 as f32);
+        let board = Board::new(ctx, card_set, &mut rng.shoe(0), &layout)?;
+        let challenge_log = ChallengeLog::new(profile.display_name(), Challenge { seed: rng.seed, burn_card: config.burn_card });
+        let leaderboard = LeaderboardClient::new(&config.leaderboard_url);
+        let calculator = RiskCalculator { family_mode: config.family_mode, audit_chips: config.audit_chips, ..RiskCalculator::default() };
+        let turn_timer = TurnTimer::new(config.turn_timer_seconds);
+        let idle_timer = IdleTimer::new(config.idle_minutes);
+
+        let mut fonts = FontManager::new(ctx)?;
+        if let Err(e) = fonts.set_user_font(ctx, &config.font) {
+            eprintln!("Could not load the font \"{}\": {}", config.font, e);
+        }
+
+        fit_screen(ctx, &layout);
 
         Ok(
             MainState {
                 board, 
-                engine: GameEngine::new(),
-                status: GameStatus::Menu,
+                engine: GameEngine { auto_stand_on_21: config.auto_stand_on_21, ..GameEngine::new() },
+                status: GameStatus::Splash,
                 wins: stats.0, 
                 power_ups_count: (stats.1, stats.2), 
                 hint_range: None,
-                time_till_game_over: SECONDS_TILL_GAME_OVER,
-                time_till_menu: SECONDS_TILL_MENU,
+                time_till_game_over: config.game_speed.seconds_till_outcome(),
+                time_till_menu: config.game_speed.seconds_on_outcome(config.auto_advance),
+                time_till_dealer_card: config.game_speed.dealer_delay(),
                 file_name: file.to_string(), // used for reset and exit(with esc)
+                card_set_choice: config.card_set.clone(),
+                language: Language::from_code(&config.language).unwrap_or(Language::English),
+                config,
+                profile,
+                history,
+                fonts,
+                leaderboard,
+                scores_unverified,
+                stream_overlay: StreamOverlay::default(),
+                transcript: Transcript::default(),
+                rng,
+                saver: Saver::new(),
+                transcript_scroll: 0,
+                calculator,
+                quiz: None,
+                drill: None,
+                drill_settings: DrillSettings::default(),
+                trainer_stats,
+                toast: None,
+                time_till_toast_gone: 0.0,
+                settings_row: 0,
+                settings_step: 0,
+                card_set_error,
+                profile_screen: ProfileScreen::default(),
+                practice: false,
+                last_hand_practice: false,
+                card_picker: CardPicker::default(),
+                practice_shoe: PracticeShoe::default(),
+                situation_input: String::new(),
+                situation: None,
+                tip: Tip::default(),
+                table_talk: TableTalk::default(),
+                sweat_waiting: false,
+                shoe_index: 0,
+                challenge_log,
+                challenge_input: String::new(),
+                challenge_friends: Vec::new(),
+                challenge_active: false,
+                layout,
+                rejection: RejectionFeedback::default(),
+                score_counters: [ScoreCounter::default(); 2],
+                hud: Hud::default(),
+                splash: Splash::default(),
+                setup: None,
+                config_editor: ConfigEditor::default(),
+                config_watcher: ConfigWatcher::new(config::CONFIG_FILE_NAME),
+                window_title: WindowTitle::default(),
+                what_if: WhatIfPanel::default(),
+                hit_warning: HitWarning::default(),
+                turn_timer,
+                idle_timer,
+                playtime_clock: PlaytimeClock::default(),
+                coach: CoachView::default(),
+                backups: backup::list(std::path::Path::new(backup::BACKUP_DIR)),
+                backup_choice: 0,
+                scenario: None,
+                scenario_actions: VecDeque::new(),
+                hand_actions: Vec::new(),
+                gallery: Gallery::load(gallery::GALLERY_FILE_NAME),
+                review: SessionReview::default(),
+                review_shown: false,
+                would_have_drawn: None,
+                session: SessionSummary::default(),
+                ghost: Ghost::default(),
+                summary_shown: false,
+                #[cfg(feature = "hot-reload")]
+                asset_watcher: None,
             }
         )
     }
 
+    /// A state showing one of the fixed scenes, for golden-image tests. Cards are placed
+    /// face up right away and nothing is ever saved.
+    pub fn for_scene(ctx: &mut Context, scene: Scene) -> GameResult<MainState> {
+        let profile = Profile { name: String::from(scene::FIXTURE_PLAYER_NAME), ..Profile::default() };
+        let mut state = MainState::new(ctx, scene::FIXTURE_STATS, "", Config::default(), profile, History::default(), TrainerStats::default())?;
+
+        state.status = match scene {
+            Scene::Menu | Scene::BulgarianMenu | Scene::SmallBulgarianMenu => GameStatus::Menu,
+            Scene::Help | Scene::BulgarianHelp | Scene::SmallBulgarianHelp => GameStatus::Help,
+            Scene::MidHand | Scene::Outcome => GameStatus::Play,
+        };
+        state.language = scene.language();
+        state.layout.scale = scene.ui_scale();
+        state.layout.compact = state.layout.compact_for(graphics::drawable_size(ctx).0);
+        state.board.relayout(&state.layout);
+        fit_screen(ctx, &state.layout);
+
+        let (player_cards, dealer_cards) = scene.cards();
+        for card_name in player_cards {
+            state.board.place_card(ctx, card_name)?;
+        }
+        state.board.turn = board::Turn::Dealer;
+        for card_name in dealer_cards {
+            state.board.place_card(ctx, card_name)?;
+        }
+        state.board.turn = board::Turn::Player;
+
+        state.engine.score(&state.board.dealed_cards_player, board::Turn::Player)?;
+        state.engine.score(&state.board.dealed_cards_dealer, board::Turn::Dealer)?;
+
+        if scene == Scene::Outcome {
+            state.engine.game_over = true;
+            state.engine.outcome = Outcome::Win;
+            state.time_till_game_over = 0.0;
+        }
+
+        Ok(state)
+    }
+
+    /// Draws the current screen off-screen and reads it back.
+    pub fn render_frame(&mut self, ctx: &mut Context) -> GameResult<Frame> {
+        let canvas = graphics::Canvas::with_window_size(ctx)?;
+
+        graphics::set_canvas(ctx, Some(&canvas));
+        let drawn = self.draw_screen(ctx);
+        graphics::set_canvas(ctx, None);
+        drawn?;
+
+        let image = canvas.to_image(ctx)?;
+        Ok(Frame {
+            width: image.width() as u32,
+            height: image.height() as u32,
+            pixels: image.to_rgba8(ctx)?,
+        })
+    }
+
+    /// Starts a hand with the scenario's cards on top of the deck and replays its actions.
+    /// The hand doesn't count towards the stats.
+    pub fn start_scenario(&mut self, scenario: Scenario) {
+        self.board.deck = board::Deck::stacked(&mut self.rng.gameplay, &scenario.deck);
+        self.scenario_actions = scenario.actions.iter().copied().collect();
+        self.scenario = Some(scenario);
+        self.status = GameStatus::Play;
+    }
+
+    fn play_scenario_action(&mut self, ctx: &mut Context, action: Action) -> GameResult<()> {
+        self.apply_action(ctx, action.into())
+    }
+
+    /// The one way the player's actions reach the hand: the engine checks and applies them, and
+    /// the board and the screen follow what it says happened.
+    fn apply_action(&mut self, ctx: &mut Context, action: GameAction) -> GameResult<()> {
+        // replays don't count, so they don't spend power-ups either
+        let power_ups = if self.practice || self.scenario.is_some() { None } else { Some(&mut self.power_ups_count) };
+
+        let events = match self.engine.apply(action, &mut self.board.turn, power_ups) {
+            Ok(events) => events,
+            Err(rejection) => {
+                self.show_toast(rejection.reason());
+                self.rejection.show(rejection);
+                return Ok(());
+            },
+        };
+
+        for event in events {
+            self.hand_actions.push(match event {
+                GameEvent::CardRequested => Action::Hit,
+                GameEvent::TurnPassed => Action::Stand,
+                GameEvent::PowerUpUsed(PowerUp::Hint) => Action::Hint,
+                GameEvent::PowerUpUsed(PowerUp::Handicap) => Action::Handicap,
+            });
+
+            match event {
+                GameEvent::CardRequested => {
+                    self.judge_situation(Action::Hit);
+                    self.review.on_action(&self.board.dealed_cards_player, Action::Hit, self.rules());
+                    self.deal_card(ctx)?;
+                },
+                GameEvent::TurnPassed => {
+                    self.judge_situation(Action::Stand);
+                    self.review.on_action(&self.board.dealed_cards_player, Action::Stand, self.rules());
+                    // standing on 21 leaves nothing to wonder about
+                    if game_engine::hand_value(&self.board.dealed_cards_player) < 21 {
+                        self.would_have_drawn = self.board.deck.top_card().cloned();
+                    }
+                },
+                GameEvent::PowerUpUsed(PowerUp::Hint) => self.show_hint(),
+                GameEvent::PowerUpUsed(PowerUp::Handicap) => self.table_talk.react(TalkEvent::HandicapUsed, self.config.table_talk, &mut self.rng.cosmetic),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// What the player's input is checked against, see table_input.rs
+    fn table_state(&self) -> TableState {
+        TableState {
+            hand_over: self.engine.game_over,
+            dealer_turn: matches!(self.board.turn, board::Turn::Dealer),
+            replaying: !self.scenario_actions.is_empty(),
+            card_moving: self.board.card_moving,
+        }
+    }
+
+    fn turn_timer_running(&self) -> bool {
+        self.turn_timer.is_on() && self.table_state().lock().is_none() && !self.board.dealed_cards_player.is_empty()
+    }
+
+    /// Rules the current hand is played by, for the best play.
+    fn rules(&self) -> Rules {
+        self.engine.rules()
+    }
+
+    /// A click on the deck or the hit key. A hard 17 or more asks first, see hit_warning.rs.
+    fn hit(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let state = self.table_state();
+        if !state.passes(Action::Hit) ||
+            (state.allows(Action::Hit) && self.config.warn_risky_hits && !self.hit_warning.allow_hit(&self.board.dealed_cards_player)) {
+            return Ok(());
+        }
+
+        self.apply_action(ctx, GameAction::Hit)
+    }
+
+    /// Ends the player's turn.
+    fn stand(&mut self, ctx: &mut Context) {
+        if let Err(e) = self.apply_action(ctx, GameAction::Stand) {
+            eprintln!("Could not stand: {}", e);
+        }
+    }
+
+    fn use_power_up(&mut self, ctx: &mut Context, power_up: PowerUp) {
+        if let Err(e) = self.apply_action(ctx, GameAction::UsePowerUp(power_up)) {
+            eprintln!("Could not use the {}: {}", power_up.name(), e);
+        }
+    }
+
+    /// A key of the key map pressed during a hand.
+    fn key_action(&mut self, ctx: &mut Context, key: event::KeyCode) {
+        let state = self.table_state();
+
+        match self.config.key_map.action(key) {
+            Some(KeyAction::Hit) => {
+                // pressing it again while the hit warning is up hits, like a new click on the deck
+                self.hit_warning.confirm();
+                if let Err(e) = self.hit(ctx) {
+                    eprintln!("Could not deal the card: {}", e);
+                }
+            },
+            Some(KeyAction::Stand) if state.passes(Action::Stand) => self.stand(ctx),
+            Some(KeyAction::Hint) if state.passes(Action::Hint) => self.use_power_up(ctx, PowerUp::Hint),
+            Some(KeyAction::Handicap) if state.passes(Action::Handicap) => self.use_power_up(ctx, PowerUp::Handicap),
+            Some(KeyAction::Coach) => {
+                self.config.coach_mode = !self.config.coach_mode;
+                if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                    eprintln!("Could not save the coach mode: {}", e);
+                }
+                let message = if self.config.coach_mode {
+                    format!("Coach view on, open {} in a second window", coach::COACH_FILE_NAME)
+                } else {
+                    String::from("Coach view off")
+                };
+                self.show_toast(message);
+            },
+            Some(KeyAction::MinimalHud) => {
+                self.config.minimal_hud = !self.config.minimal_hud;
+                if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                    eprintln!("Could not save the minimal HUD: {}", e);
+                }
+                let message = if self.config.minimal_hud { "Minimal HUD on" } else { "Minimal HUD off" };
+                self.show_toast(String::from(message));
+            },
+            Some(KeyAction::GameSpeed) => {
+                if let Err(e) = self.change_game_speed(1) {
+                    eprintln!("Could not save the game speed: {}", e);
+                }
+                self.show_toast(format!("Game speed: {}", self.config.game_speed.name()));
+            },
+            _ => (),
+        }
+    }
+
+    /// R on the menu, or once a hand is over: the next hand right away, a practice one if the
+    /// last hand was.
+    fn play_again(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if matches!(self.status, GameStatus::Play) {
+            self.reset(ctx)?;
+        }
+
+        self.practice = self.last_hand_practice;
+        self.status = GameStatus::Play;
+        if self.practice && self.situation.is_some() {
+            // a burn would take the situation's first card
+            self.deal_situation();
+            return Ok(());
+        } else if self.practice {
+            self.deal_from_practice_shoe();
+        }
+        // scenarios stack their own deck and never come through here, so they keep every card
+        self.board.burn_card(ctx, self.config.burn_card)
+    }
+
+    /// Deals the hand picked on the review screen again, as a scenario so it doesn't count.
+    fn replay_reviewed_hand(&mut self) {
+        if let Some(hand) = self.review.selected_hand() {
+            let scenario = hand.scenario();
+            self.start_scenario(scenario);
+        }
+    }
+
+    fn finish_scenario(&mut self, scenario: Scenario) {
+        self.scenario_actions.clear();
+
+        let message = match scenario.expected {
+            Some(expected) if expected != self.engine.outcome => {
+                format!("Scenario expected {}, got {}", expected.name(), self.engine.outcome.name())
+            },
+            Some(_) => format!("Scenario ended as expected: {}", self.engine.outcome.name()),
+            None => format!("Scenario ended: {}", self.engine.outcome.name()),
+        };
+        self.show_toast(message);
+    }
+
+    /// Starts watching the resources folder on disk for changed assets.
+    #[cfg(feature = "hot-reload")]
+    pub fn watch_resources(&mut self, resources_dir: &std::path::Path) {
+        self.asset_watcher = Some(AssetWatcher::new(resources_dir));
+    }
+
+    #[cfg(feature = "hot-reload")]
+    fn reload_changed_assets(&mut self, ctx: &mut Context, time_delta: f32) -> GameResult<()> {
+        let changed = match self.asset_watcher.as_mut() {
+            Some(watcher) => watcher.update(time_delta),
+            None => return Ok(()),
+        };
+
+        // the fonts and the board each reload what's theirs. A file saved half-written keeps the
+        // old asset and is tried again when it changes next.
+        for path in changed {
+            if let Err(e) = self.fonts.reload(ctx, &path).and_then(|_| self.board.reload_asset(ctx, &path)) {
+                eprintln!("Could not reload {}: {}", path, e);
+            }
+        }
+
+        Ok(())
+    }
+
     fn deal_card(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let dealed_card = self.board.deck.deal_card(ctx)?; 
+        let dealed_card = self.board.deal_card(ctx)?;
         self.board.set_card(dealed_card);                  
         self.board.assets.card_deal_sound.play(ctx)?;      
 
         Ok(())
     }
     
-    fn mouse_over_button(&self, mouse_position: Point2<f32>, required_position: Point2<f32>) -> bool {
-        let matches_horizontal = (mouse_position.x >= required_position.x - 10.0) 
-                                    && (mouse_position.x <= required_position.x + 170.0);
+    /// Checks the mouse against the area the text actually covers(on the side of the screen
+    /// the language reads from), so translated and longer labels stay clickable.
+    fn mouse_over_button(&self, ctx: &Context, mouse_position: Point2<f32>, text: &graphics::Text, required_position: Point2<f32>) -> bool {
+        let dimensions = text.dimensions(ctx);
+        let position = layout::text_position(required_position,
+                                             dimensions.w,
+                                             graphics::screen_coordinates(ctx).w,
+                                             self.language.direction());
+
+        layout::button_rect(position, dimensions.w, dimensions.h).contains(mouse_position)
+    }
 
-        let matches_vertical = (mouse_position.y >= required_position.y - 20.0) 
-                                    && (mouse_position.y <= required_position.y + 60.0);
+    /// The decorative menu font has no Cyrillic(or other non-Latin) letters.
+    fn menu_font(&self) -> graphics::Font {
+        if self.language.latin_script() {
+            self.fonts.menu
+        } else {
+            self.fonts.text_font()
+        }
+    }
 
-        matches_horizontal && matches_vertical
+    /// The label in the player's language, in family wording when that's on.
+    fn tr(&self, label: Label) -> &'static str {
+        i18n::tr_mode(self.language, self.config.family_mode, label)
+    }
+
+    fn label_text(&self, label: Label, font: graphics::Font, size: f32) -> graphics::Text {
+        let mut text = graphics::Text::new(self.tr(label));
+        text.set_font(font, graphics::PxScale::from(size));
+
+        text
+    }
+
+    fn back_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Back, self.fonts.text_font(), HELP_BACK_TEXT_SIZE)
+    }
+
+    /// Draws text at a position given for left-to-right languages, mirrored for right-to-left ones.
+    fn draw_text(&self, ctx: &mut Context, text: &graphics::Text, position: Point2<f32>) -> GameResult<()> {
+        let width = text.dimensions(ctx).w;
+        let position = layout::text_position(position, width, graphics::screen_coordinates(ctx).w, self.language.direction());
+
+        graphics::draw(ctx, text, graphics::DrawParam::default().dest(position))
+    }
+
+    /// The mouse in screen coordinates.
+    fn mouse_position(&self, ctx: &Context) -> Point2<f32> {
+        self.layout.to_screen(mouse::position(ctx))
     }
 
     fn mouse_over_deck(&self, mouse_position: Point2<f32>) -> bool {
-        let matches_horizontal = (mouse_position.x >= board::DECK_POSITION.x - card::CARD_DIMENSION_X / 2.0) 
-                                    && (mouse_position.x <= board::DECK_POSITION.x + card::CARD_DIMENSION_X / 2.0);
+        let (card_width, card_height) = self.board.card_set.card_dimensions();
+        let matches_horizontal = (mouse_position.x >= self.board.deck_position.x - card_width / 2.0) 
+                                    && (mouse_position.x <= self.board.deck_position.x + card_width / 2.0);
 
-        let matches_vertical = (mouse_position.y >= board::DECK_POSITION.y - card::CARD_DIMENSION_Y / 2.0) 
-                                    && (mouse_position.y <= board::DECK_POSITION.y + card::CARD_DIMENSION_Y / 2.0);
+        let matches_vertical = (mouse_position.y >= self.board.deck_position.y - card_height / 2.0) 
+                                    && (mouse_position.y <= self.board.deck_position.y + card_height / 2.0);
 
         matches_horizontal && matches_vertical
     }
@@ -159,19 +954,161 @@ impl MainState {
         }
     }
 
-    fn reset(&mut self, ctx: &mut Context) -> GameResult<()> {
-        if matches!(self.engine.outcome, Outcome::Win) {
-            self.increase_stats();
+    fn record_hand(&mut self) {
+        let hand = HandRecord {
+            id: self.history.next_id(),
+            timestamp: history::now(),
+            outcome: self.engine.outcome.clone(),
+            player_score: self.engine.player_score,
+            dealer_score: self.engine.dealer_score,
+            hint_used: !matches!(self.engine.hint, HintStatus::Unused),
+            handicap_used: self.engine.dealer_handicap_active,
+            player_start: self.board.dealed_cards_player.get(..2).map(game_engine::hand_value),
+            dealer_up_card: self.board.dealed_cards_dealer.first().and_then(|card| card.get_points().ok()),
+            would_have_drawn: self.would_have_drawn.as_ref().map(|card| card.name.clone()),
+        };
+
+        let id = hand.id;
+        self.history.record(&self.saver, history::HISTORY_FILE_NAME, hand);
+        self.record_challenge_hand();
+
+        if let Some(kind) = gallery::notable(&self.board.dealed_cards_player, &self.board.dealed_cards_dealer, &self.engine.outcome) {
+            let names = |cards: &[card::Card]| cards.iter().map(|card| card.name.clone()).collect();
+            self.gallery.record(&self.saver, gallery::GALLERY_FILE_NAME, GalleryHand {
+                id,
+                timestamp: history::now(),
+                kind,
+                outcome: self.engine.outcome.clone(),
+                player_cards: names(&self.board.dealed_cards_player),
+                dealer_cards: names(&self.board.dealed_cards_dealer),
+                actions: self.hand_actions.clone(),
+            });
+            self.show_toast(format!("{}! The hand is in the gallery", kind.describe()));
         }
 
-        self.save();
+        let line = transcript::describe_hand(&self.board.dealed_cards_player, &self.board.dealed_cards_dealer, &self.engine.outcome);
+        self.transcript.add(&self.saver, transcript::SESSION_LOG_FILE_NAME, line);
+
+        if !self.scores_unverified {
+            self.leaderboard.queue_score(&leaderboard::Entry {
+                name: self.profile.display_name().to_string(),
+                best_streak: self.history.longest_win_streak(),
+                wins: self.wins,
+            });
+        }
+        self.leaderboard.sync();
+    }
+
+    /// Counts the finished hand towards the missions and hands out the rewards of finished ones.
+    fn update_missions(&mut self) {
+        let hand = HandEvent {
+            outcome: self.engine.outcome.clone(),
+            player_cards: self.board.dealed_cards_player.len(),
+            player_score: self.engine.player_score,
+            power_ups_used: !matches!(self.engine.hint, HintStatus::Unused) || self.engine.dealer_handicap_active,
+        };
+
+        for mission in self.profile.missions.on_hand(&hand) {
+            match mission.reward {
+                Reward::PowerUps(hints, handicaps) => {
+                    self.power_ups_count.0 += hints;
+                    self.power_ups_count.1 += handicaps;
+                },
+                Reward::Felt(name) => {
+                    if !self.profile.earned_felts.iter().any(|earned| earned == name) {
+                        self.profile.earned_felts.push(name.to_string());
+                    }
+                },
+            }
 
-        self.board = Board::new(ctx)?;
-        self.engine = GameEngine::new();
+            self.show_toast(format!("Mission complete: {}! You get {}", mission.description, mission.reward.describe()));
+        }
+
+        self.award_achievements();
+        self.save_profile();
+    }
+
+    fn award_achievements(&mut self) {
+        let progress = Progress::new(self.wins, &self.history, &self.profile);
+        for achievement in achievements::newly_earned(&progress, &self.profile.achievements) {
+            self.profile.achievements.push(achievement.id.to_string());
+            self.show_toast(format!("Achievement: {}! See UNLOCKS for what it gives", achievement.description));
+        }
+    }
+
+    /// Adds the frame to the time played. The achievements for it are checked once a minute,
+    /// and the profile is saved with the next hand or on quitting.
+    fn update_playtime(&mut self, time_delta: f32) {
+        let seconds = self.playtime_clock.update(time_delta);
+        if seconds == 0 {
+            return;
+        }
+
+        let before = self.profile.playtime;
+        self.profile.playtime += seconds;
+        if before / playtime::MINUTE != self.profile.playtime / playtime::MINUTE {
+            let earned = self.profile.achievements.len();
+            self.award_achievements();
+            if self.profile.achievements.len() != earned {
+                self.save_profile();
+            }
+        }
+    }
+
+    fn reset(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.last_hand_practice = self.practice;
+
+        if let Some(scenario) = self.scenario.take() {
+            self.review.forget_hand();
+            self.finish_scenario(scenario);
+        } else if self.practice {
+            self.review.forget_hand();
+            self.practice = false;
+            let message = match &self.situation {
+                Some(situation) => format!("Practice hand: {}, R deals {} again", self.engine.outcome.name(), situation.name()),
+                None => format!("Practice hand: {}", self.engine.outcome.name()),
+            };
+            self.show_toast(message);
+        } else {
+            self.review.on_hand_end(&self.board.dealed_cards_player, &self.board.dealed_cards_dealer, self.engine.outcome.clone());
+            self.session.on_hand(&self.engine.outcome, self.engine.player_score, &self.board.dealed_cards_player);
+            // the cards dealt and then the rest of the deck are the deck the hand was dealt from
+            let deck: Vec<card::Card> = self.board.dealed_cards_player.iter().
+                chain(&self.board.dealed_cards_dealer).
+                chain(self.board.deck.cards_from_top()).
+                cloned().
+                collect();
+            self.ghost.play(&deck);
+
+            if matches!(self.engine.outcome, Outcome::Win) {
+                self.increase_stats();
+            }
+            self.update_missions();
+
+            self.save();
+            self.record_hand();
+            // a shoe that carries over deals the next hand too
+            self.shoe_index = self.board.deck.next_shoe(self.shoe_index);
+        }
+
+        self.would_have_drawn = None;
+        self.hand_actions.clear();
+        let board = Board::new(ctx, self.board.card_set.clone(), &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+        let last_board = std::mem::replace(&mut self.board, board);
+        if last_board.deck.carries_over() {
+            self.board.continue_shoe(last_board.deck);
+        } else if last_board.deck.cut_placed() {
+            self.show_toast(String::from("The cut card is out, shuffling"));
+        }
+        self.engine = GameEngine { auto_stand_on_21: self.config.auto_stand_on_21, ..GameEngine::new() };
         self.status = GameStatus::Menu;
-        self.time_till_game_over = SECONDS_TILL_GAME_OVER;
-        self.time_till_menu = SECONDS_TILL_MENU;
+        self.time_till_game_over = self.config.game_speed.seconds_till_outcome();
+        self.time_till_menu = self.config.game_speed.seconds_on_outcome(self.config.auto_advance);
+        self.time_till_dealer_card = self.config.game_speed.dealer_delay();
+        self.sweat_waiting = false;
         self.hint_range = None;
+        self.hit_warning = HitWarning::default();
+        self.turn_timer = TurnTimer::new(self.config.turn_timer_seconds);
 
         Ok(())
     }
@@ -187,305 +1124,2953 @@ impl MainState {
             }
             
             // check if game has reached an end state
+            let was_over = self.engine.game_over;
             self.engine.check_outcome(&mut self.board.turn);   
             self.board.calculate_result = false;
+
+            if self.engine.game_over && !was_over {
+                if let Some(event) = TalkEvent::of_hand(&self.board.dealed_cards_player, &self.board.dealed_cards_dealer, &self.engine.outcome) {
+                    self.table_talk.react(event, self.config.table_talk, &mut self.rng.cosmetic);
+                }
+            }
         }
 
         Ok(())
     }
     
-    fn update_menu(&mut self, ctx: &mut Context) {
-        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
-            let mouse_position = mouse::position(ctx);
-
-            if self.mouse_over_button(mouse_position, MENU_PLAY_TEXT_POSITION) {
-                self.status = GameStatus::Play;
-            } else if self.mouse_over_button(mouse_position, MENU_HELP_TEXT_POSITION) {
-                self.status = GameStatus::Help;
-            }
+    /// The menu comes once the intro is over or skipped, and the button of a click that
+    /// skipped it is let go, so the click doesn't press a menu button too.
+    fn update_splash(&mut self, ctx: &mut Context, time_delta: f32) {
+        self.board.card_set.assets().update(ctx);
+        self.splash.update(time_delta);
+
+        if self.splash.finished() && !mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            self.status = if self.setup.is_some() { GameStatus::Setup } else { GameStatus::Menu };
         }
-    }  
-
-    fn update_help(&mut self, ctx: &mut Context) {
-        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
-            let mouse_position = mouse::position(ctx);
+    }
 
-            if self.mouse_over_button(mouse_position, HELP_BACK_TEXT_POSITION) {
-                self.status = GameStatus::Menu;
-            }
-        }
+    /// Shows the first-run wizard after the intro, see setup.rs. Nothing is saved before it's
+    /// done.
+    pub fn start_setup(&mut self) {
+        self.setup = Some(Setup::new(self.language));
     }
 
-    fn update_game(&mut self, ctx: &mut Context, time_delta: f32) -> GameResult<()> {
-        if self.time_till_game_over <= 0.0 { // check for game over
-            if self.time_till_menu > 0.0 {
-                self.time_till_menu -= time_delta;
-            } else {
-                self.reset(ctx)?;
-            }
-        } else if self.engine.game_over && self.time_till_game_over > 0.0 {
-            self.time_till_game_over -= time_delta;
+    fn setup_key(&mut self, ctx: &mut Context, keycode: event::KeyCode) -> GameResult<()> {
+        let setup = match &mut self.setup {
+            Some(setup) => setup,
+            None => return Ok(()),
+        };
+
+        match keycode {
+            event::KeyCode::Left => setup.change(-1),
+            event::KeyCode::Right => setup.change(1),
+            event::KeyCode::Up => setup.previous_step(),
+            event::KeyCode::Back => setup.erase_char(),
+            event::KeyCode::Return if setup.next_step() => return self.finish_setup(ctx),
+            // leaving halfway saves nothing, so the wizard comes back on the next start
+            event::KeyCode::Escape => event::quit(ctx),
+            _ => (),
         }
+        // the wizard speaks the language picked
+        self.language = setup.language;
 
-        if matches!(self.board.turn, board::Turn::Dealer) { // dealer's turn
-            if !self.engine.game_over && !self.board.card_moving {
-                self.deal_card(ctx)?;
-            }
-        } else { // player's turn
-            if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
-                let mouse_position = mouse::position(ctx);
+        Ok(())
+    }
 
-                if !self.engine.game_over && self.mouse_over_deck(mouse_position) && !self.board.card_moving {
-                    self.deal_card(ctx)?;
-                    
-                    if matches!(self.engine.hint, HintStatus::Active) {
-                        self.engine.hint = HintStatus::Exhausted;
-                    }
-                }
+    /// Writes what the wizard picked to the config, the profile, the layout and the stats file.
+    fn finish_setup(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let setup = match self.setup.take() {
+            Some(setup) => setup,
+            None => return Ok(()),
+        };
+
+        self.language = setup.language;
+        self.config.language = self.language.code().to_string();
+        if !setup.name.trim().is_empty() {
+            self.profile.set_name(&setup.name);
+        }
+        if setup.tutorial {
+            self.config.what_if_odds = true;
+        }
+        if setup.ui_scale != self.layout.scale {
+            self.layout.scale = setup.ui_scale;
+            self.layout.compact = self.layout.compact_for(graphics::drawable_size(ctx).0);
+            fit_screen(ctx, &self.layout);
+            if let Err(e) = layout::save_scale(layout::LAYOUT_FILE_NAME, setup.ui_scale) {
+                eprintln!("Could not save the UI scale: {}", e);
             }
+            // the board takes its positions from the layout when it's made
+            self.board = Board::new(ctx, self.board.card_set.clone(), &mut self.rng.shoe(self.shoe_index), &self.layout)?;
         }
 
-        self.update_score()?; // update score if needed
-
-        self.board.update(ctx, time_delta);
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the setup: {}", e);
+        }
+        self.save_profile();
+        self.save();
+        self.status = if setup.tutorial { GameStatus::Help } else { GameStatus::Menu };
 
         Ok(())
     }
 
-    fn use_hint(&mut self) {
-        if self.power_ups_count.0 == 0 {
-            return;
-        }
+    fn update_menu(&mut self, ctx: &mut Context) {
+        // the card images are read in the background meanwhile, see AssetManager
+        self.board.card_set.assets().update(ctx);
 
-        if matches!(self.engine.hint, HintStatus::Unused) {
-            self.engine.hint = HintStatus::Active;
-            self.power_ups_count.0 -= 1;
-            
-            let top_card_points = self.board.deck.get_top_card().get_points().unwrap();
-            let mut rng = rand::thread_rng();
-            let rand_num: u32 = rng.gen_range(0..HINT_RANGE_SIZE);
-
-            if top_card_points - rand_num + HINT_RANGE_SIZE > 11 {
-                self.hint_range = Some((7, 11));
-            } else if (top_card_points as i32) - (rand_num as i32) < 2 {
-                self.hint_range = Some((2, 6));
-            } else {
-                self.hint_range = Some((top_card_points - rand_num, top_card_points - rand_num + HINT_RANGE_SIZE));
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            let font = self.menu_font();
+            let play_button_text = self.label_text(Label::Play, font, MENU_PLAY_TEXT_SIZE);
+            let settings_button_text = self.label_text(Label::Settings, font, MENU_SETTINGS_TEXT_SIZE);
+            let leaderboard_button_text = self.label_text(Label::Leaderboard, font, MENU_LEADERBOARD_TEXT_SIZE);
+            let history_button_text = self.label_text(Label::History, font, MENU_HISTORY_TEXT_SIZE);
+            let analysis_button_text = self.label_text(Label::Analysis, font, MENU_ANALYSIS_TEXT_SIZE);
+            let trainer_button_text = self.label_text(Label::Trainer, font, MENU_TRAINER_TEXT_SIZE);
+            let help_button_text = self.label_text(Label::Help, font, MENU_HELP_TEXT_SIZE);
+
+            if self.mouse_over_button(ctx, mouse_position, &play_button_text, self.layout.at(Anchor::MenuPlayText)) {
+                self.status = GameStatus::Play;
+            } else if self.mouse_over_button(ctx, mouse_position, &settings_button_text, self.layout.at(Anchor::MenuSettingsText)) {
+                self.status = GameStatus::Settings;
+            } else if self.mouse_over_button(ctx, mouse_position, &leaderboard_button_text, self.layout.at(Anchor::MenuLeaderboardText)) {
+                self.status = GameStatus::Leaderboard;
+                self.leaderboard.sync();
+            } else if self.mouse_over_button(ctx, mouse_position, &history_button_text, self.layout.at(Anchor::MenuHistoryText)) {
+                self.status = GameStatus::Transcript;
+                // newest hands are at the bottom
+                self.transcript_scroll = self.transcript.lines.len().saturating_sub(TRANSCRIPT_VISIBLE_ROWS);
+            } else if self.mouse_over_button(ctx, mouse_position, &analysis_button_text, self.layout.at(Anchor::MenuAnalysisText)) {
+                self.status = GameStatus::Analysis;
+            } else if self.mouse_over_button(ctx, mouse_position, &trainer_button_text, self.layout.at(Anchor::MenuTrainerText)) {
+                self.status = GameStatus::Trainer;
+            } else if self.mouse_over_button(ctx, mouse_position, &help_button_text, self.layout.at(Anchor::MenuHelpText)) {
+                self.status = GameStatus::Help;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.unlocks_button_text(), self.layout.at(Anchor::MenuUnlocksText)) {
+                self.status = GameStatus::Unlocks;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.about_button_text(), self.layout.at(Anchor::MenuAboutText)) {
+                self.status = GameStatus::About;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.profile_button_text(), self.layout.at(Anchor::MenuProfileText)) {
+                self.status = GameStatus::Profile;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.practice_button_text(), self.layout.at(Anchor::MenuPracticeText)) {
+                self.practice = true;
+                self.situation = None;
+                self.deal_from_practice_shoe();
+                self.status = GameStatus::Play;
             }
         }
-    }
-
-    fn use_handicap(&mut self) {
-        if self.power_ups_count.1 == 0 {
-            return;
-        }
+    }  
 
-        if self.engine.dealer_handicap_active == false {
-            self.engine.dealer_handicap_active = true;
-            self.power_ups_count.1 -= 1;
-        }
+    fn unlocks_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Unlocks, self.fonts.text_font(), MISSIONS_TITLE_SIZE)
     }
 
-    fn draw_menu(&self, ctx: &mut Context) -> GameResult<()> {
-        let font = graphics::Font::new(ctx, "\\font\\FancyMenuFont.ttf")?;
-
-        let mut title = graphics::Text::new("MENU");
-        title.set_font(font, graphics::PxScale::from(MENU_TITLE_SIZE));
+    fn about_button_text(&self) -> graphics::Text {
+        self.label_text(Label::About, self.fonts.text_font(), MISSIONS_TITLE_SIZE)
+    }
 
-        let mut play_button_text = graphics::Text::new("PLAY");
-        play_button_text.set_font(font, graphics::PxScale::from(MENU_PLAY_TEXT_SIZE));
+    fn profile_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Profile, self.fonts.text_font(), MISSIONS_TITLE_SIZE)
+    }
 
-        let mut help_button_text = graphics::Text::new("HELP");
-        help_button_text.set_font(font, graphics::PxScale::from(MENU_HELP_TEXT_SIZE));
+    fn practice_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Practice, self.fonts.text_font(), MISSIONS_TITLE_SIZE)
+    }
 
-        graphics::draw(ctx, &title, graphics::DrawParam::default().dest(MENU_TITLE_POSITION))?;
-        graphics::draw(ctx, &play_button_text, graphics::DrawParam::default().dest(MENU_PLAY_TEXT_POSITION))?;
-        graphics::draw(ctx, &help_button_text, graphics::DrawParam::default().dest(MENU_HELP_TEXT_POSITION))
+    fn tip_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Tip, self.fonts.text_font(), TIP_TEXT_SIZE)
     }
 
-    fn draw_help(&self, ctx: &mut Context) -> GameResult<()> {
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
+    /// A hint can be tipped once a counted hand is over. Family mode has no tipping.
+    fn can_tip(&self) -> bool {
+        matches!(self.status, GameStatus::Play) &&
+            self.engine.game_over &&
+            self.scenario.is_none() &&
+            !self.practice &&
+            !self.config.family_mode &&
+            self.power_ups_count.0 > 0
+    }
 
-        let help_description_str = "        Standard blackjack rules.
+    fn give_tip(&mut self) {
+        self.power_ups_count.0 -= 1;
+        self.profile.tips += 1;
+        self.tip.give(&mut self.rng.cosmetic);
+        self.table_talk.hush();
+    }
 
-        hit = Left-Mouse-Click over deck
-        stand = Space 
-        use hint = Key1
-        use handicap = Key2
-        exit = Escape
-        
-        hint: gives approximation of next card's points
-        handicap: dealer's score is reduced with 1 point";
+    fn undo_button_text(&self) -> graphics::Text {
+        let text = format!("{} ({:.0} s)", self.tr(Label::Undo), self.profile_screen.seconds_to_undo().ceil());
+        let mut undo_text = graphics::Text::new(text);
+        undo_text.set_font(self.fonts.text_font(), graphics::PxScale::from(PROFILE_UNDO_TEXT_SIZE));
 
-        let mut title = graphics::Text::new("HELP");
-        title.set_font(font, graphics::PxScale::from(HELP_TITLE_SIZE));
+        undo_text
+    }
 
-        let mut help_description = graphics::Text::new(help_description_str);
-        help_description.set_font(font, graphics::PxScale::from(HELP_DESCRIPTION_SIZE));
+    fn update_profile(&mut self, ctx: &mut Context, time_delta: f32) -> GameResult<()> {
+        self.profile_screen.update(time_delta);
 
-        let mut back_button_text = graphics::Text::new("BACK");
-        back_button_text.set_font(font, graphics::PxScale::from(HELP_BACK_TEXT_SIZE));
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
 
-        // create and draw a rectangle for button
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.profile_screen.cancel();
+                self.status = GameStatus::Menu;
+            } else if self.profile_screen.undo.is_some() && self.mouse_over_button(ctx, mouse_position, &self.undo_button_text(), self.layout.at(Anchor::ProfileUndoText)) {
+                self.undo_profile_action(ctx)?;
+            }
+        }
 
-        graphics::draw(ctx, &title, graphics::DrawParam::default().dest(HELP_TITLE_POSITION))?;
-        graphics::draw(ctx, &help_description, graphics::DrawParam::default().dest(HELP_DESCRIPTION_POSITION))?;
-        graphics::draw(ctx, &back_button_text, graphics::DrawParam::default().dest(HELP_BACK_TEXT_POSITION))
+        Ok(())
     }
 
-    fn draw_score(&self, ctx: &mut Context) -> GameResult<()> {  
-        self.engine.draw_score(ctx, PLAYER_SCORE_POSITION, DEALER_SCORE_POSITION)?;
+    fn update_leaderboard(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
 
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
-        
-        let mut text_player = graphics::Text::new("PLAYER SCORE:");
-        text_player.set_font(font, graphics::PxScale::from(PLAYER_TEXT_SCORE_SIZE));
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Menu;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.challenge_button_text(), self.layout.at(Anchor::LeaderboardChallengeText)) {
+                self.challenge_friends = challenge::load_friends(std::path::Path::new(challenge::CHALLENGE_DIR), &self.challenge_log);
+                // the code is out, its hands must stay the same for whoever plays them
+                self.challenge_active = true;
+                self.status = GameStatus::Challenge;
+            }
+        }
+    }
 
-        let mut text_dealer = graphics::Text::new("DEALER SCORE:");
-        text_dealer.set_font(font, graphics::PxScale::from(DEALER_TEXT_SCORE_SIZE));
+    fn challenge_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Challenge, self.fonts.text_font(), ANALYSIS_CALCULATOR_TEXT_SIZE)
+    }
 
-        graphics::draw(ctx, &text_player, graphics::DrawParam::default().dest(PLAYER_TEXT_SCORE_POSITION))?;
-        graphics::draw(ctx, &text_dealer, graphics::DrawParam::default().dest(DEALER_TEXT_SCORE_POSITION))
+    fn export_log_button_text(&self) -> graphics::Text {
+        self.label_text(Label::ExportLog, self.fonts.text_font(), ANALYSIS_CALCULATOR_TEXT_SIZE)
     }
 
-    fn draw_power_ups(&self, ctx: &mut Context) -> GameResult<()> {
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
+    fn update_challenge(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
 
-        let available_power_ups = "AVAILABLE POWER UPS:\n".to_string();
-        let first_power_up = "1. Next card approximation x".to_owned() + self.power_ups_count.0.to_string().as_str() + "\n";
-        let second_power_up = "2. Activate dealer handicap x".to_owned() + self.power_ups_count.1.to_string().as_str() + "\n";
-        let text = available_power_ups + first_power_up.as_str() + second_power_up.as_str(); 
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Leaderboard;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.export_log_button_text(), self.layout.at(Anchor::ChallengeExportText)) {
+                self.export_challenge_log();
+            }
+        }
+    }
 
-        
-        let mut text_power_ups = graphics::Text::new(text.as_str());
-        text_power_ups.set_font(font, graphics::PxScale::from(POWER_UPS_TEXT_SIZE));
-        
-        graphics::draw(ctx, &text_power_ups, graphics::DrawParam::default().dest(POWER_UPS_TEXT_POSITION))
+    /// The session's code, the seed with the rules that change the cards.
+    fn challenge(&self) -> Challenge {
+        Challenge { seed: self.rng.seed, burn_card: self.config.burn_card }
     }
 
-    fn draw_hint_text(&self, ctx: &mut Context) -> GameResult<()> {
-        if self.hint_range.is_none() {
-            return Ok(())
+    /// A counted hand goes in the challenge log, a fresh one once the code changed.
+    fn record_challenge_hand(&mut self) {
+        let challenge = self.challenge();
+        if self.challenge_log.challenge != challenge {
+            self.challenge_log = ChallengeLog::new(self.profile.display_name(), challenge);
         }
 
-        let begin = self.hint_range.unwrap().0;
-        let end = self.hint_range.unwrap().1;
-
-        let begin_str = begin.clone().to_string();
-        let end_str = end.clone().to_string();
-        let text = "NEXT CARD GIVES BETWEEN: ".to_owned() + begin_str.as_str() + "-" + end_str.as_str();
-
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
-        
-        let mut hint_text = graphics::Text::new(text);
-        hint_text.set_font(font, graphics::PxScale::from(HINT_TEXT_SIZE));
-        
-        graphics::draw(ctx, &hint_text, graphics::DrawParam::default().dest(HINT_TEXT_POSITION))
+        self.challenge_log.hands.push(ChallengeHand {
+            outcome: self.engine.outcome.clone(),
+            player_score: self.engine.player_score,
+            dealer_score: self.engine.dealer_score,
+        });
     }
 
-    fn draw_wins(&self, ctx: &mut Context) -> GameResult<()> {
-        let text = "WINS: ".to_owned() + self.wins.to_string().as_str();
+    fn export_challenge_log(&mut self) {
+        self.challenge_log.player = self.profile.display_name().to_string();
+        let dir = std::path::Path::new(challenge::CHALLENGE_DIR);
 
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
-        
-        let mut wins_text = graphics::Text::new(text);
-        wins_text.set_font(font, graphics::PxScale::from(WINS_TEXT_SIZE));
-        
-        graphics::draw(ctx, &wins_text, graphics::DrawParam::default().dest(WINS_TEXT_POSITION))
+        match self.challenge_log.export(dir) {
+            Ok(path) => self.show_toast("Log exported to ".to_owned() + path.to_string_lossy().as_ref()),
+            Err(e) => self.show_toast("Could not export the log: ".to_owned() + e.to_string().as_str()),
+        }
+        self.challenge_friends = challenge::load_friends(dir, &self.challenge_log);
     }
 
-    fn draw_game_over_text(&self, ctx: &mut Context) -> GameResult<()> {
-        let text;
-        let color;
-        match self.engine.outcome {
-            Outcome::Win => {
-                text = "YOU WIN!";
-                color = graphics::Color::from_rgb(255, 163, 26);
+    /// Plays the typed friend's code from the next hand: its seed deals the same shoes from the
+    /// first one and its burn card rule is taken on.
+    fn accept_challenge(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let challenge = match Challenge::parse(&self.challenge_input) {
+            Ok(challenge) => challenge,
+            Err(e) => {
+                self.show_toast("Not a challenge code: ".to_owned() + e.as_str());
+                return Ok(());
             },
-            Outcome::Draw => {
-                text = "YOU DRAW!";
-                color = graphics::Color::from_rgb(255, 255, 255);
+        };
+
+        self.rng.seed = challenge.seed;
+        self.shoe_index = 0;
+        self.challenge_active = true;
+        if self.config.burn_card != challenge.burn_card {
+            self.config.burn_card = challenge.burn_card;
+            if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                eprintln!("Could not save the burn card rule: {}", e);
             }
-            Outcome::Lose => {
-                text = "YOU LOSE!";
-                color = graphics::Color::from_rgb(204, 0, 0);
-            },
-            _ => {
-                text = "should not be possible";
-                color = graphics::Color::from_rgb(0, 0, 0);
-            },
         }
-        
-        let font = graphics::Font::new(ctx, "\\font\\DejaVuSerif.ttf")?;
+        self.challenge_log = ChallengeLog::new(self.profile.display_name(), challenge);
+        self.challenge_friends = challenge::load_friends(std::path::Path::new(challenge::CHALLENGE_DIR), &self.challenge_log);
+        self.challenge_input.clear();
+        self.board = Board::new(ctx, self.board.card_set.clone(), &mut self.rng.shoe(self.shoe_index), &self.layout)?;
 
-        let game_over_text = graphics::TextFragment::new(text).
-                                                     color(color).
-                                                     font(font).
-                                                     scale(graphics::PxScale::from(GAME_OVER_TEXT_SIZE));
+        self.show_toast(format!("Playing {}, the next hand is its first", challenge.code()));
+        Ok(())
+    }
 
-        graphics::draw(ctx, &graphics::Text::new(game_over_text), graphics::DrawParam::default().dest(GAME_OVER_TEXT_POSITION))?;
+    fn scroll_transcript(&mut self, rows: i32) {
+        let max_scroll = self.transcript.lines.len().saturating_sub(TRANSCRIPT_VISIBLE_ROWS) as i32;
 
-        Ok(())
+        self.transcript_scroll = (self.transcript_scroll as i32 + rows).clamp(0, max_scroll) as usize;
     }
-}
 
-impl event::EventHandler for MainState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        const DESIRED_FPS: u32 = 60;
-        let time_delta = 1.0 / (DESIRED_FPS as f32);
+    fn update_help(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
 
-        while timer::check_update_time(ctx, DESIRED_FPS) {
-            match self.status {
-                GameStatus::Menu => self.update_menu(ctx),
-                GameStatus::Help => self.update_help(ctx),
-                GameStatus::Play => self.update_game(ctx, time_delta)?,    
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Menu;
             }
         }
+    }
 
-        Ok(())
+    fn calculator_button_text(&self) -> graphics::Text {
+        self.label_text(Label::RiskCalculator, self.fonts.text_font(), ANALYSIS_CALCULATOR_TEXT_SIZE)
     }
 
-    fn key_down_event(&mut self,
+    fn update_analysis(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Menu;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.calculator_button_text(), self.layout.at(Anchor::AnalysisCalculatorText)) {
+                self.status = GameStatus::Calculator;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.heatmap_button_text(), self.layout.at(Anchor::AnalysisHeatmapText)) {
+                self.status = GameStatus::Heatmap;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.gallery_button_text(), self.layout.at(Anchor::AnalysisGalleryText)) {
+                self.status = GameStatus::Gallery;
+            }
+        }
+    }
+
+    fn gallery_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Gallery, self.fonts.text_font(), ANALYSIS_CALCULATOR_TEXT_SIZE)
+    }
+
+    fn update_gallery(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Analysis;
+            }
+        }
+    }
+
+    /// Plays the hand picked in the gallery again, the same cards and moves, as a scenario.
+    fn replay_gallery_hand(&mut self) {
+        if let Some(hand) = self.gallery.selected_hand() {
+            let scenario = hand.scenario();
+            self.start_scenario(scenario);
+        }
+    }
+
+    fn heatmap_button_text(&self) -> graphics::Text {
+        self.label_text(Label::Heatmap, self.fonts.text_font(), ANALYSIS_CALCULATOR_TEXT_SIZE)
+    }
+
+    fn update_calculator(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Analysis;
+            } else if matches!(self.status, GameStatus::Calculator) {
+                let picked = self.recent_bet_buttons(ctx).into_iter().
+                    position(|(text, position)| self.mouse_over_button(ctx, mouse_position, &text, position));
+
+                if let Some(index) = picked {
+                    self.calculator.use_recent_bet(index);
+                }
+            }
+        }
+    }
+
+    /// The recent bets as buttons in a row on the bet row, after the "recent:" label. None in
+    /// family mode, which has no bets.
+    fn recent_bet_buttons(&self, ctx: &Context) -> Vec<(graphics::Text, Point2<f32>)> {
+        if self.config.family_mode {
+            return Vec::new();
+        }
+
+        let mut position = self.layout.at(Anchor::CalculatorRecentBets);
+        position.x += self.recent_bets_label().dimensions(ctx).w + CALCULATOR_RECENT_BETS_GAP;
+
+        self.calculator.recent_bets.iter().
+            map(|bet| {
+                let mut text = graphics::Text::new(format!("[{}]", bet));
+                text.set_font(self.fonts.text_font(), graphics::PxScale::from(CALCULATOR_TEXT_SIZE));
+
+                let button = (text, position);
+                position.x += button.0.dimensions(ctx).w + CALCULATOR_RECENT_BETS_GAP;
+                button
+            }).
+            collect()
+    }
+
+    fn recent_bets_label(&self) -> graphics::Text {
+        let mut text = graphics::Text::new("recent:");
+        text.set_font(self.fonts.text_font(), graphics::PxScale::from(CALCULATOR_TEXT_SIZE));
+
+        text
+    }
+
+    fn trainer_button_text(&self, label: Label) -> graphics::Text {
+        self.label_text(label, self.fonts.text_font(), TRAINER_BUTTON_TEXT_SIZE)
+    }
+
+    fn update_trainer(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Menu;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.trainer_button_text(Label::Quiz), self.layout.at(Anchor::TrainerQuizText)) {
+                self.quiz = Some(Quiz::new(&mut rand::thread_rng()));
+                self.status = GameStatus::Quiz;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.trainer_button_text(Label::CountDrill), self.layout.at(Anchor::TrainerDrillText)) {
+                self.drill = Some(self.new_drill());
+                self.status = GameStatus::Drill;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.trainer_button_text(Label::Review), self.layout.at(Anchor::TrainerReviewText)) {
+                self.status = GameStatus::Review;
+            } else if self.mouse_over_button(ctx, mouse_position, &self.trainer_button_text(Label::Situation), self.layout.at(Anchor::TrainerSituationText)) {
+                self.start_situation();
+            }
+        }
+    }
+
+    fn save_trainer_stats(&self) {
+        if let Err(e) = self.trainer_stats.save(trainer::TRAINER_STATS_FILE_NAME) {
+            eprintln!("Could not save the trainer stats: {}", e);
+        }
+    }
+
+    fn update_quiz(&mut self, ctx: &mut Context, time_delta: f32) {
+        if let Some(quiz) = &mut self.quiz {
+            if quiz.update(&mut rand::thread_rng(), time_delta) {
+                self.trainer_stats.record_quiz(quiz);
+                self.save_trainer_stats();
+            }
+        }
+
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Trainer;
+            }
+        }
+    }
+
+    /// Enter submits a typed total, or starts a new round once one is over.
+    fn quiz_enter(&mut self) {
+        match &mut self.quiz {
+            Some(quiz) if quiz.finished() => *quiz = Quiz::new(&mut rand::thread_rng()),
+            Some(quiz) => quiz.submit(),
+            None => (),
+        }
+    }
+
+    fn update_drill(&mut self, ctx: &mut Context, time_delta: f32) {
+        if let Some(drill) = &mut self.drill {
+            if drill.update(&mut rand::thread_rng(), time_delta) {
+                self.trainer_stats.record_drill(drill);
+                self.save_trainer_stats();
+            }
+        }
+
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Trainer;
+            }
+        }
+    }
+
+    /// A round of the drill on a fresh shoe, burning its first card like the table does.
+    fn new_drill(&self) -> Drill {
+        let mut drill = Drill::new(&mut rand::thread_rng(), self.drill_settings);
+        drill.burn(self.config.burn_card);
+
+        drill
+    }
+
+    /// Enter checks the typed count, or deals a new round once one is over.
+    fn drill_enter(&mut self) {
+        match &mut self.drill {
+            Some(drill) if drill.finished() => (),
+            Some(drill) => return drill.submit(),
+            None => return,
+        }
+
+        self.drill = Some(self.new_drill());
+    }
+
+    /// Switches to the next(or previous) card set in the "cardsets" folder.
+    /// A set that fails validation is skipped over and the current one stays active.
+    fn change_card_set(&mut self, ctx: &mut Context, step: i32) -> GameResult<()> {
+        let names = card_set::available();
+        let current = names.iter().position(|name| *name == self.card_set_choice).unwrap_or(0) as i32;
+        let next = (current + step).rem_euclid(names.len() as i32) as usize;
+
+        self.card_set_choice = names[next].clone();
+
+        match CardSet::load(&self.card_set_choice) {
+            Ok(card_set) => {
+                let card_set = card_set.with_back_style(self.board.card_set.back_style()).with_light(self.config.gpu_light);
+                self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+                self.card_set_error = None;
+
+                self.config.card_set = self.card_set_choice.clone();
+                if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                    eprintln!("Could not save the card set: {}", e);
+                }
+            },
+            Err(e) => self.card_set_error = Some(e),
+        }
+
+        Ok(())
+    }
+
+    fn change_card_back(&mut self, ctx: &mut Context, step: i32) -> GameResult<()> {
+        let styles = unlocks::card_backs(&self.profile);
+        let current = styles.iter().position(|style| style.name == self.config.card_back).unwrap_or(0) as i32;
+        let next = styles[(current + step).rem_euclid(styles.len() as i32) as usize];
+
+        let card_set = self.board.card_set.clone().with_back_style(next);
+        self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+
+        self.config.card_back = next.name.to_string();
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the card back: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Switches to the next(or previous) font in the "fonts" folder, skipping any that fail to load.
+    fn change_font(&mut self, ctx: &mut Context, step: i32) -> GameResult<()> {
+        let names = fonts::available();
+        let mut current = names.iter().position(|name| *name == self.fonts.user_font_name).unwrap_or(0) as i32;
+
+        for _ in 0..names.len() {
+            current = (current + step).rem_euclid(names.len() as i32);
+
+            match self.fonts.set_user_font(ctx, &names[current as usize]) {
+                Ok(()) => break,
+                Err(e) => eprintln!("Could not load the font \"{}\": {}", names[current as usize], e),
+            }
+        }
+
+        self.config.font = self.fonts.user_font_name.clone();
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the font: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn change_language(&mut self, step: i32) -> GameResult<()> {
+        let languages = i18n::LANGUAGES;
+        let current = languages.iter().position(|language| *language == self.language).unwrap_or(0) as i32;
+
+        self.language = languages[(current + step).rem_euclid(languages.len() as i32) as usize];
+        self.config.language = self.language.code().to_string();
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the language: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Takes effect right away, a hand already on its outcome gets the new preset's wait.
+    fn change_game_speed(&mut self, step: i32) -> GameResult<()> {
+        self.config.game_speed = self.config.game_speed.next(step);
+        self.time_till_menu = self.config.game_speed.seconds_on_outcome(self.config.auto_advance);
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the game speed: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Takes effect from the next shuffle.
+    fn change_burn_card(&mut self, step: i32) -> GameResult<()> {
+        self.config.burn_card = self.config.burn_card.next(step);
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the burn card rule: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn change_felt(&mut self, step: i32) -> GameResult<()> {
+        let styles = unlocks::felts(&self.profile);
+        let current = styles.iter().position(|style| style.name == self.profile.felt).unwrap_or(0) as i32;
+        let next = styles[(current + step).rem_euclid(styles.len() as i32) as usize];
+
+        self.profile.felt = next.name.to_string();
+        self.save_profile();
+
+        Ok(())
+    }
+
+    fn change_dealer_avatar(&mut self, step: i32) -> GameResult<()> {
+        let styles = unlocks::dealer_avatars(&self.profile);
+        let current = styles.iter().position(|style| style.name == self.profile.dealer_avatar).unwrap_or(0) as i32;
+        let next = styles[(current + step).rem_euclid(styles.len() as i32) as usize];
+
+        self.profile.dealer_avatar = next.name.to_string();
+        self.save_profile();
+
+        Ok(())
+    }
+
+    fn toggle_setting(&mut self, row: SettingsRow) -> GameResult<()> {
+        match row {
+            SettingsRow::StreamMode => self.config.stream_mode = !self.config.stream_mode,
+            SettingsRow::StreamHidePowerUps => self.config.stream_hide_power_ups = !self.config.stream_hide_power_ups,
+            // the hand being played keeps its engine, the next one gets the new setting
+            SettingsRow::AutoStand => self.config.auto_stand_on_21 = !self.config.auto_stand_on_21,
+            SettingsRow::AutoAdvance => {
+                self.config.auto_advance = !self.config.auto_advance;
+                self.time_till_menu = self.config.game_speed.seconds_on_outcome(self.config.auto_advance);
+            },
+            SettingsRow::WarnRiskyHits => self.config.warn_risky_hits = !self.config.warn_risky_hits,
+            SettingsRow::WhatIfOdds => self.config.what_if_odds = !self.config.what_if_odds,
+            SettingsRow::ShowNextCard => self.config.show_next_card = !self.config.show_next_card,
+            _ => return Ok(()),
+        }
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the setting: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn toggle_gpu_light(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.config.gpu_light = !self.config.gpu_light;
+        self.reload_cards_for_gpu_light(ctx)?;
+        if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+            eprintln!("Could not save the GPU-light mode: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// The cards are loaded again at the resolution GPU-light mode has them, on a new board.
+    fn reload_cards_for_gpu_light(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let card_set = self.board.card_set.clone().with_light(self.config.gpu_light);
+        self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+
+        Ok(())
+    }
+
+    fn hide_power_ups(&self) -> bool {
+        self.config.stream_mode && self.config.stream_hide_power_ups
+    }
+
+    /// Writes the hand for stream overlays(OBS text/browser sources read the files).
+    /// The hand as the stream overlay and the coach view show it.
+    fn hand_state(&self) -> HandState {
+        let status = if self.engine.game_over {
+            self.engine.outcome.name()
+        } else if matches!(self.board.turn, board::Turn::Player) {
+            "player"
+        } else {
+            "dealer"
+        };
+
+        HandState {
+            player: self.profile.display_name().to_string(),
+            player_score: self.engine.player_score,
+            dealer_score: self.engine.effective_dealer_score(),
+            player_cards: stream_overlay::visible_cards(&self.board.dealed_cards_player),
+            dealer_cards: stream_overlay::visible_cards(&self.board.dealed_cards_dealer),
+            status: status.to_string(),
+            wins: self.wins,
+        }
+    }
+
+    fn write_stream_overlay(&mut self) {
+        let state = self.hand_state();
+
+        if let Err(e) = self.stream_overlay.write(state) {
+            eprintln!("Could not write the stream overlay: {}", e);
+        }
+    }
+
+    /// Writes the hand and, when the player has a decision to make, the best play to coach.txt.
+    fn write_coach_view(&mut self) {
+        let annotation = if self.table_state().lock().is_none() && !self.board.dealed_cards_player.is_empty() {
+            Some(self.coach.annotate(&self.board.dealed_cards_player, self.rules()))
+        } else {
+            None
+        };
+        let text = coach::coach_text(&self.hand_state(), annotation.as_ref());
+
+        if let Err(e) = self.coach.write(text) {
+            eprintln!("Could not write the coach view: {}", e);
+        }
+    }
+
+    fn show_toast(&mut self, text: String) {
+        self.toast = Some(text);
+        self.time_till_toast_gone = SECONDS_TILL_TOAST_GONE;
+    }
+
+    /// "17 vs 10" while playing(the dealer's score as the handicap leaves it), the screen's
+    /// name elsewhere.
+    fn window_title_text(&self) -> String {
+        let detail = match self.status {
+            GameStatus::Play => {
+                let scores = format!("{} vs {}", self.engine.player_score, self.engine.effective_dealer_score());
+                match self.engine.outcome {
+                    Outcome::Undecided => scores,
+                    _ => format!("{}, {}", scores, self.engine.outcome.name()),
+                }
+            },
+            GameStatus::Splash | GameStatus::Menu => String::from("Menu"),
+            GameStatus::Setup => String::from("Welcome"),
+            GameStatus::Help => String::from("Help"),
+            GameStatus::Settings | GameStatus::ConfigEditor => String::from("Settings"),
+            GameStatus::Leaderboard => String::from("Leaderboard"),
+            GameStatus::Transcript => String::from("History"),
+            GameStatus::Analysis | GameStatus::Calculator | GameStatus::Heatmap => String::from("Analysis"),
+            GameStatus::Gallery => String::from("Gallery"),
+            GameStatus::Challenge => String::from("Challenge"),
+            GameStatus::Trainer | GameStatus::Quiz | GameStatus::Drill => String::from("Trainer"),
+            GameStatus::Unlocks => String::from("Unlocks"),
+            GameStatus::About => String::from("About"),
+            GameStatus::Profile => String::from("Profile"),
+            GameStatus::Review => String::from("Review"),
+            GameStatus::SessionSummary => String::from("Session summary"),
+        };
+
+        window_title::title(&detail)
+    }
+
+    fn update_toast(&mut self, time_delta: f32) {
+        if self.toast.is_some() {
+            self.time_till_toast_gone -= time_delta;
+            if self.time_till_toast_gone <= 0.0 {
+                self.toast = None;
+            }
+        }
+    }
+
+    fn export_stats(&mut self) {
+        let summary = Summary::new(self.profile.display_name(), self.wins, self.power_ups_count, &self.history);
+
+        match stats_export::export(std::path::Path::new(stats_export::EXPORT_DIR), &summary, &self.history) {
+            Ok(dir) => self.show_toast(format!("{} {}", self.tr(Label::StatsExportedTo), dir.to_string_lossy())),
+            Err(e) => self.show_toast(format!("{}: {}", self.tr(Label::CouldNotExportStats), e)),
+        }
+    }
+
+    /// Merges stats exported on another machine(copied into the "import" folder) into these.
+    fn import_stats(&mut self) {
+        match stats_import::import(std::path::Path::new(stats_import::IMPORT_DIR), &mut self.history) {
+            Ok(imported) => {
+                self.wins += imported.wins;
+                self.power_ups_count.0 += imported.hints;
+                self.power_ups_count.1 += imported.handicaps;
+                self.save();
+                self.save_history();
+
+                self.show_toast(format!("{}: {}, {}: {}", self.tr(Label::HandsImported), imported.hands_added, self.tr(Label::WinsImported), imported.wins));
+            },
+            Err(e) => self.show_toast(format!("{}: {}", self.tr(Label::CouldNotImportStats), e)),
+        }
+    }
+
+    fn change_backup(&mut self, step: i32) {
+        if !self.backups.is_empty() {
+            self.backup_choice = (self.backup_choice as i32 + step).rem_euclid(self.backups.len() as i32) as usize;
+        }
+    }
+
+    fn backup_choice_text(&self) -> String {
+        match self.backups.get(self.backup_choice) {
+            Some(backup) => format!("< {} ({}/{}) >  {}", backup.describe(), self.backup_choice + 1, self.backups.len(), self.tr(Label::PressEnter)),
+            None => self.tr(Label::NoBackupsYet).to_string(),
+        }
+    }
+
+    fn back_up_now(&mut self) {
+        self.saver.flush();
+        let dir = std::path::Path::new(backup::BACKUP_DIR);
+        let made = backup::create(dir, &backup::save_files(&self.file_name), history::now()).
+            and_then(|backup| backup::prune(dir, backup::MAX_BACKUPS).map(|_| backup));
+
+        match made {
+            Ok(backup) => self.show_toast("Saves backed up at ".to_owned() + backup.describe().as_str()),
+            Err(e) => self.show_toast("Could not back up the saves: ".to_owned() + e.to_string().as_str()),
+        }
+
+        self.backups = backup::list(dir);
+        self.backup_choice = 0;
+    }
+
+    /// Puts the picked backup back and reloads everything from it. What was there before is
+    /// backed up first and becomes the newest backup, so restoring it undoes this.
+    fn restore_backup(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let dir = std::path::Path::new(backup::BACKUP_DIR);
+        let backup = match self.backups.get(self.backup_choice) {
+            Some(backup) => backup.clone(),
+            None => return Ok(()),
+        };
+
+        self.saver.flush();
+        let restored = backup::restore(dir, &backup, &backup::save_files(&self.file_name), history::now());
+        self.backups = backup::list(dir);
+        self.backup_choice = 0;
+
+        match restored {
+            Ok(()) => {
+                self.reload_saves(ctx)?;
+                self.show_toast("Restored the backup from ".to_owned() + backup.describe().as_str());
+            },
+            Err(e) => self.show_toast("Could not restore the backup: ".to_owned() + e.to_string().as_str()),
+        }
+
+        Ok(())
+    }
+
+    /// Reads every save file again, after they were replaced.
+    /// Runs a confirmed reset or delete. The saves are backed up first, and that backup is
+    /// what the undo button restores.
+    fn run_profile_action(&mut self, ctx: &mut Context, action: ProfileAction) -> GameResult<()> {
+        self.saver.flush();
+        let dir = std::path::Path::new(backup::BACKUP_DIR);
+        let made = backup::create(dir, &backup::save_files(&self.file_name), history::now()).
+            and_then(|backup| backup::prune(dir, backup::MAX_BACKUPS).map(|_| backup));
+        let undo = match made {
+            Ok(backup) => backup,
+            Err(e) => {
+                // without a backup there would be no way back
+                self.show_toast("Nothing was changed, the saves could not be backed up: ".to_owned() + e.to_string().as_str());
+                return Ok(());
+            },
+        };
+        self.backups = backup::list(dir);
+        self.backup_choice = 0;
+
+        self.wins = 0;
+        self.history = History::default();
+        self.trainer_stats = TrainerStats::default();
+        self.gallery = Gallery::default();
+
+        match action {
+            ProfileAction::ResetStats => self.show_toast(String::from("Statistics reset")),
+            ProfileAction::Delete => {
+                self.power_ups_count = (0, 0);
+                self.profile = Profile::default();
+
+                let card_back = self.config.card_back.clone();
+                lock_unearned_cosmetics(&mut self.config, &mut self.profile);
+                if self.config.card_back != card_back {
+                    let card_set = self.board.card_set.clone().with_back_style(card_back::find(&self.config.card_back));
+                    self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+                    if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                        eprintln!("Could not save the card back: {}", e);
+                    }
+                }
+                self.save_profile();
+
+                self.show_toast(String::from("Profile deleted"));
+            },
+            ProfileAction::Rename => (),
+        }
+
+        self.save();
+        self.save_history();
+        self.saver.write(gallery::GALLERY_FILE_NAME, self.gallery.to_csv());
+        self.trainer_stats.save(trainer::TRAINER_STATS_FILE_NAME)?;
+        self.profile_screen.offer_undo(undo);
+
+        Ok(())
+    }
+
+    fn undo_profile_action(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let undo = match self.profile_screen.undo.take() {
+            Some(undo) => undo,
+            None => return Ok(()),
+        };
+        let dir = std::path::Path::new(backup::BACKUP_DIR);
+
+        self.saver.flush();
+        let restored = backup::restore(dir, &undo, &backup::save_files(&self.file_name), history::now());
+        self.backups = backup::list(dir);
+        self.backup_choice = 0;
+
+        match restored {
+            Ok(()) => {
+                self.reload_saves(ctx)?;
+                self.show_toast(String::from("Undone"));
+            },
+            Err(e) => self.show_toast("Could not undo: ".to_owned() + e.to_string().as_str()),
+        }
+
+        Ok(())
+    }
+
+    fn reload_saves(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let (stats, contents) = Self::load(migration::read(&self.file_name, SaveFile::Stats).unwrap_or_default().as_bytes());
+        self.wins = stats.0;
+        self.power_ups_count = (stats.1, stats.2);
+
+        self.config = Config::load(config::CONFIG_FILE_NAME);
+        self.scores_unverified = Self::check_signature(&contents, &self.config);
+        // a restored config can turn family mode on, the bets go with it
+        self.calculator = RiskCalculator { family_mode: self.config.family_mode, audit_chips: self.config.audit_chips, ..RiskCalculator::default() };
+        self.profile = Profile::load(profile::PROFILE_FILE_NAME);
+        self.history = History::load(history::HISTORY_FILE_NAME);
+        self.trainer_stats = TrainerStats::load(trainer::TRAINER_STATS_FILE_NAME);
+        self.gallery = Gallery::load(gallery::GALLERY_FILE_NAME);
+        self.language = Language::from_code(&self.config.language).unwrap_or(Language::English);
+
+        let (card_set, card_set_error) = CardSet::load_or_default(&self.config.card_set);
+        let card_set = card_set.with_back_style(card_back::find(&self.config.card_back)).with_light(self.config.gpu_light);
+        self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+        self.card_set_choice = self.config.card_set.clone();
+        self.card_set_error = card_set_error;
+
+        if let Err(e) = self.fonts.set_user_font(ctx, &self.config.font) {
+            eprintln!("Could not load the font \"{}\": {}", self.config.font, e);
+        }
+
+        Ok(())
+    }
+
+    fn update_config_editor(&mut self, ctx: &mut Context) {
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.config_editor.cancel();
+                self.status = GameStatus::Settings;
+            }
+        }
+    }
+
+    /// Enter starts typing over the value of the row picked, and the second one sets it.
+    fn config_editor_enter(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.config_editor.typed.is_none() {
+            if let Some((_, value)) = self.config.entries().into_iter().nth(self.config_editor.row()) {
+                self.config_editor.edit(&value);
+            }
+            return Ok(());
+        }
+
+        let old = self.config.clone();
+        if let Some(key) = self.config_editor.submit(&mut self.config) {
+            self.apply_config(ctx, &old)?;
+            if let Err(e) = self.config.save(config::CONFIG_FILE_NAME) {
+                eprintln!("Could not save the config: {}", e);
+            }
+            self.show_toast(format!("{} set{}", key, config_editor::applies_note(&key)));
+        }
+
+        Ok(())
+    }
+
+    /// config.txt changed on disk: the game takes it as it is now. The game's own saves come
+    /// back the same and change nothing.
+    fn reload_config(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let old = self.config.clone();
+        self.config = Config::load(config::CONFIG_FILE_NAME);
+        if self.config == old {
+            return Ok(());
+        }
+
+        let changed = config_editor::changed_keys(&old, &self.config);
+        self.apply_config(ctx, &old)?;
+        let restart: Vec<&String> = changed.iter().filter(|key| config::applies(key) == config::Applies::Restart).collect();
+        if restart.is_empty() {
+            self.show_toast(format!("{} changed: {}", config::CONFIG_FILE_NAME, changed.join(", ")));
+        } else {
+            self.show_toast(format!("{} changed, restart for {}", config::CONFIG_FILE_NAME, restart.iter().map(|key| key.as_str()).collect::<Vec<&str>>().join(", ")));
+        }
+
+        Ok(())
+    }
+
+    /// Makes the game follow the config after it changed from `old`. Whatever is read from the
+    /// config as it's used needs nothing here.
+    fn apply_config(&mut self, ctx: &mut Context, old: &Config) -> GameResult<()> {
+        lock_unearned_cosmetics(&mut self.config, &mut self.profile);
+        let changed = config_editor::changed_keys(old, &self.config);
+        let changed = |key: &str| changed.iter().any(|changed| changed == key);
+
+        if changed("language") {
+            self.language = Language::from_code(&self.config.language).unwrap_or(Language::English);
+        }
+        if changed("card_set") || changed("card_back") {
+            let (card_set, card_set_error) = CardSet::load_or_default(&self.config.card_set);
+            let card_set = card_set.with_back_style(card_back::find(&self.config.card_back)).with_light(self.config.gpu_light);
+            self.board = Board::new(ctx, card_set, &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+            self.card_set_choice = self.config.card_set.clone();
+            self.card_set_error = card_set_error;
+        }
+        if changed("font") {
+            if let Err(e) = self.fonts.set_user_font(ctx, &self.config.font) {
+                eprintln!("Could not load the font \"{}\": {}", self.config.font, e);
+            }
+        }
+        if changed("gpu_light") {
+            self.reload_cards_for_gpu_light(ctx)?;
+        }
+        if changed("left_handed") {
+            self.layout.mirrored = self.config.left_handed;
+            // the board takes its positions from the layout when it's made
+            self.board = Board::new(ctx, self.board.card_set.clone(), &mut self.rng.shoe(self.shoe_index), &self.layout)?;
+        }
+        if changed("family_mode") || changed("audit_chips") {
+            self.calculator = RiskCalculator { family_mode: self.config.family_mode, audit_chips: self.config.audit_chips, ..RiskCalculator::default() };
+        }
+        if changed("turn_timer_seconds") {
+            self.turn_timer = TurnTimer::new(self.config.turn_timer_seconds);
+        }
+        if changed("idle_minutes") {
+            self.idle_timer = IdleTimer::new(self.config.idle_minutes);
+        }
+        if changed("sign_saves") {
+            // signed or not from now on, the unverified mark stays either way
+            self.save();
+        }
+        self.engine.auto_stand_on_21 = self.config.auto_stand_on_21;
+        self.time_till_menu = self.config.game_speed.seconds_on_outcome(self.config.auto_advance);
+
+        Ok(())
+    }
+
+    /// Saves and goes back to the menu after a while without input, see idle.rs.
+    fn leave_idle_session(&mut self, ctx: &mut Context) -> GameResult<()> {
+        match self.status {
+            // a finished hand is counted and saved on the way
+            GameStatus::Play if self.engine.game_over => self.reset(ctx)?,
+            // the hand being played, and the wizard, which saves nothing until it's done
+            GameStatus::Play | GameStatus::Setup => return Ok(()),
+            _ => self.status = GameStatus::Menu,
+        }
+
+        self.save();
+        self.save_profile();
+        self.saver.flush();
+        self.show_toast(String::from("Nothing happened for a while: saved and back at the menu"));
+
+        Ok(())
+    }
+
+    fn editing_name(&self) -> bool {
+        (matches!(self.status, GameStatus::Settings) && SETTINGS_ROWS[self.settings_row] == SettingsRow::Name) ||
+            (matches!(self.status, GameStatus::Profile) && self.profile_screen.selected() == ProfileAction::Rename)
+    }
+
+    /// Applies typed characters(and backspace) to the player name.
+    fn edit_name(&mut self, character: Option<char>) {
+        let mut name = self.profile.name.clone();
+        match character {
+            Some(c) => name.push(c),
+            None => { name.pop(); },
+        }
+        self.profile.set_name(&name);
+
+        // the name is saved on every change, so there's nothing to lose when leaving the screen
+        self.save_profile();
+    }
+
+    fn update_settings(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.settings_step != 0 {
+            let step = self.settings_step;
+            self.settings_step = 0;
+
+            match SETTINGS_ROWS[self.settings_row] {
+                SettingsRow::CardSet => self.change_card_set(ctx, step)?,
+                SettingsRow::CardBack => self.change_card_back(ctx, step)?,
+                SettingsRow::Felt => self.change_felt(step)?,
+                SettingsRow::DealerAvatar => self.change_dealer_avatar(step)?,
+                SettingsRow::Font => self.change_font(ctx, step)?,
+                SettingsRow::Language => self.change_language(step)?,
+                row @ (SettingsRow::StreamMode | SettingsRow::StreamHidePowerUps | SettingsRow::AutoStand | SettingsRow::AutoAdvance | SettingsRow::WarnRiskyHits | SettingsRow::WhatIfOdds | SettingsRow::ShowNextCard) => self.toggle_setting(row)?,
+                SettingsRow::GameSpeed => self.change_game_speed(step)?,
+                SettingsRow::BurnCard => self.change_burn_card(step)?,
+                SettingsRow::GpuLight => self.toggle_gpu_light(ctx)?,
+                SettingsRow::RestoreBackup => self.change_backup(step),
+                SettingsRow::Name | SettingsRow::ExportStats | SettingsRow::ImportStats | SettingsRow::BackUpNow | SettingsRow::Advanced => (),
+            }
+        }
+
+        if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+            let mouse_position = self.mouse_position(ctx);
+
+            if self.mouse_over_button(ctx, mouse_position, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)) {
+                self.status = GameStatus::Menu;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_game(&mut self, ctx: &mut Context, time_delta: f32) -> GameResult<()> {
+        if self.time_till_game_over <= 0.0 { // check for game over
+            match self.time_till_menu {
+                Some(time) if time > 0.0 => self.time_till_menu = Some(time - time_delta),
+                Some(_) if self.config.auto_advance => self.play_again(ctx)?,
+                Some(_) => self.reset(ctx)?,
+                None => (),
+            }
+        } else if self.engine.game_over && self.time_till_game_over > 0.0 {
+            self.time_till_game_over -= time_delta;
+        }
+
+        if matches!(self.board.turn, board::Turn::Dealer) { // dealer's turn
+            if self.engine.dealer_must_draw() && !self.board.card_moving {
+                if self.sweating() && !self.sweat_waiting {
+                    self.sweat_waiting = true;
+                    self.wait_on_heartbeat(ctx);
+                }
+
+                if self.time_till_dealer_card > 0.0 {
+                    self.time_till_dealer_card -= time_delta;
+                } else {
+                    self.deal_card(ctx)?;
+                    self.time_till_dealer_card = self.config.game_speed.dealer_delay();
+                    self.sweat_waiting = false;
+                }
+            }
+        } else if !self.scenario_actions.is_empty() { // player's turn, replayed from a scenario
+            if !self.engine.game_over && !self.board.card_moving {
+                if let Some(action) = self.scenario_actions.pop_front() {
+                    self.play_scenario_action(ctx, action)?;
+                }
+            }
+        } else { // player's turn
+            if mouse::button_pressed(ctx, mouse::MouseButton::Left) {
+                let mouse_position = self.mouse_position(ctx);
+
+                // holding the button only hits, a click on a locked deck is told off once in
+                // mouse_button_down_event
+                if self.mouse_over_deck(mouse_position) && self.table_state().allows(Action::Hit) {
+                    self.hit(ctx)?;
+                }
+            }
+        }
+
+        // every decision gets the full time, it only runs while the player can act on a
+        // dealt hand
+        if self.turn_timer_running() {
+            if self.turn_timer.update(time_delta) {
+                self.show_toast(format!("Time's up, standing on {}", self.engine.player_score));
+                self.stand(ctx);
+            }
+        } else {
+            self.turn_timer.restart();
+        }
+
+        self.update_score()?; // update score if needed
+        self.score_counters[0].set(self.engine.player_score);
+        self.score_counters[1].set(self.engine.effective_dealer_score());
+        for counter in &mut self.score_counters {
+            counter.update(time_delta);
+        }
+
+        if self.config.stream_mode {
+            self.write_stream_overlay();
+        }
+        if self.config.coach_mode {
+            self.write_coach_view();
+        }
+        if self.what_if_shown() {
+            self.what_if.ask(&self.board.dealed_cards_player, self.board.deck.shoe(), self.rules());
+        }
+        self.what_if.update();
+
+        let animation_speed = if self.sweating() { sweat::SWEAT_ANIMATION_SPEED } else { self.config.game_speed.animation_speed() };
+        self.board.update(ctx, time_delta, animation_speed);
+        self.hit_warning.update(time_delta);
+
+        Ok(())
+    }
+
+    /// The range the next card's points are in, once the hint is used.
+    fn show_hint(&mut self) {
+        let top_card_points = self.board.deck.peek().rank.points();
+        let rand_num: u32 = self.rng.gameplay.gen_range(0..HINT_RANGE_SIZE);
+
+        if top_card_points - rand_num + HINT_RANGE_SIZE > 11 {
+            self.hint_range = Some((7, 11));
+        } else if (top_card_points as i32) - (rand_num as i32) < 2 {
+            self.hint_range = Some((2, 6));
+        } else {
+            self.hint_range = Some((top_card_points - rand_num, top_card_points - rand_num + HINT_RANGE_SIZE));
+        }
+    }
+
+    /// Puts the picked card on top of the deck, to be dealt next to whoever draws.
+    fn stack_picked_card(&mut self) {
+        let card_name = self.card_picker.card_name();
+
+        if self.board.deck.put_on_top(&card_name) {
+            self.show_toast(format!("{} is the next card", self.card_picker.describe()));
+        } else {
+            self.show_toast(format!("{} isn't left in the shoe", self.card_picker.describe()));
+        }
+    }
+
+    /// Enter on the trainer screen: practice hands from the typed situation, until the player
+    /// picks plain practice on the menu.
+    fn start_situation(&mut self) {
+        match Situation::parse(&self.situation_input) {
+            Ok(situation) => {
+                self.situation = Some(situation);
+                self.practice = true;
+                self.status = GameStatus::Play;
+                self.deal_situation();
+            },
+            Err(e) => self.show_toast("Can't deal that: ".to_owned() + e.as_str()),
+        }
+    }
+
+    /// The situation's cards go on top of a shuffled deck and are dealt to the player, the
+    /// way a scenario deals its moves.
+    fn deal_situation(&mut self) {
+        if let Some(situation) = &self.situation {
+            let cards = situation.deal(&mut self.rng.gameplay);
+            self.board.deck = board::Deck::stacked(&mut self.rng.gameplay, &cards);
+            self.scenario_actions = [Action::Hit, Action::Hit].into_iter().collect();
+        }
+    }
+
+    /// Tells the player how the decision on the situation's two cards compares to the best play.
+    fn judge_situation(&mut self, chosen: Action) {
+        let deciding = self.practice && self.scenario_actions.is_empty() && self.board.dealed_cards_player.len() == 2;
+        if let Some(situation) = self.situation.as_ref().filter(|_| deciding) {
+            let verdict = situation.verdict(&probability::best_play(self.rules()), &self.board.dealed_cards_player, chosen);
+            self.show_toast(verdict);
+        }
+    }
+
+    /// A built practice shoe replaces the deck, a standard one leaves it(and its cut card) be.
+    fn deal_from_practice_shoe(&mut self) {
+        if !self.practice_shoe.is_standard() {
+            self.board.deck = board::Deck::with_composition(&mut self.rng.gameplay, &self.practice_shoe.counts);
+        }
+    }
+
+    /// Changes the practice shoe at the card picker's rank. The deck is shuffled from the new
+    /// shoe right away, the cards on the table stay.
+    fn build_practice_shoe(&mut self, change: ShoeChange) {
+        let rank = self.card_picker.rank;
+        let changed = match change {
+            ShoeChange::Step(step) => self.practice_shoe.change(rank, step),
+            ShoeChange::RemoveRank => self.practice_shoe.remove_rank(rank),
+            ShoeChange::Standard => {
+                self.practice_shoe = PracticeShoe::default();
+                true
+            },
+        };
+        if !changed {
+            self.show_toast(format!("The shoe holds 0 to {} of a rank, and at least one card", practice::MAX_COPIES));
+            return;
+        }
+
+        self.board.deck = board::Deck::with_composition(&mut self.rng.gameplay, &self.practice_shoe.counts);
+        self.show_toast(format!("Practice shoe: {}", self.practice_shoe.describe()));
+    }
+
+    fn draw_config_editor(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::AdvancedSettings, font, SETTINGS_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::ConfigEditorTitle))?;
+
+        let mut position = self.layout.at(Anchor::ConfigEditorFirstRow);
+        for (i, (key, value)) in self.config.entries().into_iter().enumerate() {
+            let selected = i == self.config_editor.row();
+            let row_str = match &self.config_editor.typed {
+                Some(typed) if selected => format!("{} = {}_", key, typed),
+                _ => format!("{} = {}{}", key, value, config_editor::applies_note(&key)),
+            };
+
+            let color = if selected { graphics::Color::from_rgb(255, 163, 26) } else { graphics::Color::WHITE };
+            let row_fragment = graphics::TextFragment::new(row_str).
+                                                       color(color).
+                                                       font(font).
+                                                       scale(graphics::PxScale::from(CONFIG_EDITOR_TEXT_SIZE));
+
+            self.draw_text(ctx, &graphics::Text::new(row_fragment), position)?;
+            position.y += CONFIG_EDITOR_ROW_SPACING;
+        }
+
+        let (controls_str, color) = match &self.config_editor.error {
+            Some(error) => (error.clone(), graphics::Color::from_rgb(204, 0, 0)),
+            None => (String::from("Up/Down = select, Enter = edit, then Enter = set or Escape = cancel"), graphics::Color::WHITE),
+        };
+        let controls_fragment = graphics::TextFragment::new(controls_str).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+        graphics::draw(ctx, &graphics::Text::new(controls_fragment), graphics::DrawParam::default().dest(self.layout.at(Anchor::ConfigEditorControls)))?;
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_setup(&self, ctx: &mut Context) -> GameResult<()> {
+        let setup = match &self.setup {
+            Some(setup) => setup,
+            None => return Ok(()),
+        };
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Welcome, font, SETTINGS_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::SetupTitle))?;
+
+        let mut position = self.layout.at(Anchor::SetupFirstRow);
+        for step in setup::SETUP_STEPS {
+            let selected = *step == setup.step();
+            let tr = |label| self.tr(label).to_owned();
+            let row_str = match step {
+                SetupStep::Language => tr(Label::Language) + ":  < " + setup.language.native_name() + " >",
+                SetupStep::Name => tr(Label::Name) + ":  " + setup.name.as_str() + if selected { "_" } else { "" },
+                SetupStep::UiScale => tr(Label::UiScale) + ":  < " + format!("{:.0}%", setup.ui_scale * 100.0).as_str() + " >",
+                SetupStep::Tutorial => tr(Label::Tutorial) + ":  < " + on_off(setup.tutorial) + " >",
+            };
+
+            let color = if selected { graphics::Color::from_rgb(255, 163, 26) } else { graphics::Color::WHITE };
+            let row_fragment = graphics::TextFragment::new(row_str).
+                                                       color(color).
+                                                       font(font).
+                                                       scale(graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+
+            self.draw_text(ctx, &graphics::Text::new(row_fragment), position)?;
+            position.y += SETTINGS_ROW_SPACING * 2.0;
+        }
+
+        let mut controls_text = graphics::Text::new("Left/Right = change, type the name, Enter = next, Up = back, Escape = quit.\nThe tutorial opens the help screen and shows the odds of hitting and standing while you play.");
+        controls_text.set_font(font, graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+
+        graphics::draw(ctx, &controls_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::SetupControls)))
+    }
+
+    fn draw_session_summary(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::SessionSummary, font, REVIEW_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::SessionSummaryTitle))?;
+
+        for (i, line) in self.session_summary().lines().into_iter().enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(SESSION_SUMMARY_TEXT_SIZE));
+
+            self.draw_text(ctx, &line_text, self.layout.offset(Anchor::SessionSummaryFirstRow, 0.0, i as f32 * SESSION_SUMMARY_LINE_SPACING))?;
+        }
+
+        let mut controls_text = graphics::Text::new("Escape = quit, Enter = keep playing");
+        controls_text.set_font(font, graphics::PxScale::from(REVIEW_TEXT_SIZE));
+
+        self.draw_text(ctx, &controls_text, self.layout.at(Anchor::SessionSummaryControls))
+    }
+
+    fn draw_splash(&self, ctx: &mut Context) -> GameResult<()> {
+        splash::draw(ctx,
+                     &self.splash,
+                     &self.fonts,
+                     self.tr(Label::MenuTitle),
+                     self.menu_font(),
+                     self.layout.at(Anchor::SplashTitle),
+                     self.layout.at(Anchor::SplashFirstCard))
+    }
+
+    fn draw_menu(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.menu_font();
+
+        let title = self.label_text(Label::MenuTitle, font, MENU_TITLE_SIZE);
+        let play_button_text = self.label_text(Label::Play, font, MENU_PLAY_TEXT_SIZE);
+        let settings_button_text = self.label_text(Label::Settings, font, MENU_SETTINGS_TEXT_SIZE);
+        let leaderboard_button_text = self.label_text(Label::Leaderboard, font, MENU_LEADERBOARD_TEXT_SIZE);
+        let history_button_text = self.label_text(Label::History, font, MENU_HISTORY_TEXT_SIZE);
+        let analysis_button_text = self.label_text(Label::Analysis, font, MENU_ANALYSIS_TEXT_SIZE);
+        let trainer_button_text = self.label_text(Label::Trainer, font, MENU_TRAINER_TEXT_SIZE);
+        let help_button_text = self.label_text(Label::Help, font, MENU_HELP_TEXT_SIZE);
+
+        self.draw_text(ctx, &title, self.layout.at(Anchor::MenuTitle))?;
+        self.draw_text(ctx, &play_button_text, self.layout.at(Anchor::MenuPlayText))?;
+        self.draw_text(ctx, &settings_button_text, self.layout.at(Anchor::MenuSettingsText))?;
+        self.draw_text(ctx, &leaderboard_button_text, self.layout.at(Anchor::MenuLeaderboardText))?;
+        self.draw_text(ctx, &history_button_text, self.layout.at(Anchor::MenuHistoryText))?;
+        self.draw_text(ctx, &analysis_button_text, self.layout.at(Anchor::MenuAnalysisText))?;
+        self.draw_text(ctx, &trainer_button_text, self.layout.at(Anchor::MenuTrainerText))?;
+        self.draw_text(ctx, &help_button_text, self.layout.at(Anchor::MenuHelpText))?;
+
+        self.draw_missions(ctx)
+    }
+
+    fn draw_missions(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Missions, font, MISSIONS_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::MissionsPanel))?;
+
+        // the mission and its reward below it, with a blank line before the next mission
+        let lines = self.profile.missions.active.iter().
+            flat_map(|active| vec![active.describe(), format!("   reward: {}", active.mission().reward.describe()), String::new()]);
+
+        for (i, line) in lines.enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(MISSIONS_TEXT_SIZE));
+
+            let position = self.layout.offset(Anchor::MissionsPanel, 0.0, MISSIONS_TITLE_SIZE + i as f32 * MISSIONS_LINE_SPACING);
+            self.draw_text(ctx, &line_text, position)?;
+        }
+
+        self.draw_text(ctx, &self.unlocks_button_text(), self.layout.at(Anchor::MenuUnlocksText))?;
+        self.draw_text(ctx, &self.about_button_text(), self.layout.at(Anchor::MenuAboutText))?;
+        self.draw_text(ctx, &self.profile_button_text(), self.layout.at(Anchor::MenuProfileText))?;
+        self.draw_text(ctx, &self.practice_button_text(), self.layout.at(Anchor::MenuPracticeText))
+    }
+
+    fn draw_profile(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Profile, font, PROFILE_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::ProfileTitle))?;
+
+        let summary = [
+            format!("{}: {}", self.tr(Label::Name), self.profile.display_name()),
+            format!("Wins: {}, hands played: {}", self.wins, self.history.hands.len()),
+            format!("Hints: {}, handicaps: {}", self.power_ups_count.0, self.power_ups_count.1),
+            format!("Achievements: {}/{}", self.profile.achievements.len(), achievements::ACHIEVEMENTS.len()),
+            format!("Time played: {}", playtime::describe(self.profile.playtime)),
+        ];
+        let mut lines: Vec<(String, Point2<f32>, graphics::Color)> = summary.iter().enumerate().
+            map(|(i, line)| {
+                let position = self.layout.offset(Anchor::ProfileSummary, 0.0, i as f32 * PROFILE_LINE_SPACING);
+                (line.clone(), position, graphics::Color::from_rgb(255, 255, 255))
+            }).
+            collect();
+
+        for (i, action) in PROFILE_ACTIONS.iter().enumerate() {
+            let selected = i == self.profile_screen.row;
+            let row_str = match action {
+                // a cursor shows that typing changes the name
+                ProfileAction::Rename => self.tr(Label::Rename).to_owned() + ":  " + self.profile.name.as_str() + if selected { "_" } else { "" },
+                ProfileAction::ResetStats => format!("{}:  {}", self.tr(Label::ResetStats), self.tr(Label::PressEnter)),
+                ProfileAction::Delete => format!("{}:  {}", self.tr(Label::DeleteProfile), self.tr(Label::PressEnter)),
+            };
+            let color = if selected {
+                graphics::Color::from_rgb(255, 163, 26)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+            let position = self.layout.offset(Anchor::ProfileFirstRow, 0.0, i as f32 * PROFILE_LINE_SPACING);
+
+            lines.push((row_str, position, color));
+        }
+
+        if let Some(prompt) = self.profile_screen.prompt() {
+            lines.push((prompt.to_string(), self.layout.at(Anchor::ProfilePrompt), graphics::Color::from_rgb(204, 0, 0)));
+        }
+
+        for (line, position, color) in lines {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(PROFILE_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        if self.profile_screen.undo.is_some() {
+            self.draw_text(ctx, &self.undo_button_text(), self.layout.at(Anchor::ProfileUndoText))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_review(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Review, font, REVIEW_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::ReviewTitle))?;
+
+        let summary = if self.review.hands.is_empty() {
+            "No mistakes this session: every hit and stand was the best play".to_string()
+        } else {
+            format!("{} hand(s) this session with a hit or a stand that costs, {:.1} points per 100 hands in all",
+                    self.review.hands.len(), self.review.ev_lost() * 100.0)
+        };
+        let mut lines = vec![(summary, self.layout.at(Anchor::ReviewSummary), graphics::Color::from_rgb(255, 255, 255))];
+
+        // the rows scroll so the selected hand stays on screen
+        let first = self.review.selected.saturating_sub(REVIEW_VISIBLE_ROWS - 1);
+        for (i, hand) in self.review.hands.iter().enumerate().skip(first).take(REVIEW_VISIBLE_ROWS) {
+            let short_names = |names: &[String]| names.iter().
+                map(|name| card::Card::new(name).short_name()).
+                collect::<Vec<String>>().
+                join(" ");
+            let mistakes = hand.mistakes.iter().
+                map(|mistake| mistake.describe()).
+                collect::<Vec<String>>().
+                join("; ");
+            let row_str = format!("{}. {} vs {}, {}: {}",
+                                  i + 1, short_names(&hand.player_cards), short_names(&hand.dealer_cards), hand.outcome.name(), mistakes);
+            let color = if i == self.review.selected {
+                graphics::Color::from_rgb(255, 163, 26)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+            let position = self.layout.offset(Anchor::ReviewFirstRow, 0.0, (i - first) as f32 * REVIEW_LINE_SPACING);
+
+            lines.push((row_str, position, color));
+        }
+
+        if !self.review.hands.is_empty() {
+            lines.push(("Up/Down to pick a hand, Enter to play the same cards again(it doesn't count)".to_string(),
+                        self.layout.at(Anchor::ReviewControls),
+                        graphics::Color::from_rgb(255, 255, 255)));
+        }
+
+        for (line, position, color) in lines {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(REVIEW_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_gallery(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Gallery, font, REVIEW_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::GalleryTitle))?;
+
+        let summary = if self.gallery.hands.is_empty() {
+            "No notable hands yet: a five card 21 or a push of 21 against 21 ends up here".to_string()
+        } else {
+            format!("{} notable hand(s)", self.gallery.hands.len())
+        };
+        let mut lines = vec![(summary, self.layout.at(Anchor::GallerySummary), graphics::Color::from_rgb(255, 255, 255))];
+
+        // the rows scroll so the selected hand stays on screen
+        let first = self.gallery.selected.saturating_sub(REVIEW_VISIBLE_ROWS - 1);
+        for (i, hand) in self.gallery.hands.iter().enumerate().skip(first).take(REVIEW_VISIBLE_ROWS) {
+            let short_names = |names: &[String]| names.iter().
+                map(|name| card::Card::new(name).short_name()).
+                collect::<Vec<String>>().
+                join(" ");
+            let row_str = format!("{}. {}: {} vs {}, {} (hand #{}, {})",
+                                  i + 1, hand.kind.describe(), short_names(&hand.player_cards), short_names(&hand.dealer_cards),
+                                  hand.outcome.name(), hand.id, backup::format_timestamp(hand.timestamp));
+            let color = if i == self.gallery.selected {
+                graphics::Color::from_rgb(255, 163, 26)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+            let position = self.layout.offset(Anchor::GalleryFirstRow, 0.0, (i - first) as f32 * REVIEW_LINE_SPACING);
+
+            lines.push((row_str, position, color));
+        }
+
+        if !self.gallery.hands.is_empty() {
+            lines.push(("Up/Down to pick a hand, Enter to watch it again(it doesn't count)".to_string(),
+                        self.layout.at(Anchor::GalleryControls),
+                        graphics::Color::from_rgb(255, 255, 255)));
+        }
+
+        for (line, position, color) in lines {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(REVIEW_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_about(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::About, font, ABOUT_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::AboutTitle))?;
+
+        for (i, line) in credits::lines().into_iter().enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(ABOUT_TEXT_SIZE));
+            let position = self.layout.offset(Anchor::AboutText, 0.0, i as f32 * ABOUT_LINE_SPACING);
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_unlocks(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Unlocks, font, UNLOCKS_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::UnlocksTitle))?;
+
+        let earned_color = graphics::Color::from_rgb(255, 255, 255);
+        let locked_color = graphics::Color::from_rgb(140, 140, 140);
+
+        let mut achievement_lines = vec![(String::from("Achievements"), earned_color)];
+        for achievement in achievements::ACHIEVEMENTS {
+            let earned = self.profile.achievements.iter().any(|id| id == achievement.id);
+            let line = match (earned, achievement.hidden) {
+                (true, _) => format!("{}: earned", achievement.description),
+                (false, true) => String::from("???: a hidden one, not yet"),
+                (false, false) => format!("{}: not yet", achievement.description),
+            };
+            achievement_lines.push((line, if earned { earned_color } else { locked_color }));
+        }
+
+        let mut cosmetic_lines = vec![(String::from("Cosmetics"), earned_color)];
+        for unlock in unlocks::UNLOCKS {
+            let line = if unlock.requirement.met(&self.profile) {
+                (format!("{}: unlocked", unlock.cosmetic.describe()), earned_color)
+            } else {
+                (format!("{}: locked, {}", unlock.cosmetic.describe(), unlock.requirement.describe()), locked_color)
+            };
+            cosmetic_lines.push(line);
+        }
+
+        for (lines, start) in [(achievement_lines, self.layout.at(Anchor::UnlocksAchievements)), (cosmetic_lines, self.layout.at(Anchor::UnlocksItems))] {
+            for (i, (line, color)) in lines.into_iter().enumerate() {
+                let line_fragment = graphics::TextFragment::new(line).
+                                                            color(color).
+                                                            font(font).
+                                                            scale(graphics::PxScale::from(UNLOCKS_TEXT_SIZE));
+                let position = Point2 { x: start.x, y: start.y + i as f32 * UNLOCKS_LINE_SPACING };
+
+                graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+            }
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_help(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let help_description_str = help::text(&self.config.key_map, self.language);
+
+        let title = self.label_text(Label::Help, font, HELP_TITLE_SIZE);
+
+        let mut help_description = graphics::Text::new(help_description_str);
+        help_description.set_font(font, graphics::PxScale::from(HELP_DESCRIPTION_SIZE));
+
+        let back_button_text = self.back_button_text();
+
+        // create and draw a rectangle for button
+
+        self.draw_text(ctx, &title, self.layout.at(Anchor::HelpTitle))?;
+        graphics::draw(ctx, &help_description, graphics::DrawParam::default().dest(self.layout.at(Anchor::HelpDescription)))?;
+        self.draw_text(ctx, &back_button_text, self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_leaderboard(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Leaderboard, font, LEADERBOARD_TITLE_SIZE);
+
+        let back_button_text = self.back_button_text();
+
+        self.draw_text(ctx, &title, self.layout.at(Anchor::LeaderboardTitle))?;
+
+        let mut lines = Vec::new();
+        if !self.leaderboard.enabled() {
+            lines.push(format!("{} {}: leaderboard_url", self.tr(Label::LeaderboardOff), config::CONFIG_FILE_NAME));
+        } else {
+            if self.scores_unverified {
+                lines.push(self.tr(Label::ScoresNotSent).to_string());
+            }
+            if self.leaderboard.syncing() {
+                lines.push(self.tr(Label::Updating).to_string());
+            } else if let Some(error) = &self.leaderboard.error {
+                lines.push(format!("{} ({})", self.tr(Label::OfflineScores), error));
+            }
+
+            lines.push(format!("{:<3} {:<17} {:<17} {}", "#", self.tr(Label::LeaderboardName), self.tr(Label::LeaderboardBestStreak), self.tr(Label::LeaderboardWins)));
+            for (i, entry) in self.leaderboard.entries.iter().enumerate() {
+                lines.push(format!("{:<3} {:<17} {:<17} {}", i + 1, entry.name, entry.best_streak, entry.wins));
+            }
+        }
+
+        let mut position = self.layout.at(Anchor::LeaderboardFirstRow);
+        for line in lines {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(LEADERBOARD_TEXT_SIZE));
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+            position.y += LEADERBOARD_ROW_SPACING;
+        }
+
+        self.draw_text(ctx, &self.challenge_button_text(), self.layout.at(Anchor::LeaderboardChallengeText))?;
+        self.draw_text(ctx, &back_button_text, self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_challenge(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Challenge, font, REVIEW_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::ChallengeTitle))?;
+
+        let mut lines = vec![
+            format!("Your code: {}  ({} hand(s) played on it)", self.challenge().code(), self.challenge_log.hands.len()),
+            String::from("A friend who types it plays the same shoes under the same rules from their next hand"),
+            format!("Friend's code: {}_", self.challenge_input),
+            String::from("Enter plays it from the next hand, on a fresh challenge log"),
+        ];
+        match self.challenge_friends.first() {
+            // the wins and the first hands, a long session's later ones don't fit
+            Some(friend) => lines.extend(challenge::compare(&self.challenge_log, friend).into_iter().take(REVIEW_VISIBLE_ROWS)),
+            None => lines.push(format!("Export your log and copy a friend's log of the same code into the \"{}\" folder to compare", challenge::CHALLENGE_DIR)),
+        }
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(REVIEW_TEXT_SIZE));
+            let position = self.layout.offset(Anchor::ChallengeFirstRow, 0.0, i as f32 * REVIEW_LINE_SPACING);
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.export_log_button_text(), self.layout.at(Anchor::ChallengeExportText))?;
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_transcript(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::History, font, TRANSCRIPT_TITLE_SIZE);
+
+        let back_button_text = self.back_button_text();
+
+        self.draw_text(ctx, &title, self.layout.at(Anchor::TranscriptTitle))?;
+
+        if self.transcript.lines.is_empty() {
+            let mut empty_text = graphics::Text::new(self.tr(Label::NoHandsThisSession));
+            empty_text.set_font(font, graphics::PxScale::from(TRANSCRIPT_TEXT_SIZE));
+
+            graphics::draw(ctx, &empty_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::TranscriptFirstRow)))?;
+        }
+
+        let mut position = self.layout.at(Anchor::TranscriptFirstRow);
+        for (i, line) in self.transcript.lines.iter().enumerate().skip(self.transcript_scroll).take(TRANSCRIPT_VISIBLE_ROWS) {
+            // red and black suits, like on the cards
+            let line_text = self.fonts.card_text(&(format!("{}. ", i + 1) + line.as_str()), TRANSCRIPT_TEXT_SIZE);
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+            position.y += TRANSCRIPT_ROW_SPACING;
+        }
+
+        self.draw_text(ctx, &back_button_text, self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// Charts of the whole hand history: win rate over time, outcomes and average hand values.
+    fn draw_analysis(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Analysis, font, ANALYSIS_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::AnalysisTitle))?;
+
+        let area = |(x, y, w, h): (f32, f32, f32, f32)| graphics::Rect::new(x, y, w, h);
+        let mut captions = Vec::new();
+
+        match analytics::average_hand_values(&self.history) {
+            None => {
+                let (x, y, _, _) = ANALYSIS_WIN_RATE_AREA;
+                captions.push((self.tr(Label::NoHandsYet).to_string(), Point2 { x, y }));
+            },
+            Some((player_average, dealer_average)) => {
+                let win_rates = analytics::win_rate_over_time(&self.history, analytics::MAX_LINE_POINTS);
+                let win_rate_area = area(ANALYSIS_WIN_RATE_AREA);
+                analytics::draw_line_chart(ctx, win_rate_area, &win_rates, 1.0, graphics::Color::from_rgb(255, 163, 26))?;
+
+                let outcome_color = |outcome: &Outcome| match outcome {
+                    Outcome::Win => graphics::Color::from_rgb(0, 204, 0),
+                    Outcome::Lose => graphics::Color::from_rgb(204, 0, 0),
+                    _ => graphics::Color::from_rgb(160, 160, 160),
+                };
+                let counts = analytics::outcome_counts(&self.history);
+                let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(0) as f32;
+                let bars: Vec<(f32, graphics::Color)> = counts.iter().map(|(outcome, count)| (*count as f32, outcome_color(outcome))).collect();
+                let outcomes_area = area(ANALYSIS_OUTCOMES_AREA);
+                analytics::draw_bar_chart(ctx, outcomes_area, &bars, max_count)?;
+
+                let hand_value_bars = [(player_average, graphics::Color::from_rgb(255, 255, 255)), (dealer_average, graphics::Color::from_rgb(255, 163, 26))];
+                let hand_value_area = area(ANALYSIS_HAND_VALUE_AREA);
+                analytics::draw_bar_chart(ctx, hand_value_area, &hand_value_bars, ANALYSIS_MAX_HAND_VALUE)?;
+
+                let below = |chart: graphics::Rect| Point2 { x: chart.x, y: chart.y + chart.h + 20.0 };
+                let above = |chart: graphics::Rect| Point2 { x: chart.x, y: chart.y - 50.0 };
+
+                captions.push((format!("WIN RATE OVER {} HANDS", self.history.hands.len()), above(win_rate_area)));
+                captions.push((format!("now {:.0}%", win_rates.last().unwrap_or(&0.0) * 100.0), below(win_rate_area)));
+                captions.push((String::from("OUTCOMES"), above(outcomes_area)));
+                captions.push((format!("{} wins, {} draws, {} losses", counts[0].1, counts[1].1, counts[2].1), below(outcomes_area)));
+                captions.push((String::from("AVERAGE HAND"), above(hand_value_area)));
+                captions.push((format!("you {:.1}, dealer {:.1}", player_average, dealer_average), below(hand_value_area)));
+            },
+        }
+
+        if let Some(simulation) = &self.calculator.simulation {
+            captions.push((String::from("Last simulation: ") + simulation.summary().as_str(), self.layout.offset(Anchor::AnalysisCalculatorText, 0.0, -60.0)));
+        }
+        captions.push((String::from("Time played: ") + playtime::describe(self.profile.playtime).as_str(), self.layout.offset(Anchor::AnalysisCalculatorText, 0.0, -100.0)));
+
+        for (caption, position) in captions {
+            let mut caption_text = graphics::Text::new(caption);
+            caption_text.set_font(font, graphics::PxScale::from(ANALYSIS_TEXT_SIZE));
+
+            graphics::draw(ctx, &caption_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.calculator_button_text(), self.layout.at(Anchor::AnalysisCalculatorText))?;
+        self.draw_text(ctx, &self.heatmap_button_text(), self.layout.at(Anchor::AnalysisHeatmapText))?;
+        self.draw_text(ctx, &self.gallery_button_text(), self.layout.at(Anchor::AnalysisGalleryText))?;
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// Outcomes by starting total and dealer up-card, and the situations that went worst.
+    fn draw_heatmap(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Heatmap, font, HEATMAP_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::HeatmapTitle))?;
+
+        let heatmap = analytics::outcome_heatmap(&self.history);
+        let (x, y, w, h) = HEATMAP_AREA;
+        analytics::draw_heatmap(ctx, graphics::Rect::new(x, y, w, h), &heatmap)?;
+
+        let cell_width = w / analytics::UP_CARDS.count() as f32;
+        let cell_height = h / analytics::STARTING_TOTALS.count() as f32;
+        let up_card_name = |up_card: u32| if up_card == 11 { String::from("A") } else { up_card.to_string() };
+
+        let mut lines = vec![(String::from("YOU \\ DEALER"), Point2 { x: x - 180.0, y: y - 40.0 })];
+        for (i, up_card) in analytics::UP_CARDS.enumerate() {
+            lines.push((up_card_name(up_card), Point2 { x: x + (i as f32 + 0.4) * cell_width, y: y - 40.0 }));
+        }
+        for (i, total) in analytics::STARTING_TOTALS.enumerate() {
+            lines.push((total.to_string(), Point2 { x: x - 50.0, y: y + i as f32 * cell_height }));
+        }
+
+        lines.push((String::from("WORST SPOTS"), self.layout.at(Anchor::HeatmapWorst)));
+        let worst = heatmap.worst(HEATMAP_MIN_HANDS, HEATMAP_WORST_COUNT);
+        if worst.is_empty() {
+            lines.push((format!("Play each spot {} times to see it here", HEATMAP_MIN_HANDS),
+                        self.layout.offset(Anchor::HeatmapWorst, 0.0, HEATMAP_LINE_SPACING)));
+        }
+        for (i, (total, up_card, cell)) in worst.into_iter().enumerate() {
+            lines.push((format!("{} vs {}: won {} of {}", total, up_card_name(up_card), cell.wins, cell.hands),
+                        self.layout.offset(Anchor::HeatmapWorst, 0.0, (i + 1) as f32 * HEATMAP_LINE_SPACING)));
+        }
+
+        lines.push((String::from("Rows are what your first two cards were worth, columns the dealer's first card. Green spots win more than they lose, red ones lose more, grey ones weren't played."),
+                    self.layout.at(Anchor::HeatmapLegend)));
+        lines.push((String::from("Hands logged before the starting hand was recorded aren't counted."),
+                    self.layout.offset(Anchor::HeatmapLegend, 0.0, HEATMAP_LINE_SPACING)));
+
+        for (line, position) in lines {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(HEATMAP_TEXT_SIZE));
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// Expected value and risk of ruin for the typed bet and bankroll, see probability.rs.
+    fn draw_calculator(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::RiskCalculator, font, CALCULATOR_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::CalculatorTitle))?;
+
+        let mut position = self.layout.at(Anchor::CalculatorFirstRow);
+        for (i, row) in self.calculator.rows().iter().enumerate() {
+            let row_str = match row {
+                CalculatorRow::Bet => format!("{}:  {}", self.tr(Label::Bet), self.calculator.bet),
+                CalculatorRow::Bankroll => format!("{}:  {}", self.tr(Label::Bankroll), self.calculator.bankroll),
+                CalculatorRow::Handicap => format!("{}:  < {} >", self.tr(Label::DealerHandicap), on_off(self.calculator.rules.dealer_handicap)),
+                CalculatorRow::Strategy => format!("{}:  < {} >", self.tr(Label::Betting), self.calculator.betting_strategy().name()),
+                CalculatorRow::Simulate => format!("{}:  {}", self.tr(Label::Simulate), self.tr(Label::PressEnter)),
+            };
+
+            let color = if i == self.calculator.row {
+                graphics::Color::from_rgb(255, 163, 26)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+
+            let row_fragment = graphics::TextFragment::new(row_str).
+                                                       color(color).
+                                                       font(font).
+                                                       scale(graphics::PxScale::from(CALCULATOR_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(row_fragment), graphics::DrawParam::default().dest(position))?;
+            position.y += CALCULATOR_ROW_SPACING;
+        }
+
+        let mut lines = self.calculator.results();
+        if let Some(simulation) = &self.calculator.simulation {
+            // one of the sessions, to show how the bankroll swings
+            let (x, y, w, h) = CALCULATOR_SIMULATION_AREA;
+            let bankroll: Vec<f32> = simulation.example.bankroll.iter().map(|money| *money as f32).collect();
+            let peak = bankroll.iter().copied().fold(1.0, f32::max);
+            analytics::draw_line_chart(ctx, graphics::Rect::new(x, y, w, h), &bankroll, peak, graphics::Color::from_rgb(255, 163, 26))?;
+
+            lines.push(simulation.summary());
+        }
+        let controls = if self.config.family_mode {
+            "Left/Right = change the rule."
+        } else {
+            "Up/Down = select, type a number or use Left/Right to change it. Click a chip to bet it, right click to take it back."
+        };
+        lines.push(controls.to_owned() + " Best play: drawing or standing, whichever does better.");
+
+        let mut position = self.layout.at(Anchor::CalculatorFirstResult);
+        for line in lines {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(CALCULATOR_TEXT_SIZE));
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+            position.y += CALCULATOR_RESULT_SPACING;
+        }
+
+        if !self.config.family_mode {
+            self.draw_chips(ctx)?;
+        }
+
+        let recent_bets = self.recent_bet_buttons(ctx);
+        if !recent_bets.is_empty() {
+            self.draw_text(ctx, &self.recent_bets_label(), self.layout.at(Anchor::CalculatorRecentBets))?;
+            for (text, position) in recent_bets {
+                self.draw_text(ctx, &text, position)?;
+            }
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// The chip tray with the values on the chips, and the bet as stacks of chips.
+    fn draw_chips(&self, ctx: &mut Context) -> GameResult<()> {
+        chips::draw_tray(ctx, self.layout.at(Anchor::CalculatorChipTray))?;
+
+        for (i, denomination) in chips::DENOMINATIONS.iter().enumerate() {
+            // dark values on the white chip, light ones on the others
+            let color = if i == 0 { graphics::Color::BLACK } else { graphics::Color::WHITE };
+            let value = graphics::Text::new(graphics::TextFragment::new(denomination.to_string()).
+                                                                   color(color).
+                                                                   font(self.fonts.text_font()).
+                                                                   scale(graphics::PxScale::from(CALCULATOR_CHIP_TEXT_SIZE)));
+            let dimensions = value.dimensions(ctx);
+            let position = self.layout.offset(Anchor::CalculatorChipTray,
+                                              i as f32 * chips::CHIP_SPACING - dimensions.w / 2.0,
+                                              -dimensions.h / 2.0);
+
+            graphics::draw(ctx, &value, graphics::DrawParam::default().dest(position))?;
+        }
+
+        chips::draw_stacks(ctx, self.layout.at(Anchor::CalculatorChipStacks), &self.calculator.bet_chips())
+    }
+
+    fn draw_trainer(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Trainer, font, TRAINER_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::TrainerTitle))?;
+
+        self.draw_text(ctx, &self.trainer_button_text(Label::Quiz), self.layout.at(Anchor::TrainerQuizText))?;
+        self.draw_text(ctx, &self.trainer_button_text(Label::CountDrill), self.layout.at(Anchor::TrainerDrillText))?;
+        self.draw_text(ctx, &self.trainer_button_text(Label::Review), self.layout.at(Anchor::TrainerReviewText))?;
+        self.draw_text(ctx, &self.trainer_button_text(Label::Situation), self.layout.at(Anchor::TrainerSituationText))?;
+
+        let mut quiz_description = format!("{} quick questions: the basic strategy action for a hand, or what a hand is worth. Faster answers score more.",
+                                           quiz::QUESTIONS_PER_ROUND);
+        if let Some(quiz) = self.quiz.as_ref().filter(|quiz| quiz.finished()) {
+            quiz_description += format!("  Last round: {}/{} right, score {}.", quiz.correct, quiz::QUESTIONS_PER_ROUND, quiz.score).as_str();
+        }
+        let drill_description = format!("Cards are dealt face up, keep the running Hi-Lo count. You are asked for it {} times.",
+                                        drill::STOPS_PER_ROUND);
+        let drill_settings = format!("Speed: {} (Left/Right)    Decks: {} (Up/Down)    Shoe panel: {} (Tab)",
+                                     self.drill_settings.speed.name(),
+                                     self.drill_settings.decks,
+                                     self.drill_settings.shoe_view.name());
+
+        let below = |position: Point2<f32>, offset: f32| Point2 { x: position.x, y: position.y + offset };
+        let mut lines = vec![
+            (quiz_description, below(self.layout.at(Anchor::TrainerQuizText), TRAINER_DESCRIPTION_OFFSET)),
+            (drill_description, below(self.layout.at(Anchor::TrainerDrillText), TRAINER_DESCRIPTION_OFFSET)),
+            (drill_settings, below(self.layout.at(Anchor::TrainerDrillText), TRAINER_DESCRIPTION_OFFSET + TRAINER_LINE_SPACING)),
+            (format!("Type a starting hand: {}_", self.situation_input), below(self.layout.at(Anchor::TrainerSituationText), TRAINER_DESCRIPTION_OFFSET)),
+            (String::from("like soft 18, hard 16 or pair of 8s, then Enter"),
+             below(self.layout.at(Anchor::TrainerSituationText), TRAINER_DESCRIPTION_OFFSET + TRAINER_LINE_SPACING)),
+        ];
+        for (i, line) in self.trainer_stats.summary().into_iter().enumerate() {
+            lines.push((line, below(self.layout.at(Anchor::TrainerStats), i as f32 * TRAINER_LINE_SPACING)));
+        }
+
+        for (line, position) in lines {
+            let mut text = graphics::Text::new(line);
+            text.set_font(font, graphics::PxScale::from(TRAINER_TEXT_SIZE));
+            graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_quiz(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Quiz, font, QUIZ_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::QuizTitle))?;
+
+        let quiz = match &self.quiz {
+            Some(quiz) => quiz,
+            None => return self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)),
+        };
+
+        let mut lines = Vec::new();
+        if quiz.finished() {
+            lines.push((format!("Round over: {}/{} right, {:.1} s per answer, score {}.",
+                                quiz.correct, quiz::QUESTIONS_PER_ROUND, quiz.average_seconds(), quiz.score),
+                        self.layout.at(Anchor::QuizStatus),
+                        graphics::Color::WHITE));
+            lines.push((self.tr(Label::QuizPlayAgain).to_string(), self.layout.at(Anchor::QuizPrompt), graphics::Color::WHITE));
+        } else {
+            let question = &quiz.question;
+            let hand: Vec<String> = question.player.iter().map(|card| card.short_name()).collect();
+
+            let dealer_text = self.fonts.card_text(&format!("{}: {}", self.tr(Label::QuizDealerShows), question.dealer_up.short_name()), QUIZ_CARDS_SIZE);
+            let player_text = self.fonts.card_text(&format!("{}: {}", self.tr(Label::QuizYourHand), hand.join(" ")), QUIZ_CARDS_SIZE);
+            graphics::draw(ctx, &dealer_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::QuizDealer)))?;
+            graphics::draw(ctx, &player_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::QuizPlayer)))?;
+
+            let prompt = match question.kind {
+                QuestionKind::Action => String::from("Basic strategy says? H = hit, S = stand, D = double, P = split"),
+                QuestionKind::Total => format!("{}: {}_", self.tr(Label::QuizTotalPrompt), quiz.typed),
+            };
+            lines.push((format!("Question {}/{}   Score {}", quiz.question_number, quiz::QUESTIONS_PER_ROUND, quiz.score),
+                        self.layout.at(Anchor::QuizStatus),
+                        graphics::Color::WHITE));
+            lines.push((prompt, self.layout.at(Anchor::QuizPrompt), graphics::Color::WHITE));
+
+            match quiz.feedback {
+                Some(true) => lines.push((self.tr(Label::QuizRight).to_string(), self.layout.at(Anchor::QuizFeedback), graphics::Color::from_rgb(0, 204, 0))),
+                Some(false) => lines.push((format!("{} {}", self.tr(Label::QuizAnswerIs), question.correct_answer()), self.layout.at(Anchor::QuizFeedback), graphics::Color::from_rgb(204, 0, 0))),
+                None => (),
+            }
+
+            let timer_position = self.layout.at(Anchor::QuizTimer);
+            let timer_rect = graphics::Rect::new(timer_position.x,
+                                                 timer_position.y,
+                                                 QUIZ_TIMER_WIDTH * quiz.time_left / quiz::SECONDS_PER_QUESTION,
+                                                 QUIZ_TIMER_HEIGHT);
+            if timer_rect.w > 0.0 {
+                let timer = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), timer_rect, graphics::Color::from_rgb(255, 163, 26))?;
+                graphics::draw(ctx, &timer, graphics::DrawParam::default())?;
+            }
+        }
+
+        for (line, position, color) in lines {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(QUIZ_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    fn draw_drill(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::CountDrill, font, QUIZ_TITLE_SIZE);
+        self.draw_text(ctx, &title, self.layout.at(Anchor::QuizTitle))?;
+
+        let drill = match &self.drill {
+            Some(drill) => drill,
+            None => return self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText)),
+        };
+
+        let mut lines = Vec::new();
+        if drill.finished() {
+            lines.push((format!("Round over: {}/{} counts right after {} cards.", drill.correct, drill.stops, drill.cards_dealt),
+                        self.layout.at(Anchor::QuizStatus),
+                        graphics::Color::WHITE));
+            lines.push((self.tr(Label::DrillDealAgain).to_string(), self.layout.at(Anchor::QuizPrompt), graphics::Color::WHITE));
+        } else {
+            lines.push((format!("Stop {}/{}   Cards dealt {}   {} deck(s), {}",
+                                (drill.stops + 1).min(drill::STOPS_PER_ROUND),
+                                drill::STOPS_PER_ROUND,
+                                drill.cards_dealt,
+                                drill.settings.decks,
+                                drill.settings.speed.name()),
+                        self.layout.at(Anchor::QuizStatus),
+                        graphics::Color::WHITE));
+
+            if let Some(card) = &drill.shown {
+                let card_text = self.fonts.card_text(&card.short_name(), DRILL_CARD_SIZE);
+                graphics::draw(ctx, &card_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::DrillCard)))?;
+            }
+            if drill.burned {
+                lines.push((self.tr(Label::DrillBurnCard).to_string(), self.layout.at(Anchor::QuizFeedback), graphics::Color::WHITE));
+            }
+            if drill.asking {
+                lines.push((format!("{}: {}_", self.tr(Label::DrillCountPrompt), drill.typed),
+                            self.layout.at(Anchor::QuizPrompt),
+                            graphics::Color::WHITE));
+            }
+
+            match drill.feedback {
+                Some(true) => lines.push((self.tr(Label::QuizRight).to_string(), self.layout.at(Anchor::QuizFeedback), graphics::Color::from_rgb(0, 204, 0))),
+                Some(false) => lines.push((format!("{} {}", self.tr(Label::DrillCountIs), drill.running_count), self.layout.at(Anchor::QuizFeedback), graphics::Color::from_rgb(204, 0, 0))),
+                None => (),
+            }
+        }
+
+        for (line, position, color) in lines {
+            let line_fragment = graphics::TextFragment::new(line).
+                                                        color(color).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(QUIZ_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(line_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        self.draw_drill_shoe_panel(ctx, drill)?;
+        self.draw_text(ctx, &self.back_button_text(), self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// What's left in the drill's shoe, folded to the number of cards until Tab opens it.
+    fn draw_drill_shoe_panel(&self, ctx: &mut Context, drill: &Drill) -> GameResult<()> {
+        let settings = self.drill_settings;
+        let mut lines = vec![format!("SHOE: {} cards left (Tab to {})", drill.cards_left(), if settings.shoe_panel_open { "fold" } else { "open" })];
+        if settings.shoe_panel_open {
+            lines.extend(drill.shoe_lines(settings.shoe_view));
+        }
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(self.fonts.text_font(), graphics::PxScale::from(SHOE_PANEL_TEXT_SIZE));
+            let position = self.layout.offset(Anchor::DrillShoePanel, 0.0, i as f32 * SHOE_PANEL_LINE_SPACING);
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_settings(&self, ctx: &mut Context) -> GameResult<()> {
+        let font = self.fonts.text_font();
+
+        let title = self.label_text(Label::Settings, font, SETTINGS_TITLE_SIZE);
+
+        let back_button_text = self.back_button_text();
+
+        self.draw_text(ctx, &title, self.layout.at(Anchor::SettingsTitle))?;
+
+        let mut position = self.layout.at(Anchor::SettingsFirstRow);
+        for (i, row) in SETTINGS_ROWS.iter().enumerate() {
+            let selected = i == self.settings_row;
+            let tr = |label| self.tr(label).to_owned();
+            let row_str = match row {
+                SettingsRow::CardSet => tr(Label::CardSet) + ":  < " + self.card_set_choice.as_str() + " >",
+                SettingsRow::CardBack => tr(Label::CardBack) + ":  < " + self.config.card_back.as_str() + " >",
+                SettingsRow::Felt => tr(Label::Felt) + ":  < " + self.profile.felt.as_str() + " >",
+                SettingsRow::DealerAvatar => tr(Label::DealerAvatar) + ":  < " + self.profile.dealer_avatar.as_str() + " >",
+                SettingsRow::Font => tr(Label::Font) + ":  < " + self.fonts.user_font_name.as_str() + " >",
+                // a cursor shows that typing changes the name
+                SettingsRow::Name => tr(Label::Name) + ":  " + self.profile.name.as_str() + if selected { "_" } else { "" },
+                SettingsRow::Language => tr(Label::Language) + ":  < " + self.language.native_name() + " >",
+                SettingsRow::ExportStats => tr(Label::ExportStats) + ":  " + self.tr(Label::PressEnter),
+                SettingsRow::StreamMode => tr(Label::StreamMode) + ":  < " + on_off(self.config.stream_mode) + " >",
+                SettingsRow::StreamHidePowerUps => tr(Label::StreamHidePowerUps) + ":  < " + on_off(self.config.stream_hide_power_ups) + " >",
+                SettingsRow::GameSpeed => tr(Label::GameSpeed) + ":  < " + self.config.game_speed.name() + " >",
+                SettingsRow::AutoStand => tr(Label::AutoStand) + ":  < " + on_off(self.config.auto_stand_on_21) + " >",
+                SettingsRow::AutoAdvance => tr(Label::AutoAdvance) + ":  < " + on_off(self.config.auto_advance) + " >",
+                SettingsRow::WarnRiskyHits => tr(Label::WarnRiskyHits) + ":  < " + on_off(self.config.warn_risky_hits) + " >",
+                SettingsRow::WhatIfOdds => tr(Label::WhatIfOdds) + ":  < " + on_off(self.config.what_if_odds) + " >",
+                SettingsRow::ShowNextCard => tr(Label::ShowNextCard) + ":  < " + on_off(self.config.show_next_card) + " >",
+                SettingsRow::BurnCard => tr(Label::BurnCard) + ":  < " + self.config.burn_card.name() + " >",
+                SettingsRow::GpuLight => tr(Label::GpuLight) + ":  < " + on_off(self.config.gpu_light) + " >",
+                SettingsRow::ImportStats => format!("{}:  {}({} \"{}\")", tr(Label::ImportStats), self.tr(Label::PressEnter), self.tr(Label::ReadsTheFolder), stats_import::IMPORT_DIR),
+                SettingsRow::BackUpNow => tr(Label::BackUpNow) + ":  " + self.tr(Label::PressEnter),
+                SettingsRow::Advanced => format!("{}:  {}({} {})", tr(Label::AdvancedSettings), self.tr(Label::PressEnter), self.tr(Label::EveryKeyOf), config::CONFIG_FILE_NAME),
+                SettingsRow::RestoreBackup => tr(Label::RestoreBackup) + ":  " + self.backup_choice_text().as_str(),
+            };
+
+            let color = if selected {
+                graphics::Color::from_rgb(255, 163, 26)
+            } else {
+                graphics::Color::from_rgb(255, 255, 255)
+            };
+
+            let row_fragment = graphics::TextFragment::new(row_str).
+                                                       color(color).
+                                                       font(font).
+                                                       scale(graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+
+            self.draw_text(ctx, &graphics::Text::new(row_fragment), position)?;
+            position.y += SETTINGS_ROW_SPACING;
+        }
+
+        if let Some(error) = &self.card_set_error {
+            let error_str = error.to_string() + ", using \"" + self.board.card_set.name.as_str() + "\" instead";
+            let error_fragment = graphics::TextFragment::new(error_str).
+                                                         color(graphics::Color::from_rgb(204, 0, 0)).
+                                                         font(font).
+                                                         scale(graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+
+            graphics::draw(ctx, &graphics::Text::new(error_fragment), graphics::DrawParam::default().dest(position))?;
+        }
+
+        let controls_str = "Up/Down = select, Left/Right = change, Enter = export/import/back up/restore, type to edit the name. Custom card sets go in the \"".to_owned() + card_set::CARD_SETS_DIR + "\" folder.";
+        let mut controls_text = graphics::Text::new(controls_str);
+        controls_text.set_font(font, graphics::PxScale::from(SETTINGS_TEXT_SIZE));
+
+        graphics::draw(ctx, &controls_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::SettingsControls)))?;
+
+        self.draw_text(ctx, &back_button_text, self.layout.at(Anchor::HelpBackText))
+    }
+
+    /// Plate with the player's name, between the dealer's and the player's cards.
+    fn draw_name_plate(&self, ctx: &mut Context) -> GameResult<()> {
+        let plate_position = self.layout.at(Anchor::NamePlate);
+        let plate_rect = graphics::Rect::new(plate_position.x, plate_position.y, NAME_PLATE_WIDTH, NAME_PLATE_HEIGHT);
+        let plate = graphics::Mesh::new_rounded_rectangle(ctx,
+                                                          graphics::DrawMode::fill(),
+                                                          plate_rect,
+                                                          8.0,
+                                                          graphics::Color::new(0.0, 0.0, 0.0, 0.45))?;
+        let border = graphics::Mesh::new_rounded_rectangle(ctx,
+                                                           graphics::DrawMode::stroke(2.0),
+                                                           plate_rect,
+                                                           8.0,
+                                                           graphics::Color::from_rgb(255, 163, 26))?;
+
+        let font = self.fonts.text_font();
+
+        let mut name_text = graphics::Text::new(self.profile.display_name());
+        name_text.set_font(font, graphics::PxScale::from(NAME_PLATE_TEXT_SIZE));
+        let text_position = self.layout.offset(Anchor::NamePlate, 14.0, 7.0);
+
+        graphics::draw(ctx, &plate, graphics::DrawParam::default())?;
+        graphics::draw(ctx, &border, graphics::DrawParam::default())?;
+        graphics::draw(ctx, &name_text, graphics::DrawParam::default().dest(text_position))
+    }
+
+    fn draw_toast(&self, ctx: &mut Context) -> GameResult<()> {
+        if let Some(toast) = &self.toast {
+            let font = self.fonts.text_font();
+
+            let mut toast_text = graphics::Text::new(toast.as_str());
+            toast_text.set_font(font, graphics::PxScale::from(TOAST_TEXT_SIZE));
+
+            // the reason for a turned down action shakes
+            let position = self.layout.offset(Anchor::Toast, self.rejection.shake_offset(), 0.0);
+            graphics::draw(ctx, &toast_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        Ok(())
+    }
+
+    fn hud_state(&self) -> HudState<'static> {
+        let hint_shown = matches!(self.engine.hint, HintStatus::Active);
+
+        HudState {
+            counters: self.score_counters,
+            dealer_handicap_active: self.engine.dealer_handicap_active,
+            power_ups: if self.practice { None } else { Some(self.power_ups_count) },
+            wins: self.wins,
+            practice_label: if self.practice { Some(self.tr(Label::Practice)) } else { None },
+            hint_range: self.hint_range.filter(|_| hint_shown),
+        }
+    }
+
+    /// Scores, power ups, wins and the hint, the power ups being left out in stream mode when
+    /// the settings say so.
+    fn draw_hud(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let state = self.hud_state();
+        let elements: Vec<HudElement> = HudElement::PLAY.iter().
+            copied().
+            filter(|element| !self.hide_power_ups() || !matches!(element, HudElement::PowerUps | HudElement::Hint)).
+            filter(|element| !self.config.minimal_hud || *element == HudElement::Scores).
+            collect();
+
+        self.hud.draw(ctx, self.fonts.text_font(), &self.layout, &state, &elements)
+    }
+
+    /// The TIP button once a hand is over, and the dealer's thanks after a tip.
+    fn draw_tip(&self, ctx: &mut Context) -> GameResult<()> {
+        if self.can_tip() {
+            self.draw_text(ctx, &self.tip_button_text(), self.layout.at(Anchor::TipText))?;
+        }
+
+        if let Some(line) = self.tip.line() {
+            let mut thanks = graphics::Text::new(format!("\"{}\"", line));
+            thanks.set_font(self.fonts.text_font(), graphics::PxScale::from(DEALER_THANKS_SIZE));
+            self.draw_text(ctx, &thanks, self.layout.at(Anchor::DealerThanks))?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_hit_warning(&self, ctx: &mut Context) -> GameResult<()> {
+        if !self.hit_warning.showing() {
+            return Ok(());
+        }
+
+        let (hard, _) = game_engine::hand_totals(&self.board.dealed_cards_player);
+        let warning_fragment = graphics::TextFragment::new(format!("Hard {}: click the deck again to hit", hard)).
+                                                            color(graphics::Color::from_rgb(255, 163, 26)).
+                                                            font(self.fonts.text_font()).
+                                                            scale(graphics::PxScale::from(HIT_WARNING_TEXT_SIZE));
+
+        self.draw_text(ctx, &graphics::Text::new(warning_fragment), self.layout.at(Anchor::HitWarning))
+    }
+
+    /// Says why the deck is greyed out, when it's worth saying.
+    fn draw_table_lock(&self, ctx: &mut Context) -> GameResult<()> {
+        let label = match self.table_state().lock().and_then(|lock| lock.label()) {
+            Some(label) => label,
+            None => return Ok(()),
+        };
+
+        let mut lock_text = graphics::Text::new(label);
+        lock_text.set_font(self.fonts.text_font(), graphics::PxScale::from(TABLE_LOCK_TEXT_SIZE));
+
+        self.draw_text(ctx, &lock_text, self.layout.at(Anchor::TableLock))
+    }
+
+    /// While the player decides and the setting is on, not while a card is on its way.
+    fn what_if_shown(&self) -> bool {
+        self.config.what_if_odds && matches!(self.status, GameStatus::Play) && self.table_state().allows(Action::Hit)
+    }
+
+    /// The odds of standing and of hitting on the hand, once they are worked out.
+    fn draw_what_if(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec![self.tr(Label::WhatIf).to_string()];
+        match self.what_if.odds() {
+            Some(odds) => {
+                lines.push(what_if::describe(self.language, Label::WhatIfStand, &odds.stand));
+                lines.push(what_if::describe(self.language, Label::WhatIfHit, &odds.hit));
+            },
+            None => lines.push(self.tr(Label::WorkingOutOdds).to_string()),
+        }
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(self.fonts.text_font(), graphics::PxScale::from(WHAT_IF_TEXT_SIZE));
+            let position = self.layout.offset(Anchor::WhatIfPanel, 0.0, i as f32 * WHAT_IF_LINE_SPACING);
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        Ok(())
+    }
+
+    /// What's left in the shoe by rank, and the card picker under it.
+    fn draw_shoe_panel(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec![format!("SHOE ({}): {} cards left", self.practice_shoe.describe(), self.board.deck.remaining())];
+        for (rank, left) in self.board.deck.composition() {
+            lines.push(format!("{}: {}", rank, left));
+        }
+
+        let font = self.fonts.text_font();
+        for (i, line) in lines.into_iter().enumerate() {
+            let mut line_text = graphics::Text::new(line);
+            line_text.set_font(font, graphics::PxScale::from(SHOE_PANEL_TEXT_SIZE));
+            let position = self.layout.offset(Anchor::ShoePanel, 0.0, i as f32 * SHOE_PANEL_LINE_SPACING);
+
+            graphics::draw(ctx, &line_text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        let picker = format!("NEXT CARD: < {} >\nLeft/Right = rank, Up/Down = suit\nEnter = put it on top\n+/- = more/fewer of the rank, Delete = none\nHome = a standard deck",
+                             self.card_picker.describe());
+        let mut picker_text = graphics::Text::new(picker);
+        picker_text.set_font(font, graphics::PxScale::from(SHOE_PANEL_TEXT_SIZE));
+
+        graphics::draw(ctx, &picker_text, graphics::DrawParam::default().dest(self.layout.at(Anchor::CardPicker)))
+    }
+
+    /// Whether the dealer is drawing slowly to a heartbeat, see sweat.rs.
+    fn sweating(&self) -> bool {
+        let counted = self.scenario.is_none() && !self.practice;
+
+        counted &&
+            matches!(self.board.turn, board::Turn::Dealer) &&
+            !self.engine.game_over &&
+            sweat::allowed(self.config.sweat_mode, self.config.game_speed) &&
+            sweat::worth_sweating(game_engine::hand_value(&self.board.dealed_cards_player), banner::is_milestone(self.wins + 1))
+    }
+
+    /// The dealer's next card waits on a heartbeat.
+    fn wait_on_heartbeat(&mut self, ctx: &mut Context) {
+        self.time_till_dealer_card = sweat::SWEAT_DEALER_DELAY;
+        if let Err(e) = self.board.assets.heartbeat_sound.play(ctx) {
+            eprintln!("Could not play the heartbeat: {}", e);
+        }
+    }
+
+    /// The banner of the hand just over, see banner.rs.
+    fn banner(&self) -> Option<Banner> {
+        let counted = self.scenario.is_none() && !self.practice;
+
+        Banner::of(&self.engine.outcome, &self.board.dealed_cards_player, Some(self.wins + 1).filter(|_| counted))
+    }
+
+    fn draw_game_over_text(&self, ctx: &mut Context) -> GameResult<()> {
+        let banner = match self.banner() {
+            Some(banner) => banner,
+            None => return Ok(()),
+        };
+        let text = match banner {
+            Banner::Lose => self.tr(Label::YouLose),
+            Banner::Draw => self.tr(Label::YouDraw),
+            Banner::Win | Banner::BigWin | Banner::Milestone(_) => self.tr(Label::YouWin),
+        };
+
+        let font = self.fonts.text_font();
+        let graphics::Rect { w: screen_width, h: screen_height, .. } = graphics::screen_coordinates(ctx);
+
+        // bigger on stream, so it reads well in a small player window
+        let (size, position) = if self.config.stream_mode {
+            (STREAM_GAME_OVER_TEXT_SIZE, self.layout.at(Anchor::StreamGameOverText))
+        } else {
+            (GAME_OVER_TEXT_SIZE, self.layout.at(Anchor::GameOverText))
+        };
+        let size = size * banner.scale();
+
+        if let Some(wash) = banner.wash().filter(|_| !self.config.gpu_light) {
+            let table = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::Rect::new(0.0, 0.0, screen_width, screen_height), wash)?;
+            graphics::draw(ctx, &table, graphics::DrawParam::default())?;
+        }
+
+        let subtitle = banner.subtitle();
+        if subtitle.is_some() {
+            // a ribbon across the table, behind the text and its line
+            let ribbon_rect = graphics::Rect::new(0.0, position.y - BANNER_RIBBON_PADDING, screen_width, size * 1.2 + HINDSIGHT_TEXT_SIZE * 1.5 + 2.0 * BANNER_RIBBON_PADDING);
+            let ribbon = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), ribbon_rect, graphics::Color::new(0.0, 0.0, 0.0, 0.6))?;
+            let edge = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(4.0), ribbon_rect, banner.color())?;
+            graphics::draw(ctx, &ribbon, graphics::DrawParam::default())?;
+            graphics::draw(ctx, &edge, graphics::DrawParam::default())?;
+        }
+
+        let game_over_text = graphics::TextFragment::new(text).
+                                                     color(banner.color()).
+                                                     font(font).
+                                                     scale(graphics::PxScale::from(size));
+
+        self.draw_text(ctx, &graphics::Text::new(game_over_text), position)?;
+
+        let mut below = Point2 { x: position.x, y: position.y + size * 1.2 };
+        if let Some(subtitle) = subtitle {
+            let subtitle_text = graphics::TextFragment::new(subtitle).
+                                                        color(banner.color()).
+                                                        font(font).
+                                                        scale(graphics::PxScale::from(HINDSIGHT_TEXT_SIZE * 1.5));
+            self.draw_text(ctx, &graphics::Text::new(subtitle_text), below)?;
+            below.y += HINDSIGHT_TEXT_SIZE * 1.5 + BANNER_RIBBON_PADDING;
+        }
+
+        if let Some(next) = self.would_have_drawn.as_ref().filter(|_| self.config.show_next_card) {
+            let mut hindsight_text = graphics::Text::new(hindsight::describe(&self.board.dealed_cards_player, next));
+            hindsight_text.set_font(font, graphics::PxScale::from(HINDSIGHT_TEXT_SIZE));
+
+            self.draw_text(ctx, &hindsight_text, below)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_screen(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let felt = felt::find(&self.profile.felt);
+        // the felt's pattern is a mesh of its own every frame
+        if self.config.gpu_light {
+            graphics::clear(ctx, felt.color());
+        } else {
+            felt.draw(ctx)?;
+        }
+
+        match self.status {
+            GameStatus::Splash => self.draw_splash(ctx)?,
+            GameStatus::Setup => self.draw_setup(ctx)?,
+            GameStatus::Menu => self.draw_menu(ctx)?,
+            GameStatus::Help => self.draw_help(ctx)?,
+            GameStatus::Settings => self.draw_settings(ctx)?,
+            GameStatus::ConfigEditor => self.draw_config_editor(ctx)?,
+            GameStatus::Leaderboard => self.draw_leaderboard(ctx)?,
+            GameStatus::Transcript => self.draw_transcript(ctx)?,
+            GameStatus::Analysis => self.draw_analysis(ctx)?,
+            GameStatus::Calculator => self.draw_calculator(ctx)?,
+            GameStatus::Heatmap => self.draw_heatmap(ctx)?,
+            GameStatus::Gallery => self.draw_gallery(ctx)?,
+            GameStatus::Challenge => self.draw_challenge(ctx)?,
+            GameStatus::Trainer => self.draw_trainer(ctx)?,
+            GameStatus::Quiz => self.draw_quiz(ctx)?,
+            GameStatus::Drill => self.draw_drill(ctx)?,
+            GameStatus::Unlocks => self.draw_unlocks(ctx)?,
+            GameStatus::About => self.draw_about(ctx)?,
+            GameStatus::Profile => self.draw_profile(ctx)?,
+            GameStatus::Review => self.draw_review(ctx)?,
+            GameStatus::SessionSummary => self.draw_session_summary(ctx)?,
+            GameStatus::Play => {
+                if self.time_till_game_over <= 0.0 {
+                    self.draw_game_over_text(ctx)?;
+                } else {
+                    // a card on its way is over too soon to grey the deck for
+                    self.board.draw(ctx, !matches!(self.table_state().lock(), None | Some(Lock::CardMoving)))?;
+                    self.board.draw_value_badges(ctx, self.fonts.text_font(), VALUE_BADGE_TEXT_SIZE)?;
+                    self.board.draw_stamps(ctx, self.fonts.text_font(), STAMP_TEXT_SIZE)?;
+                    if !self.config.minimal_hud {
+                        let placard_lines = placard::lines(self.rules(), self.config.family_mode, self.config.burn_card, self.board.deck.cut_placed());
+                        placard::draw(ctx, self.fonts.text_font(), PLACARD_TEXT_SIZE, self.layout.at(Anchor::Placard), &placard_lines)?;
+                    }
+                    let dealer_position = self.layout.offset(Anchor::DealerAvatar, 0.0, -self.tip.bob_offset());
+                    avatar::find(&self.profile.dealer_avatar).draw(ctx, dealer_position)?;
+                    self.draw_tip(ctx)?;
+                    if let Some(line) = self.table_talk.line().filter(|_| !self.config.minimal_hud) {
+                        bubble::draw(ctx, self.fonts.text_font(), TABLE_TALK_TEXT_SIZE, self.layout.at(Anchor::TableTalk), self.layout.mirrored, line)?;
+                    }
+                    if !self.config.minimal_hud {
+                        self.draw_name_plate(ctx)?;
+                    }
+                    if self.turn_timer_running() {
+                        turn_timer::draw(ctx, &self.turn_timer, self.layout.at(Anchor::TurnTimer), self.fonts.text_font(), TURN_TIMER_TEXT_SIZE)?;
+                    }
+                    self.draw_hud(ctx)?;
+                    self.draw_hit_warning(ctx)?;
+                    self.draw_table_lock(ctx)?;
+                    if self.practice {
+                        self.draw_shoe_panel(ctx)?;
+                    }
+                    if self.what_if_shown() && !self.config.minimal_hud {
+                        self.draw_what_if(ctx)?;
+                    }
+                    rejection::draw_flash(ctx, &self.rejection)?;
+                }
+            },
+        }
+
+        self.draw_toast(ctx)
+    }
+}
+
+/// Reads the stats line: wins, hints and handicaps separated by spaces.
+pub fn parse_stats(line: &str) -> Option<(u32, u32, u32)> {
+    let mut numbers = line.split_whitespace().map(|number| number.parse::<u32>().ok());
+    let stats = (numbers.next()??, numbers.next()??, numbers.next()??);
+
+    if numbers.next().is_some() {
+        return None;
+    }
+
+    Some(stats)
+}
+
+/// Sets the screen coordinates for the window's size at the UI scale.
+fn fit_screen(ctx: &mut Context, layout: &Layout) {
+    let (width, height) = graphics::drawable_size(ctx);
+    if let Err(e) = graphics::set_screen_coordinates(ctx, layout.screen_rect(width, height)) {
+        eprintln!("Could not resize the screen: {}", e);
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+/// Cosmetics that aren't unlocked(picked before they were locked, or no longer earned) go back
+/// to the defaults.
+fn lock_unearned_cosmetics(config: &mut Config, profile: &mut Profile) {
+    if !unlocks::is_unlocked(Cosmetic::CardBack(card_back::find(&config.card_back).name), profile) {
+        config.card_back = card_back::CLASSIC.name.to_string();
+    }
+    if !unlocks::is_unlocked(Cosmetic::Felt(felt::find(&profile.felt).name), profile) {
+        profile.felt = Profile::default().felt;
+    }
+    if !unlocks::is_unlocked(Cosmetic::DealerAvatar(avatar::find(&profile.dealer_avatar).name), profile) {
+        profile.dealer_avatar = Profile::default().dealer_avatar;
+    }
+}
+
+impl event::EventHandler for MainState {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        const DESIRED_FPS: u32 = 60;
+        let time_delta = 1.0 / (DESIRED_FPS as f32);
+
+        while timer::check_update_time(ctx, DESIRED_FPS) {
+            #[cfg(feature = "hot-reload")]
+            self.reload_changed_assets(ctx, time_delta)?;
+            self.update_toast(time_delta);
+            self.tip.update(time_delta);
+            self.table_talk.update(time_delta);
+            self.rejection.update(time_delta);
+            self.leaderboard.update();
+            self.update_playtime(time_delta);
+            if self.idle_timer.update(time_delta) {
+                self.leave_idle_session(ctx)?;
+            }
+            // a hand in play keeps the config it started with
+            if !matches!(self.status, GameStatus::Play) && self.config_watcher.changed(time_delta) {
+                self.reload_config(ctx)?;
+            }
+
+            match self.status {
+                GameStatus::Splash => self.update_splash(ctx, time_delta),
+                GameStatus::Setup => self.board.card_set.assets().update(ctx),
+                GameStatus::Menu => self.update_menu(ctx),
+                GameStatus::Help => self.update_help(ctx),
+                GameStatus::Settings => self.update_settings(ctx)?,
+                GameStatus::ConfigEditor => self.update_config_editor(ctx),
+                GameStatus::Leaderboard => self.update_leaderboard(ctx),
+                GameStatus::Transcript => self.update_help(ctx),
+                GameStatus::Analysis => self.update_analysis(ctx),
+                GameStatus::Calculator => self.update_calculator(ctx),
+                GameStatus::Heatmap => self.update_calculator(ctx),
+                GameStatus::Gallery => self.update_gallery(ctx),
+                GameStatus::Challenge => self.update_challenge(ctx),
+                GameStatus::Trainer => self.update_trainer(ctx),
+                GameStatus::Quiz => self.update_quiz(ctx, time_delta),
+                GameStatus::Drill => self.update_drill(ctx, time_delta),
+                GameStatus::Unlocks => self.update_help(ctx),
+                GameStatus::About => self.update_help(ctx),
+                GameStatus::Profile => self.update_profile(ctx, time_delta)?,
+                GameStatus::Review => self.update_help(ctx),
+                GameStatus::SessionSummary => (),
+                GameStatus::Play => self.update_game(ctx, time_delta)?,    
+            }
+            snapshot::record(&self.engine.snapshot());
+
+            if let Some(title) = self.window_title.update(self.window_title_text(), time_delta) {
+                graphics::set_window_title(ctx, &title);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn key_down_event(&mut self,
                       ctx: &mut Context,
                       keycode: event::KeyCode,
                       _keymod: input::keyboard::KeyMods,
-                      _repeat: bool) {
+                      repeat: bool) {
+            self.idle_timer.input();
+            // any key skips the intro, and does nothing else
+            if matches!(self.status, GameStatus::Splash) {
+                self.splash.skip();
+                return;
+            }
+            if matches!(self.status, GameStatus::Setup) {
+                if let Err(e) = self.setup_key(ctx, keycode) {
+                    eprintln!("Could not finish the setup: {}", e);
+                }
+                return;
+            }
+
+            let playing = matches!(self.status, GameStatus::Play);
+            let hand_over = playing && self.engine.game_over;
+
             match keycode {
-                event::KeyCode::Space => self.board.turn = board::Turn::Dealer,
-                event::KeyCode::Key1 => self.use_hint(),
-                event::KeyCode::Key2 => self.use_handicap(),
+                // quick keys, so there's no waiting between hands
+                event::KeyCode::R if hand_over || matches!(self.status, GameStatus::Menu) => {
+                    if let Err(e) = self.play_again(ctx) {
+                        eprintln!("Could not start the next hand: {}", e);
+                    }
+                },
+                event::KeyCode::Return if hand_over => {
+                    if let Err(e) = self.reset(ctx) {
+                        eprintln!("Could not finish the hand: {}", e);
+                    }
+                },
+                key if playing && !repeat && self.config.key_map.action(key).is_some() => self.key_action(ctx, key),
+                event::KeyCode::Left if playing && self.practice => self.card_picker.change_rank(-1),
+                event::KeyCode::Right if playing && self.practice => self.card_picker.change_rank(1),
+                event::KeyCode::Up if playing && self.practice => self.card_picker.change_suit(-1),
+                event::KeyCode::Down if playing && self.practice => self.card_picker.change_suit(1),
+                event::KeyCode::Return if playing && self.practice => self.stack_picked_card(),
+                event::KeyCode::Equals | event::KeyCode::Plus | event::KeyCode::NumpadAdd if playing && self.practice => {
+                    self.build_practice_shoe(ShoeChange::Step(1));
+                },
+                event::KeyCode::Minus | event::KeyCode::NumpadSubtract if playing && self.practice => {
+                    self.build_practice_shoe(ShoeChange::Step(-1));
+                },
+                event::KeyCode::Delete if playing && self.practice => self.build_practice_shoe(ShoeChange::RemoveRank),
+                event::KeyCode::Home if playing && self.practice => self.build_practice_shoe(ShoeChange::Standard),
+                event::KeyCode::Escape if matches!(self.status, GameStatus::ConfigEditor) && self.config_editor.typed.is_some() => self.config_editor.cancel(),
+                event::KeyCode::Up if matches!(self.status, GameStatus::ConfigEditor) => self.config_editor.select(-1, self.config.entries().len()),
+                event::KeyCode::Down if matches!(self.status, GameStatus::ConfigEditor) => self.config_editor.select(1, self.config.entries().len()),
+                event::KeyCode::Back if matches!(self.status, GameStatus::ConfigEditor) => self.config_editor.erase_char(),
+                event::KeyCode::Return if matches!(self.status, GameStatus::ConfigEditor) => {
+                    if let Err(e) = self.config_editor_enter(ctx) {
+                        eprintln!("Could not apply the setting: {}", e);
+                    }
+                },
+                event::KeyCode::Back if self.editing_name() => self.edit_name(None),
+                event::KeyCode::Back if matches!(self.status, GameStatus::Calculator) => self.calculator.erase_digit(),
+                event::KeyCode::Back if matches!(self.status, GameStatus::Quiz) => {
+                    if let Some(quiz) = &mut self.quiz {
+                        quiz.erase_char();
+                    }
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::Quiz) => self.quiz_enter(),
+                event::KeyCode::Back if matches!(self.status, GameStatus::Drill) => {
+                    if let Some(drill) = &mut self.drill {
+                        drill.erase_char();
+                    }
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::Drill) => self.drill_enter(),
+                event::KeyCode::Left if matches!(self.status, GameStatus::Trainer) => self.drill_settings.change_speed(-1),
+                event::KeyCode::Right if matches!(self.status, GameStatus::Trainer) => self.drill_settings.change_speed(1),
+                event::KeyCode::Up if matches!(self.status, GameStatus::Trainer) => self.drill_settings.change_decks(1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Trainer) => self.drill_settings.change_decks(-1),
+                event::KeyCode::Tab if matches!(self.status, GameStatus::Trainer) => self.drill_settings.change_shoe_view(),
+                event::KeyCode::Back if matches!(self.status, GameStatus::Trainer) => {
+                    self.situation_input.pop();
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::Trainer) => self.start_situation(),
+                event::KeyCode::Tab if matches!(self.status, GameStatus::Drill) => {
+                    self.drill_settings.shoe_panel_open = !self.drill_settings.shoe_panel_open;
+                },
+                event::KeyCode::Left if matches!(self.status, GameStatus::Calculator) => self.calculator.change(-1),
+                event::KeyCode::Right if matches!(self.status, GameStatus::Calculator) => self.calculator.change(1),
+                event::KeyCode::Up if matches!(self.status, GameStatus::Calculator) => self.calculator.select(-1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Calculator) => self.calculator.select(1),
+                event::KeyCode::Return if matches!(self.status, GameStatus::Calculator) && self.calculator.selected() == CalculatorRow::Simulate => {
+                    self.calculator.simulate();
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::Settings) => {
+                    match SETTINGS_ROWS[self.settings_row] {
+                        SettingsRow::ExportStats => self.export_stats(),
+                        SettingsRow::ImportStats => self.import_stats(),
+                        SettingsRow::BackUpNow => self.back_up_now(),
+                        SettingsRow::Advanced => {
+                            self.config_editor = ConfigEditor::default();
+                            self.status = GameStatus::ConfigEditor;
+                        },
+                        SettingsRow::RestoreBackup => {
+                            if let Err(e) = self.restore_backup(ctx) {
+                                eprintln!("Could not reload the restored saves: {}", e);
+                            }
+                        },
+                        _ => (),
+                    }
+                },
+                event::KeyCode::Left if matches!(self.status, GameStatus::Settings) => self.settings_step -= 1,
+                event::KeyCode::Right if matches!(self.status, GameStatus::Settings) => self.settings_step += 1,
+                event::KeyCode::Up if matches!(self.status, GameStatus::Settings) => {
+                    self.settings_row = (self.settings_row + SETTINGS_ROWS.len() - 1) % SETTINGS_ROWS.len();
+                },
+                event::KeyCode::Down if matches!(self.status, GameStatus::Settings) => {
+                    self.settings_row = (self.settings_row + 1) % SETTINGS_ROWS.len();
+                },
+                event::KeyCode::Up if matches!(self.status, GameStatus::Profile) => self.profile_screen.select(-1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Profile) => self.profile_screen.select(1),
+                event::KeyCode::Return if matches!(self.status, GameStatus::Profile) => {
+                    if let Some(action) = self.profile_screen.enter() {
+                        if let Err(e) = self.run_profile_action(ctx, action) {
+                            eprintln!("Could not apply the profile change: {}", e);
+                        }
+                    }
+                },
+                event::KeyCode::Up if matches!(self.status, GameStatus::Transcript) => self.scroll_transcript(-1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Transcript) => self.scroll_transcript(1),
+                event::KeyCode::Up if matches!(self.status, GameStatus::Review) => self.review.select(-1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Review) => self.review.select(1),
+                event::KeyCode::Return if matches!(self.status, GameStatus::Review) => self.replay_reviewed_hand(),
+                event::KeyCode::Up if matches!(self.status, GameStatus::Gallery) => self.gallery.select(-1),
+                event::KeyCode::Down if matches!(self.status, GameStatus::Gallery) => self.gallery.select(1),
+                event::KeyCode::Return if matches!(self.status, GameStatus::Gallery) => self.replay_gallery_hand(),
+                event::KeyCode::Back if matches!(self.status, GameStatus::Challenge) => {
+                    self.challenge_input.pop();
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::Challenge) => {
+                    if let Err(e) = self.accept_challenge(ctx) {
+                        eprintln!("Could not take on the challenge: {}", e);
+                    }
+                },
+                // the session's mistakes are shown once before quitting
+                event::KeyCode::Escape if !playing && !self.review_shown && !self.review.hands.is_empty() => {
+                    self.review_shown = true;
+                    self.status = GameStatus::Review;
+                },
+                event::KeyCode::Escape if !playing && !self.summary_shown && self.session.hands > 0 => {
+                    self.summary_shown = true;
+                    self.status = GameStatus::SessionSummary;
+                },
+                event::KeyCode::Return if matches!(self.status, GameStatus::SessionSummary) => {
+                    self.summary_shown = false;
+                    self.status = GameStatus::Menu;
+                },
                 event::KeyCode::Escape => {
                     self.save();
+                    self.save_profile();
+                    self.log_session();
+                    self.remember_window(ctx);
+                    self.saver.flush();
                     event::quit(ctx)
                 },
                 _ => (), 
             }
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let casino_green = graphics::Color::from_rgb(21, 50, 30);
-        graphics::clear(ctx, casino_green);
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: mouse::MouseButton, x: f32, y: f32) {
+        self.idle_timer.input();
+        let Point2 { x, y } = self.layout.to_screen(Point2 { x, y });
 
-        match self.status {
-            GameStatus::Menu => self.draw_menu(ctx)?,
-            GameStatus::Help => self.draw_help(ctx)?,
-            GameStatus::Play => {
-                if self.time_till_game_over <= 0.0 {
-                    self.draw_game_over_text(ctx)?;
+        if matches!(self.status, GameStatus::Splash) {
+            self.splash.skip();
+            return;
+        }
+
+        // a new click on the deck while the hit warning is up, update() hits
+        if matches!(self.status, GameStatus::Play) && button == mouse::MouseButton::Left && self.mouse_over_deck(Point2 { x, y }) {
+            self.hit_warning.confirm();
+
+            if self.time_till_game_over > 0.0 && !self.table_state().allows(Action::Hit) {
+                if let Err(e) = self.hit(ctx) {
+                    eprintln!("Could not deal the card: {}", e);
+                }
+            }
+        }
+
+        // after a shuffle a click on the side of the shoe places the cut card
+        if matches!(self.status, GameStatus::Play) && button == mouse::MouseButton::Left && self.scenario.is_none() && self.board.can_place_cut() {
+            if let Some(cut) = self.board.cut_at(Point2 { x, y }) {
+                // a carried over deck would deal the next hand from what this one's hits left
+                if self.challenge_active {
+                    self.show_toast(String::from("No cut card on a challenge, every hand gets a shoe of its own"));
                 } else {
-                    self.board.draw(ctx)?;
-                    self.draw_score(ctx)?;
-                    self.draw_power_ups(ctx)?;
-                    self.draw_wins(ctx)?;
-                    if matches!(self.engine.hint, HintStatus::Active) {
-                        self.draw_hint_text(ctx)?
-                    }
+                    let cut = self.board.deck.place_cut(cut);
+                    self.show_toast(format!("Cut card placed, the deck is dealt down to {} cards", cut));
                 }
-            },
+            }
+        }
+
+        // a tip per click
+        if self.can_tip() && button == mouse::MouseButton::Left && self.mouse_over_button(ctx, Point2 { x, y }, &self.tip_button_text(), self.layout.at(Anchor::TipText)) {
+            self.give_tip();
         }
-        
+
+        if !matches!(self.status, GameStatus::Calculator) || self.config.family_mode {
+            return;
+        }
+
+        // one chip per click, which the held button checks in update can't tell apart
+        if let Some(denomination) = chips::tray_chip_at(self.layout.at(Anchor::CalculatorChipTray), Point2 { x, y }) {
+            match button {
+                mouse::MouseButton::Left => self.calculator.add_chip(denomination),
+                mouse::MouseButton::Right => self.calculator.remove_chip(denomination),
+                _ => (),
+            }
+        }
+    }
+
+    /// The window's close button
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        self.save_profile();
+        self.log_session();
+        self.remember_window(ctx);
+        self.saver.flush();
+        false
+    }
+
+    /// The screen keeps its size in pixels(times the UI scale, see layout.rs) at any window size.
+    /// A narrow window gets the compact table, the hand on it carries on.
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, _height: f32) {
+        fit_screen(ctx, &self.layout);
+
+        let compact = self.layout.compact_for(width);
+        if compact != self.layout.compact {
+            self.layout.compact = compact;
+            self.board.relayout(&self.layout);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, _x: f32, _y: f32, _dx: f32, _dy: f32) {
+        self.idle_timer.input();
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, y: f32) {
+        self.idle_timer.input();
+        if matches!(self.status, GameStatus::Transcript) {
+            self.scroll_transcript(-y.signum() as i32);
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        if let (GameStatus::Setup, Some(setup)) = (&self.status, &mut self.setup) {
+            setup.type_char(character);
+        } else if matches!(self.status, GameStatus::ConfigEditor) {
+            self.config_editor.type_char(character);
+        } else if self.editing_name() && !character.is_control() {
+            self.edit_name(Some(character));
+        } else if matches!(self.status, GameStatus::Calculator) {
+            if let Some(digit) = character.to_digit(10) {
+                self.calculator.type_digit(digit);
+            }
+        } else if let (GameStatus::Quiz, Some(quiz)) = (&self.status, &mut self.quiz) {
+            quiz.type_char(character);
+        } else if let (GameStatus::Drill, Some(drill)) = (&self.status, &mut self.drill) {
+            drill.type_char(character);
+        } else if matches!(self.status, GameStatus::Trainer) && !character.is_control() && self.situation_input.chars().count() < SITUATION_MAX_LENGTH {
+            self.situation_input.push(character);
+        } else if matches!(self.status, GameStatus::Challenge) && !character.is_control() && self.challenge_input.chars().count() < challenge::CODE_MAX_LENGTH {
+            self.challenge_input.push(character);
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.draw_screen(ctx)?;
+
         graphics::present(ctx)?;
 
         Ok(())
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stats_reads_three_numbers() {
+        assert_eq!(parse_stats("12 2 1"), Some((12, 2, 1)));
+        assert_eq!(parse_stats("12 2 1\n"), Some((12, 2, 1)));
+    }
+
+    #[test]
+    fn parse_stats_rejects_damaged_lines() {
+        assert_eq!(parse_stats(""), None);
+        assert_eq!(parse_stats("12 2"), None);
+        assert_eq!(parse_stats("12 -2 1"), None);
+        assert_eq!(parse_stats("12 2 1 7"), None);
+        assert_eq!(parse_stats("99999999999 0 0"), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! Versions of the save files and the steps that upgrade old ones. A file is upgraded one
+//! version at a time when it's read, and the original is kept next to it as a backup, so a
+//! new release never throws away what a player has saved.
+//!
+//! The "key = value" files and the stats file carry a "version = N" line(the stats parser only
+//! reads the first line, so it can follow the numbers). The hand history is a CSV file and is
+//! told apart by its header instead. Files written before versioning count as version 1.
+
+use crate::config;
+use crate::history;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+
+pub const VERSION_KEY: &str = "version";
+
+const HISTORY_V1_HEADER: &str = "id,timestamp,outcome,player_score,dealer_score,hint_used,handicap_used";
+const HISTORY_V2_HEADER: &str = "id,timestamp,outcome,player_score,dealer_score,hint_used,handicap_used,player_start,dealer_up_card";
+
+/// Every header the hand history has had, oldest first. The position is the version minus 1.
+const HISTORY_HEADERS: &[&str] = &[HISTORY_V1_HEADER, HISTORY_V2_HEADER, history::CSV_HEADER];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SaveFile {
+    Stats,
+    Config,
+    Profile,
+    History,
+    Trainer,
+}
+
+/// Turns the contents of version `from` into version `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub migrate: fn(&str) -> String,
+}
+
+#[derive(Debug)]
+pub struct MigrationError {
+    details: String,
+}
+
+impl MigrationError {
+    fn new(msg: &str) -> Self {
+        MigrationError {
+            details: msg.to_string()
+        }
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl Error for MigrationError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// Version 2 only marks the version, the contents stay as they were.
+fn add_version_line(contents: &str) -> String {
+    let mut upgraded = contents.trim_end().to_string();
+    if !upgraded.is_empty() {
+        upgraded.push('\n');
+    }
+
+    upgraded + VERSION_KEY + " = 2\n"
+}
+
+const VERSION_LINE_MIGRATIONS: &[Migration] = &[Migration { from: 1, migrate: add_version_line }];
+
+/// Version 2 of the history has the starting hand, which older hands don't know. Hands are
+/// appended to the file without rewriting it, so an old file can already have new lines.
+fn add_starting_hand_columns(contents: &str) -> String {
+    let old_columns = HISTORY_V1_HEADER.split(',').count();
+
+    let mut upgraded = HISTORY_HEADERS[1].to_string() + "\n";
+    for line in contents.lines().skip(1).filter(|line| !line.trim().is_empty()) {
+        upgraded += line.trim_end();
+        if line.trim_end().split(',').count() == old_columns {
+            upgraded += ",,";
+        }
+        upgraded += "\n";
+    }
+
+    upgraded
+}
+
+/// Version 3 has the card a hit would have brought, see hindsight.rs.
+fn add_would_have_drawn_column(contents: &str) -> String {
+    let old_columns = HISTORY_V2_HEADER.split(',').count();
+
+    let mut upgraded = HISTORY_HEADERS[2].to_string() + "\n";
+    for line in contents.lines().skip(1).filter(|line| !line.trim().is_empty()) {
+        upgraded += line.trim_end();
+        if line.trim_end().split(',').count() == old_columns {
+            upgraded += ",";
+        }
+        upgraded += "\n";
+    }
+
+    upgraded
+}
+
+const HISTORY_MIGRATIONS: &[Migration] = &[
+    Migration { from: 1, migrate: add_starting_hand_columns },
+    Migration { from: 2, migrate: add_would_have_drawn_column },
+];
+
+impl SaveFile {
+    pub fn current_version(&self) -> u32 {
+        match self {
+            SaveFile::History => HISTORY_HEADERS.len() as u32,
+            _ => 2,
+        }
+    }
+
+    pub fn migrations(&self) -> &'static [Migration] {
+        match self {
+            SaveFile::History => HISTORY_MIGRATIONS,
+            _ => VERSION_LINE_MIGRATIONS,
+        }
+    }
+
+    /// The line to end a freshly written file with
+    pub fn version_line(&self) -> String {
+        format!("{} = {}\n", VERSION_KEY, self.current_version())
+    }
+
+    pub fn version_of(&self, contents: &str) -> u32 {
+        match self {
+            SaveFile::History => {
+                let header = contents.lines().next().unwrap_or_default().trim();
+                // no header at all is an empty log, which any version reads
+                HISTORY_HEADERS.iter().position(|known| *known == header).map(|i| i as u32 + 1).unwrap_or(self.current_version())
+            },
+            _ => {
+                contents.lines().
+                    filter_map(config::parse_line).
+                    find(|(key, _)| *key == VERSION_KEY).
+                    and_then(|(_, value)| value.parse().ok()).
+                    unwrap_or(1)
+            },
+        }
+    }
+
+    /// Upgrades the contents to the current version.
+    pub fn upgrade(&self, contents: &str) -> Result<String, MigrationError> {
+        let mut version = self.version_of(contents);
+        if version > self.current_version() {
+            return Err(MigrationError::new(&format!("version {} is newer than this game knows(up to {})", version, self.current_version())));
+        }
+
+        let mut upgraded = contents.to_string();
+        while version < self.current_version() {
+            let migration = self.migrations().iter().
+                find(|migration| migration.from == version).
+                ok_or_else(|| MigrationError::new(&format!("no way to upgrade from version {}", version)))?;
+
+            upgraded = (migration.migrate)(&upgraded);
+            version += 1;
+        }
+
+        Ok(upgraded)
+    }
+}
+
+/// Reads a save file and upgrades it. The original of an upgraded file is kept as
+/// "<file>.v<old version>.bak". A file from a newer release is read as it is, since the
+/// parsers skip what they don't know.
+pub fn read(file_name: &str, kind: SaveFile) -> io::Result<String> {
+    let contents = fs::read_to_string(file_name)?;
+    let version = kind.version_of(&contents);
+
+    match kind.upgrade(&contents) {
+        Ok(upgraded) => {
+            if version < kind.current_version() {
+                let backup = format!("{}.v{}.bak", file_name, version);
+                if let Err(e) = fs::write(&backup, &contents) {
+                    eprintln!("Could not back up {} before upgrading it: {}", file_name, e);
+                }
+            }
+
+            Ok(upgraded)
+        },
+        Err(e) => {
+            eprintln!("{}: {}", file_name, e);
+            Ok(contents)
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::history::History;
+    use crate::main_state;
+    use crate::profile::Profile;
+    use crate::trainer::TrainerStats;
+
+    const V1_STATS: &str = include_str!("../tests/fixtures/saves/v1/stats.txt");
+    const V1_CONFIG: &str = include_str!("../tests/fixtures/saves/v1/config.txt");
+    const V1_PROFILE: &str = include_str!("../tests/fixtures/saves/v1/profile.txt");
+    const V1_TRAINER: &str = include_str!("../tests/fixtures/saves/v1/trainer.txt");
+    const V1_HISTORY: &str = include_str!("../tests/fixtures/saves/v1/history.csv");
+
+    fn upgraded(kind: SaveFile, contents: &str) -> String {
+        let upgraded = kind.upgrade(contents).unwrap();
+        assert_eq!(kind.version_of(&upgraded), kind.current_version());
+
+        upgraded
+    }
+
+    #[test]
+    fn every_version_has_a_way_up() {
+        for kind in [SaveFile::Stats, SaveFile::Config, SaveFile::Profile, SaveFile::History, SaveFile::Trainer] {
+            for version in 1..kind.current_version() {
+                assert!(kind.migrations().iter().any(|migration| migration.from == version), "{:?} {}", kind, version);
+            }
+        }
+    }
+
+    #[test]
+    fn v1_stats_keep_the_numbers() {
+        let stats = upgraded(SaveFile::Stats, V1_STATS);
+
+        assert_eq!(SaveFile::Stats.version_of(V1_STATS), 1);
+        assert_eq!(main_state::parse_stats(stats.lines().next().unwrap()), Some((12, 3, 1)));
+    }
+
+    #[test]
+    fn v1_key_value_files_keep_their_values() {
+        let config = Config::parse(&upgraded(SaveFile::Config, V1_CONFIG));
+        assert_eq!(config, Config { language: String::from("bg"), ..Config::default() });
+
+        let profile = Profile::parse(&upgraded(SaveFile::Profile, V1_PROFILE));
+        assert_eq!((profile.name.as_str(), profile.felt.as_str()), ("Kris", "royal blue"));
+
+        let trainer = TrainerStats::parse(&upgraded(SaveFile::Trainer, V1_TRAINER));
+        assert_eq!(trainer, TrainerStats::parse(V1_TRAINER));
+        assert_eq!(trainer.best_quiz_score, 1310);
+    }
+
+    #[test]
+    fn v1_history_keeps_every_hand() {
+        let history = History::parse(&upgraded(SaveFile::History, V1_HISTORY));
+
+        assert_eq!(history.hands.len(), 2);
+        assert_eq!(history.hands[1].player_start, None);
+    }
+
+    #[test]
+    fn v1_history_with_new_hands_appended() {
+        let contents = V1_HISTORY.to_owned() + "3,1700000120,win,20,19,false,false,13,10\n";
+        let history = History::parse(&upgraded(SaveFile::History, &contents));
+
+        assert_eq!(history.hands.len(), 3);
+        assert_eq!(history.hands[2].dealer_up_card, Some(10));
+    }
+
+    #[test]
+    fn v2_history_gets_the_new_column() {
+        let contents = HISTORY_V2_HEADER.to_owned() + "\n1,1700000000,lose,18,20,false,false,18,10\n";
+        let upgraded = upgraded(SaveFile::History, &contents);
+
+        assert_eq!(upgraded, history::CSV_HEADER.to_owned() + "\n1,1700000000,lose,18,20,false,false,18,10,\n");
+        assert_eq!(History::parse(&upgraded).hands[0].would_have_drawn, None);
+    }
+
+    #[test]
+    fn current_files_are_left_alone() {
+        let contents = Profile::default().to_file_contents();
+
+        assert_eq!(SaveFile::Profile.upgrade(&contents).unwrap(), contents);
+    }
+
+    #[test]
+    fn newer_files_are_refused() {
+        assert!(SaveFile::Config.upgrade("version = 99\n").is_err());
+    }
+}
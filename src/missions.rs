@@ -0,0 +1,245 @@
+//! Missions: small goals that rotate, a few at a time. Each finished hand is reported as a
+//! `HandEvent`, and a finished mission grants its reward and makes room for the next one.
+
+use crate::game_engine::Outcome;
+
+/// Missions shown at once
+pub const ACTIVE_MISSIONS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    /// Win hands holding at least this many cards
+    WinWithCards(usize),
+    /// Win hands worth exactly 21
+    WinWith21,
+    /// Win hands without the hint or the handicap
+    WinWithoutPowerUps,
+    /// Win hands one after another, anything else starts over
+    WinInARow,
+    PlayHands,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reward {
+    /// Hints and dealer handicaps
+    PowerUps(u32, u32),
+    /// A felt from felt::REWARD_STYLES
+    Felt(&'static str),
+}
+
+impl Reward {
+    pub fn describe(&self) -> String {
+        match self {
+            Reward::PowerUps(hints, 0) => format!("{} hint(s)", hints),
+            Reward::PowerUps(0, handicaps) => format!("{} handicap(s)", handicaps),
+            Reward::PowerUps(hints, handicaps) => format!("{} hint(s) and {} handicap(s)", hints, handicaps),
+            Reward::Felt(name) => format!("the {} felt", name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Mission {
+    /// Stored in the profile
+    pub id: &'static str,
+    pub description: &'static str,
+    pub goal: Goal,
+    pub target: u32,
+    pub reward: Reward,
+}
+
+/// Every mission, in the order they rotate in.
+pub const MISSIONS: &[Mission] = &[
+    Mission { id: "five_card_wins", description: "Win 3 hands with 5+ cards", goal: Goal::WinWithCards(5), target: 3, reward: Reward::PowerUps(1, 1) },
+    Mission { id: "twenty_ones", description: "Win 2 hands with exactly 21", goal: Goal::WinWith21, target: 2, reward: Reward::PowerUps(0, 1) },
+    Mission { id: "three_in_a_row", description: "Win 3 hands in a row", goal: Goal::WinInARow, target: 3, reward: Reward::Felt("gold pinstripe") },
+    Mission { id: "clean_wins", description: "Win 5 hands without power-ups", goal: Goal::WinWithoutPowerUps, target: 5, reward: Reward::PowerUps(2, 0) },
+    Mission { id: "regular", description: "Play 20 hands", goal: Goal::PlayHands, target: 20, reward: Reward::PowerUps(1, 0) },
+    Mission { id: "five_in_a_row", description: "Win 5 hands in a row", goal: Goal::WinInARow, target: 5, reward: Reward::Felt("midnight dots") },
+    Mission { id: "four_card_wins", description: "Win 5 hands with 4+ cards", goal: Goal::WinWithCards(4), target: 5, reward: Reward::PowerUps(1, 1) },
+];
+
+fn find(id: &str) -> Option<usize> {
+    MISSIONS.iter().position(|mission| mission.id == id)
+}
+
+/// What the missions learn about a finished hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandEvent {
+    pub outcome: Outcome,
+    pub player_cards: usize,
+    pub player_score: u32,
+    pub power_ups_used: bool,
+}
+
+impl Goal {
+    /// New progress after a hand
+    fn progress(&self, progress: u32, hand: &HandEvent) -> u32 {
+        let won = hand.outcome == Outcome::Win;
+
+        match self {
+            Goal::WinWithCards(cards) if won && hand.player_cards >= *cards => progress + 1,
+            Goal::WinWith21 if won && hand.player_score == 21 => progress + 1,
+            Goal::WinWithoutPowerUps if won && !hand.power_ups_used => progress + 1,
+            Goal::WinInARow if won => progress + 1,
+            Goal::WinInARow => 0,
+            Goal::PlayHands => progress + 1,
+            _ => progress,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveMission {
+    /// Index in MISSIONS
+    pub mission: usize,
+    pub progress: u32,
+}
+
+impl ActiveMission {
+    pub fn mission(&self) -> &'static Mission {
+        &MISSIONS[self.mission]
+    }
+
+    pub fn describe(&self) -> String {
+        let mission = self.mission();
+
+        format!("{}: {}/{}", mission.description, self.progress, mission.target)
+    }
+}
+
+/// The missions being worked on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Missions {
+    pub active: Vec<ActiveMission>,
+}
+
+impl Default for Missions {
+    fn default() -> Self {
+        Missions {
+            active: (0..ACTIVE_MISSIONS).map(|mission| ActiveMission { mission, progress: 0 }).collect(),
+        }
+    }
+}
+
+impl Missions {
+    /// Reads "id:progress" pairs separated by commas, as written by `to_value`.
+    /// Unknown missions are dropped and the list is topped up to ACTIVE_MISSIONS.
+    pub fn parse_value(value: &str) -> Self {
+        let mut missions = Missions { active: Vec::new() };
+
+        for pair in value.split(',') {
+            let (id, progress) = match pair.split_once(':') {
+                Some(parts) => parts,
+                None => continue,
+            };
+            if let (Some(mission), Ok(progress)) = (find(id.trim()), progress.trim().parse()) {
+                if missions.active.len() < ACTIVE_MISSIONS && !missions.is_active(mission) {
+                    missions.active.push(ActiveMission { mission, progress });
+                }
+            }
+        }
+
+        while missions.active.len() < ACTIVE_MISSIONS {
+            let after = missions.active.last().map(|active| active.mission).unwrap_or(MISSIONS.len() - 1);
+            let next = missions.next_inactive(after);
+            missions.active.push(ActiveMission { mission: next, progress: 0 });
+        }
+
+        missions
+    }
+
+    pub fn to_value(&self) -> String {
+        self.active.iter().
+            map(|active| format!("{}:{}", active.mission().id, active.progress)).
+            collect::<Vec<String>>().
+            join(",")
+    }
+
+    fn is_active(&self, mission: usize) -> bool {
+        self.active.iter().any(|active| active.mission == mission)
+    }
+
+    /// The first mission after `after`(wrapping around) that isn't being worked on.
+    fn next_inactive(&self, after: usize) -> usize {
+        (1..=MISSIONS.len()).
+            map(|step| (after + step) % MISSIONS.len()).
+            find(|mission| !self.is_active(*mission)).
+            unwrap_or(after)
+    }
+
+    /// Counts the hand towards every mission. Finished missions are returned and replaced by
+    /// the ones after the newest mission, which go to the end of the list.
+    pub fn on_hand(&mut self, hand: &HandEvent) -> Vec<&'static Mission> {
+        for active in &mut self.active {
+            active.progress = active.mission().goal.progress(active.progress, hand);
+        }
+
+        let (finished, kept): (Vec<ActiveMission>, Vec<ActiveMission>) = self.active.drain(..).
+            partition(|active| active.progress >= active.mission().target);
+        self.active = kept;
+
+        for done in &finished {
+            let after = self.active.last().map(|active| active.mission).unwrap_or(done.mission);
+            let next = self.next_inactive(after);
+            self.active.push(ActiveMission { mission: next, progress: 0 });
+        }
+
+        finished.iter().map(|done| done.mission()).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(outcome: Outcome, player_cards: usize, player_score: u32) -> HandEvent {
+        HandEvent { outcome, player_cards, player_score, power_ups_used: false }
+    }
+
+    #[test]
+    fn value_round_trip() {
+        let mut missions = Missions::default();
+        missions.on_hand(&hand(Outcome::Win, 5, 19));
+
+        assert_eq!(Missions::parse_value(&missions.to_value()), missions);
+    }
+
+    #[test]
+    fn damaged_value_is_topped_up() {
+        let missions = Missions::parse_value("clean_wins:2,nonsense,unknown:4,clean_wins:1");
+
+        assert_eq!(missions.active.len(), ACTIVE_MISSIONS);
+        assert_eq!(missions.active[0], ActiveMission { mission: find("clean_wins").unwrap(), progress: 2 });
+        assert_eq!(Missions::parse_value(""), Missions::default());
+    }
+
+    #[test]
+    fn streak_starts_over_after_a_loss() {
+        let mut missions = Missions::default();
+        let streak = missions.active.iter().position(|active| active.mission().goal == Goal::WinInARow).unwrap();
+
+        missions.on_hand(&hand(Outcome::Win, 2, 18));
+        missions.on_hand(&hand(Outcome::Win, 2, 18));
+        assert_eq!(missions.active[streak].progress, 2);
+
+        missions.on_hand(&hand(Outcome::Draw, 2, 18));
+        assert_eq!(missions.active[streak].progress, 0);
+    }
+
+    #[test]
+    fn finished_mission_rotates_out() {
+        let mut missions = Missions::default();
+
+        let mut finished = Vec::new();
+        for _ in 0..3 {
+            finished.extend(missions.on_hand(&hand(Outcome::Win, 5, 21)));
+        }
+
+        let ids: Vec<&str> = finished.iter().map(|mission| mission.id).collect();
+        assert_eq!(ids, ["twenty_ones", "five_card_wins", "three_in_a_row"]);
+        assert!(missions.active.iter().all(|active| active.progress < active.mission().target));
+        assert!(!missions.is_active(0));
+    }
+}
@@ -0,0 +1,93 @@
+//! The rules placard on the felt. Its lines come from the constants the engine plays by, so
+//! it always says what the game does.
+
+use crate::burn::BurnCard;
+use crate::game_engine::{DEALER_HANDICAP_POINTS, DEALER_STANDS_ON};
+use crate::probability::Rules;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+const PADDING: f32 = 10.0;
+const LINE_SPACING: f32 = 4.0;
+
+/// The rules of the hand being played, one per line. Family mode talks about points instead
+/// of paying. `dealt_to_cut` is for a deck dealt over several hands, down to the cut card the
+/// player placed.
+pub fn lines(rules: Rules, family_mode: bool, burn: BurnCard, dealt_to_cut: bool) -> Vec<String> {
+    let outcomes = if family_mode { "A win scores a point, ties score nothing" } else { "Wins pay 1:1, ties are a draw" };
+    // the board, and with it the deck, is made again for every hand unless the cut card says
+    // otherwise
+    let shuffle = if dealt_to_cut { "One deck, dealt down to the cut card" } else { "One deck, shuffled every hand" };
+    let mut lines = vec![
+        String::from(outcomes),
+        format!("Dealer stands on all {}s", DEALER_STANDS_ON),
+        String::from(shuffle),
+    ];
+
+    if burn.burns() {
+        lines.push(format!("First card burned, {}", burn.name()));
+    }
+
+    if rules.dealer_handicap {
+        lines.push(format!("Handicap: dealer counts {} less", DEALER_HANDICAP_POINTS));
+    }
+
+    lines
+}
+
+/// Draws the placard with `lines` on it, its top left corner at `position`.
+pub fn draw(ctx: &mut Context, font: graphics::Font, size: f32, position: Point2<f32>, lines: &[String]) -> GameResult<()> {
+    let texts: Vec<graphics::Text> = lines.iter().
+        map(|line| {
+            let mut text = graphics::Text::new(line.as_str());
+            text.set_font(font, graphics::PxScale::from(size));
+            text
+        }).
+        collect();
+
+    let width = texts.iter().map(|text| text.dimensions(ctx).w).fold(0.0, f32::max);
+    let height = texts.iter().map(|text| text.dimensions(ctx).h + LINE_SPACING).sum::<f32>();
+    let rect = graphics::Rect::new(position.x, position.y, width + 2.0 * PADDING, height + 2.0 * PADDING);
+
+    let plate = graphics::Mesh::new_rounded_rectangle(ctx, graphics::DrawMode::fill(), rect, 8.0, graphics::Color::new(0.0, 0.0, 0.0, 0.35))?;
+    let border = graphics::Mesh::new_rounded_rectangle(ctx, graphics::DrawMode::stroke(2.0), rect, 8.0, graphics::Color::from_rgb(212, 175, 55))?;
+    graphics::draw(ctx, &plate, graphics::DrawParam::default())?;
+    graphics::draw(ctx, &border, graphics::DrawParam::default())?;
+
+    let mut line_position = Point2 { x: position.x + PADDING, y: position.y + PADDING };
+    for text in &texts {
+        graphics::draw(ctx, text, graphics::DrawParam::default().dest(line_position))?;
+        line_position.y += text.dimensions(ctx).h + LINE_SPACING;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placard_follows_the_rules() {
+        let placard = lines(Rules::default(), false, BurnCard::Off, false);
+        assert_eq!(placard[2], "One deck, shuffled every hand");
+        assert_eq!(placard[1], "Dealer stands on all 17s");
+        assert!(!placard.iter().any(|line| line.contains("Handicap")));
+
+        let with_handicap = lines(Rules { dealer_handicap: true }, false, BurnCard::Shown, true);
+        assert_eq!(with_handicap[2], "One deck, dealt down to the cut card");
+        assert_eq!(with_handicap[3], "First card burned, shown");
+        assert_eq!(with_handicap.last().unwrap(), "Handicap: dealer counts 1 less");
+    }
+
+    #[test]
+    fn family_placard_pays_nothing() {
+        assert!(!lines(Rules::default(), true, BurnCard::Off, false).join(" ").contains("pay"));
+    }
+}
@@ -0,0 +1,51 @@
+//! Time played: every second the game runs counts, on any screen, and is added up in the profile
+//! across sessions. It's shown on the profile and analysis screens and earns achievements of its
+//! own(see achievements.rs).
+
+/// Seconds in a minute, achievements are checked once a minute
+pub const MINUTE: u64 = 60;
+pub const HOUR: u64 = 60 * MINUTE;
+
+/// Gathers the frames into whole seconds for the profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlaytimeClock {
+    /// Less than a second
+    unsaved: f32,
+}
+
+impl PlaytimeClock {
+    /// The whole seconds passed with this frame.
+    pub fn update(&mut self, time_delta: f32) -> u64 {
+        self.unsaved += time_delta;
+        let seconds = self.unsaved.floor();
+        self.unsaved -= seconds;
+
+        seconds as u64
+    }
+}
+
+/// "12h 05m"
+pub fn describe(seconds: u64) -> String {
+    format!("{}h {:02}m", seconds / HOUR, seconds % HOUR / MINUTE)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_add_up_to_seconds() {
+        let mut clock = PlaytimeClock::default();
+        let seconds: u64 = (0..150).map(|_| clock.update(1.0 / 60.0)).sum();
+
+        assert_eq!(seconds, 2);
+        assert_eq!(clock.update(0.6), 1);
+    }
+
+    #[test]
+    fn describes_hours_and_minutes() {
+        assert_eq!(describe(0), "0h 00m");
+        assert_eq!(describe(10 * HOUR + 5 * MINUTE + 59), "10h 05m");
+    }
+}
@@ -0,0 +1,199 @@
+//! Minimal PNG reading and writing for RGBA screenshots. Images are written with uncompressed
+//! deflate blocks, and only such images can be read back, which is all the golden-image tests
+//! need. Card images are compressed, GPU-light mode decodes them with the image crate ggez uses.
+
+use std::fmt;
+use std::error::Error;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const MAX_STORED_BLOCK: usize = 65535;
+
+#[derive(Debug, Clone)]
+pub struct PngError {
+    details: String,
+}
+
+impl PngError {
+    fn new(msg: &str) -> Self {
+        PngError {
+            details: msg.to_string()
+        }
+    }
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.details)
+    }
+}
+
+impl Error for PngError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encodes 8-bit RGBA pixels(row by row, top to bottom) as a PNG file.
+pub fn encode_rgba(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let row_len = width as usize * 4;
+
+    // every row starts with filter type 0(none)
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in pixels.chunks(row_len).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(MAX_STORED_BLOCK).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push(if i + 1 == blocks.len() { 1 } else { 0 });
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bits, RGBA, no interlacing
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib);
+    write_chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+/// Decodes a PNG written by `encode_rgba` into (width, height, RGBA pixels).
+pub fn decode_rgba(png: &[u8]) -> Result<(u32, u32, Vec<u8>), PngError> {
+    if png.len() < 8 || &png[..8] != SIGNATURE {
+        return Err(PngError::new("not a PNG file"));
+    }
+
+    let mut size = None;
+    let mut zlib = Vec::new();
+    let mut rest = &png[8..];
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() < 12 + length {
+            return Err(PngError::new("truncated chunk"));
+        }
+        let (kind, data) = (&rest[4..8], &rest[8..8 + length]);
+
+        match kind {
+            b"IHDR" => {
+                if data.len() != 13 || data[8..13] != [8, 6, 0, 0, 0] {
+                    return Err(PngError::new("only 8-bit RGBA images are supported"));
+                }
+                size = Some((u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+                             u32::from_be_bytes([data[4], data[5], data[6], data[7]])));
+            },
+            b"IDAT" => zlib.extend_from_slice(data),
+            _ => (),
+        }
+        rest = &rest[12 + length..];
+    }
+    let (width, height) = size.ok_or_else(|| PngError::new("missing IHDR chunk"))?;
+
+    // zlib header, then stored blocks only
+    let mut raw = Vec::new();
+    let mut position = 2;
+    loop {
+        if position + 5 > zlib.len() {
+            return Err(PngError::new("truncated image data"));
+        }
+        let block_header = zlib[position];
+        if block_header & 0b110 != 0 {
+            return Err(PngError::new("compressed image data is not supported"));
+        }
+        let length = u16::from_le_bytes([zlib[position + 1], zlib[position + 2]]) as usize;
+        position += 5;
+
+        if position + length > zlib.len() {
+            return Err(PngError::new("truncated image data"));
+        }
+        raw.extend_from_slice(&zlib[position..position + length]);
+        position += length;
+
+        if block_header & 1 == 1 {
+            break;
+        }
+    }
+
+    let row_len = width as usize * 4;
+    if raw.len() != (row_len + 1) * height as usize {
+        return Err(PngError::new("image data doesn't match the size"));
+    }
+
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    for row in raw.chunks(row_len + 1) {
+        if row[0] != 0 {
+            return Err(PngError::new("filtered rows are not supported"));
+        }
+        pixels.extend_from_slice(&row[1..]);
+    }
+
+    Ok((width, height, pixels))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_matches_known_value() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn round_trip() {
+        let (width, height) = (130, 170); // bigger than one stored block
+        let pixels: Vec<u8> = (0..width * height * 4).map(|i| (i % 251) as u8).collect();
+
+        let png = encode_rgba(width, height, &pixels);
+
+        assert_eq!(decode_rgba(&png).unwrap(), (width, height, pixels));
+    }
+
+    #[test]
+    fn decode_rejects_other_files() {
+        assert!(decode_rgba(b"GIF89a").is_err());
+    }
+}
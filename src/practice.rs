@@ -0,0 +1,232 @@
+//! Practice mode: hands that don't count, power-ups without limit, what's left in the shoe
+//! on show and a picker that puts any card still in the shoe on top of it. The shoe can be
+//! built for a drill, like only nines, tens and aces or no fives, one rank at a time.
+
+use crate::card::Card;
+
+/// Rank part of the card names and how the shoe panel shows it
+pub const RANKS: &[(&str, &str)] = &[
+    ("ace", "A"),
+    ("2", "2"),
+    ("3", "3"),
+    ("4", "4"),
+    ("5", "5"),
+    ("6", "6"),
+    ("7", "7"),
+    ("8", "8"),
+    ("9", "9"),
+    ("10", "10"),
+    ("jack", "J"),
+    ("queen", "Q"),
+    ("king", "K"),
+];
+
+/// Suit part of the card names and its symbol
+pub const SUITS: &[(&str, char)] = &[
+    ("clubs", '♣'),
+    ("diamonds", '♦'),
+    ("hearts", '♥'),
+    ("spades", '♠'),
+];
+
+/// Copies of a rank in a standard deck
+pub const STANDARD_COPIES: usize = 4;
+/// Copies of a rank a practice shoe can hold, two decks' worth
+pub const MAX_COPIES: usize = 8;
+
+/// How many cards of each rank are left, in RANKS order.
+pub fn composition(cards: &[Card]) -> Vec<(&'static str, usize)> {
+    RANKS.iter().
+        map(|(rank, short_name)| {
+            let left = cards.iter().
+                filter(|card| card.name.split("_of_").next() == Some(*rank)).
+                count();
+            (*short_name, left)
+        }).
+        collect()
+}
+
+/// `counts[i]` cards of RANKS[i], the suits taking turns.
+pub fn cards_of(counts: &[usize]) -> Vec<Card> {
+    RANKS.iter().
+        zip(counts).
+        flat_map(|((rank, _), count)| (0..*count).map(move |i| Card::new(&format!("{}_of_{}", rank, SUITS[i % SUITS.len()].0)))).
+        collect()
+}
+
+/// What the practice shoe is made of: copies of each rank, in RANKS order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PracticeShoe {
+    pub counts: Vec<usize>,
+}
+
+impl Default for PracticeShoe {
+    fn default() -> Self {
+        PracticeShoe { counts: vec![STANDARD_COPIES; RANKS.len()] }
+    }
+}
+
+impl PracticeShoe {
+    pub fn is_standard(&self) -> bool {
+        *self == PracticeShoe::default()
+    }
+
+    /// One more or one less of RANKS[rank]. False when that would go past MAX_COPIES or leave
+    /// the shoe without cards.
+    pub fn change(&mut self, rank: usize, step: i32) -> bool {
+        let count = self.counts[rank] as i32 + step;
+        if count < 0 || count > MAX_COPIES as i32 || (count == 0 && self.total() == self.counts[rank]) {
+            return false;
+        }
+
+        self.counts[rank] = count as usize;
+        true
+    }
+
+    /// None of RANKS[rank], unless it's all that's left.
+    pub fn remove_rank(&mut self, rank: usize) -> bool {
+        if self.counts[rank] == 0 || self.total() == self.counts[rank] {
+            return false;
+        }
+
+        self.counts[rank] = 0;
+        true
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// "standard", "only 9, 10, A" or "no 5, 4 more K"
+    pub fn describe(&self) -> String {
+        if self.is_standard() {
+            return String::from("standard");
+        }
+
+        let names = |ranks: Vec<usize>| ranks.iter().map(|rank| RANKS[*rank].1).collect::<Vec<&str>>().join(", ");
+        let kept: Vec<usize> = (0..RANKS.len()).filter(|rank| self.counts[*rank] > 0).collect();
+        if kept.len() <= RANKS.len() / 2 {
+            // the ace reads best last, as in "9, 10, A"
+            let (aces, others): (Vec<usize>, Vec<usize>) = kept.into_iter().partition(|rank| *rank == 0);
+            return format!("only {}", names(others.into_iter().chain(aces).collect()));
+        }
+
+        let mut changes = Vec::new();
+        let removed: Vec<usize> = (0..RANKS.len()).filter(|rank| self.counts[*rank] == 0).collect();
+        if !removed.is_empty() {
+            changes.push(format!("no {}", names(removed)));
+        }
+        for (rank, count) in self.counts.iter().enumerate().filter(|(_, count)| **count != 0 && **count != STANDARD_COPIES) {
+            if *count > STANDARD_COPIES {
+                changes.push(format!("{} more {}", count - STANDARD_COPIES, RANKS[rank].1));
+            } else {
+                changes.push(format!("{} fewer {}", STANDARD_COPIES - count, RANKS[rank].1));
+            }
+        }
+
+        changes.join(", ")
+    }
+}
+
+/// The card picked to go on top of the shoe, changed with the arrow keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CardPicker {
+    /// Index in RANKS
+    pub rank: usize,
+    /// Index in SUITS
+    pub suit: usize,
+}
+
+impl CardPicker {
+    pub fn change_rank(&mut self, step: i32) {
+        self.rank = (self.rank as i32 + step).rem_euclid(RANKS.len() as i32) as usize;
+    }
+
+    pub fn change_suit(&mut self, step: i32) {
+        self.suit = (self.suit as i32 + step).rem_euclid(SUITS.len() as i32) as usize;
+    }
+
+    /// Name of the picked card, as in card::all()
+    pub fn card_name(&self) -> String {
+        format!("{}_of_{}", RANKS[self.rank].0, SUITS[self.suit].0)
+    }
+
+    /// "A♠"
+    pub fn describe(&self) -> String {
+        format!("{}{}", RANKS[self.rank].1, SUITS[self.suit].1)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card;
+
+    #[test]
+    fn full_deck_has_four_of_each() {
+        let composition = composition(&card::all());
+
+        assert_eq!(composition.len(), RANKS.len());
+        assert!(composition.iter().all(|(_, left)| *left == 4));
+    }
+
+    #[test]
+    fn dealt_cards_are_not_counted() {
+        let mut cards = card::all();
+        cards.retain(|card| card.name != "10_of_hearts" && card.name != "queen_of_spades");
+
+        let composition = composition(&cards);
+        assert_eq!(composition[9], ("10", 3));
+        assert_eq!(composition[11], ("Q", 3));
+        assert_eq!(composition[0], ("A", 4));
+    }
+
+    #[test]
+    fn practice_shoe_holds_what_was_asked() {
+        let mut shoe = PracticeShoe::default();
+        assert!(shoe.remove_rank(4));
+        assert_eq!(shoe.describe(), "no 5");
+
+        for rank in [1, 2, 3, 5, 6, 7, 10, 11, 12] {
+            shoe.remove_rank(rank);
+        }
+        assert_eq!(shoe.describe(), "only 9, 10, A");
+
+        let cards = cards_of(&shoe.counts);
+        assert_eq!(cards.len(), 12);
+        assert_eq!(composition(&cards)[8], ("9", 4));
+
+        assert!(shoe.change(9, 1));
+        assert_eq!(composition(&cards_of(&shoe.counts))[9], ("10", 5));
+    }
+
+    #[test]
+    fn practice_shoe_is_never_empty() {
+        let mut shoe = PracticeShoe { counts: vec![0; RANKS.len()] };
+        shoe.counts[0] = 1;
+
+        assert!(!shoe.change(0, -1));
+        assert!(!shoe.remove_rank(0));
+        assert!(!PracticeShoe::default().change(0, MAX_COPIES as i32));
+        assert_eq!(shoe.total(), 1);
+    }
+
+    #[test]
+    fn picker_names_real_cards() {
+        let names: Vec<String> = card::all().into_iter().map(|card| card.name).collect();
+        let mut picker = CardPicker::default();
+
+        for _ in 0..RANKS.len() {
+            for _ in 0..SUITS.len() {
+                assert!(names.contains(&picker.card_name()), "{}", picker.card_name());
+                picker.change_suit(1);
+            }
+            picker.change_rank(1);
+        }
+
+        picker.change_rank(-1);
+        picker.change_suit(-1);
+        assert_eq!(picker.describe(), "K♠");
+    }
+}
@@ -0,0 +1,293 @@
+//! Odds of the game's hands, worked out for an infinite deck(every draw is a fresh card, so
+//! the odds don't depend on what's already on the table). That's a good approximation of a
+//! reshuffled 52-card deck and keeps the numbers exact and cheap to compute.
+//!
+//! The game's own rules are used: the player draws first without seeing any dealer card and
+//! stops at 21 at the latest, the dealer then draws to 17 or more(an ace counts as 11 when it
+//! fits), wins pay 1:1 and ties are a draw. The handicap takes its points off the dealer's
+//! hand before anything else: the dealer draws to 17 as the hand counts, and at 22 stands on 21.
+
+use crate::game_engine;
+
+/// Points a card can be worth, an ace counted as 1.
+const CARD_POINTS: std::ops::RangeInclusive<u32> = 1..=10;
+
+/// The dealer keeps drawing below this
+pub const DEALER_STANDS_ON: u32 = game_engine::DEALER_STANDS_ON;
+
+/// Rules that change the odds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rules {
+    /// The dealer's hand counts one point less(the handicap power up)
+    pub dealer_handicap: bool,
+}
+
+impl Rules {
+    /// Points the dealer's hand counts less
+    pub fn handicap_points(&self) -> u32 {
+        if self.dealer_handicap { game_engine::DEALER_HANDICAP_POINTS } else { 0 }
+    }
+}
+
+fn card_probability(points: u32) -> f64 {
+    // 10, jack, queen and king are all worth 10
+    if points == 10 { 4.0 / 13.0 } else { 1.0 / 13.0 }
+}
+
+/// Best value of a hand given its points with aces as 1 and whether it holds an ace.
+pub fn best_value(hard: u32, has_ace: bool) -> u32 {
+    if has_ace && hard + 10 <= 21 { hard + 10 } else { hard }
+}
+
+/// How the dealer's hand ends: `final_values[v]` is the chance to stop at `v`, as the hand
+/// counts after the handicap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DealerOdds {
+    pub final_values: [f64; 22],
+    pub bust: f64,
+}
+
+fn dealer_from(hard: u32, has_ace: bool, odds: &mut DealerOdds, probability: f64, rules: Rules) {
+    // a hand over 21 is always past 17 too
+    let value = best_value(hard, has_ace);
+    if !game_engine::dealer_must_draw(value, rules) {
+        let effective = game_engine::effective_dealer_score(value, rules);
+        if effective > 21 {
+            odds.bust += probability;
+        } else {
+            odds.final_values[effective as usize] += probability;
+        }
+        return;
+    }
+
+    for points in CARD_POINTS {
+        dealer_from(hard + points, has_ace || points == 1, odds, probability * card_probability(points), rules);
+    }
+}
+
+/// Odds of the dealer's final hand, drawing from an empty hand.
+pub fn dealer_odds(rules: Rules) -> DealerOdds {
+    let mut odds = DealerOdds { final_values: [0.0; 22], bust: 0.0 };
+    dealer_from(0, false, &mut odds, 1.0, rules);
+
+    odds
+}
+
+/// Chances of winning, drawing and losing a hand(they add up to 1).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HandOdds {
+    pub win: f64,
+    pub draw: f64,
+    pub lose: f64,
+}
+
+impl HandOdds {
+    /// Expected result of a hand, in bets
+    pub fn expected_value(&self) -> f64 {
+        self.win - self.lose
+    }
+
+    /// Variance of a hand's result, in bets squared
+    pub fn variance(&self) -> f64 {
+        self.win + self.lose - self.expected_value().powi(2)
+    }
+}
+
+fn stand_odds(value: u32, dealer: &DealerOdds) -> HandOdds {
+    let mut odds = HandOdds { win: dealer.bust, ..HandOdds::default() };
+
+    for (dealer_value, probability) in dealer.final_values.iter().enumerate() {
+        let dealer_value = dealer_value as u32;
+
+        if value > dealer_value {
+            odds.win += probability;
+        } else if value == dealer_value {
+            odds.draw += probability;
+        } else {
+            odds.lose += probability;
+        }
+    }
+
+    odds
+}
+
+/// The better choice between drawing and standing for every hand, and the odds that gives.
+#[derive(Debug, Clone)]
+pub struct BestPlay {
+    /// Odds of a whole hand played this way
+    pub odds: HandOdds,
+    /// Indexed by the hand's points with aces as 1 and whether it holds an ace
+    draws: [[bool; 2]; 22],
+    /// Expected results of drawing and of standing, indexed like `draws`
+    values: [[(f64, f64); 2]; 22],
+}
+
+impl BestPlay {
+    pub fn should_draw(&self, hard: u32, has_ace: bool) -> bool {
+        hard <= 21 && self.draws[hard as usize][has_ace as usize]
+    }
+
+    /// Expected results(in bets) of drawing and of standing on a hand, playing the best way
+    /// after the draw. A bust hand has lost either way.
+    pub fn expected_values(&self, hard: u32, has_ace: bool) -> (f64, f64) {
+        if hard > 21 {
+            return (-1.0, -1.0);
+        }
+
+        self.values[hard as usize][has_ace as usize]
+    }
+}
+
+pub fn best_play(rules: Rules) -> BestPlay {
+    let dealer = dealer_odds(rules);
+    let bust = HandOdds { lose: 1.0, ..HandOdds::default() };
+
+    // best odds from every hand, from the biggest down, since drawing only ever leads to bigger hands
+    let mut best = [[HandOdds::default(); 2]; 22];
+    let mut draws = [[false; 2]; 22];
+    let mut values = [[(0.0, 0.0); 2]; 22];
+    for hard in (0..=21).rev() {
+        for has_ace in [false, true] {
+            let value = best_value(hard, has_ace);
+            let stand = stand_odds(value, &dealer);
+
+            // the turn ends by itself at 21
+            if value == 21 {
+                best[hard as usize][has_ace as usize] = stand;
+                values[hard as usize][has_ace as usize] = (-1.0, stand.expected_value());
+                continue;
+            }
+
+            let mut hit = HandOdds::default();
+            for points in CARD_POINTS {
+                let next = if hard + points > 21 { bust } else { best[(hard + points) as usize][(has_ace || points == 1) as usize] };
+                let probability = card_probability(points);
+
+                hit.win += probability * next.win;
+                hit.draw += probability * next.draw;
+                hit.lose += probability * next.lose;
+            }
+
+            let draw = hit.expected_value() > stand.expected_value();
+            best[hard as usize][has_ace as usize] = if draw { hit } else { stand };
+            draws[hard as usize][has_ace as usize] = draw;
+            values[hard as usize][has_ace as usize] = (hit.expected_value(), stand.expected_value());
+        }
+    }
+
+    BestPlay { odds: best[0][0], draws, values }
+}
+
+/// Odds of a hand when the player always makes the better choice between drawing and standing.
+pub fn best_play_odds(rules: Rules) -> HandOdds {
+    best_play(rules).odds
+}
+
+/// Chance of eventually losing the whole bankroll when betting `bet` on every hand, using the
+/// usual approximation exp(-2 * EV * bankroll / variance) with the bankroll counted in bets.
+/// A game without an edge for the player ends in ruin sooner or later.
+pub fn risk_of_ruin(odds: &HandOdds, bet: u32, bankroll: u32) -> f64 {
+    if bet == 0 {
+        return 0.0;
+    }
+    if bankroll < bet || odds.expected_value() <= 0.0 {
+        return 1.0;
+    }
+
+    let bankroll_in_bets = bankroll as f64 / bet as f64;
+
+    (-2.0 * odds.expected_value() * bankroll_in_bets / odds.variance()).exp().min(1.0)
+}
+
+/// How many hands the bankroll lasts on average when the game has an edge against the player.
+pub fn expected_hands_to_ruin(odds: &HandOdds, bet: u32, bankroll: u32) -> Option<f64> {
+    if bet == 0 || odds.expected_value() >= 0.0 {
+        return None;
+    }
+
+    Some(bankroll as f64 / (-odds.expected_value() * bet as f64))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn dealer_odds_add_up() {
+        let odds = dealer_odds(Rules::default());
+        let total: f64 = odds.final_values.iter().sum::<f64>() + odds.bust;
+
+        assert!((total - 1.0).abs() < EPSILON);
+        assert!(odds.final_values[..17].iter().all(|p| *p == 0.0));
+        // the well known ~28% for a dealer that stands on soft 17
+        assert!(odds.bust > 0.27 && odds.bust < 0.30, "bust is {}", odds.bust);
+    }
+
+    #[test]
+    fn best_play_beats_standing_on_nothing() {
+        let odds = best_play_odds(Rules::default());
+        let stand_right_away = 2.0 * dealer_odds(Rules::default()).bust - 1.0;
+
+        assert!((odds.win + odds.draw + odds.lose - 1.0).abs() < EPSILON);
+        assert!(odds.expected_value() > stand_right_away);
+        // drawing first is the dealer's advantage
+        assert!(odds.expected_value() < 0.0);
+    }
+
+    #[test]
+    fn best_play_draws_on_small_hands_only() {
+        let best = best_play(Rules::default());
+
+        assert!(best.should_draw(0, false));
+        assert!(best.should_draw(8, false));
+        assert!(!best.should_draw(20, false));
+        assert!(!best.should_draw(25, false));
+    }
+
+    #[test]
+    fn expected_values_agree_with_the_best_play() {
+        let best = best_play(Rules::default());
+
+        for hard in 0..=21 {
+            for has_ace in [false, true] {
+                let (hit, stand) = best.expected_values(hard, has_ace);
+                assert_eq!(hit > stand, best.should_draw(hard, has_ace), "{} {}", hard, has_ace);
+            }
+        }
+        assert!((best.expected_values(0, false).0 - best.odds.expected_value()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn handicap_dealer_draws_on_to_an_effective_17() {
+        let plain = dealer_odds(Rules::default());
+        let odds = dealer_odds(Rules { dealer_handicap: true });
+        let total: f64 = odds.final_values.iter().sum::<f64>() + odds.bust;
+
+        assert!((total - 1.0).abs() < EPSILON);
+        // drawing on a raw 17 costs more busts than standing on 22 saves
+        assert!(odds.bust > plain.bust);
+        assert!(odds.final_values[..17].iter().all(|p| *p == 0.0));
+    }
+
+    #[test]
+    fn handicap_helps_the_player() {
+        let with_handicap = best_play_odds(Rules { dealer_handicap: true });
+
+        assert!(with_handicap.expected_value() > best_play_odds(Rules::default()).expected_value());
+    }
+
+    #[test]
+    fn risk_of_ruin_bounds() {
+        let losing = HandOdds { win: 0.4, draw: 0.1, lose: 0.5 };
+        let winning = HandOdds { win: 0.5, draw: 0.1, lose: 0.4 };
+
+        assert_eq!(risk_of_ruin(&losing, 10, 1000), 1.0);
+        assert_eq!(risk_of_ruin(&winning, 10, 5), 1.0);
+        assert!(risk_of_ruin(&winning, 10, 1000) < risk_of_ruin(&winning, 10, 100));
+        assert!((expected_hands_to_ruin(&losing, 10, 1000).unwrap() - 1000.0).abs() < 1e-6);
+        assert_eq!(expected_hands_to_ruin(&winning, 10, 1000), None);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::config;
+use crate::migration::{self, SaveFile};
+use crate::missions::Missions;
+
+use std::fs;
+use std::io;
+
+pub const PROFILE_FILE_NAME: &str = "profile.txt";
+pub const MAX_NAME_LENGTH: usize = 16;
+const DEFAULT_NAME: &str = "Player";
+
+/// Who is playing: the name shown on the table(and in stats), their felt and their missions.
+/// Stored as "key = value" lines like the config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+
+    /// One of the styles in felt::STYLES, or an earned one from felt::REWARD_STYLES
+    pub felt: String,
+
+    pub missions: Missions,
+
+    /// Names of the felts earned from missions
+    pub earned_felts: Vec<String>,
+
+    /// Ids of the earned achievements, see achievements::ACHIEVEMENTS
+    pub achievements: Vec<String>,
+
+    /// One of the styles in avatar::STYLES
+    pub dealer_avatar: String,
+
+    /// Hints given to the dealer as tips
+    pub tips: u32,
+
+    /// Seconds the game ran, see playtime.rs
+    pub playtime: u64,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            name: String::from(DEFAULT_NAME),
+            felt: String::from("casino green"),
+            missions: Missions::default(),
+            earned_felts: Vec::new(),
+            achievements: Vec::new(),
+            dealer_avatar: String::from("none"),
+            tips: 0,
+            playtime: 0,
+        }
+    }
+}
+
+/// Comma separated names, empty ones skipped
+fn parse_list(value: &str) -> Vec<String> {
+    value.split(',').
+        map(str::trim).
+        filter(|name| !name.is_empty()).
+        map(String::from).
+        collect()
+}
+
+impl Profile {
+    pub fn parse(contents: &str) -> Self {
+        let mut profile = Profile::default();
+
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            match key {
+                "name" => profile.set_name(value),
+                "felt" if !value.is_empty() => profile.felt = value.to_string(),
+                "missions" => profile.missions = Missions::parse_value(value),
+                "earned_felts" => profile.earned_felts = parse_list(value),
+                "achievements" => profile.achievements = parse_list(value),
+                "dealer_avatar" if !value.is_empty() => profile.dealer_avatar = value.to_string(),
+                "tips" => profile.tips = value.parse().unwrap_or(0),
+                "playtime" => profile.playtime = value.parse().unwrap_or(0),
+                _ => (),
+            }
+        }
+
+        profile
+    }
+
+    pub fn load(file_name: &str) -> Self {
+        match migration::read(file_name, SaveFile::Profile) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Profile::default(),
+        }
+    }
+
+    pub fn to_file_contents(&self) -> String {
+        format!("name = {}\nfelt = {}\nmissions = {}\nearned_felts = {}\nachievements = {}\ndealer_avatar = {}\ntips = {}\nplaytime = {}\n",
+                self.name,
+                self.felt,
+                self.missions.to_value(),
+                self.earned_felts.join(","),
+                self.achievements.join(","),
+                self.dealer_avatar,
+                self.tips,
+                self.playtime) + &SaveFile::Profile.version_line()
+    }
+
+    pub fn save(&self, file_name: &str) -> io::Result<()> {
+        fs::write(file_name, self.to_file_contents())
+    }
+
+    /// Keeps the name printable, on one line and short enough for the name plate.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.chars().
+            filter(|c| !c.is_control()).
+            take(MAX_NAME_LENGTH).
+            collect();
+    }
+
+    /// The name to show, never empty
+    pub fn display_name(&self) -> &str {
+        match self.name.trim() {
+            "" => DEFAULT_NAME,
+            name => name,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_contents_round_trip() {
+        let profile = Profile {
+            name: String::from("Kris"),
+            felt: String::from("royal blue"),
+            earned_felts: vec![String::from("gold pinstripe"), String::from("midnight dots")],
+            achievements: vec![String::from("first_win")],
+            dealer_avatar: String::from("croupier"),
+            tips: 3,
+            playtime: 36_000,
+            ..Profile::default()
+        };
+
+        assert_eq!(Profile::parse(&profile.to_file_contents()), profile);
+    }
+
+    #[test]
+    fn set_name_strips_control_characters_and_limits_length() {
+        let mut profile = Profile::default();
+        profile.set_name("a\tvery long name that goes on");
+
+        assert_eq!(profile.name, "avery long name ");
+        assert!(profile.name.chars().count() <= MAX_NAME_LENGTH);
+    }
+
+    #[test]
+    fn display_name_is_never_empty() {
+        let mut profile = Profile::default();
+        profile.set_name("   ");
+
+        assert_eq!(profile.display_name(), DEFAULT_NAME);
+    }
+}
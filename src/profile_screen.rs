@@ -0,0 +1,151 @@
+//! The profile screen: renaming, resetting the statistics and deleting the profile. Resetting
+//! and deleting throw data away, so both ask twice, and the saves are backed up right before
+//! so the action can be undone for a few seconds after.
+
+use crate::backup::Backup;
+
+/// Times Enter has to be pressed again before a reset or a delete goes through
+pub const CONFIRMATIONS: u32 = 2;
+/// How long the undo button stays after a reset or a delete
+pub const SECONDS_TO_UNDO: f32 = 15.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileAction {
+    /// Typing edits the name, nothing to confirm
+    Rename,
+    /// Wins, the hand history and the trainer statistics go back to zero
+    ResetStats,
+    /// Everything of the player's is gone, the settings stay
+    Delete,
+}
+
+pub const PROFILE_ACTIONS: &[ProfileAction] = &[
+    ProfileAction::Rename,
+    ProfileAction::ResetStats,
+    ProfileAction::Delete,
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileScreen {
+    pub row: usize,
+    /// Times the selected action was asked about so far
+    pub confirmations: u32,
+    /// The backup made right before the last reset or delete, while it can still be undone
+    pub undo: Option<Backup>,
+    time_till_undo_gone: f32,
+}
+
+impl ProfileScreen {
+    pub fn selected(&self) -> ProfileAction {
+        PROFILE_ACTIONS[self.row]
+    }
+
+    /// Picking another action forgets the confirmations given so far.
+    pub fn select(&mut self, step: i32) {
+        let rows = PROFILE_ACTIONS.len() as i32;
+        self.row = (self.row as i32 + step).rem_euclid(rows) as usize;
+        self.confirmations = 0;
+    }
+
+    /// Enter on the selected action. Returns the action on the press that confirms it.
+    pub fn enter(&mut self) -> Option<ProfileAction> {
+        if self.selected() == ProfileAction::Rename {
+            return None;
+        }
+
+        if self.confirmations < CONFIRMATIONS {
+            self.confirmations += 1;
+            return None;
+        }
+
+        self.confirmations = 0;
+        Some(self.selected())
+    }
+
+    /// Leaving the screen cancels a half confirmed action.
+    pub fn cancel(&mut self) {
+        self.confirmations = 0;
+    }
+
+    /// The question for the selected action, while it's being confirmed
+    pub fn prompt(&self) -> Option<&'static str> {
+        match (self.selected(), self.confirmations) {
+            (_, 0) | (ProfileAction::Rename, _) => None,
+            (ProfileAction::ResetStats, 1) => Some("Reset the wins, the hand history and the trainer statistics? Press Enter again"),
+            (ProfileAction::Delete, 1) => Some("Delete the profile with its statistics, unlocks and missions? Press Enter again"),
+            (ProfileAction::ResetStats, _) => Some("Are you sure? Press Enter once more to reset"),
+            (ProfileAction::Delete, _) => Some("Are you sure? Press Enter once more to delete"),
+        }
+    }
+
+    pub fn offer_undo(&mut self, backup: Backup) {
+        self.undo = Some(backup);
+        self.time_till_undo_gone = SECONDS_TO_UNDO;
+    }
+
+    pub fn seconds_to_undo(&self) -> f32 {
+        self.time_till_undo_gone.max(0.0)
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        if self.undo.is_some() {
+            self.time_till_undo_gone -= time_delta;
+            if self.time_till_undo_gone <= 0.0 {
+                self.undo = None;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_needs_two_confirmations() {
+        let mut screen = ProfileScreen::default();
+        screen.select(2);
+        assert_eq!(screen.selected(), ProfileAction::Delete);
+
+        assert_eq!(screen.enter(), None);
+        assert!(screen.prompt().is_some());
+        assert_eq!(screen.enter(), None);
+        assert_eq!(screen.enter(), Some(ProfileAction::Delete));
+        assert_eq!(screen.prompt(), None);
+    }
+
+    #[test]
+    fn moving_away_cancels() {
+        let mut screen = ProfileScreen::default();
+        screen.select(1);
+        screen.enter();
+        screen.enter();
+
+        screen.select(1);
+        screen.select(-1);
+        assert_eq!(screen.enter(), None);
+        assert_eq!(screen.confirmations, 1);
+    }
+
+    #[test]
+    fn rename_is_never_confirmed() {
+        let mut screen = ProfileScreen::default();
+
+        for _ in 0..=CONFIRMATIONS {
+            assert_eq!(screen.enter(), None);
+        }
+        assert_eq!(screen.prompt(), None);
+    }
+
+    #[test]
+    fn undo_runs_out() {
+        let mut screen = ProfileScreen::default();
+        screen.offer_undo(Backup { timestamp: 1, path: std::path::PathBuf::from("backups/1") });
+
+        screen.update(SECONDS_TO_UNDO - 1.0);
+        assert!(screen.undo.is_some());
+        screen.update(1.0);
+        assert!(screen.undo.is_none());
+    }
+}
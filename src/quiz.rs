@@ -0,0 +1,260 @@
+use crate::card::{self, Card};
+use crate::game_engine;
+use crate::strategy::{self, Action};
+
+use rand::{Rng, seq::SliceRandom};
+
+pub const QUESTIONS_PER_ROUND: u32 = 10;
+/// Seconds to answer before the question counts as missed
+pub const SECONDS_PER_QUESTION: f32 = 6.0;
+/// How long the right answer stays on screen
+pub const SECONDS_OF_FEEDBACK: f32 = 1.2;
+const POINTS_PER_ANSWER: u32 = 100;
+/// Extra points for answering right away, less the longer it takes
+const MAX_SPEED_BONUS: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuestionKind {
+    /// Which basic strategy action is right
+    Action,
+    /// What the hand is worth
+    Total,
+}
+
+#[derive(Debug, Clone)]
+pub struct Question {
+    pub kind: QuestionKind,
+    pub player: Vec<Card>,
+    pub dealer_up: Card,
+}
+
+impl Question {
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        let mut deck = card::all();
+        deck.shuffle(rng);
+
+        let kind = if rng.gen_bool(0.5) { QuestionKind::Action } else { QuestionKind::Total };
+        // totals get more interesting with a third card now and then
+        let player_cards = if kind == QuestionKind::Total && rng.gen_bool(0.4) { 3 } else { 2 };
+        let dealer_up = deck.pop().unwrap();
+        let player = deck.split_off(deck.len() - player_cards);
+
+        Question { kind, player, dealer_up }
+    }
+
+    pub fn correct_action(&self) -> Action {
+        strategy::basic_strategy(&self.player, &self.dealer_up)
+    }
+
+    pub fn correct_total(&self) -> u32 {
+        game_engine::hand_value(&self.player)
+    }
+
+    pub fn correct_answer(&self) -> String {
+        match self.kind {
+            QuestionKind::Action => self.correct_action().name().to_string(),
+            QuestionKind::Total => self.correct_total().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    Action(Action),
+    Total(u32),
+}
+
+/// A round of quick questions, scored on right answers and speed.
+#[derive(Debug, Clone)]
+pub struct Quiz {
+    pub question: Question,
+    pub question_number: u32,
+    pub time_left: f32,
+    /// Digits typed for a total
+    pub typed: String,
+    /// Whether the last answer was right, shown until the next question
+    pub feedback: Option<bool>,
+    feedback_time: f32,
+    pub correct: u32,
+    pub answered: u32,
+    pub score: u32,
+    answer_seconds: f32,
+}
+
+impl Quiz {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        Quiz {
+            question: Question::random(rng),
+            question_number: 1,
+            time_left: SECONDS_PER_QUESTION,
+            typed: String::new(),
+            feedback: None,
+            feedback_time: 0.0,
+            correct: 0,
+            answered: 0,
+            score: 0,
+            answer_seconds: 0.0,
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.question_number > QUESTIONS_PER_ROUND
+    }
+
+    fn waiting_for_answer(&self) -> bool {
+        !self.finished() && self.feedback.is_none()
+    }
+
+    pub fn answer(&mut self, answer: Answer) {
+        if !self.waiting_for_answer() {
+            return;
+        }
+
+        let right = match answer {
+            Answer::Action(action) => action == self.question.correct_action(),
+            Answer::Total(total) => total == self.question.correct_total(),
+        };
+
+        self.answered += 1;
+        self.answer_seconds += SECONDS_PER_QUESTION - self.time_left;
+        if right {
+            self.correct += 1;
+            self.score += POINTS_PER_ANSWER + (MAX_SPEED_BONUS * self.time_left / SECONDS_PER_QUESTION) as u32;
+        }
+
+        self.feedback = Some(right);
+        self.feedback_time = SECONDS_OF_FEEDBACK;
+    }
+
+    /// A typed key: an action key or a digit of the total.
+    pub fn type_char(&mut self, character: char) {
+        if !self.waiting_for_answer() {
+            return;
+        }
+
+        match self.question.kind {
+            QuestionKind::Action => {
+                if let Some(action) = Action::from_key(character) {
+                    self.answer(Answer::Action(action));
+                }
+            },
+            QuestionKind::Total => {
+                if character.is_ascii_digit() && self.typed.len() < 2 {
+                    self.typed.push(character);
+                }
+            },
+        }
+    }
+
+    pub fn erase_char(&mut self) {
+        self.typed.pop();
+    }
+
+    /// Enter submits the typed total.
+    pub fn submit(&mut self) {
+        if self.question.kind == QuestionKind::Total {
+            if let Ok(total) = self.typed.parse() {
+                self.answer(Answer::Total(total));
+            }
+        }
+    }
+
+    /// Counts the time down. True on the update that ends the round.
+    pub fn update<R: Rng>(&mut self, rng: &mut R, time_delta: f32) -> bool {
+        if self.finished() {
+            return false;
+        }
+
+        if self.feedback.is_some() {
+            self.feedback_time -= time_delta;
+            if self.feedback_time <= 0.0 {
+                self.question_number += 1;
+                self.question = Question::random(rng);
+                self.time_left = SECONDS_PER_QUESTION;
+                self.typed.clear();
+                self.feedback = None;
+
+                return self.finished();
+            }
+        } else {
+            self.time_left -= time_delta;
+            if self.time_left <= 0.0 {
+                // out of time counts as a wrong answer
+                self.time_left = 0.0;
+                self.answered += 1;
+                self.answer_seconds += SECONDS_PER_QUESTION;
+                self.feedback = Some(false);
+                self.feedback_time = SECONDS_OF_FEEDBACK;
+            }
+        }
+
+        false
+    }
+
+    /// Average seconds per question so far
+    pub fn average_seconds(&self) -> f32 {
+        if self.answered == 0 { 0.0 } else { self.answer_seconds / self.answered as f32 }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn quiz_with(kind: QuestionKind, player: &[&str], dealer: &str) -> Quiz {
+        let mut quiz = Quiz::new(&mut StdRng::seed_from_u64(3));
+        quiz.question = Question {
+            kind,
+            player: player.iter().map(|name| Card::new(name)).collect(),
+            dealer_up: Card::new(dealer),
+        };
+
+        quiz
+    }
+
+    #[test]
+    fn right_action_scores_with_bonus() {
+        let mut quiz = quiz_with(QuestionKind::Action, &["10_of_hearts", "6_of_clubs"], "10_of_spades");
+
+        quiz.type_char('h');
+
+        assert_eq!(quiz.feedback, Some(true));
+        assert_eq!(quiz.score, POINTS_PER_ANSWER + MAX_SPEED_BONUS as u32);
+    }
+
+    #[test]
+    fn typed_total_is_checked_on_enter() {
+        let mut quiz = quiz_with(QuestionKind::Total, &["ace_of_hearts", "6_of_clubs", "9_of_clubs"], "10_of_spades");
+
+        quiz.type_char('1');
+        quiz.type_char('6');
+        quiz.type_char('h');
+        assert_eq!(quiz.feedback, None);
+
+        quiz.submit();
+        assert_eq!(quiz.feedback, Some(true));
+        assert_eq!(quiz.correct, 1);
+    }
+
+    #[test]
+    fn timeout_is_a_miss_and_round_ends() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut quiz = Quiz::new(&mut rng);
+
+        let mut ended = 0;
+        for _ in 0..QUESTIONS_PER_ROUND {
+            quiz.update(&mut rng, SECONDS_PER_QUESTION + 0.1);
+            assert_eq!(quiz.feedback, Some(false));
+            if quiz.update(&mut rng, SECONDS_OF_FEEDBACK + 0.1) {
+                ended += 1;
+            }
+        }
+
+        assert!(quiz.finished());
+        assert_eq!(ended, 1);
+        assert_eq!((quiz.correct, quiz.score), (0, 0));
+        assert_eq!(quiz.average_seconds(), SECONDS_PER_QUESTION);
+    }
+}
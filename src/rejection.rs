@@ -0,0 +1,99 @@
+//! Feedback for an action the hand doesn't allow(see game_action.rs): the screen's edge flashes
+//! red and the reason shakes for a moment, so a key that does nothing says why.
+
+use crate::game_action::Rejection;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+};
+
+/// How long the flash and the shake last
+pub const FEEDBACK_SECONDS: f32 = 0.6;
+/// How far the reason moves to each side while shaking
+const SHAKE_WIDTH: f32 = 8.0;
+const SHAKES_PER_SECOND: f32 = 12.0;
+const EDGE_WIDTH: f32 = 12.0;
+
+/// The last rejected action, while its feedback lasts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RejectionFeedback {
+    rejection: Option<Rejection>,
+    time_left: f32,
+}
+
+impl RejectionFeedback {
+    pub fn show(&mut self, rejection: Rejection) {
+        self.rejection = Some(rejection);
+        self.time_left = FEEDBACK_SECONDS;
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        if self.rejection.is_some() {
+            self.time_left -= time_delta;
+            if self.time_left <= 0.0 {
+                self.rejection = None;
+            }
+        }
+    }
+
+    pub fn rejection(&self) -> Option<Rejection> {
+        self.rejection
+    }
+
+    /// How far to the side to draw the reason, 0 once the feedback is over.
+    pub fn shake_offset(&self) -> f32 {
+        if self.rejection.is_none() {
+            return 0.0;
+        }
+
+        let time = FEEDBACK_SECONDS - self.time_left;
+        let fade = self.time_left / FEEDBACK_SECONDS;
+        (time * SHAKES_PER_SECOND * 2.0 * std::f32::consts::PI).sin() * SHAKE_WIDTH * fade
+    }
+
+    /// Opacity of the red flash, fading out.
+    pub fn flash_alpha(&self) -> f32 {
+        if self.rejection.is_none() { 0.0 } else { 0.6 * self.time_left / FEEDBACK_SECONDS }
+    }
+}
+
+/// Draws the red edge around the screen while the feedback lasts.
+pub fn draw_flash(ctx: &mut Context, feedback: &RejectionFeedback) -> GameResult<()> {
+    if feedback.rejection().is_none() {
+        return Ok(());
+    }
+
+    let graphics::Rect { w: width, h: height, .. } = graphics::screen_coordinates(ctx);
+    let rect = graphics::Rect::new(EDGE_WIDTH / 2.0, EDGE_WIDTH / 2.0, width - EDGE_WIDTH, height - EDGE_WIDTH);
+    let color = graphics::Color::new(0.85, 0.05, 0.05, feedback.flash_alpha());
+
+    let edge = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(EDGE_WIDTH), rect, color)?;
+    graphics::draw(ctx, &edge, graphics::DrawParam::default())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feedback_fades_out() {
+        let mut feedback = RejectionFeedback::default();
+        assert_eq!(feedback.flash_alpha(), 0.0);
+
+        feedback.show(Rejection::DealerTurn);
+        assert_eq!(feedback.rejection(), Some(Rejection::DealerTurn));
+
+        feedback.update(FEEDBACK_SECONDS / 3.0);
+        let alpha = feedback.flash_alpha();
+        assert!(alpha > 0.0);
+        feedback.update(FEEDBACK_SECONDS / 3.0);
+        assert!(feedback.flash_alpha() < alpha);
+
+        feedback.update(FEEDBACK_SECONDS);
+        assert_eq!(feedback.rejection(), None);
+        assert_eq!(feedback.shake_offset(), 0.0);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::asset_manager::AssetFile;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    audio,
+    filesystem,
+};
+
+use std::env;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+// Paths are relative to the mounted "resources" folder and always use forward slashes,
+// which ggez's virtual filesystem accepts on every platform.
+pub const ICON_PATH: &str = "/icons/black_jack.png";
+pub const CARD_BACK_PATH: &str = "/card_images/card_back.png";
+pub const DEFAULT_FONT_PATH: &str = "/font/DejaVuSerif.ttf";
+pub const MENU_FONT_PATH: &str = "/font/FancyMenuFont.ttf";
+pub const CARD_DEAL_SOUND_PATH: &str = "/sfx/card_deal.wav";
+pub const CARD_FLIP_SOUND_PATH: &str = "/sfx/card_flip.wav";
+pub const HEARTBEAT_SOUND_PATH: &str = "/sfx/heartbeat.wav";
+
+/// Where the embedded icon gets written when the resources folder doesn't have one.
+/// ggez creates it in the per-user data directory, which is always mounted.
+const FALLBACK_ICON_PATH: &str = "/black_jack_icon.png";
+
+const FALLBACK_ICON: &[u8] = include_bytes!("../resources/icons/black_jack.png");
+
+#[cfg(feature = "embed-assets")]
+macro_rules! embedded_assets {
+    ($($path:literal),* $(,)?) => {
+        &[$((concat!("/", $path), include_bytes!(concat!("../resources/", $path)) as &[u8])),*]
+    };
+}
+
+/// Every asset the game needs, built into the executable with the "embed-assets" feature.
+#[cfg(feature = "embed-assets")]
+const EMBEDDED_ASSETS: &[(&str, &[u8])] = embedded_assets![
+    "font/DejaVuSerif.ttf",
+    "font/FancyMenuFont.ttf",
+    "sfx/card_deal.wav",
+    "sfx/card_flip.wav",
+    "sfx/heartbeat.wav",
+    "card_images/card_back.png",
+    "card_backs/shimmer.png",
+    "card_images/ace_of_clubs.png",
+    "card_images/ace_of_diamonds.png",
+    "card_images/ace_of_hearts.png",
+    "card_images/ace_of_spades.png",
+    "card_images/2_of_clubs.png",
+    "card_images/2_of_diamonds.png",
+    "card_images/2_of_hearts.png",
+    "card_images/2_of_spades.png",
+    "card_images/3_of_clubs.png",
+    "card_images/3_of_diamonds.png",
+    "card_images/3_of_hearts.png",
+    "card_images/3_of_spades.png",
+    "card_images/4_of_clubs.png",
+    "card_images/4_of_diamonds.png",
+    "card_images/4_of_hearts.png",
+    "card_images/4_of_spades.png",
+    "card_images/5_of_clubs.png",
+    "card_images/5_of_diamonds.png",
+    "card_images/5_of_hearts.png",
+    "card_images/5_of_spades.png",
+    "card_images/6_of_clubs.png",
+    "card_images/6_of_diamonds.png",
+    "card_images/6_of_hearts.png",
+    "card_images/6_of_spades.png",
+    "card_images/7_of_clubs.png",
+    "card_images/7_of_diamonds.png",
+    "card_images/7_of_hearts.png",
+    "card_images/7_of_spades.png",
+    "card_images/8_of_clubs.png",
+    "card_images/8_of_diamonds.png",
+    "card_images/8_of_hearts.png",
+    "card_images/8_of_spades.png",
+    "card_images/9_of_clubs.png",
+    "card_images/9_of_diamonds.png",
+    "card_images/9_of_hearts.png",
+    "card_images/9_of_spades.png",
+    "card_images/10_of_clubs.png",
+    "card_images/10_of_diamonds.png",
+    "card_images/10_of_hearts.png",
+    "card_images/10_of_spades.png",
+    "card_images/jack_of_clubs.png",
+    "card_images/jack_of_diamonds.png",
+    "card_images/jack_of_hearts.png",
+    "card_images/jack_of_spades.png",
+    "card_images/queen_of_clubs.png",
+    "card_images/queen_of_diamonds.png",
+    "card_images/queen_of_hearts.png",
+    "card_images/queen_of_spades.png",
+    "card_images/king_of_clubs.png",
+    "card_images/king_of_diamonds.png",
+    "card_images/king_of_hearts.png",
+    "card_images/king_of_spades.png",
+];
+
+#[cfg(feature = "embed-assets")]
+fn embedded(path: &str) -> Option<&'static [u8]> {
+    EMBEDDED_ASSETS.iter().
+        find(|(asset_path, _)| *asset_path == path).
+        map(|(_, bytes)| *bytes)
+}
+
+pub fn card_image_path(card_name: &str) -> String {
+    format!("/card_images/{}.png", card_name)
+}
+
+/// Folders on disk the resources can be in, as ggez mounts them: next to the executable, and in
+/// the source tree when started with cargo.
+pub fn resource_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        dirs.push(PathBuf::from(manifest_dir).join("resources"));
+    }
+    if let Some(exe_dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.to_path_buf())) {
+        dirs.push(exe_dir.join("resources"));
+    }
+
+    dirs
+}
+
+/// Where to read an asset(given by its ggez path) in the background. None for embedded ones,
+/// which are in memory already.
+pub fn asset_file(path: &str) -> Option<AssetFile> {
+    #[cfg(feature = "embed-assets")]
+    if embedded(path).is_some() {
+        return None;
+    }
+
+    let relative = path.trim_start_matches('/');
+    Some(AssetFile { key: path.to_string(), files: resource_dirs().iter().map(|dir| dir.join(relative)).collect() })
+}
+
+// With "embed-assets" the loaders below read from the executable first and only fall back
+// to the resources folder for files that aren't embedded(e.g. a custom card set).
+
+/// The file's bytes, for images decoded by the caller(see AssetManager).
+pub fn read(ctx: &mut Context, path: &str) -> GameResult<Vec<u8>> {
+    #[cfg(feature = "embed-assets")]
+    if let Some(bytes) = embedded(path) {
+        return Ok(bytes.to_vec());
+    }
+
+    let mut bytes = Vec::new();
+    filesystem::open(ctx, path)?.read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub fn load_font(ctx: &mut Context, path: &str) -> GameResult<graphics::Font> {
+    #[cfg(feature = "embed-assets")]
+    if let Some(bytes) = embedded(path) {
+        return graphics::Font::new_glyph_font_bytes(ctx, bytes);
+    }
+
+    graphics::Font::new(ctx, path)
+}
+
+pub fn load_sound(ctx: &mut Context, path: &str) -> GameResult<audio::Source> {
+    #[cfg(feature = "embed-assets")]
+    if let Some(bytes) = embedded(path) {
+        return audio::Source::from_data(ctx, audio::SoundData::from_bytes(bytes));
+    }
+
+    audio::Source::new(ctx, path)
+}
+
+fn write_fallback_icon(ctx: &mut Context) -> GameResult<()> {
+    let mut file = filesystem::create(ctx, FALLBACK_ICON_PATH)?;
+    file.write_all(FALLBACK_ICON)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+pub fn set_window_icon(ctx: &mut Context) -> GameResult<()> {
+    if filesystem::exists(ctx, ICON_PATH) && graphics::set_window_icon(ctx, Some(ICON_PATH)).is_ok() {
+        return Ok(());
+    }
+
+    // the resources folder is missing(or the icon is broken) -> use the copy built into the binary
+    if !filesystem::exists(ctx, FALLBACK_ICON_PATH) {
+        write_fallback_icon(ctx)?;
+    }
+
+    graphics::set_window_icon(ctx, Some(FALLBACK_ICON_PATH))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_image_path_uses_forward_slashes() {
+        assert_eq!(card_image_path("ace_of_spades"), "/card_images/ace_of_spades.png");
+    }
+
+    #[cfg(not(feature = "embed-assets"))]
+    #[test]
+    fn asset_files_are_in_the_resource_dirs() {
+        let file = asset_file(CARD_DEAL_SOUND_PATH).unwrap();
+
+        assert_eq!(file.key, CARD_DEAL_SOUND_PATH);
+        assert!(file.files.iter().all(|path| path.ends_with("resources/sfx/card_deal.wav")));
+        assert!(file.files.iter().any(|path| path.exists()));
+    }
+
+    #[cfg(feature = "embed-assets")]
+    #[test]
+    fn every_card_is_embedded() {
+        for card in crate::card::all() {
+            assert!(embedded(&card_image_path(&card.name)).is_some(), "{} is not embedded", card.name);
+        }
+
+        assert!(embedded(CARD_BACK_PATH).is_some());
+        assert!(embedded(DEFAULT_FONT_PATH).is_some());
+        assert!(embedded(MENU_FONT_PATH).is_some());
+        assert!(embedded(CARD_DEAL_SOUND_PATH).is_some());
+        assert!(embedded(CARD_FLIP_SOUND_PATH).is_some());
+        assert!(embedded(HEARTBEAT_SOUND_PATH).is_some());
+    }
+}
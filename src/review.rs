@@ -0,0 +1,227 @@
+//! Mistake review: every hit and stand of a counted hand is checked against the best play for
+//! the game's rules(see probability.rs), and the hands with a mistake are kept for the rest of
+//! the session. The game deals without a seed, so a hand is kept as the cards it was dealt in
+//! order, which is all a scenario needs to deal it again.
+
+use crate::card::Card;
+use crate::game_engine::Outcome;
+use crate::probability::{self, BestPlay, Rules};
+use crate::scenario::{Action, Scenario};
+
+/// Points of a hand with aces as 1 and whether it holds an ace, as the best play is indexed.
+pub fn hard_total(cards: &[Card]) -> (u32, bool) {
+    let has_ace = cards.iter().any(|card| card.is_an_ace());
+    let hard = cards.iter().
+        map(|card| if card.is_an_ace() { 1 } else { card.get_points().unwrap_or(10) }).
+        sum();
+
+    (hard, has_ace)
+}
+
+pub fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::Hit => "hit",
+        Action::Stand => "stand",
+        Action::Hint => "hint",
+        Action::Handicap => "handicap",
+    }
+}
+
+/// A hit or a stand the best play wouldn't have made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mistake {
+    /// Best value of the hand at the time
+    pub value: u32,
+    pub chosen: Action,
+    pub recommended: Action,
+    /// How much worse the chosen action is, in bets per hand
+    pub ev_lost: f64,
+}
+
+impl Mistake {
+    /// "stood on 12, hit is better by 4.8 points per 100 hands", a won hand being a point and a
+    /// lost one minus a point, so family mode can show it too
+    pub fn describe(&self) -> String {
+        let chosen = match self.chosen {
+            Action::Stand => "stood",
+            _ => "hit",
+        };
+
+        format!("{} on {}, {} is better by {:.1} points per 100 hands",
+                chosen, self.value, action_name(self.recommended), self.ev_lost * 100.0)
+    }
+}
+
+/// Checks a hit or a stand on `cards` against the best play.
+pub fn check(best: &BestPlay, cards: &[Card], chosen: Action) -> Option<Mistake> {
+    let (hard, has_ace) = hard_total(cards);
+    let recommended = if best.should_draw(hard, has_ace) { Action::Hit } else { Action::Stand };
+    if chosen == recommended {
+        return None;
+    }
+
+    let (hit, stand) = best.expected_values(hard, has_ace);
+    let ev_lost = match chosen {
+        Action::Hit => stand - hit,
+        _ => hit - stand,
+    };
+
+    Some(Mistake { value: probability::best_value(hard, has_ace), chosen, recommended, ev_lost })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewedHand {
+    pub player_cards: Vec<String>,
+    pub dealer_cards: Vec<String>,
+    pub outcome: Outcome,
+    pub mistakes: Vec<Mistake>,
+}
+
+impl ReviewedHand {
+    /// The same cards in the same order, for the player to play again. It doesn't count.
+    pub fn scenario(&self) -> Scenario {
+        Scenario {
+            deck: self.player_cards.iter().chain(&self.dealer_cards).cloned().collect(),
+            actions: Vec::new(),
+            expected: None,
+        }
+    }
+
+    pub fn ev_lost(&self) -> f64 {
+        self.mistakes.iter().map(|mistake| mistake.ev_lost).sum()
+    }
+}
+
+/// The hands of this session with a mistake, and the one picked on the review screen.
+#[derive(Debug, Clone, Default)]
+pub struct SessionReview {
+    pub hands: Vec<ReviewedHand>,
+    pub selected: usize,
+    /// Hits and stands of the finished hands that counted
+    pub decisions: u32,
+    /// Mistakes and decisions of the hand being played
+    pending: Vec<Mistake>,
+    pending_decisions: u32,
+    rules: Rules,
+    best: Option<BestPlay>,
+}
+
+impl SessionReview {
+    /// Called right before the player hits or stands on `cards`.
+    pub fn on_action(&mut self, cards: &[Card], chosen: Action, rules: Rules) {
+        if self.best.is_none() || self.rules != rules {
+            self.best = Some(probability::best_play(rules));
+            self.rules = rules;
+        }
+        self.pending_decisions += 1;
+
+        if let Some(mistake) = self.best.as_ref().and_then(|best| check(best, cards, chosen)) {
+            self.pending.push(mistake);
+        }
+    }
+
+    /// Keeps the finished hand when it had a mistake.
+    pub fn on_hand_end(&mut self, player_cards: &[Card], dealer_cards: &[Card], outcome: Outcome) {
+        self.decisions += std::mem::take(&mut self.pending_decisions);
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let names = |cards: &[Card]| cards.iter().map(|card| card.name.clone()).collect();
+        self.hands.push(ReviewedHand {
+            player_cards: names(player_cards),
+            dealer_cards: names(dealer_cards),
+            outcome,
+            mistakes: std::mem::take(&mut self.pending),
+        });
+    }
+
+    /// Hands that don't count(scenarios, practice) aren't reviewed.
+    pub fn forget_hand(&mut self) {
+        self.pending.clear();
+        self.pending_decisions = 0;
+    }
+
+    pub fn select(&mut self, step: i32) {
+        if !self.hands.is_empty() {
+            self.selected = (self.selected as i32 + step).rem_euclid(self.hands.len() as i32) as usize;
+        }
+    }
+
+    pub fn selected_hand(&self) -> Option<&ReviewedHand> {
+        self.hands.get(self.selected)
+    }
+
+    pub fn ev_lost(&self) -> f64 {
+        self.hands.iter().map(ReviewedHand::ev_lost).sum()
+    }
+
+    pub fn mistakes(&self) -> u32 {
+        self.hands.iter().map(|hand| hand.mistakes.len() as u32).sum()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn aces_count_as_one() {
+        assert_eq!(hard_total(&cards(&["ace_of_spades", "6_of_hearts"])), (7, true));
+        assert_eq!(hard_total(&cards(&["king_of_clubs", "queen_of_clubs"])), (20, false));
+    }
+
+    #[test]
+    fn only_worse_actions_are_mistakes() {
+        let best = probability::best_play(Rules::default());
+        let twenty = cards(&["king_of_clubs", "queen_of_clubs"]);
+
+        assert_eq!(check(&best, &twenty, Action::Stand), None);
+
+        let mistake = check(&best, &twenty, Action::Hit).unwrap();
+        assert_eq!(mistake.value, 20);
+        assert_eq!(mistake.recommended, Action::Stand);
+        assert!(mistake.ev_lost > 0.0);
+        assert!(mistake.describe().starts_with("hit on 20, stand is better by"));
+    }
+
+    #[test]
+    fn hands_without_mistakes_are_not_kept() {
+        let mut review = SessionReview::default();
+        let player = cards(&["king_of_clubs", "queen_of_clubs"]);
+        let dealer = cards(&["9_of_hearts", "8_of_spades"]);
+
+        review.on_action(&player, Action::Stand, Rules::default());
+        review.on_hand_end(&player, &dealer, Outcome::Win);
+        assert!(review.hands.is_empty());
+
+        review.on_action(&player[..1], Action::Stand, Rules::default());
+        review.forget_hand();
+        review.on_hand_end(&player, &dealer, Outcome::Win);
+        assert!(review.hands.is_empty());
+        // the forgotten hand's stand doesn't count
+        assert_eq!(review.decisions, 1);
+    }
+
+    #[test]
+    fn replay_deals_the_same_cards() {
+        let mut review = SessionReview::default();
+        let player = cards(&["5_of_clubs", "7_of_diamonds"]);
+        let dealer = cards(&["10_of_hearts", "8_of_spades"]);
+
+        review.on_action(&[], Action::Hit, Rules::default());
+        review.on_action(&player[..1], Action::Hit, Rules::default());
+        review.on_action(&player, Action::Stand, Rules::default());
+        review.on_hand_end(&player, &dealer, Outcome::Lose);
+
+        let hand = review.selected_hand().unwrap();
+        assert_eq!(hand.mistakes.len(), 1);
+        assert_eq!(hand.scenario().deck, ["5_of_clubs", "7_of_diamonds", "10_of_hearts", "8_of_spades"]);
+        assert!(hand.scenario().actions.is_empty());
+    }
+}
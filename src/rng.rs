@@ -0,0 +1,74 @@
+//! The game's random numbers come from two streams seeded apart from each other: one for what
+//! decides hands(shuffles, the hint's range) and one for looks(the dealer's thanks and other
+//! effects). Drawing from one never moves the other, so a replay or a fairness check of the
+//! hands isn't thrown off by how often an effect happened to play. Every shoe is shuffled by a
+//! stream of its own, from the session's seed and the shoe's number, so two players on the same
+//! seed get the same shoes whatever else they do in between(see challenge.rs).
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Spreads the shoe numbers over the seeds
+const SHOE_SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    /// Shuffles and anything else that changes how a hand goes
+    pub gameplay: StdRng,
+    /// Effects that only change how things look
+    pub cosmetic: StdRng,
+    /// The session's seed, the shoes are shuffled from it
+    pub seed: u64,
+}
+
+impl GameRng {
+    pub fn new() -> Self {
+        GameRng { gameplay: StdRng::from_entropy(), cosmetic: StdRng::from_entropy(), seed: rand::thread_rng().gen() }
+    }
+
+    /// The hands play out the same for the same seed, whatever the effects do.
+    pub fn seeded(gameplay_seed: u64) -> Self {
+        GameRng { gameplay: StdRng::seed_from_u64(gameplay_seed), cosmetic: StdRng::from_entropy(), seed: gameplay_seed }
+    }
+
+    /// Shuffles the session's shoe number `index`, the same for the same seed.
+    pub fn shoe(&self, index: u32) -> StdRng {
+        StdRng::seed_from_u64(self.seed.wrapping_add(SHOE_SEED_STEP.wrapping_mul(index as u64)))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_do_not_move_the_gameplay_stream() {
+        let mut quiet = GameRng::seeded(42);
+        let mut busy = GameRng::seeded(42);
+
+        for _ in 0..100 {
+            let _: u32 = busy.cosmetic.gen();
+        }
+
+        let draws = |rng: &mut GameRng| (0..10).map(|_| rng.gameplay.gen_range(0..52)).collect::<Vec<u32>>();
+        assert_eq!(draws(&mut quiet), draws(&mut busy));
+    }
+
+    #[test]
+    fn shoes_follow_the_seed_alone() {
+        let mut played = GameRng::seeded(7);
+        let _: u32 = played.gameplay.gen();
+        let fresh = GameRng::seeded(7);
+
+        let first_draw = |mut rng: StdRng| rng.gen::<u64>();
+        assert_eq!(first_draw(played.shoe(3)), first_draw(fresh.shoe(3)));
+        assert_ne!(first_draw(fresh.shoe(3)), first_draw(fresh.shoe(4)));
+        assert_ne!(first_draw(fresh.shoe(0)), first_draw(GameRng::seeded(8).shoe(0)));
+    }
+}
@@ -0,0 +1,144 @@
+//! Writes save files on a background thread, so finishing a hand never waits for the disk.
+//! The files are written in the order they were handed over. Anything reading the save files
+//! from disk(backups, quitting) flushes first, dropping the saver flushes too.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+enum Job {
+    /// Replaces the file with the contents
+    Write { file_name: String, contents: String },
+    /// Adds the text at the end of the file, after `header` when the file is new
+    Append { file_name: String, header: Option<String>, text: String },
+    /// Answers once every job before it is done
+    Flush(Sender<()>),
+}
+
+fn run(job: &Job) -> io::Result<()> {
+    match job {
+        Job::Flush(done) => {
+            let _ = done.send(());
+            Ok(())
+        },
+        Job::Write { file_name, contents } => fs::write(file_name, contents),
+        Job::Append { file_name, header, text } => {
+            let new_file = !Path::new(file_name).exists();
+            let mut file = OpenOptions::new().create(true).append(true).open(file_name)?;
+
+            if let (true, Some(header)) = (new_file, header) {
+                writeln!(file, "{}", header)?;
+            }
+            write!(file, "{}", text)
+        },
+    }
+}
+
+fn file_name(job: &Job) -> &str {
+    match job {
+        Job::Write { file_name, .. } | Job::Append { file_name, .. } => file_name,
+        Job::Flush(_) => "",
+    }
+}
+
+pub struct Saver {
+    sender: Option<Sender<Job>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Saver {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        // ends once the saver is dropped and everything sent is written
+        let worker = thread::spawn(move || {
+            for job in receiver {
+                if let Err(e) = run(&job) {
+                    eprintln!("Could not save {}: {}", file_name(&job), e);
+                }
+            }
+        });
+
+        Saver { sender: Some(sender), worker: Some(worker) }
+    }
+
+    fn send(&self, job: Job) {
+        let job = match &self.sender {
+            Some(sender) => match sender.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => job,
+            },
+            None => job,
+        };
+
+        // the worker is gone, better to wait for the disk than to lose the save
+        if let Err(e) = run(&job) {
+            eprintln!("Could not save {}: {}", file_name(&job), e);
+        }
+    }
+
+    pub fn write(&self, file_name: &str, contents: String) {
+        self.send(Job::Write { file_name: file_name.to_string(), contents });
+    }
+
+    pub fn append(&self, file_name: &str, header: Option<&str>, text: String) {
+        self.send(Job::Append { file_name: file_name.to_string(), header: header.map(str::to_string), text });
+    }
+
+    /// Waits for everything handed over so far to be written.
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        self.send(Job::Flush(done));
+
+        let _ = wait.recv();
+    }
+}
+
+impl Default for Saver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Saver {
+    fn drop(&mut self) {
+        self.sender = None;
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_writes_everything_in_order() {
+        let dir = std::env::temp_dir().join(format!("blackjack_saver_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (stats, log) = (dir.join("stats.txt"), dir.join("log.csv"));
+        let (stats, log) = (stats.to_str().unwrap(), log.to_str().unwrap());
+
+        let saver = Saver::new();
+        saver.write(stats, String::from("1 0 0\n"));
+        saver.write(stats, String::from("2 0 0\n"));
+        saver.append(log, Some("id"), String::from("1\n"));
+        saver.append(log, Some("id"), String::from("2\n"));
+        saver.flush();
+
+        assert_eq!(fs::read_to_string(stats).unwrap(), "2 0 0\n");
+        assert_eq!(fs::read_to_string(log).unwrap(), "id\n1\n2\n");
+
+        // dropping the saver waits for the last saves
+        saver.write(stats, String::from("3 0 0\n"));
+        drop(saver);
+        assert_eq!(fs::read_to_string(stats).unwrap(), "3 0 0\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
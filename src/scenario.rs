@@ -0,0 +1,238 @@
+//! Scenarios replay a hand exactly: the cards on top of the deck, what the player does and
+//! how the hand should end. They're written like config.txt, one "key = value" per line:
+//!
+//! ```text
+//! # the player stands on 16 and the dealer busts
+//! deck = king_of_diamonds, 6_of_hearts, 10_of_spades, 5_of_diamonds, 9_of_clubs
+//! actions = hit, hit, stand
+//! expected = win
+//! ```
+//!
+//! Cards are dealt from the start of `deck`, the rest of the deck is shuffled below them.
+//! `--scenario <file>` plays one in the game, `Scenario::play` runs it on the engine alone.
+
+use crate::board::Turn;
+use crate::card::{self, Card};
+use crate::config;
+use crate::game_action::{GameEvent, Rejection};
+use crate::game_engine::{GameEngine, Outcome};
+
+use std::fmt;
+use std::error::Error;
+use std::fs;
+
+#[derive(Debug, Clone)]
+pub struct ScenarioError {
+    details: String,
+    rejection: Option<Rejection>,
+}
+
+impl ScenarioError {
+    fn new(msg: &str) -> Self {
+        ScenarioError {
+            details: msg.to_string(),
+            rejection: None,
+        }
+    }
+
+    fn rejected(rejection: Rejection) -> Self {
+        ScenarioError {
+            details: rejection.reason(),
+            rejection: Some(rejection),
+        }
+    }
+
+    /// The engine turned down one of the actions, as it would have in the game.
+    pub fn rejection(&self) -> Option<Rejection> {
+        self.rejection
+    }
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.details)
+    }
+}
+
+impl Error for ScenarioError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// What the player does on their turn, the same as clicking the deck or pressing a key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Hint,
+    Handicap,
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hit" => Some(Action::Hit),
+            "stand" => Some(Action::Stand),
+            "hint" => Some(Action::Hint),
+            "handicap" => Some(Action::Handicap),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// Names of the cards on top of the deck, in dealing order
+    pub deck: Vec<String>,
+    pub actions: Vec<Action>,
+    /// None when the scenario only sets up the table
+    pub expected: Option<Outcome>,
+}
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(|item| item.trim()).filter(|item| !item.is_empty())
+}
+
+impl Scenario {
+    pub fn parse(contents: &str) -> Result<Self, ScenarioError> {
+        let mut scenario = Scenario { deck: Vec::new(), actions: Vec::new(), expected: None };
+
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            match key {
+                "deck" => {
+                    for name in list(value) {
+                        if !card::all().iter().any(|card| card.name == name) {
+                            return Err(ScenarioError::new(&format!("unknown card \"{}\"", name)));
+                        }
+                        if scenario.deck.iter().any(|card| card == name) {
+                            return Err(ScenarioError::new(&format!("{} is in the deck twice", name)));
+                        }
+                        scenario.deck.push(name.to_string());
+                    }
+                },
+                "actions" => {
+                    for name in list(value) {
+                        let action = Action::from_name(name).
+                            ok_or_else(|| ScenarioError::new(&format!("unknown action \"{}\"", name)))?;
+                        scenario.actions.push(action);
+                    }
+                },
+                "expected" => {
+                    let outcome = Outcome::from_name(value).
+                        ok_or_else(|| ScenarioError::new(&format!("unknown outcome \"{}\"", value)))?;
+                    scenario.expected = Some(outcome);
+                },
+                _ => return Err(ScenarioError::new(&format!("unknown key \"{}\"", key))),
+            }
+        }
+
+        if scenario.deck.is_empty() {
+            return Err(ScenarioError::new("the scenario has no deck"));
+        }
+
+        Ok(scenario)
+    }
+
+    pub fn load(file_name: &str) -> Result<Self, ScenarioError> {
+        let contents = fs::read_to_string(file_name).
+            map_err(|e| ScenarioError::new(&format!("could not read {}: {}", file_name, e)))?;
+
+        Self::parse(&contents)
+    }
+
+    /// Plays the hand on the engine alone, the way the game does it: the player acts until they
+    /// stand, bust or reach 21, then the dealer draws until the engine decides the hand.
+    /// Hints and handicaps cost nothing here, as in a replay, but an action the engine turns
+    /// down is an error.
+    pub fn play(&self) -> Result<Outcome, ScenarioError> {
+        let mut deck = self.deck.iter();
+        let mut deal = |hand: &mut Vec<Card>| match deck.next() {
+            Some(name) => {
+                hand.push(Card::new(name));
+                Ok(())
+            },
+            None => Err(ScenarioError::new("the deck ran out")),
+        };
+
+        let mut engine = GameEngine::new();
+        let mut turn = Turn::Player;
+        let (mut player, mut dealer) = (Vec::new(), Vec::new());
+
+        for action in &self.actions {
+            if engine.game_over || matches!(turn, Turn::Dealer) {
+                break;
+            }
+
+            let events = engine.apply((*action).into(), &mut turn, None).map_err(ScenarioError::rejected)?;
+            if events.contains(&GameEvent::CardRequested) {
+                deal(&mut player)?;
+                engine.score(&player, Turn::Player).map_err(|e| ScenarioError::new(&e.to_string()))?;
+                engine.check_outcome(&mut turn);
+            }
+        }
+
+        if matches!(turn, Turn::Dealer) {
+            while !engine.game_over {
+                deal(&mut dealer)?;
+                engine.score(&dealer, Turn::Dealer).map_err(|e| ScenarioError::new(&e.to_string()))?;
+                engine.check_outcome(&mut turn);
+            }
+        }
+
+        Ok(engine.outcome)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_action::PowerUp;
+
+    #[test]
+    fn parse_reads_all_keys() {
+        let scenario = Scenario::parse("# comment\ndeck = ace_of_spades, king_of_hearts\nactions = hit, stand\nexpected = win\n").unwrap();
+
+        assert_eq!(scenario.deck, vec!["ace_of_spades", "king_of_hearts"]);
+        assert_eq!(scenario.actions, vec![Action::Hit, Action::Stand]);
+        assert_eq!(scenario.expected, Some(Outcome::Win));
+    }
+
+    #[test]
+    fn parse_rejects_mistakes() {
+        assert!(Scenario::parse("deck = joker").is_err());
+        assert!(Scenario::parse("deck = 2_of_clubs, 2_of_clubs").is_err());
+        assert!(Scenario::parse("deck = 2_of_clubs\nactions = split").is_err());
+        assert!(Scenario::parse("actions = hit").is_err());
+    }
+
+    #[test]
+    fn dealer_busts() {
+        let scenario = Scenario::parse("deck = king_of_diamonds, 6_of_hearts, 10_of_spades, 5_of_diamonds, 9_of_clubs\nactions = hit, hit, stand").unwrap();
+
+        assert_eq!(scenario.play().unwrap(), Outcome::Win);
+    }
+
+    #[test]
+    fn handicap_turns_a_loss_into_a_draw() {
+        let deck = "deck = 10_of_hearts, 7_of_hearts, 10_of_clubs, 8_of_clubs\n";
+
+        assert_eq!(Scenario::parse(&format!("{}actions = hit, hit, stand", deck)).unwrap().play().unwrap(), Outcome::Lose);
+        assert_eq!(Scenario::parse(&format!("{}actions = handicap, hit, hit, stand", deck)).unwrap().play().unwrap(), Outcome::Draw);
+    }
+
+    #[test]
+    fn power_up_used_twice_is_rejected_as_in_the_game() {
+        let scenario = Scenario::parse("deck = 10_of_hearts, 7_of_hearts, 10_of_clubs, 8_of_clubs\nactions = hint, hit, hint, stand").unwrap();
+
+        assert_eq!(scenario.play().unwrap_err().rejection(), Some(Rejection::AlreadyActive(PowerUp::Hint)));
+    }
+
+    #[test]
+    fn short_deck_is_an_error() {
+        let scenario = Scenario::parse("deck = 2_of_clubs\nactions = hit, stand").unwrap();
+
+        assert!(scenario.play().is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::i18n::Language;
+use crate::setup;
+
+/// Fixed screens with fixture data, rendered for golden-image tests and layout reviews.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scene {
+    Menu,
+    Help,
+    /// The player has two cards and the dealer one
+    MidHand,
+    /// The "YOU WIN!" banner after a finished hand
+    Outcome,
+    /// The menu in Bulgarian, the Cyrillic in the regular font
+    BulgarianMenu,
+    BulgarianHelp,
+    /// The Bulgarian screens at the smallest UI scale, the smallest the Cyrillic gets
+    SmallBulgarianMenu,
+    SmallBulgarianHelp,
+}
+
+pub const SCENES: &[Scene] = &[
+    Scene::Menu,
+    Scene::Help,
+    Scene::MidHand,
+    Scene::Outcome,
+    Scene::BulgarianMenu,
+    Scene::BulgarianHelp,
+    Scene::SmallBulgarianMenu,
+    Scene::SmallBulgarianHelp,
+];
+
+/// Stats shown in the scenes: wins, hints and handicaps
+pub const FIXTURE_STATS: &[u8] = b"12 2 1";
+pub const FIXTURE_PLAYER_NAME: &str = "Kris";
+
+impl Scene {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scene::Menu => "menu",
+            Scene::Help => "help",
+            Scene::MidHand => "mid-hand",
+            Scene::Outcome => "outcome",
+            Scene::BulgarianMenu => "menu-bg",
+            Scene::BulgarianHelp => "help-bg",
+            Scene::SmallBulgarianMenu => "menu-bg-small",
+            Scene::SmallBulgarianHelp => "help-bg-small",
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        match self {
+            Scene::BulgarianMenu | Scene::BulgarianHelp | Scene::SmallBulgarianMenu | Scene::SmallBulgarianHelp => Language::Bulgarian,
+            _ => Language::English,
+        }
+    }
+
+    /// The layout's scale, see layout.rs
+    pub fn ui_scale(&self) -> f32 {
+        match self {
+            Scene::SmallBulgarianMenu | Scene::SmallBulgarianHelp => setup::UI_SCALES[0],
+            _ => 1.0,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        SCENES.iter().find(|scene| scene.name() == name).copied()
+    }
+
+    /// Cards on the table as (player's, dealer's)
+    pub fn cards(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            Scene::MidHand => (&["king_of_diamonds", "6_of_hearts"], &["10_of_spades"]),
+            Scene::Outcome => (&["10_of_hearts", "queen_of_spades"], &["9_of_clubs", "8_of_diamonds"]),
+            _ => (&[], &[]),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for scene in SCENES {
+            assert_eq!(Scene::from_name(scene.name()), Some(*scene));
+        }
+        assert_eq!(Scene::from_name("credits"), None);
+    }
+}
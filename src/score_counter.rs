@@ -0,0 +1,100 @@
+//! The big score numbers count up to a new score instead of jumping to it, and flash when
+//! they pass 17(where the dealer stops) or 21.
+
+/// How long counting up to a new score takes
+pub const COUNT_SECONDS: f32 = 0.3;
+const FLASH_SECONDS: f32 = 0.4;
+/// Scores that flash when a count passes them
+pub const THRESHOLDS: [u32; 2] = [17, 21];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScoreCounter {
+    from: u32,
+    to: u32,
+    elapsed: f32,
+    flash_left: f32,
+}
+
+impl ScoreCounter {
+    /// Starts counting to a new score. A lower score, like the 0 of a new hand, is shown
+    /// right away.
+    pub fn set(&mut self, score: u32) {
+        if score == self.to {
+            return;
+        }
+
+        if score < self.to {
+            *self = ScoreCounter { from: score, to: score, ..ScoreCounter::default() };
+            return;
+        }
+
+        let shown = self.shown();
+        if THRESHOLDS.iter().any(|threshold| shown < *threshold && *threshold <= score) {
+            self.flash_left = COUNT_SECONDS + FLASH_SECONDS;
+        }
+        self.from = shown;
+        self.to = score;
+        self.elapsed = 0.0;
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        self.elapsed += time_delta;
+        self.flash_left = (self.flash_left - time_delta).max(0.0);
+    }
+
+    /// The number to draw
+    pub fn shown(&self) -> u32 {
+        let progress = (self.elapsed / COUNT_SECONDS).min(1.0);
+
+        self.from + ((self.to - self.from) as f32 * progress).round() as u32
+    }
+
+    /// Whether the number is drawn in the flash color
+    pub fn flashing(&self) -> bool {
+        self.flash_left > 0.0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_up_to_the_score() {
+        let mut counter = ScoreCounter::default();
+        counter.set(10);
+        assert_eq!(counter.shown(), 0);
+
+        counter.update(COUNT_SECONDS / 2.0);
+        assert_eq!(counter.shown(), 5);
+
+        counter.update(COUNT_SECONDS);
+        assert_eq!(counter.shown(), 10);
+        assert!(!counter.flashing());
+    }
+
+    #[test]
+    fn passing_a_threshold_flashes() {
+        let mut counter = ScoreCounter::default();
+        counter.set(15);
+        counter.update(COUNT_SECONDS);
+
+        counter.set(19);
+        assert!(counter.flashing());
+
+        counter.update(COUNT_SECONDS + FLASH_SECONDS);
+        assert!(!counter.flashing());
+    }
+
+    #[test]
+    fn new_hand_starts_at_zero_right_away() {
+        let mut counter = ScoreCounter::default();
+        counter.set(18);
+        counter.update(COUNT_SECONDS);
+
+        counter.set(0);
+        assert_eq!(counter.shown(), 0);
+        assert!(!counter.flashing());
+    }
+}
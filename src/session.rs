@@ -0,0 +1,126 @@
+//! The summary of a session, shown once before quitting and written at the end of the session
+//! log(see transcript.rs): the hands played, wins less losses, the best winning hand, how many
+//! of the hits and stands were the best play(see review.rs) and how the best play did on the
+//! same cards(see ghost.rs).
+
+use crate::card::Card;
+use crate::game_engine::Outcome;
+
+/// Totals of the counted hands since the game started.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    pub hands: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Score and short card names of the best hand won, fewer cards being better at a score
+    pub best_hand: Option<(u32, Vec<String>)>,
+    /// Hits and stands checked against the best play, and those that weren't
+    pub decisions: u32,
+    pub mistakes: u32,
+    /// The ghost's wins less losses, None before it played a hand
+    pub optimal_net: Option<i32>,
+}
+
+impl SessionSummary {
+    pub fn on_hand(&mut self, outcome: &Outcome, player_score: u32, player_cards: &[Card]) {
+        self.hands += 1;
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Lose => self.losses += 1,
+            Outcome::Undecided => (),
+        }
+
+        let better = match &self.best_hand {
+            Some((score, cards)) => (player_score, std::cmp::Reverse(player_cards.len())) > (*score, std::cmp::Reverse(cards.len())),
+            None => true,
+        };
+        if *outcome == Outcome::Win && better {
+            self.best_hand = Some((player_score, player_cards.iter().map(Card::short_name).collect()));
+        }
+    }
+
+    /// Wins less losses, each hand being one
+    pub fn net(&self) -> i32 {
+        self.wins as i32 - self.losses as i32
+    }
+
+    /// Share of the decisions that were the best play, None before any
+    pub fn accuracy(&self) -> Option<f32> {
+        if self.decisions == 0 {
+            return None;
+        }
+
+        Some(self.decisions.saturating_sub(self.mistakes) as f32 * 100.0 / self.decisions as f32)
+    }
+
+    /// Lines for the summary screen
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Hands played: {}", self.hands),
+            format!("Won {}, pushed {}, lost {}", self.wins, self.draws, self.losses),
+            format!("Net: {:+} (hands won less hands lost)", self.net()),
+        ];
+
+        lines.push(match &self.best_hand {
+            Some((score, cards)) => format!("Best hand: {} ({})", cards.join(" "), score),
+            None => String::from("Best hand: no hand won"),
+        });
+        if let Some(optimal) = self.optimal_net {
+            lines.push(format!("You: {:+}, Optimal: {:+} (the best play on the same cards, without power-ups)", self.net(), optimal));
+        }
+        if let Some(accuracy) = self.accuracy() {
+            lines.push(format!("Strategy: {:.0}% of {} hits and stands were the best play", accuracy, self.decisions));
+        }
+
+        lines
+    }
+
+    /// The closing line of the session in the session log
+    pub fn log_line(&self) -> String {
+        format!("=== Session summary: {} ===", self.lines().join(", "))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn counts_the_hands() {
+        let mut summary = SessionSummary::default();
+        summary.on_hand(&Outcome::Win, 21, &cards(&["5_of_clubs", "6_of_hearts", "king_of_spades"]));
+        summary.on_hand(&Outcome::Lose, 25, &cards(&["queen_of_clubs", "5_of_hearts", "king_of_hearts"]));
+        summary.on_hand(&Outcome::Lose, 17, &cards(&["10_of_clubs", "7_of_hearts"]));
+        summary.on_hand(&Outcome::Win, 21, &cards(&["ace_of_clubs", "king_of_diamonds"]));
+        summary.on_hand(&Outcome::Win, 20, &cards(&["10_of_diamonds", "queen_of_diamonds"]));
+
+        assert_eq!((summary.hands, summary.wins, summary.draws, summary.losses), (5, 3, 0, 2));
+        assert_eq!(summary.net(), 1);
+        // the two card 21 beats the three card one
+        assert_eq!(summary.best_hand, Some((21, vec![String::from("A♣"), String::from("K♦")])));
+        assert_eq!(summary.accuracy(), None);
+    }
+
+    #[test]
+    fn optimal_play_is_compared() {
+        let summary = SessionSummary { hands: 20, wins: 4, losses: 16, optimal_net: Some(4), ..SessionSummary::default() };
+
+        assert!(summary.lines().contains(&String::from("You: -12, Optimal: +4 (the best play on the same cards, without power-ups)")));
+    }
+
+    #[test]
+    fn log_line_has_everything() {
+        let summary = SessionSummary { hands: 4, wins: 1, draws: 1, losses: 2, decisions: 8, mistakes: 2, ..SessionSummary::default() };
+
+        assert_eq!(summary.log_line(),
+                   "=== Session summary: Hands played: 4, Won 1, pushed 1, lost 2, Net: -1 (hands won less hands lost), \
+                    Best hand: no hand won, Strategy: 75% of 8 hits and stands were the best play ===");
+    }
+}
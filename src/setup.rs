@@ -0,0 +1,127 @@
+//! The first-run wizard, shown after the intro when there are no saves yet: the language, the
+//! player's name, the UI scale and whether to start with the tutorial. Left and Right change
+//! a step, Enter goes on to the next one and the last Enter writes the profile, the config and
+//! the stats file.
+
+use crate::i18n::{self, Language};
+use crate::profile::MAX_NAME_LENGTH;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetupStep {
+    Language,
+    Name,
+    UiScale,
+    Tutorial,
+}
+
+pub const SETUP_STEPS: &[SetupStep] = &[SetupStep::Language, SetupStep::Name, SetupStep::UiScale, SetupStep::Tutorial];
+
+/// The layout's scale(see layout.rs) the player can pick, for screens smaller than the window
+pub const UI_SCALES: &[f32] = &[0.75, 0.85, 1.0];
+
+/// What the player picked so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setup {
+    step: usize,
+    pub language: Language,
+    pub name: String,
+    pub ui_scale: f32,
+    /// Opens the help screen once the wizard is done and turns the odds panel on(see what_if.rs),
+    /// there's no separate tutorial
+    pub tutorial: bool,
+}
+
+impl Setup {
+    /// Starts on the first step, in the language the game came up in.
+    pub fn new(language: Language) -> Self {
+        Setup {
+            step: 0,
+            language,
+            name: String::new(),
+            ui_scale: 1.0,
+            tutorial: true,
+        }
+    }
+
+    pub fn step(&self) -> SetupStep {
+        SETUP_STEPS[self.step]
+    }
+
+    /// Left and Right, on the step being made. The name is typed instead.
+    pub fn change(&mut self, step: i32) {
+        match self.step() {
+            SetupStep::Language => {
+                let current = i18n::LANGUAGES.iter().position(|language| *language == self.language).unwrap_or(0) as i32;
+                self.language = i18n::LANGUAGES[(current + step).rem_euclid(i18n::LANGUAGES.len() as i32) as usize];
+            },
+            SetupStep::UiScale => {
+                let current = UI_SCALES.iter().position(|scale| *scale == self.ui_scale).unwrap_or(0) as i32;
+                self.ui_scale = UI_SCALES[(current + step).clamp(0, UI_SCALES.len() as i32 - 1) as usize];
+            },
+            SetupStep::Tutorial => self.tutorial = !self.tutorial,
+            SetupStep::Name => (),
+        }
+    }
+
+    pub fn type_char(&mut self, character: char) {
+        if self.step() == SetupStep::Name && !character.is_control() && self.name.chars().count() < MAX_NAME_LENGTH {
+            self.name.push(character);
+        }
+    }
+
+    pub fn erase_char(&mut self) {
+        if self.step() == SetupStep::Name {
+            self.name.pop();
+        }
+    }
+
+    /// Enter. True once the last step is made.
+    pub fn next_step(&mut self) -> bool {
+        if self.step + 1 == SETUP_STEPS.len() {
+            return true;
+        }
+
+        self.step += 1;
+        false
+    }
+
+    /// Back to the step before, to change it.
+    pub fn previous_step(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_go_in_order() {
+        let mut setup = Setup::new(Language::English);
+        setup.change(1);
+        assert_eq!(setup.language, Language::Bulgarian);
+
+        assert!(!setup.next_step());
+        setup.type_char('K');
+        setup.type_char('\u{8}');
+        setup.erase_char();
+        setup.type_char('R');
+        assert_eq!(setup.name, "R");
+
+        assert!(!setup.next_step());
+        setup.change(-5);
+        assert_eq!(setup.ui_scale, UI_SCALES[0]);
+        // typing only goes into the name
+        setup.type_char('x');
+        assert_eq!(setup.name, "R");
+
+        assert!(!setup.next_step());
+        setup.change(1);
+        assert!(!setup.tutorial);
+        assert!(setup.next_step());
+
+        setup.previous_step();
+        assert_eq!(setup.step(), SetupStep::UiScale);
+    }
+}
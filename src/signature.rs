@@ -0,0 +1,185 @@
+//! Signed saves, for players on an online leaderboard(see leaderboard.rs). With `sign_saves`
+//! on, stats.txt ends with an HMAC-SHA256 of the rest of the file. A file whose numbers were
+//! edited by hand no longer matches it, and from then on its scores aren't sent: the file is
+//! marked "unverified" inside the signed part, so signing it again doesn't clear the mark.
+//!
+//! The key ships with the game, so this stops a quick edit of the wins, not a determined cheat.
+
+pub const SIGNATURE_KEY: &str = "signature";
+pub const UNVERIFIED_KEY: &str = "unverified";
+
+const HMAC_KEY: &[u8] = b"blackjack stats, please play fair";
+const BLOCK_SIZE: usize = 64;
+
+/// Whether the signature of a file holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seal {
+    Signed,
+    Unsigned,
+    /// Signed, but changed since
+    Broken,
+}
+
+/// `contents` with its signature line at the end.
+pub fn signed(contents: &str) -> String {
+    format!("{}{} = {}\n", contents, SIGNATURE_KEY, hex(&hmac(HMAC_KEY, contents.as_bytes())))
+}
+
+pub fn check(contents: &str) -> Seal {
+    let start = match contents.rfind(&format!("{} = ", SIGNATURE_KEY)) {
+        Some(start) => start,
+        None => return Seal::Unsigned,
+    };
+    let (signed_part, signature_line) = contents.split_at(start);
+    let signature = signature_line.trim_end().trim_start_matches(SIGNATURE_KEY).trim_start().trim_start_matches('=').trim();
+
+    if signature == hex(&hmac(HMAC_KEY, signed_part.as_bytes())) {
+        Seal::Signed
+    } else {
+        Seal::Broken
+    }
+}
+
+/// Whether the scores of a stats file can't be trusted: it was marked before, or `signing` is
+/// on and the signature doesn't match. A file without a signature(from before signing was
+/// turned on, or a backup of one) is trusted and gets signed when it's saved next.
+pub fn unverified(contents: &str, signing: bool) -> bool {
+    let marked = contents.lines().
+        filter_map(crate::config::parse_line).
+        any(|(key, value)| key == UNVERIFIED_KEY && value == "true");
+
+    marked || (signing && check(contents) == Seal::Broken)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// HMAC(RFC 2104) with SHA-256.
+fn hmac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let inner: Vec<u8> = block.iter().map(|byte| byte ^ 0x36).chain(message.iter().copied()).collect();
+    let outer: Vec<u8> = block.iter().map(|byte| byte ^ 0x5c).chain(sha256(&inner)).collect();
+
+    sha256(&outer)
+}
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256(FIPS 180-4), small enough to keep here rather than add a dependency for.
+fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+    // a 1 bit, zeros up to 8 bytes short of a block and then the length in bits
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % BLOCK_SIZE != BLOCK_SIZE - 8 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+    for chunk in padded.chunks(BLOCK_SIZE) {
+        let mut words = [0u32; 64];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = words[i - 15].rotate_right(7) ^ words[i - 15].rotate_right(18) ^ (words[i - 15] >> 3);
+            let s1 = words[i - 2].rotate_right(17) ^ words[i - 2].rotate_right(19) ^ (words[i - 2] >> 10);
+            words[i] = words[i - 16].wrapping_add(s0).wrapping_add(words[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(ROUND_CONSTANTS[i]).wrapping_add(words[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+
+    digest
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_and_hmac_match_the_standards() {
+        assert_eq!(hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hex(&sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        // RFC 4231, test case 2
+        assert_eq!(hex(&hmac(b"Jefe", b"what do ya want for nothing?")), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn an_edit_breaks_the_seal() {
+        let contents = signed("12 2 1\nversion = 2\n");
+
+        assert_eq!(check(&contents), Seal::Signed);
+        assert_eq!(check(&contents.replace("12 2 1", "99 2 1")), Seal::Broken);
+        assert_eq!(check("12 2 1\nversion = 2\n"), Seal::Unsigned);
+    }
+
+    #[test]
+    fn unverified_sticks() {
+        let contents = signed("12 2 1\nversion = 2\n");
+        assert!(!unverified(&contents, true));
+        assert!(!unverified("12 2 1\n", false));
+        assert!(unverified(&contents.replace("12 2 1", "99 2 1"), true));
+
+        // signed again by the game, with the mark in the signed part
+        let marked = signed("99 2 1\nversion = 2\nunverified = true\n");
+        assert!(unverified(&marked, true));
+        assert!(unverified(&marked, false));
+    }
+
+    #[test]
+    fn first_start_with_signing_trusts_the_unsigned_file() {
+        // stats.txt as it was saved before sign_saves was turned on
+        let before = "12 2 1\nversion = 2\n";
+        assert!(!unverified(before, true));
+
+        // the next save signs it, and it holds from then on
+        let saved = signed(before);
+        assert_eq!(check(&saved), Seal::Signed);
+        assert!(!unverified(&saved, true));
+    }
+}
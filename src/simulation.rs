@@ -0,0 +1,337 @@
+use crate::board::Turn;
+use crate::game_action::{GameAction, PowerUp};
+use crate::game_engine::{GameEngine, Outcome};
+use crate::probability::{self, BestPlay, Rules};
+
+use rand::Rng;
+
+/// Hands in one simulated session
+pub const SIMULATED_HANDS: usize = 1000;
+/// Sessions simulated to estimate how often the bankroll runs out
+pub const SIMULATED_SESSIONS: usize = 200;
+
+/// How the bet changes from hand to hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BettingStrategy {
+    /// Always the base bet
+    Flat,
+    /// Doubles the bet after every loss and goes back to the base bet after a win
+    Martingale,
+    /// Bets the share of the bankroll that grows it fastest: edge / variance, nothing without an edge
+    Kelly,
+}
+
+pub const STRATEGIES: &[BettingStrategy] = &[BettingStrategy::Flat, BettingStrategy::Martingale, BettingStrategy::Kelly];
+
+impl BettingStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BettingStrategy::Flat => "flat",
+            BettingStrategy::Martingale => "Martingale",
+            BettingStrategy::Kelly => "Kelly",
+        }
+    }
+
+    /// Bet for the next hand, never more than the bankroll or than a win could add to it.
+    pub fn next_bet(&self, base_bet: u32, last_bet: u32, last_outcome: Option<&Outcome>, bankroll: u32, best: &BestPlay) -> u32 {
+        let bet = match self {
+            BettingStrategy::Flat => base_bet,
+            BettingStrategy::Martingale => match last_outcome {
+                Some(Outcome::Lose) => last_bet.saturating_mul(2),
+                Some(Outcome::Draw) => last_bet,
+                _ => base_bet,
+            },
+            BettingStrategy::Kelly => {
+                let fraction = (best.odds.expected_value() / best.odds.variance()).clamp(0.0, 1.0);
+                (bankroll as f64 * fraction) as u32
+            },
+        };
+
+        bet.min(bankroll).min(u32::MAX - bankroll)
+    }
+}
+
+fn draw_points<R: Rng>(rng: &mut R) -> u32 {
+    // ace counted as 1, 10 to king as 10
+    rng.gen_range(1..=13).min(10)
+}
+
+/// Plays one hand with a freshly shuffled deck for every card, the player making the best choice
+/// from `best`. The engine decides the outcome, as in the game.
+pub fn play_hand<R: Rng>(rng: &mut R, best: &BestPlay, rules: Rules) -> Outcome {
+    play_hand_with(|| Some(draw_points(rng)), best, rules)
+}
+
+/// Plays one hand with the cards `draw` gives, in points with an ace as 1, the player making the
+/// best choice from `best`. Cards running out end the drawing. The player's actions go through
+/// GameEngine::apply, as the game's do.
+pub fn play_hand_with<F: FnMut() -> Option<u32>>(mut draw: F, best: &BestPlay, rules: Rules) -> Outcome {
+    let mut engine = GameEngine::new();
+    let mut turn = Turn::Player;
+    if rules.dealer_handicap {
+        let _ = engine.apply(GameAction::UsePowerUp(PowerUp::Handicap), &mut turn, None);
+    }
+
+    let (mut hard, mut has_ace) = (0, false);
+    while matches!(turn, Turn::Player) && !engine.game_over && best.should_draw(hard, has_ace) {
+        if engine.apply(GameAction::Hit, &mut turn, None).is_err() {
+            break;
+        }
+        let points = match draw() {
+            Some(points) => points,
+            None => break,
+        };
+        hard += points;
+        has_ace |= points == 1;
+
+        engine.player_score = probability::best_value(hard, has_ace);
+        engine.check_outcome(&mut turn);
+    }
+    // a hand that's over or already the dealer's turns the stand down, nothing to do then
+    let _ = engine.apply(GameAction::Stand, &mut turn, None);
+
+    let (mut hard, mut has_ace) = (0, false);
+    while engine.dealer_must_draw() {
+        let points = match draw() {
+            Some(points) => points,
+            None => break,
+        };
+        hard += points;
+        has_ace |= points == 1;
+
+        engine.dealer_score = probability::best_value(hard, has_ace);
+        engine.check_outcome(&mut turn);
+    }
+
+    engine.outcome
+}
+
+/// Bankroll after a hand: a win pays the bet, a draw gives it back and a loss keeps it.
+pub fn settle(money: u32, bet: u32, outcome: &Outcome) -> u32 {
+    match outcome {
+        Outcome::Win => money + bet,
+        Outcome::Lose => money - bet,
+        _ => money,
+    }
+}
+
+/// The chips of one simulated hand. No chip appears or disappears: the bankroll before the hand,
+/// minus the bet put on the table, plus what the table pays back is the bankroll after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChipAudit {
+    pub strategy: BettingStrategy,
+    pub hand: usize,
+    pub before: u32,
+    pub bet: u32,
+    pub outcome: Outcome,
+    pub after: u32,
+}
+
+impl ChipAudit {
+    /// What the table pays back for the bet, counted apart from settle.
+    fn paid_back(&self) -> u64 {
+        match self.outcome {
+            Outcome::Win => 2 * self.bet as u64,
+            Outcome::Draw => self.bet as u64,
+            _ => 0,
+        }
+    }
+
+    pub fn balances(&self) -> bool {
+        self.bet <= self.before && self.before as u64 - self.bet as u64 + self.paid_back() == self.after as u64
+    }
+
+    /// Everything about the hand, for when it doesn't balance.
+    pub fn dump(&self) -> String {
+        format!("chips not conserved in hand {} of a {} session: bankroll {}, bet {}, {}, paid back {}, bankroll after {} instead of {}",
+                self.hand,
+                self.strategy.name(),
+                self.before,
+                self.bet,
+                self.outcome.name(),
+                self.paid_back(),
+                self.after,
+                (self.before as u64 + self.paid_back()).saturating_sub(self.bet as u64))
+    }
+}
+
+/// One session with a betting strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// Bankroll before the first hand and after every hand
+    pub bankroll: Vec<u32>,
+    /// The hand that took the last of the bankroll
+    pub ruined_at: Option<usize>,
+    /// A hand's chips didn't balance(see ChipAudit), the session stopped before it
+    pub unbalanced: bool,
+}
+
+impl Session {
+    pub fn final_bankroll(&self) -> u32 {
+        *self.bankroll.last().unwrap_or(&0)
+    }
+}
+
+/// Every hand's chips are checked in debug builds, and in release builds with `audit`. A debug
+/// build panics on a hand that doesn't balance, a release build logs it and marks the session.
+pub fn simulate_session<R: Rng>(rng: &mut R, strategy: BettingStrategy, base_bet: u32, bankroll: u32, hands: usize, rules: Rules, audit: bool) -> Session {
+    let best = probability::best_play(rules);
+    let mut session = Session { bankroll: vec![bankroll], ruined_at: None, unbalanced: false };
+
+    let (mut money, mut last_bet, mut last_outcome) = (bankroll, base_bet, None);
+    for hand in 1..=hands {
+        let bet = strategy.next_bet(base_bet, last_bet, last_outcome.as_ref(), money, &best);
+        if bet == 0 {
+            break;
+        }
+
+        let outcome = play_hand(rng, &best, rules);
+        let before = money;
+        money = settle(money, bet, &outcome);
+
+        if audit || cfg!(debug_assertions) {
+            let round = ChipAudit { strategy, hand, before, bet, outcome: outcome.clone(), after: money };
+            if !round.balances() {
+                if cfg!(debug_assertions) {
+                    panic!("{}", round.dump());
+                }
+                eprintln!("{}", round.dump());
+                session.unbalanced = true;
+                break;
+            }
+        }
+        session.bankroll.push(money);
+
+        if money == 0 {
+            session.ruined_at = Some(hand);
+            break;
+        }
+        last_bet = bet;
+        last_outcome = Some(outcome);
+    }
+
+    session
+}
+
+/// Many sessions with the same strategy, one of them kept to chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub strategy: BettingStrategy,
+    pub starting_bankroll: u32,
+    pub example: Session,
+    /// Share of the sessions that lost the whole bankroll
+    pub ruined: f64,
+    pub average_final_bankroll: f64,
+    /// The chip audit caught a session that didn't balance, so the numbers can't be trusted
+    pub failed: bool,
+}
+
+impl Simulation {
+    pub fn run<R: Rng>(rng: &mut R, strategy: BettingStrategy, base_bet: u32, bankroll: u32, rules: Rules, audit: bool) -> Self {
+        let sessions: Vec<Session> = (0..SIMULATED_SESSIONS).
+            map(|_| simulate_session(rng, strategy, base_bet, bankroll, SIMULATED_HANDS, rules, audit)).
+            collect();
+
+        let ruined = sessions.iter().filter(|session| session.ruined_at.is_some()).count();
+        let total: f64 = sessions.iter().map(|session| session.final_bankroll() as f64).sum();
+        let failed = sessions.iter().any(|session| session.unbalanced);
+
+        Simulation {
+            strategy,
+            starting_bankroll: bankroll,
+            ruined: ruined as f64 / sessions.len() as f64,
+            average_final_bankroll: total / sessions.len() as f64,
+            failed,
+            example: sessions.into_iter().next().unwrap(),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        if self.failed {
+            return format!("{} betting: the chip audit failed, the simulation is wrong(see the log)", self.strategy.name());
+        }
+
+        format!("{} betting from {}: {:.0}% of {} sessions went broke within {} hands, {:.0} left on average",
+                self.strategy.name(),
+                self.starting_bankroll,
+                self.ruined * 100.0,
+                SIMULATED_SESSIONS,
+                SIMULATED_HANDS,
+                self.average_final_bankroll)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn martingale_doubles_after_losses() {
+        let best = probability::best_play(Rules::default());
+        let strategy = BettingStrategy::Martingale;
+
+        assert_eq!(strategy.next_bet(10, 40, Some(&Outcome::Lose), 1000, &best), 80);
+        assert_eq!(strategy.next_bet(10, 40, Some(&Outcome::Win), 1000, &best), 10);
+        assert_eq!(strategy.next_bet(10, 640, Some(&Outcome::Lose), 1000, &best), 1000);
+    }
+
+    #[test]
+    fn kelly_only_bets_with_an_edge() {
+        let strategy = BettingStrategy::Kelly;
+
+        let mut best = probability::best_play(Rules { dealer_handicap: true });
+        // with the handicap the dealer draws on to an effective 17, which keeps the house edge
+        assert_eq!(strategy.next_bet(10, 10, None, 1000, &probability::best_play(Rules::default())), 0);
+        assert_eq!(strategy.next_bet(10, 10, None, 1000, &best), 0);
+
+        best.odds = probability::HandOdds { win: 0.5, draw: 0.1, lose: 0.4 };
+        assert!(strategy.next_bet(10, 10, None, 1000, &best) > 0);
+    }
+
+    #[test]
+    fn simulated_win_rate_matches_the_odds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let best = probability::best_play(Rules::default());
+
+        let hands = 20000;
+        let wins = (0..hands).filter(|_| play_hand(&mut rng, &best, Rules::default()) == Outcome::Win).count();
+
+        assert!((wins as f64 / hands as f64 - best.odds.win).abs() < 0.02);
+    }
+
+    #[test]
+    fn sessions_stop_when_broke() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let session = simulate_session(&mut rng, BettingStrategy::Martingale, 10, 100, SIMULATED_HANDS, Rules::default(), false);
+
+        if let Some(hand) = session.ruined_at {
+            assert_eq!(session.final_bankroll(), 0);
+            assert_eq!(session.bankroll.len(), hand + 1);
+        }
+        assert!(session.bankroll.len() <= SIMULATED_HANDS + 1);
+    }
+
+    #[test]
+    fn audit_catches_lost_chips() {
+        let round = |outcome: Outcome, after: u32| ChipAudit { strategy: BettingStrategy::Flat, hand: 3, before: 100, bet: 10, outcome, after };
+
+        assert!(round(Outcome::Win, settle(100, 10, &Outcome::Win)).balances());
+        assert!(round(Outcome::Draw, settle(100, 10, &Outcome::Draw)).balances());
+        assert!(round(Outcome::Lose, settle(100, 10, &Outcome::Lose)).balances());
+
+        let broken = round(Outcome::Win, 100);
+        assert!(!broken.balances());
+        assert!(broken.dump().contains("hand 3 of a flat session"));
+        assert!(broken.dump().ends_with("bankroll after 100 instead of 110"));
+    }
+
+    #[test]
+    fn bets_fit_what_a_win_pays() {
+        let best = probability::best_play(Rules { dealer_handicap: true });
+
+        assert_eq!(BettingStrategy::Flat.next_bet(10, 10, None, u32::MAX - 4, &best), 4);
+        assert_eq!(BettingStrategy::Kelly.next_bet(10, 10, None, u32::MAX, &best), 0);
+    }
+}
@@ -0,0 +1,150 @@
+//! Situations to drill: the player types a starting hand on the trainer screen, like "soft 18",
+//! "hard 16" or "pair of 8s", and practice hands start from it again and again. The two cards
+//! go on top of a shuffled deck, so the suits, the cards that follow and the dealer's hand are
+//! new every time. The dealer only draws once the player stands, so there's no up-card to set.
+
+use crate::card::Card;
+use crate::probability::BestPlay;
+use crate::review::{self, action_name};
+use crate::scenario::Action;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Rank names worth 10
+const TENS: &[&str] = &["10", "jack", "queen", "king"];
+const SUITS: &[&str] = &["clubs", "diamonds", "hearts", "spades"];
+
+/// A starting hand, as its two ranks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Situation {
+    /// Two cards without an ace making this, 4 to 20
+    Hard(u32),
+    /// An ace and a card making this, 13 to 20
+    Soft(u32),
+    /// Two of the same points, 1 being aces
+    Pair(u32),
+}
+
+/// "8", "8s", "a", "ace" or "aces" as points, aces being 1
+fn parse_points(text: &str) -> Option<u32> {
+    match text {
+        "a" | "ace" | "aces" => Some(1),
+        "ten" | "tens" => Some(10),
+        _ => text.trim_end_matches('s').parse().ok().filter(|points| (2..=10).contains(points)),
+    }
+}
+
+impl Situation {
+    /// "soft 18", "hard 16", "16", "pair of 8s", "pair of aces"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim().to_lowercase();
+        if text.contains("vs") {
+            return Err(String::from("the dealer draws after you stand, so only your hand can be set"));
+        }
+
+        let number = |text: &str| text.trim().parse::<u32>().ok();
+        let situation = if let Some(pair) = text.strip_prefix("pair of ").or_else(|| text.strip_prefix("pair ")) {
+            parse_points(pair.trim()).map(Situation::Pair)
+        } else if let Some(soft) = text.strip_prefix("soft ") {
+            number(soft).filter(|total| (13..=20).contains(total)).map(Situation::Soft)
+        } else {
+            number(text.strip_prefix("hard ").unwrap_or(&text)).filter(|total| (4..=20).contains(total)).map(Situation::Hard)
+        };
+
+        situation.ok_or_else(|| String::from("try \"soft 18\", \"hard 16\" or \"pair of 8s\" (soft 13 to 20, hard 4 to 20)"))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Situation::Hard(total) => format!("hard {}", total),
+            Situation::Soft(total) => format!("soft {}", total),
+            Situation::Pair(1) => String::from("a pair of aces"),
+            Situation::Pair(points) => format!("a pair of {}s", points),
+        }
+    }
+
+    /// Names of two cards making the hand, suits and ranks picked with `rng`.
+    pub fn deal<R: Rng>(&self, rng: &mut R) -> Vec<String> {
+        let (first, second) = match self {
+            Situation::Hard(total) => {
+                // any split of the total into two cards of 2 to 10
+                let firsts: Vec<u32> = (2..=10).filter(|first| (2..=10).contains(&(total - first))).collect();
+                let first = *firsts.choose(rng).unwrap();
+                (first, total - first)
+            },
+            Situation::Soft(total) => (1, total - 11),
+            Situation::Pair(points) => (*points, *points),
+        };
+
+        // a pair takes different suits, and no card can be dealt twice anyway
+        let mut suits = SUITS.to_vec();
+        suits.shuffle(rng);
+        [first, second].iter().zip(suits).
+            map(|(points, suit)| {
+                let rank = match points {
+                    1 => String::from("ace"),
+                    10 => TENS.choose(rng).unwrap().to_string(),
+                    points => points.to_string(),
+                };
+                format!("{}_of_{}", rank, suit)
+            }).
+            collect()
+    }
+
+    /// What the player is told after deciding on the situation's two `cards`.
+    pub fn verdict(&self, best: &BestPlay, cards: &[Card], chosen: Action) -> String {
+        match review::check(best, cards, chosen) {
+            Some(mistake) => format!("Not the best play: {}", mistake.describe()),
+            None => format!("Right, {} is the best play on {}", action_name(chosen), self.name()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_engine;
+    use crate::probability::{self, Rules};
+
+    #[test]
+    fn parses_the_usual_ways_of_saying_it() {
+        assert_eq!(Situation::parse("soft 18"), Ok(Situation::Soft(18)));
+        assert_eq!(Situation::parse("Hard 16"), Ok(Situation::Hard(16)));
+        assert_eq!(Situation::parse("12"), Ok(Situation::Hard(12)));
+        assert_eq!(Situation::parse("pair of 8s"), Ok(Situation::Pair(8)));
+        assert_eq!(Situation::parse("pair of aces"), Ok(Situation::Pair(1)));
+
+        assert!(Situation::parse("soft 21").is_err());
+        assert!(Situation::parse("pair of 11s").is_err());
+        assert!(Situation::parse("16 vs 10").is_err());
+    }
+
+    #[test]
+    fn deals_the_hand_asked_for() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..50 {
+            let hard: Vec<Card> = Situation::Hard(16).deal(&mut rng).iter().map(|name| Card::new(name)).collect();
+            assert_eq!(game_engine::hand_value(&hard), 16);
+            assert!(!hard.iter().any(Card::is_an_ace));
+
+            let soft: Vec<Card> = Situation::Soft(18).deal(&mut rng).iter().map(|name| Card::new(name)).collect();
+            assert_eq!(game_engine::hand_value(&soft), 18);
+            assert!(soft[0].is_an_ace());
+
+            let pair = Situation::Pair(8).deal(&mut rng);
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn says_whether_the_decision_was_best() {
+        let best = probability::best_play(Rules::default());
+        let cards: Vec<Card> = ["10_of_clubs", "king_of_hearts"].iter().map(|name| Card::new(name)).collect();
+
+        assert_eq!(Situation::Hard(20).verdict(&best, &cards, Action::Stand), "Right, stand is the best play on hard 20");
+        assert!(Situation::Hard(20).verdict(&best, &cards, Action::Hit).starts_with("Not the best play: hit on 20"));
+    }
+}
@@ -0,0 +1,167 @@
+//! The engine's state at one moment, written as "key = value" lines like the save files, and
+//! what changed between two of them. Debug builds print every change to the terminal, and the
+//! last state is added to a panic's message.
+
+use crate::config;
+use crate::game_engine::{HintStatus, Outcome};
+
+use std::sync::Mutex;
+
+/// The state the last panic report will show, see record
+static LAST_SNAPSHOT: Mutex<Option<EngineSnapshot>> = Mutex::new(None);
+
+fn hint_name(hint: &HintStatus) -> &'static str {
+    match hint {
+        HintStatus::Unused => "unused",
+        HintStatus::Active => "active",
+        HintStatus::Exhausted => "exhausted",
+    }
+}
+
+fn hint_from_name(name: &str) -> Option<HintStatus> {
+    match name {
+        "unused" => Some(HintStatus::Unused),
+        "active" => Some(HintStatus::Active),
+        "exhausted" => Some(HintStatus::Exhausted),
+        _ => None,
+    }
+}
+
+/// Everything in a GameEngine, see GameEngine::snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSnapshot {
+    pub player_score: u32,
+    pub dealer_score: u32,
+    /// Name of the HintStatus
+    pub hint: &'static str,
+    pub dealer_handicap_active: bool,
+    pub game_over: bool,
+    pub outcome: Outcome,
+    pub auto_stand_on_21: bool,
+}
+
+impl EngineSnapshot {
+    pub fn new(player_score: u32, dealer_score: u32, hint: &HintStatus, dealer_handicap_active: bool,
+               game_over: bool, outcome: &Outcome, auto_stand_on_21: bool) -> Self {
+        EngineSnapshot {
+            player_score,
+            dealer_score,
+            hint: hint_name(hint),
+            dealer_handicap_active,
+            game_over,
+            outcome: outcome.clone(),
+            auto_stand_on_21,
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, String); 7] {
+        [
+            ("player_score", self.player_score.to_string()),
+            ("dealer_score", self.dealer_score.to_string()),
+            ("hint", self.hint.to_string()),
+            ("dealer_handicap_active", self.dealer_handicap_active.to_string()),
+            ("game_over", self.game_over.to_string()),
+            ("outcome", self.outcome.name().to_string()),
+            ("auto_stand_on_21", self.auto_stand_on_21.to_string()),
+        ]
+    }
+
+    pub fn to_text(&self) -> String {
+        self.fields().iter().map(|(key, value)| format!("{} = {}\n", key, value)).collect()
+    }
+
+    /// Reads to_text back. None when a line is missing or can't be read.
+    pub fn parse(contents: &str) -> Option<Self> {
+        let mut values = std::collections::HashMap::new();
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            values.insert(key, value);
+        }
+
+        Some(EngineSnapshot {
+            player_score: values.get("player_score")?.parse().ok()?,
+            dealer_score: values.get("dealer_score")?.parse().ok()?,
+            hint: hint_name(&hint_from_name(values.get("hint")?)?),
+            dealer_handicap_active: values.get("dealer_handicap_active")?.parse().ok()?,
+            game_over: values.get("game_over")?.parse().ok()?,
+            outcome: Outcome::from_name(values.get("outcome")?)?,
+            auto_stand_on_21: values.get("auto_stand_on_21")?.parse().ok()?,
+        })
+    }
+
+    /// "player_score: 12 -> 19" for every value that differs in `later`, in field order.
+    pub fn diff(&self, later: &EngineSnapshot) -> Vec<String> {
+        self.fields().iter().
+            zip(later.fields().iter()).
+            filter(|(before, after)| before.1 != after.1).
+            map(|((key, before), (_, after))| format!("{}: {} -> {}", key, before, after)).
+            collect()
+    }
+}
+
+/// Keeps `snapshot` for the panic report. In debug builds the changes since the last one are
+/// printed too.
+pub fn record(snapshot: &EngineSnapshot) {
+    let mut last = match LAST_SNAPSHOT.lock() {
+        Ok(last) => last,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    if last.as_ref() == Some(snapshot) {
+        return;
+    }
+    if cfg!(debug_assertions) {
+        if let Some(before) = last.as_ref() {
+            eprintln!("engine: {}", before.diff(snapshot).join(", "));
+        }
+    }
+
+    *last = Some(snapshot.clone());
+}
+
+/// Adds the last recorded engine state to the message of any panic.
+pub fn add_to_panic_reports() {
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        // try_lock, the panic may have happened while recording
+        if let Ok(last) = LAST_SNAPSHOT.try_lock() {
+            if let Some(snapshot) = last.as_ref() {
+                eprintln!("Engine state at the time:\n{}", snapshot.to_text());
+            }
+        }
+    }));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_engine::GameEngine;
+
+    #[test]
+    fn text_round_trip() {
+        let mut engine = GameEngine::new();
+        engine.player_score = 19;
+        engine.hint = HintStatus::Exhausted;
+        engine.outcome = Outcome::Win;
+
+        let snapshot = engine.snapshot();
+        assert_eq!(EngineSnapshot::parse(&snapshot.to_text()), Some(snapshot));
+        assert_eq!(EngineSnapshot::parse("player_score = 19"), None);
+    }
+
+    #[test]
+    fn diff_lists_what_changed() {
+        let mut engine = GameEngine::new();
+        let before = engine.snapshot();
+        assert!(before.diff(&before).is_empty());
+
+        engine.player_score = 22;
+        engine.game_over = true;
+        engine.outcome = Outcome::Lose;
+
+        assert_eq!(before.diff(&engine.snapshot()), ["player_score: 0 -> 22", "game_over: false -> true", "outcome: undecided -> lose"]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Game speed presets: how fast the cards move, how long the dealer waits between cards and
+//! how long a finished hand stays up, picked as one setting.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum GameSpeed {
+    Casual,
+    #[default]
+    Normal,
+    Speed,
+}
+
+pub const SPEEDS: &[GameSpeed] = &[GameSpeed::Casual, GameSpeed::Normal, GameSpeed::Speed];
+
+impl GameSpeed {
+    /// Also the value in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameSpeed::Casual => "casual",
+            GameSpeed::Normal => "normal",
+            GameSpeed::Speed => "speed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        SPEEDS.iter().find(|speed| speed.name() == name).copied()
+    }
+
+    pub fn next(&self, step: i32) -> Self {
+        let current = SPEEDS.iter().position(|speed| speed == self).unwrap_or(0) as i32;
+
+        SPEEDS[(current + step).rem_euclid(SPEEDS.len() as i32) as usize]
+    }
+
+    /// How many times faster than normal the cards move and flip
+    pub fn animation_speed(&self) -> f32 {
+        match self {
+            GameSpeed::Casual => 0.75,
+            GameSpeed::Normal => 1.0,
+            GameSpeed::Speed => 2.0,
+        }
+    }
+
+    /// How long the dealer waits before drawing each card
+    pub fn dealer_delay(&self) -> f32 {
+        match self {
+            GameSpeed::Casual => 0.8,
+            GameSpeed::Normal => 0.3,
+            GameSpeed::Speed => 0.0,
+        }
+    }
+
+    /// How long the finished hand stays on the table before the outcome is shown
+    pub fn seconds_till_outcome(&self) -> f32 {
+        match self {
+            GameSpeed::Casual => 4.0,
+            GameSpeed::Normal => 4.0,
+            GameSpeed::Speed => 1.5,
+        }
+    }
+
+    /// How long the outcome is shown before going back to the menu, or on to the next hand with
+    /// `auto_advance`. Otherwise Casual waits for Enter or R.
+    pub fn seconds_on_outcome(&self, auto_advance: bool) -> Option<f32> {
+        match self {
+            GameSpeed::Casual if auto_advance => Some(5.0),
+            GameSpeed::Casual => None,
+            GameSpeed::Normal => Some(3.0),
+            GameSpeed::Speed => Some(1.0),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for speed in SPEEDS {
+            assert_eq!(GameSpeed::from_name(speed.name()), Some(*speed));
+        }
+        assert_eq!(GameSpeed::from_name("warp"), None);
+    }
+
+    #[test]
+    fn next_wraps_around() {
+        assert_eq!(GameSpeed::Speed.next(1), GameSpeed::Casual);
+        assert_eq!(GameSpeed::Casual.next(-1), GameSpeed::Speed);
+        assert_eq!(GameSpeed::Normal.next(1), GameSpeed::Speed);
+    }
+
+    #[test]
+    fn faster_presets_wait_less() {
+        for pair in SPEEDS.windows(2) {
+            assert!(pair[0].animation_speed() < pair[1].animation_speed());
+            assert!(pair[0].dealer_delay() > pair[1].dealer_delay());
+            assert!(pair[0].seconds_till_outcome() >= pair[1].seconds_till_outcome());
+        }
+    }
+
+    #[test]
+    fn only_casual_waits_for_a_key() {
+        assert_eq!(GameSpeed::Casual.seconds_on_outcome(false), None);
+        assert!(SPEEDS.iter().all(|speed| speed.seconds_on_outcome(true).is_some()));
+        assert_eq!(GameSpeed::Normal.seconds_on_outcome(false), GameSpeed::Normal.seconds_on_outcome(true));
+    }
+}
@@ -0,0 +1,106 @@
+//! The intro before the menu: the title fades in while a few cards slide onto the felt. Any key
+//! or click skips it, and the card images keep loading meanwhile(see AssetManager).
+
+use crate::card::Card;
+use crate::fonts::FontManager;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+/// How long the intro lasts when it isn't skipped
+pub const SPLASH_SECONDS: f32 = 2.6;
+const FADE_IN_SECONDS: f32 = 0.9;
+/// How long each card takes to slide in, the next one starting a little later
+const SLIDE_SECONDS: f32 = 0.6;
+const CARD_DELAY: f32 = 0.2;
+
+pub const SPLASH_CARDS: [&str; 4] = ["ace_of_spades", "king_of_hearts", "queen_of_clubs", "jack_of_diamonds"];
+const CARD_SIZE: f32 = 160.0;
+const CARD_SPACING: f32 = 170.0;
+const TITLE_SIZE: f32 = 140.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Splash {
+    elapsed: f32,
+}
+
+impl Splash {
+    pub fn update(&mut self, time_delta: f32) {
+        self.elapsed = (self.elapsed + time_delta).min(SPLASH_SECONDS);
+    }
+
+    pub fn skip(&mut self) {
+        self.elapsed = SPLASH_SECONDS;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= SPLASH_SECONDS
+    }
+
+    /// Opacity of the title
+    pub fn title_alpha(&self) -> f32 {
+        (self.elapsed / FADE_IN_SECONDS).min(1.0)
+    }
+
+    /// How far the `i`th card got, from 0 off the left of the screen to 1 in its place. It
+    /// slows down as it arrives.
+    pub fn card_progress(&self, i: usize) -> f32 {
+        let time = (self.elapsed - i as f32 * CARD_DELAY) / SLIDE_SECONDS;
+        let time = time.clamp(0.0, 1.0);
+
+        1.0 - (1.0 - time).powi(3)
+    }
+}
+
+/// The title at `title_position` in `title_font`, and the cards in a row from `first_card_position`.
+pub fn draw(ctx: &mut Context, splash: &Splash, fonts: &FontManager, title: &str, title_font: graphics::Font, title_position: Point2<f32>, first_card_position: Point2<f32>) -> GameResult<()> {
+    let title_fragment = graphics::TextFragment::new(title).
+                                                  color(graphics::Color::new(1.0, 1.0, 1.0, splash.title_alpha())).
+                                                  font(title_font).
+                                                  scale(graphics::PxScale::from(TITLE_SIZE));
+    graphics::draw(ctx, &graphics::Text::new(title_fragment), graphics::DrawParam::default().dest(title_position))?;
+
+    for (i, name) in SPLASH_CARDS.iter().enumerate() {
+        let place = first_card_position.x + i as f32 * CARD_SPACING;
+        let x = -CARD_SIZE + (place + CARD_SIZE) * splash.card_progress(i);
+        let card_text = fonts.card_text(&Card::new(name).short_name(), CARD_SIZE);
+
+        graphics::draw(ctx, &card_text, graphics::DrawParam::default().dest(Point2 { x, y: first_card_position.y }))?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cards_arrive_one_after_another() {
+        let mut splash = Splash::default();
+        assert_eq!(splash.title_alpha(), 0.0);
+        assert_eq!(splash.card_progress(0), 0.0);
+
+        splash.update(CARD_DELAY);
+        assert!(splash.card_progress(0) > 0.0);
+        assert_eq!(splash.card_progress(1), 0.0);
+
+        splash.update(SPLASH_SECONDS);
+        assert!(splash.finished());
+        assert_eq!(splash.title_alpha(), 1.0);
+        assert_eq!(splash.card_progress(SPLASH_CARDS.len() - 1), 1.0);
+    }
+
+    #[test]
+    fn skipping_ends_it() {
+        let mut splash = Splash::default();
+        splash.skip();
+
+        assert!(splash.finished());
+    }
+}
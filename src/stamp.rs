@@ -0,0 +1,148 @@
+//! "BUST" and "BLACKJACK" stamped over a hand once it busts or makes 21 with two cards. A stamp
+//! comes down big and shrinks to its size, a little tilted like a rubber stamp.
+
+use crate::card::Card;
+use crate::game_engine;
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+/// How long a stamp takes to come down
+const SCALE_IN_SECONDS: f32 = 0.25;
+/// Size of a stamp when it appears, compared to its final size
+const START_SCALE: f32 = 2.5;
+/// Radians, counterclockwise
+const ROTATION: f32 = -0.25;
+const BORDER_PADDING: f32 = 10.0;
+const BORDER_WIDTH: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StampKind {
+    Bust,
+    Blackjack,
+}
+
+impl StampKind {
+    /// What the hand's cards get stamped with, if anything.
+    pub fn of(cards: &[Card]) -> Option<Self> {
+        let value = game_engine::hand_value(cards);
+
+        if value > 21 {
+            Some(StampKind::Bust)
+        } else if value == 21 && cards.len() == 2 {
+            Some(StampKind::Blackjack)
+        } else {
+            None
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            StampKind::Bust => "BUST",
+            StampKind::Blackjack => "BLACKJACK",
+        }
+    }
+
+    fn color(&self) -> graphics::Color {
+        match self {
+            StampKind::Bust => graphics::Color::from_rgb(204, 0, 0),
+            StampKind::Blackjack => graphics::Color::from_rgb(212, 175, 55),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stamp {
+    pub kind: StampKind,
+    /// Seconds since it appeared
+    pub age: f32,
+}
+
+impl Stamp {
+    /// Size compared to the final one, easing out from START_SCALE to 1.
+    pub fn scale(&self) -> f32 {
+        let progress = (self.age / SCALE_IN_SECONDS).min(1.0);
+        let eased = 1.0 - (1.0 - progress).powi(2);
+
+        START_SCALE + (1.0 - START_SCALE) * eased
+    }
+}
+
+/// Follows a hand: a stamp appears when the hand gets one and stays while it has it.
+pub fn update(stamp: &mut Option<Stamp>, cards: &[Card], time_delta: f32) {
+    match (StampKind::of(cards), stamp.as_mut()) {
+        (Some(kind), Some(current)) if current.kind == kind => current.age += time_delta,
+        (Some(kind), _) => *stamp = Some(Stamp { kind, age: 0.0 }),
+        (None, _) => *stamp = None,
+    }
+}
+
+/// Turns `point` around the origin.
+fn rotate(point: (f32, f32), angle: f32) -> (f32, f32) {
+    let (sin, cos) = angle.sin_cos();
+
+    (point.0 * cos - point.1 * sin, point.0 * sin + point.1 * cos)
+}
+
+/// Draws the stamp tilted and centered on `center`.
+pub fn draw(ctx: &mut Context, stamp: &Stamp, center: Point2<f32>, font: graphics::Font, size: f32) -> GameResult<()> {
+    let scale = stamp.scale();
+    let text = graphics::Text::new(graphics::TextFragment::new(stamp.kind.text()).
+                                                             color(stamp.kind.color()).
+                                                             font(font).
+                                                             scale(graphics::PxScale::from(size * scale)));
+    let dimensions = text.dimensions(ctx);
+    let (half_w, half_h) = (dimensions.w / 2.0, dimensions.h / 2.0);
+
+    // a border around the text, its corners turned like the text
+    let corners: Vec<Point2<f32>> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter().
+        map(|(x, y)| {
+            let (dx, dy) = rotate((x * (half_w + BORDER_PADDING), y * (half_h + BORDER_PADDING)), ROTATION);
+            Point2 { x: center.x + dx, y: center.y + dy }
+        }).
+        collect();
+    let border = graphics::Mesh::new_polygon(ctx, graphics::DrawMode::stroke(BORDER_WIDTH * scale), &corners, stamp.kind.color())?;
+    graphics::draw(ctx, &border, graphics::DrawParam::default())?;
+
+    // text turns around its top left corner, so that corner goes where the middle ends up
+    let (dx, dy) = rotate((-half_w, -half_h), ROTATION);
+    let top_left = Point2 { x: center.x + dx, y: center.y + dy };
+    graphics::draw(ctx, &text, graphics::DrawParam::default().dest(top_left).rotation(ROTATION))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn hands_get_the_right_stamp() {
+        assert_eq!(StampKind::of(&cards(&["ace_of_spades", "king_of_hearts"])), Some(StampKind::Blackjack));
+        assert_eq!(StampKind::of(&cards(&["7_of_spades", "4_of_hearts", "king_of_hearts"])), None);
+        assert_eq!(StampKind::of(&cards(&["9_of_spades", "4_of_hearts", "king_of_hearts"])), Some(StampKind::Bust));
+        assert_eq!(StampKind::of(&[]), None);
+    }
+
+    #[test]
+    fn stamp_comes_down_and_stays() {
+        let bust = cards(&["9_of_spades", "4_of_hearts", "king_of_hearts"]);
+        let mut stamp = None;
+
+        update(&mut stamp, &bust, 0.1);
+        assert_eq!(stamp.unwrap().scale(), START_SCALE);
+
+        update(&mut stamp, &bust, SCALE_IN_SECONDS);
+        assert_eq!(stamp.unwrap().scale(), 1.0);
+
+        update(&mut stamp, &[], 0.1);
+        assert_eq!(stamp, None);
+    }
+}
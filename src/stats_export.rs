@@ -0,0 +1,180 @@
+use crate::game_engine::Outcome;
+use crate::history::History;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const EXPORT_DIR: &str = "exports";
+const STATS_CSV_NAME: &str = "stats.csv";
+const HANDS_CSV_NAME: &str = "hands.csv";
+const JSON_NAME: &str = "stats.json";
+
+/// Lifetime numbers for a player, partly from the stats file and partly counted from the history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub player: String,
+    pub wins: u32,
+    pub hints: u32,
+    pub handicaps: u32,
+    pub hands_played: usize,
+    pub hand_wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Summary {
+    pub fn new(player: &str, wins: u32, power_ups_count: (u32, u32), history: &History) -> Self {
+        Summary {
+            player: player.to_string(),
+            wins,
+            hints: power_ups_count.0,
+            handicaps: power_ups_count.1,
+            hands_played: history.hands.len(),
+            hand_wins: history.count(&Outcome::Win),
+            draws: history.count(&Outcome::Draw),
+            losses: history.count(&Outcome::Lose),
+        }
+    }
+
+    /// Win rate over the logged hands, 0 when nothing was played yet
+    pub fn win_rate(&self) -> f32 {
+        if self.hands_played == 0 {
+            return 0.0;
+        }
+
+        self.hand_wins as f32 / self.hands_played as f32
+    }
+
+    pub fn to_csv(&self) -> String {
+        format!("stat,value\nplayer,{}\nwins,{}\nhints,{}\nhandicaps,{}\nhands_played,{}\nhand_wins,{}\ndraws,{}\nlosses,{}\nwin_rate,{:.3}\n",
+                csv_field(&self.player),
+                self.wins,
+                self.hints,
+                self.handicaps,
+                self.hands_played,
+                self.hand_wins,
+                self.draws,
+                self.losses,
+                self.win_rate())
+    }
+}
+
+/// Everything in one JSON document: the summary and every hand.
+pub fn to_json(summary: &Summary, history: &History) -> String {
+    let mut json = String::from("{\n  \"summary\": {\n");
+    json += &format!("    \"player\": {},\n", json_string(&summary.player));
+    json += &format!("    \"wins\": {},\n", summary.wins);
+    json += &format!("    \"hints\": {},\n", summary.hints);
+    json += &format!("    \"handicaps\": {},\n", summary.handicaps);
+    json += &format!("    \"hands_played\": {},\n", summary.hands_played);
+    json += &format!("    \"hand_wins\": {},\n", summary.hand_wins);
+    json += &format!("    \"draws\": {},\n", summary.draws);
+    json += &format!("    \"losses\": {},\n", summary.losses);
+    json += &format!("    \"win_rate\": {:.3}\n", summary.win_rate());
+    json += "  },\n  \"hands\": [";
+
+    for (i, hand) in history.hands.iter().enumerate() {
+        if i > 0 {
+            json += ",";
+        }
+        json += &format!("\n    {{\"id\": {}, \"timestamp\": {}, \"outcome\": \"{}\", \"player_score\": {}, \"dealer_score\": {}, \"hint_used\": {}, \"handicap_used\": {}}}",
+                         hand.id,
+                         hand.timestamp,
+                         hand.outcome.name(),
+                         hand.player_score,
+                         hand.dealer_score,
+                         hand.hint_used,
+                         hand.handicap_used);
+    }
+    if !history.hands.is_empty() {
+        json += "\n  ";
+    }
+    json += "]\n}\n";
+
+    json
+}
+
+/// Writes stats.csv, hands.csv and stats.json into `dir` and returns the folder they're in.
+pub fn export(dir: &Path, summary: &Summary, history: &History) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    fs::write(dir.join(STATS_CSV_NAME), summary.to_csv())?;
+    fs::write(dir.join(HANDS_CSV_NAME), history.to_csv())?;
+    fs::write(dir.join(JSON_NAME), to_json(summary, history))?;
+
+    // absolute path, so the player knows where to look
+    dir.canonicalize()
+}
+
+/// Quotes a CSV field if it contains anything that would break the row.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            c if c.is_control() => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HandRecord;
+
+    fn history() -> History {
+        let hand = |id, outcome| HandRecord {
+            id,
+            timestamp: 1_700_000_000,
+            outcome,
+            player_score: 19,
+            dealer_score: 20,
+            hint_used: false,
+            handicap_used: false,
+            player_start: None,
+            dealer_up_card: None,
+            would_have_drawn: None,
+        };
+
+        History { hands: vec![hand(1, Outcome::Win), hand(2, Outcome::Lose), hand(3, Outcome::Win), hand(4, Outcome::Draw)] }
+    }
+
+    #[test]
+    fn summary_counts_outcomes() {
+        let summary = Summary::new("Kris", 7, (1, 2), &history());
+
+        assert_eq!((summary.hand_wins, summary.draws, summary.losses), (2, 1, 1));
+        assert_eq!(summary.win_rate(), 0.5);
+    }
+
+    #[test]
+    fn csv_quotes_awkward_names() {
+        assert_eq!(csv_field("Kris"), "Kris");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn json_escapes_and_lists_hands() {
+        let summary = Summary::new("say \"hi\"", 0, (0, 0), &history());
+        let json = to_json(&summary, &history());
+
+        assert!(json.contains("\"player\": \"say \\\"hi\\\"\""));
+        assert_eq!(json.matches("\"outcome\"").count(), 4);
+        assert!(to_json(&summary, &History::default()).contains("\"hands\": []"));
+    }
+}
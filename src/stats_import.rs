@@ -0,0 +1,158 @@
+use crate::game_engine::Outcome;
+use crate::history::History;
+
+use std::fmt;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+pub const IMPORT_DIR: &str = "import";
+
+#[derive(Debug, Clone)]
+pub struct StatsImportError {
+    details: String,
+}
+
+impl StatsImportError {
+    fn new(msg: &str) -> Self {
+        StatsImportError {
+            details: msg.to_string()
+        }
+    }
+}
+
+impl fmt::Display for StatsImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.details)
+    }
+}
+
+impl Error for StatsImportError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// What an export from another machine adds to the local stats.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Imported {
+    pub hands_added: usize,
+    pub wins: u32,
+    pub hints: u32,
+    pub handicaps: u32,
+}
+
+/// The hints and handicaps `wins` earn, a hint every 2nd win and a handicap every 3rd.
+fn power_ups_earned(wins: u32) -> (u32, u32) {
+    (wins / 2, wins / 3)
+}
+
+/// Reads the counters from an exported stats.csv.
+pub fn parse_summary_csv(contents: &str) -> Option<(u32, u32, u32)> {
+    let mut counters = (None, None, None);
+
+    for (key, value) in contents.lines().filter_map(|line| line.trim().split_once(',')) {
+        match key {
+            "wins" => counters.0 = value.parse().ok(),
+            "hints" => counters.1 = value.parse().ok(),
+            "handicaps" => counters.2 = value.parse().ok(),
+            _ => (),
+        }
+    }
+
+    Some((counters.0?, counters.1?, counters.2?))
+}
+
+/// Merges a folder with "stats.csv" and "hands.csv"(as written by the export) into `history`.
+/// The counters from stats.csv are added less what the hands already in the log account for:
+/// their wins, and the power-ups those earned and used here. Older exports have no hands.csv,
+/// their counters are added whole.
+pub fn import(dir: &Path, history: &mut History) -> Result<Imported, StatsImportError> {
+    let stats = fs::read_to_string(dir.join("stats.csv")).
+        map_err(|_| StatsImportError::new(&format!("No stats.csv in {}", dir.display())))?;
+    let (wins, hints, handicaps) = parse_summary_csv(&stats).
+        ok_or_else(|| StatsImportError::new("stats.csv is not a stats export"))?;
+
+    let hands = match fs::read_to_string(dir.join("hands.csv")) {
+        Ok(contents) => History::parse(&contents),
+        Err(_) => History::default(),
+    };
+
+    let known: Vec<_> = hands.hands.iter().filter(|hand| history.contains(hand)).collect();
+    let known_wins = known.iter().filter(|hand| hand.outcome == Outcome::Win).count() as u32;
+    let known_hints = known.iter().filter(|hand| hand.hint_used).count() as u32;
+    let known_handicaps = known.iter().filter(|hand| hand.handicap_used).count() as u32;
+    let earned = power_ups_earned(known_wins);
+
+    let exported_hands = hands.hands.len();
+    let hands_added = history.merge(hands);
+    if exported_hands > 0 && hands_added == 0 {
+        return Err(StatsImportError::new("These stats were already imported"));
+    }
+
+    Ok(Imported {
+        hands_added,
+        wins: wins.saturating_sub(known_wins),
+        hints: (hints + known_hints).saturating_sub(earned.0),
+        handicaps: (handicaps + known_handicaps).saturating_sub(earned.1),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::tests::hand;
+    use crate::stats_export::{self, Summary};
+
+    #[test]
+    fn parse_summary_reads_exported_csv() {
+        let summary = Summary::new("Kris", 9, (2, 3), &History::default());
+
+        assert_eq!(parse_summary_csv(&summary.to_csv()), Some((9, 2, 3)));
+        assert_eq!(parse_summary_csv("stat,value\nwins,1\n"), None);
+    }
+
+    #[test]
+    fn import_merges_once() {
+        let dir = std::env::temp_dir().join(format!("blackjack_import_test_{}", std::process::id()));
+        let exported = History { hands: vec![hand(1, Outcome::Win)] };
+        stats_export::export(&dir, &Summary::new("Kris", 4, (1, 0), &exported), &exported).unwrap();
+
+        let mut history = History::default();
+        let imported = import(&dir, &mut history).unwrap();
+        assert_eq!(imported, Imported { hands_added: 1, wins: 4, hints: 1, handicaps: 0 });
+        assert!(import(&dir, &mut history).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn overlapping_import_adds_only_the_new_wins() {
+        let dir = std::env::temp_dir().join(format!("blackjack_import_overlap_test_{}", std::process::id()));
+        let mut history = History::default();
+
+        let first = History { hands: vec![hand(1, Outcome::Win), hand(2, Outcome::Lose)] };
+        stats_export::export(&dir, &Summary::new("Kris", 1, (0, 0), &first), &first).unwrap();
+        assert_eq!(import(&dir, &mut history).unwrap(), Imported { hands_added: 2, wins: 1, hints: 0, handicaps: 0 });
+
+        let second = History { hands: vec![hand(1, Outcome::Win), hand(2, Outcome::Lose), hand(3, Outcome::Win), hand(4, Outcome::Draw)] };
+        stats_export::export(&dir, &Summary::new("Kris", 3, (1, 1), &second), &second).unwrap();
+        // hands 1 and 2 are here already, with their win and the two hints they used
+        assert_eq!(import(&dir, &mut history).unwrap(), Imported { hands_added: 2, wins: 2, hints: 3, handicaps: 1 });
+        assert_eq!(history.hands.len(), 4);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn older_export_without_hands_adds_its_counters() {
+        let dir = std::env::temp_dir().join(format!("blackjack_import_old_test_{}", std::process::id()));
+        stats_export::export(&dir, &Summary::new("Kris", 9, (2, 3), &History::default()), &History::default()).unwrap();
+        fs::remove_file(dir.join("hands.csv")).unwrap();
+
+        assert_eq!(import(&dir, &mut History::default()).unwrap(), Imported { hands_added: 0, wins: 9, hints: 2, handicaps: 3 });
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+//! Basic strategy for two-card hands against the dealer's up-card, as in the common chart for
+//! several decks where the dealer stands on soft 17 and doubling after a split is allowed.
+//! The game itself has no doubling or splitting, this is for the trainer.
+
+use crate::card::Card;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+}
+
+pub const ACTIONS: &[Action] = &[Action::Hit, Action::Stand, Action::Double, Action::Split];
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Hit => "hit",
+            Action::Stand => "stand",
+            Action::Double => "double",
+            Action::Split => "split",
+        }
+    }
+
+    /// Key to press for the action in the trainer
+    pub fn key(&self) -> char {
+        match self {
+            Action::Hit => 'h',
+            Action::Stand => 's',
+            Action::Double => 'd',
+            Action::Split => 'p',
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Self> {
+        ACTIONS.iter().find(|action| action.key() == key.to_ascii_lowercase()).copied()
+    }
+}
+
+fn points(card: &Card) -> u32 {
+    card.get_points().unwrap_or(10)
+}
+
+fn hard_total(total: u32, dealer: u32) -> Action {
+    match total {
+        0..=8 => Action::Hit,
+        9 if (3..=6).contains(&dealer) => Action::Double,
+        10 if dealer <= 9 => Action::Double,
+        11 if dealer <= 10 => Action::Double,
+        12 if (4..=6).contains(&dealer) => Action::Stand,
+        13..=16 if dealer <= 6 => Action::Stand,
+        17..=21 => Action::Stand,
+        _ => Action::Hit,
+    }
+}
+
+/// `other` is the card next to the ace
+fn soft_total(other: u32, dealer: u32) -> Action {
+    match other {
+        2 | 3 if (5..=6).contains(&dealer) => Action::Double,
+        4 | 5 if (4..=6).contains(&dealer) => Action::Double,
+        6 if (3..=6).contains(&dealer) => Action::Double,
+        7 if (3..=6).contains(&dealer) => Action::Double,
+        7 if dealer <= 8 => Action::Stand,
+        8..=10 => Action::Stand,
+        _ => Action::Hit,
+    }
+}
+
+fn pair(card_points: u32, dealer: u32) -> Option<Action> {
+    let split = match card_points {
+        11 | 8 => true,
+        2 | 3 | 7 => dealer <= 7,
+        4 => (5..=6).contains(&dealer),
+        6 => dealer <= 6,
+        9 => dealer <= 9 && dealer != 7,
+        _ => false,
+    };
+
+    if split { Some(Action::Split) } else { None }
+}
+
+/// The chart's action for a two-card hand. The up-card counts 2 to 11(ace).
+pub fn basic_strategy(player: &[Card], dealer_up: &Card) -> Action {
+    let dealer = points(dealer_up);
+
+    if let [first, second] = player {
+        let (a, b) = (points(first), points(second));
+
+        if a == b {
+            if let Some(action) = pair(a, dealer) {
+                return action;
+            }
+        }
+        if a == 11 && b != 11 {
+            return soft_total(b, dealer);
+        }
+        if b == 11 && a != 11 {
+            return soft_total(a, dealer);
+        }
+    }
+
+    hard_total(crate::game_engine::hand_value(player), dealer)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(player: &[&str], dealer: &str) -> Action {
+        let cards: Vec<Card> = player.iter().map(|name| Card::new(name)).collect();
+
+        basic_strategy(&cards, &Card::new(dealer))
+    }
+
+    #[test]
+    fn hard_hands() {
+        assert_eq!(action(&["10_of_hearts", "6_of_clubs"], "6_of_spades"), Action::Stand);
+        assert_eq!(action(&["10_of_hearts", "6_of_clubs"], "7_of_spades"), Action::Hit);
+        assert_eq!(action(&["6_of_hearts", "5_of_clubs"], "king_of_spades"), Action::Double);
+        assert_eq!(action(&["6_of_hearts", "5_of_clubs"], "ace_of_spades"), Action::Hit);
+        assert_eq!(action(&["10_of_hearts", "2_of_clubs"], "3_of_spades"), Action::Hit);
+    }
+
+    #[test]
+    fn soft_hands() {
+        assert_eq!(action(&["ace_of_hearts", "7_of_clubs"], "2_of_spades"), Action::Stand);
+        assert_eq!(action(&["7_of_clubs", "ace_of_hearts"], "4_of_spades"), Action::Double);
+        assert_eq!(action(&["ace_of_hearts", "7_of_clubs"], "9_of_spades"), Action::Hit);
+        assert_eq!(action(&["ace_of_hearts", "2_of_clubs"], "4_of_spades"), Action::Hit);
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(action(&["ace_of_hearts", "ace_of_clubs"], "10_of_spades"), Action::Split);
+        assert_eq!(action(&["9_of_hearts", "9_of_clubs"], "7_of_spades"), Action::Stand);
+        assert_eq!(action(&["5_of_hearts", "5_of_clubs"], "6_of_spades"), Action::Double);
+        assert_eq!(action(&["king_of_hearts", "queen_of_clubs"], "6_of_spades"), Action::Stand);
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        for action in ACTIONS {
+            assert_eq!(Action::from_key(action.key()), Some(*action));
+        }
+        assert_eq!(Action::from_key('S'), Some(Action::Stand));
+    }
+}
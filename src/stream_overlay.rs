@@ -0,0 +1,121 @@
+use crate::card::{Card, CardFlipState};
+
+use std::fs;
+use std::io;
+
+pub const OVERLAY_TEXT_FILE_NAME: &str = "stream_overlay.txt";
+pub const OVERLAY_JSON_FILE_NAME: &str = "stream_overlay.json";
+
+/// What a stream overlay shows about the hand being played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandState {
+    pub player: String,
+    pub player_score: u32,
+    pub dealer_score: u32,
+    pub player_cards: Vec<String>,
+    pub dealer_cards: Vec<String>,
+
+    /// "player", "dealer" or the outcome once the hand is over
+    pub status: String,
+    pub wins: u32,
+}
+
+/// Names of the cards the viewers can already see(face down ones are left out).
+pub fn visible_cards(cards: &[Card]) -> Vec<String> {
+    cards.iter().
+        filter(|card| matches!(card.flip_state, CardFlipState::Front)).
+        map(|card| card.name.replace('_', " ")).
+        collect()
+}
+
+impl HandState {
+    pub fn to_text(&self) -> String {
+        format!("{} {} - {} Dealer\n{}: {}\nDealer: {}\nStatus: {}\nWins: {}\n",
+                self.player,
+                self.player_score,
+                self.dealer_score,
+                self.player,
+                self.player_cards.join(", "),
+                self.dealer_cards.join(", "),
+                self.status,
+                self.wins)
+    }
+
+    pub fn to_json(&self) -> String {
+        let list = |cards: &[String]| cards.iter().map(|card| json_string(card)).collect::<Vec<String>>().join(", ");
+
+        format!("{{\"player\": {}, \"player_score\": {}, \"dealer_score\": {}, \"player_cards\": [{}], \"dealer_cards\": [{}], \"status\": {}, \"wins\": {}}}\n",
+                json_string(&self.player),
+                self.player_score,
+                self.dealer_score,
+                list(&self.player_cards),
+                list(&self.dealer_cards),
+                json_string(&self.status),
+                self.wins)
+    }
+}
+
+fn json_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Keeps the overlay files up to date, touching the disk only when the hand changed.
+#[derive(Debug, Default)]
+pub struct StreamOverlay {
+    last_written: Option<HandState>,
+}
+
+impl StreamOverlay {
+    pub fn write(&mut self, state: HandState) -> io::Result<()> {
+        if self.last_written.as_ref() == Some(&state) {
+            return Ok(());
+        }
+
+        fs::write(OVERLAY_TEXT_FILE_NAME, state.to_text())?;
+        fs::write(OVERLAY_JSON_FILE_NAME, state.to_json())?;
+        self.last_written = Some(state);
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> HandState {
+        HandState {
+            player: String::from("Kris \"the\" streamer"),
+            player_score: 18,
+            dealer_score: 10,
+            player_cards: vec![String::from("8 of hearts"), String::from("king of spades")],
+            dealer_cards: vec![String::from("10 of clubs")],
+            status: String::from("player"),
+            wins: 3,
+        }
+    }
+
+    #[test]
+    fn text_lists_cards() {
+        assert!(state().to_text().contains("Kris \"the\" streamer: 8 of hearts, king of spades\n"));
+    }
+
+    #[test]
+    fn json_escapes_name() {
+        let json = state().to_json();
+
+        assert!(json.starts_with("{\"player\": \"Kris \\\"the\\\" streamer\""));
+        assert!(json.contains("\"dealer_cards\": [\"10 of clubs\"]"));
+    }
+
+    #[test]
+    fn visible_cards_skips_face_down() {
+        let mut face_down = Card::new("ace_of_spades");
+        face_down.flip_state = CardFlipState::Back;
+        let mut face_up = Card::new("2_of_hearts");
+        face_up.flip_state = CardFlipState::Front;
+
+        assert_eq!(visible_cards(&[face_down, face_up]), vec![String::from("2 of hearts")]);
+    }
+}
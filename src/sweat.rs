@@ -0,0 +1,46 @@
+//! Sweat mode: when a counted hand has a lot riding on the dealer's draw, the dealer's cards come
+//! slowly, each after a heartbeat. There are no bets in the game, so what's riding is a strong
+//! hand stood on or a win that would reach a milestone(see banner.rs). Speed mode never sweats.
+
+use crate::speed::GameSpeed;
+
+/// How long the dealer waits before each card while sweating
+pub const SWEAT_DEALER_DELAY: f32 = 1.5;
+/// The cards' animation speed while sweating
+pub const SWEAT_ANIMATION_SPEED: f32 = 0.4;
+
+/// Stood on this or more, the dealer's draw is worth sweating
+const SWEAT_FROM: u32 = 18;
+
+/// `sweat_mode` is the config's, speed mode turns it off.
+pub fn allowed(sweat_mode: bool, speed: GameSpeed) -> bool {
+    sweat_mode && speed != GameSpeed::Speed
+}
+
+/// Whether the dealer's draw against `player_value` is worth sweating. `milestone` is whether
+/// a win would reach a milestone of wins.
+pub fn worth_sweating(player_value: u32, milestone: bool) -> bool {
+    player_value <= 21 && (player_value >= SWEAT_FROM || milestone)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweats_only_when_something_rides_on_it() {
+        assert!(worth_sweating(20, false));
+        assert!(worth_sweating(13, true));
+        assert!(!worth_sweating(16, false));
+        // a bust is lost before the dealer draws
+        assert!(!worth_sweating(24, true));
+    }
+
+    #[test]
+    fn speed_mode_never_sweats() {
+        assert!(allowed(true, GameSpeed::Normal));
+        assert!(!allowed(true, GameSpeed::Speed));
+        assert!(!allowed(false, GameSpeed::Casual));
+    }
+}
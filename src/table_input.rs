@@ -0,0 +1,104 @@
+//! What the player can do at the table right now. The deck click and the keys ask here before
+//! acting, and the table asks why they're locked, so a click that does nothing shows the reason.
+//! A hand that is over or the dealer's turn is for the engine to turn down(see
+//! game_action.rs), which says why; replays and moving cards only hold input back.
+
+use crate::scenario::Action;
+
+/// Why the player's actions are ignored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lock {
+    HandOver,
+    /// The dealer is drawing
+    DealerTurn,
+    /// A scenario is playing its actions
+    Replaying,
+    /// A card is still on its way, or flipping
+    CardMoving,
+}
+
+impl Lock {
+    /// What the table says about it, if anything
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            Lock::DealerTurn => Some("Dealer drawing..."),
+            Lock::Replaying => Some("Replaying the scenario..."),
+            Lock::HandOver | Lock::CardMoving => None,
+        }
+    }
+}
+
+/// What the locks depend on, taken from the board and the engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TableState {
+    pub hand_over: bool,
+    pub dealer_turn: bool,
+    pub replaying: bool,
+    pub card_moving: bool,
+}
+
+impl TableState {
+    pub fn lock(&self) -> Option<Lock> {
+        if self.hand_over {
+            Some(Lock::HandOver)
+        } else if self.dealer_turn {
+            Some(Lock::DealerTurn)
+        } else if self.replaying {
+            Some(Lock::Replaying)
+        } else if self.card_moving {
+            Some(Lock::CardMoving)
+        } else {
+            None
+        }
+    }
+
+    pub fn allows(&self, action: Action) -> bool {
+        match self.lock() {
+            None => true,
+            // the card on its way still has to land on the player's row, the power-ups don't
+            // care
+            Some(Lock::CardMoving) => matches!(action, Action::Hint | Action::Handicap),
+            Some(_) => false,
+        }
+    }
+
+    /// Whether the action goes on to the engine, allowed or to be turned down there.
+    pub fn passes(&self, action: Action) -> bool {
+        matches!(self.lock(), Some(Lock::HandOver) | Some(Lock::DealerTurn)) || self.allows(action)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dealer_turn_locks_everything() {
+        let state = TableState { dealer_turn: true, ..TableState::default() };
+
+        assert_eq!(state.lock(), Some(Lock::DealerTurn));
+        assert!([Action::Hit, Action::Stand, Action::Hint, Action::Handicap].iter().all(|action| !state.allows(*action)));
+        assert_eq!(state.lock().unwrap().label(), Some("Dealer drawing..."));
+        assert!(state.passes(Action::Stand));
+    }
+
+    #[test]
+    fn moving_card_only_locks_hit_and_stand() {
+        let state = TableState { card_moving: true, ..TableState::default() };
+
+        assert!(!state.allows(Action::Hit));
+        assert!(!state.allows(Action::Stand));
+        assert!(state.allows(Action::Hint));
+        assert_eq!(state.lock().unwrap().label(), None);
+        assert!(!state.passes(Action::Hit));
+    }
+
+    #[test]
+    fn player_turn_allows_everything() {
+        let state = TableState::default();
+
+        assert_eq!(state.lock(), None);
+        assert!(state.allows(Action::Hit) && state.allows(Action::Stand));
+    }
+}
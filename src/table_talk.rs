@@ -0,0 +1,175 @@
+//! Table talk: now and then the dealer says something about what just happened at the table, a
+//! bust, a blackjack, the end of a hand or the handicap being used, in a speech bubble(see
+//! bubble.rs). How often is the "table_talk" key of config.txt, off keeps the dealer quiet.
+
+use crate::card::Card;
+use crate::game_engine::{self, Outcome};
+
+use rand::Rng;
+
+/// How long a line stays
+pub const TALK_SECONDS: f32 = 2.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Chattiness {
+    Off,
+    Rarely,
+    #[default]
+    Sometimes,
+    Often,
+}
+
+pub const CHATTINESS: &[Chattiness] = &[Chattiness::Off, Chattiness::Rarely, Chattiness::Sometimes, Chattiness::Often];
+
+impl Chattiness {
+    /// Also the value in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Chattiness::Off => "off",
+            Chattiness::Rarely => "rarely",
+            Chattiness::Sometimes => "sometimes",
+            Chattiness::Often => "often",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        CHATTINESS.iter().find(|chattiness| chattiness.name() == name).copied()
+    }
+
+    /// Chance of a line for each event
+    fn chance(&self) -> f64 {
+        match self {
+            Chattiness::Off => 0.0,
+            Chattiness::Rarely => 0.15,
+            Chattiness::Sometimes => 0.4,
+            Chattiness::Often => 0.8,
+        }
+    }
+}
+
+/// What the dealer can have something to say about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TalkEvent {
+    PlayerBust,
+    Blackjack,
+    DealerBust,
+    PlayerWin,
+    DealerWin,
+    Push,
+    HandicapUsed,
+}
+
+impl TalkEvent {
+    /// The event of a hand that just ended.
+    pub fn of_hand(player_cards: &[Card], dealer_cards: &[Card], outcome: &Outcome) -> Option<Self> {
+        let player_value = game_engine::hand_value(player_cards);
+
+        match outcome {
+            Outcome::Lose if player_value > 21 => Some(TalkEvent::PlayerBust),
+            Outcome::Lose => Some(TalkEvent::DealerWin),
+            Outcome::Win if player_value == 21 && player_cards.len() == 2 => Some(TalkEvent::Blackjack),
+            Outcome::Win if game_engine::hand_value(dealer_cards) > 21 => Some(TalkEvent::DealerBust),
+            Outcome::Win => Some(TalkEvent::PlayerWin),
+            Outcome::Draw => Some(TalkEvent::Push),
+            Outcome::Undecided => None,
+        }
+    }
+
+    pub fn lines(&self) -> &'static [&'static str] {
+        match self {
+            TalkEvent::PlayerBust => &["Ouch, bust", "One card too many", "That one hurt"],
+            TalkEvent::Blackjack => &["Blackjack, nicely done!", "Can't beat that one", "Lucky draw!"],
+            TalkEvent::DealerBust => &["I went over, it's yours", "Too many for me", "The house busts!"],
+            TalkEvent::PlayerWin => &["Nice hand!", "Well played", "You had me there"],
+            TalkEvent::DealerWin => &["The house takes this one", "Better luck next hand", "Close one"],
+            TalkEvent::Push => &["A push, nobody loses", "Even this time"],
+            TalkEvent::HandicapUsed => &["Going easy on me?", "A point off, fair enough"],
+        }
+    }
+}
+
+/// What the dealer is saying, while it lasts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableTalk {
+    line: Option<&'static str>,
+    time_left: f32,
+}
+
+impl TableTalk {
+    /// Maybe says something about `event`, as often as `chattiness` has it.
+    pub fn react<R: Rng>(&mut self, event: TalkEvent, chattiness: Chattiness, rng: &mut R) {
+        if !rng.gen_bool(chattiness.chance()) {
+            return;
+        }
+
+        let lines = event.lines();
+        self.line = Some(lines[rng.gen_range(0..lines.len())]);
+        self.time_left = TALK_SECONDS;
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        if self.line.is_some() {
+            self.time_left -= time_delta;
+            if self.time_left <= 0.0 {
+                self.line = None;
+            }
+        }
+    }
+
+    pub fn line(&self) -> Option<&'static str> {
+        self.line
+    }
+
+    pub fn hush(&mut self) {
+        self.line = None;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for chattiness in CHATTINESS {
+            assert_eq!(Chattiness::from_name(chattiness.name()), Some(*chattiness));
+        }
+        assert_eq!(Chattiness::from_name("never"), None);
+    }
+
+    #[test]
+    fn tells_the_hands_apart() {
+        let twenty = cards(&["king_of_clubs", "queen_of_hearts"]);
+        let bust = cards(&["king_of_clubs", "queen_of_hearts", "5_of_spades"]);
+
+        assert_eq!(TalkEvent::of_hand(&bust, &twenty, &Outcome::Lose), Some(TalkEvent::PlayerBust));
+        assert_eq!(TalkEvent::of_hand(&cards(&["ace_of_clubs", "king_of_hearts"]), &twenty, &Outcome::Win), Some(TalkEvent::Blackjack));
+        assert_eq!(TalkEvent::of_hand(&twenty, &bust, &Outcome::Win), Some(TalkEvent::DealerBust));
+        assert_eq!(TalkEvent::of_hand(&twenty, &twenty, &Outcome::Draw), Some(TalkEvent::Push));
+    }
+
+    #[test]
+    fn off_keeps_quiet_and_lines_run_out() {
+        let mut rng = rand::thread_rng();
+        let mut talk = TableTalk::default();
+
+        for _ in 0..100 {
+            talk.react(TalkEvent::PlayerBust, Chattiness::Off, &mut rng);
+        }
+        assert_eq!(talk.line(), None);
+
+        // often is still not always
+        while talk.line().is_none() {
+            talk.react(TalkEvent::PlayerBust, Chattiness::Often, &mut rng);
+        }
+        assert!(TalkEvent::PlayerBust.lines().contains(&talk.line().unwrap()));
+
+        talk.update(TALK_SECONDS);
+        assert_eq!(talk.line(), None);
+    }
+}
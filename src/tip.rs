@@ -0,0 +1,78 @@
+//! Tipping the dealer: once a hand is over a hint can be handed to the dealer, who says thanks
+//! and bobs for a moment. It does nothing for the game, apart from counting towards a hidden
+//! achievement.
+
+use rand::Rng;
+
+/// How long the dealer's thanks stay
+pub const TIP_SECONDS: f32 = 3.0;
+/// How far the dealer moves up while bobbing
+const BOB_HEIGHT: f32 = 12.0;
+const BOBS_PER_SECOND: f32 = 2.0;
+
+pub const THANKS: &[&str] = &[
+    "Thank you kindly!",
+    "Much appreciated!",
+    "You're too generous!",
+    "Good luck on the next one!",
+];
+
+/// The dealer's reaction to the last tip, while it lasts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tip {
+    line: Option<&'static str>,
+    time_left: f32,
+}
+
+impl Tip {
+    pub fn give<R: Rng>(&mut self, rng: &mut R) {
+        self.line = Some(THANKS[rng.gen_range(0..THANKS.len())]);
+        self.time_left = TIP_SECONDS;
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        if self.line.is_some() {
+            self.time_left -= time_delta;
+            if self.time_left <= 0.0 {
+                self.line = None;
+            }
+        }
+    }
+
+    /// What the dealer says, while thanking
+    pub fn line(&self) -> Option<&'static str> {
+        self.line
+    }
+
+    /// How far up to draw the dealer, 0 when not thanking.
+    pub fn bob_offset(&self) -> f32 {
+        if self.line.is_none() {
+            return 0.0;
+        }
+
+        let time = TIP_SECONDS - self.time_left;
+        (time * BOBS_PER_SECOND * std::f32::consts::PI).sin().abs() * BOB_HEIGHT
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thanks_run_out() {
+        let mut tip = Tip::default();
+        assert_eq!(tip.bob_offset(), 0.0);
+
+        tip.give(&mut rand::thread_rng());
+        assert!(THANKS.contains(&tip.line().unwrap()));
+
+        tip.update(TIP_SECONDS / 4.0);
+        assert!(tip.bob_offset() > 0.0);
+
+        tip.update(TIP_SECONDS);
+        assert_eq!(tip.line(), None);
+        assert_eq!(tip.bob_offset(), 0.0);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::config;
+use crate::drill::Drill;
+use crate::migration::{self, SaveFile};
+use crate::quiz::Quiz;
+
+use std::fs;
+use std::io;
+
+pub const TRAINER_STATS_FILE_NAME: &str = "trainer.txt";
+
+/// Totals of every finished quiz and drill round, stored as "key = value" lines like the config.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainerStats {
+    pub quiz_rounds: u32,
+    pub quiz_questions: u32,
+    pub quiz_correct: u32,
+    pub best_quiz_score: u32,
+    pub drill_rounds: u32,
+    pub drill_stops: u32,
+    pub drill_correct: u32,
+}
+
+fn percent(part: u32, whole: u32) -> f32 {
+    if whole == 0 { 0.0 } else { part as f32 * 100.0 / whole as f32 }
+}
+
+impl TrainerStats {
+    pub fn parse(contents: &str) -> Self {
+        let mut stats = TrainerStats::default();
+
+        for (key, value) in contents.lines().filter_map(config::parse_line) {
+            let field = match key {
+                "quiz_rounds" => &mut stats.quiz_rounds,
+                "quiz_questions" => &mut stats.quiz_questions,
+                "quiz_correct" => &mut stats.quiz_correct,
+                "best_quiz_score" => &mut stats.best_quiz_score,
+                "drill_rounds" => &mut stats.drill_rounds,
+                "drill_stops" => &mut stats.drill_stops,
+                "drill_correct" => &mut stats.drill_correct,
+                _ => continue,
+            };
+            *field = value.parse().unwrap_or(*field);
+        }
+
+        stats
+    }
+
+    pub fn load(file_name: &str) -> Self {
+        match migration::read(file_name, SaveFile::Trainer) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => TrainerStats::default(),
+        }
+    }
+
+    pub fn to_file_contents(&self) -> String {
+        format!("quiz_rounds = {}\nquiz_questions = {}\nquiz_correct = {}\nbest_quiz_score = {}\ndrill_rounds = {}\ndrill_stops = {}\ndrill_correct = {}\n",
+                self.quiz_rounds,
+                self.quiz_questions,
+                self.quiz_correct,
+                self.best_quiz_score,
+                self.drill_rounds,
+                self.drill_stops,
+                self.drill_correct) + &SaveFile::Trainer.version_line()
+    }
+
+    pub fn save(&self, file_name: &str) -> io::Result<()> {
+        fs::write(file_name, self.to_file_contents())
+    }
+
+    pub fn record_quiz(&mut self, quiz: &Quiz) {
+        self.quiz_rounds += 1;
+        self.quiz_questions += quiz.answered;
+        self.quiz_correct += quiz.correct;
+        self.best_quiz_score = self.best_quiz_score.max(quiz.score);
+    }
+
+    pub fn record_drill(&mut self, drill: &Drill) {
+        self.drill_rounds += 1;
+        self.drill_stops += drill.stops;
+        self.drill_correct += drill.correct;
+    }
+
+    /// Lines for the trainer screen
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!("Quiz: {} rounds, {:.0}% right, best score {}",
+                    self.quiz_rounds, percent(self.quiz_correct, self.quiz_questions), self.best_quiz_score),
+            format!("Count drill: {} rounds, {:.0}% of the counts right",
+                    self.drill_rounds, percent(self.drill_correct, self.drill_stops)),
+        ]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_contents_round_trip() {
+        let stats = TrainerStats { quiz_rounds: 2, quiz_questions: 20, quiz_correct: 15, best_quiz_score: 1420, drill_rounds: 3, drill_stops: 15, drill_correct: 9 };
+
+        assert_eq!(TrainerStats::parse(&stats.to_file_contents()), stats);
+    }
+
+    #[test]
+    fn damaged_values_keep_the_default() {
+        let stats = TrainerStats::parse("quiz_rounds = many\ndrill_rounds = 4\nnoise");
+
+        assert_eq!(stats, TrainerStats { drill_rounds: 4, ..TrainerStats::default() });
+    }
+
+    #[test]
+    fn summary_without_rounds() {
+        assert_eq!(TrainerStats::default().summary()[1], "Count drill: 0 rounds, 0% of the counts right");
+    }
+}
@@ -0,0 +1,83 @@
+use crate::card::Card;
+use crate::game_engine::{self, Outcome};
+use crate::history;
+use crate::saver::Saver;
+
+pub const SESSION_LOG_FILE_NAME: &str = "session_log.txt";
+
+fn cards_with_value(cards: &[Card]) -> String {
+    let names: Vec<String> = cards.iter().map(|card| card.short_name()).collect();
+
+    format!("{} ({})", names.join(" "), game_engine::hand_value(cards))
+}
+
+/// One line describing a finished hand, e.g.
+/// "You: K♦ 6♥ (16) hit → 4♣ (20), Dealer: 10♠ 7♦ (17), Result: WIN"
+pub fn describe_hand(player_cards: &[Card], dealer_cards: &[Card], outcome: &Outcome) -> String {
+    // the first two cards are the starting hand, every card after that was a hit
+    let starting = player_cards.len().min(2);
+    let mut player = cards_with_value(&player_cards[..starting]);
+    for hit in starting..player_cards.len() {
+        player += " hit → ";
+        player += &player_cards[hit].short_name();
+        player += &format!(" ({})", game_engine::hand_value(&player_cards[..=hit]));
+    }
+    if game_engine::hand_value(player_cards) > 21 {
+        player += " bust";
+    }
+
+    let dealer = if dealer_cards.is_empty() {
+        String::from("-")
+    } else {
+        cards_with_value(dealer_cards)
+    };
+
+    format!("You: {}, Dealer: {}, Result: {}", player, dealer, outcome.name().to_uppercase())
+}
+
+/// Transcripts of the hands played since the game started, also appended to the session log.
+#[derive(Debug, Default)]
+pub struct Transcript {
+    pub lines: Vec<String>,
+}
+
+impl Transcript {
+    pub fn add(&mut self, saver: &Saver, file_name: &str, line: String) {
+        let mut text = String::new();
+        if self.lines.is_empty() {
+            text += &format!("=== Session started at {} ===\n", history::now());
+        }
+        text += &line;
+        text += "\n";
+        saver.append(file_name, None, text);
+
+        self.lines.push(line);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    #[test]
+    fn describe_hand_lists_hits() {
+        let player = cards(&["king_of_diamonds", "6_of_hearts", "4_of_clubs"]);
+        let dealer = cards(&["10_of_spades", "7_of_diamonds"]);
+
+        assert_eq!(describe_hand(&player, &dealer, &Outcome::Win),
+                   "You: K♦ 6♥ (16) hit → 4♣ (20), Dealer: 10♠ 7♦ (17), Result: WIN");
+    }
+
+    #[test]
+    fn describe_hand_marks_bust() {
+        let player = cards(&["king_of_diamonds", "6_of_hearts", "queen_of_clubs"]);
+
+        assert_eq!(describe_hand(&player, &[], &Outcome::Lose),
+                   "You: K♦ 6♥ (16) hit → Q♣ (26) bust, Dealer: -, Result: LOSE");
+    }
+}
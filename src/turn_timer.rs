@@ -0,0 +1,134 @@
+//! A time limit on each of the player's decisions, shown as a ring that empties. When it runs
+//! out the player stands. The game has only the one seat, so the limit is the table's, set in
+//! config.txt.
+
+use ggez::{
+    Context,
+    GameResult,
+    graphics,
+    mint::Point2,
+};
+
+pub const RING_RADIUS: f32 = 22.0;
+const RING_WIDTH: f32 = 5.0;
+/// Points of the ring when full
+const RING_SEGMENTS: usize = 48;
+/// Below this much of the time left the ring turns red
+const HURRY_FRACTION: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TurnTimer {
+    limit: f32,
+    time_left: f32,
+}
+
+impl TurnTimer {
+    /// `limit` in seconds, 0 turns the timer off.
+    pub fn new(limit: u32) -> Self {
+        TurnTimer { limit: limit as f32, time_left: limit as f32 }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.limit > 0.0
+    }
+
+    /// Full time again, for the next decision.
+    pub fn restart(&mut self) {
+        self.time_left = self.limit;
+    }
+
+    /// True once, when the time runs out.
+    pub fn update(&mut self, time_delta: f32) -> bool {
+        if !self.is_on() || self.time_left <= 0.0 {
+            return false;
+        }
+
+        self.time_left -= time_delta;
+        self.time_left <= 0.0
+    }
+
+    pub fn fraction_left(&self) -> f32 {
+        if self.is_on() { (self.time_left / self.limit).max(0.0) } else { 0.0 }
+    }
+
+    /// Whole seconds left, rounded up so it shows 1 until the end
+    pub fn seconds_left(&self) -> u32 {
+        self.time_left.max(0.0).ceil() as u32
+    }
+}
+
+/// Points of the ring's arc, clockwise from the top, as long as the time left.
+pub fn ring_points(center: Point2<f32>, radius: f32, fraction: f32) -> Vec<Point2<f32>> {
+    let segments = (RING_SEGMENTS as f32 * fraction.clamp(0.0, 1.0)).ceil() as usize;
+
+    (0..=segments).
+        map(|i| {
+            let angle = (i as f32 / RING_SEGMENTS as f32).min(fraction) * std::f32::consts::TAU;
+            Point2 { x: center.x + radius * angle.sin(), y: center.y - radius * angle.cos() }
+        }).
+        collect()
+}
+
+/// The ring around `center`, with the seconds left in it.
+pub fn draw(ctx: &mut Context, timer: &TurnTimer, center: Point2<f32>, font: graphics::Font, size: f32) -> GameResult<()> {
+    let fraction = timer.fraction_left();
+    let color = if fraction < HURRY_FRACTION { graphics::Color::from_rgb(204, 0, 0) } else { graphics::Color::from_rgb(255, 163, 26) };
+
+    let mut mesh = graphics::MeshBuilder::new();
+    mesh.circle(graphics::DrawMode::fill(), center, RING_RADIUS, 0.5, graphics::Color::new(0.0, 0.0, 0.0, 0.45))?;
+    let points = ring_points(center, RING_RADIUS, fraction);
+    // a line needs two different points
+    if points.len() >= 2 {
+        mesh.line(&points, RING_WIDTH, color)?;
+    }
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+
+    let mut seconds = graphics::Text::new(timer.seconds_left().to_string());
+    seconds.set_font(font, graphics::PxScale::from(size));
+    let dimensions = seconds.dimensions(ctx);
+    let position = Point2 { x: center.x - dimensions.w / 2.0, y: center.y - dimensions.h / 2.0 };
+
+    graphics::draw(ctx, &seconds, graphics::DrawParam::default().dest(position))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_out_once() {
+        let mut timer = TurnTimer::new(10);
+
+        assert!(!timer.update(4.0));
+        assert_eq!(timer.seconds_left(), 6);
+        assert!(timer.update(6.5));
+        assert!(!timer.update(1.0));
+
+        timer.restart();
+        assert_eq!(timer.fraction_left(), 1.0);
+    }
+
+    #[test]
+    fn off_never_runs_out() {
+        let mut timer = TurnTimer::new(0);
+
+        assert!(!timer.is_on());
+        assert!(!timer.update(100.0));
+    }
+
+    #[test]
+    fn ring_follows_the_time_left() {
+        let center = Point2 { x: 0.0, y: 0.0 };
+
+        let full = ring_points(center, 10.0, 1.0);
+        assert_eq!(full.len(), RING_SEGMENTS + 1);
+        assert!((full[0].y + 10.0).abs() < 0.001);
+
+        let quarter = ring_points(center, 10.0, 0.25);
+        let end = quarter.last().unwrap();
+        assert!((end.x - 10.0).abs() < 0.001 && end.y.abs() < 0.001);
+        assert_eq!(ring_points(center, 10.0, 0.0).len(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+//! Cosmetics that have to be earned first. Anything not listed here is available from the start.
+
+use crate::achievements;
+use crate::avatar;
+use crate::card_back;
+use crate::felt;
+use crate::missions::{self, Reward};
+use crate::profile::Profile;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cosmetic {
+    CardBack(&'static str),
+    Felt(&'static str),
+    DealerAvatar(&'static str),
+}
+
+impl Cosmetic {
+    pub fn describe(&self) -> String {
+        match self {
+            Cosmetic::CardBack(name) => format!("Card back \"{}\"", name),
+            Cosmetic::Felt(name) => format!("Felt \"{}\"", name),
+            Cosmetic::DealerAvatar(name) => format!("Dealer \"{}\"", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Requirement {
+    /// Id of an achievement in achievements::ACHIEVEMENTS
+    Achievement(&'static str),
+    /// Id of a mission in missions::MISSIONS that rewards the cosmetic
+    Mission(&'static str),
+}
+
+impl Requirement {
+    pub fn met(&self, profile: &Profile) -> bool {
+        match self {
+            Requirement::Achievement(id) => profile.achievements.iter().any(|earned| earned == id),
+            Requirement::Mission(id) => match missions::MISSIONS.iter().find(|mission| mission.id == *id).map(|mission| mission.reward) {
+                Some(Reward::Felt(name)) => profile.earned_felts.iter().any(|earned| earned == name),
+                _ => false,
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Requirement::Achievement(id) => achievements::find(id).map(|achievement| achievement.description).unwrap_or(id).to_string(),
+            Requirement::Mission(id) => {
+                let description = missions::MISSIONS.iter().find(|mission| mission.id == *id).map(|mission| mission.description).unwrap_or(id);
+                format!("Mission: {}", description)
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Unlock {
+    pub cosmetic: Cosmetic,
+    pub requirement: Requirement,
+}
+
+pub const UNLOCKS: &[Unlock] = &[
+    Unlock { cosmetic: Cosmetic::DealerAvatar("croupier"), requirement: Requirement::Achievement("first_win") },
+    Unlock { cosmetic: Cosmetic::Felt("blue dots"), requirement: Requirement::Achievement("ten_wins") },
+    Unlock { cosmetic: Cosmetic::CardBack("shimmer"), requirement: Requirement::Achievement("ten_wins") },
+    Unlock { cosmetic: Cosmetic::DealerAvatar("cowboy"), requirement: Requirement::Achievement("five_in_a_row") },
+    Unlock { cosmetic: Cosmetic::Felt("charcoal"), requirement: Requirement::Achievement("hundred_hands") },
+    Unlock { cosmetic: Cosmetic::DealerAvatar("robot"), requirement: Requirement::Achievement("fifty_wins") },
+    Unlock { cosmetic: Cosmetic::Felt("gold pinstripe"), requirement: Requirement::Mission("three_in_a_row") },
+    Unlock { cosmetic: Cosmetic::Felt("midnight dots"), requirement: Requirement::Mission("five_in_a_row") },
+];
+
+pub fn is_unlocked(cosmetic: Cosmetic, profile: &Profile) -> bool {
+    UNLOCKS.iter().
+        filter(|unlock| unlock.cosmetic == cosmetic).
+        all(|unlock| unlock.requirement.met(profile))
+}
+
+pub fn card_backs(profile: &Profile) -> Vec<&'static card_back::CardBackStyle> {
+    card_back::STYLES.iter().filter(|style| is_unlocked(Cosmetic::CardBack(style.name), profile)).collect()
+}
+
+pub fn felts(profile: &Profile) -> Vec<&'static felt::FeltStyle> {
+    felt::STYLES.iter().
+        chain(felt::REWARD_STYLES).
+        filter(|style| is_unlocked(Cosmetic::Felt(style.name), profile)).
+        collect()
+}
+
+pub fn dealer_avatars(profile: &Profile) -> Vec<&'static avatar::DealerAvatar> {
+    avatar::STYLES.iter().filter(|style| is_unlocked(Cosmetic::DealerAvatar(style.name), profile)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_unlock_names_a_real_item_and_requirement() {
+        for unlock in UNLOCKS {
+            let exists = match unlock.cosmetic {
+                Cosmetic::CardBack(name) => card_back::STYLES.iter().any(|style| style.name == name),
+                Cosmetic::Felt(name) => felt::find(name).name == name,
+                Cosmetic::DealerAvatar(name) => avatar::find(name).name == name,
+            };
+            assert!(exists, "{:?}", unlock.cosmetic);
+
+            match unlock.requirement {
+                Requirement::Achievement(id) => assert!(achievements::find(id).is_some(), "{}", id),
+                Requirement::Mission(id) => assert!(missions::MISSIONS.iter().any(|mission| mission.id == id), "{}", id),
+            }
+        }
+    }
+
+    #[test]
+    fn new_player_gets_the_free_items_only() {
+        let profile = Profile::default();
+
+        assert_eq!(card_backs(&profile).len(), 1);
+        assert_eq!(dealer_avatars(&profile).len(), 1);
+        assert!(felts(&profile).iter().all(|style| felt::STYLES.iter().any(|free| free.name == style.name)));
+    }
+
+    #[test]
+    fn requirements_unlock_items() {
+        let profile = Profile {
+            achievements: vec![String::from("ten_wins")],
+            earned_felts: vec![String::from("gold pinstripe")],
+            ..Profile::default()
+        };
+
+        assert!(is_unlocked(Cosmetic::CardBack("shimmer"), &profile));
+        assert!(is_unlocked(Cosmetic::Felt("gold pinstripe"), &profile));
+        assert!(!is_unlocked(Cosmetic::Felt("midnight dots"), &profile));
+        assert!(!is_unlocked(Cosmetic::DealerAvatar("robot"), &profile));
+    }
+}
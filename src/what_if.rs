@@ -0,0 +1,311 @@
+//! The what-if panel: the odds of standing and of hitting on the player's hand right now, worked
+//! out exactly from the cards left in the deck instead of probability.rs's infinite deck. After
+//! a hit the player goes on the way the best play says(see probability::best_play), and the
+//! dealer draws from whatever is left. The odds only change with the cards, so they are worked
+//! out once per decision, on a background thread since the first decision of a hand takes a
+//! good part of a second, and kept.
+
+use crate::card::Card;
+use crate::game_engine;
+use crate::i18n::{self, Label, Language};
+use crate::probability::{self, BestPlay, HandOdds, Rules};
+use crate::review;
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// How many cards of each point value are left, an ace counting 1: `counts[points - 1]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Shoe {
+    counts: [u8; 10],
+}
+
+impl Shoe {
+    pub fn of(cards: &[Card]) -> Self {
+        let mut shoe = Shoe::default();
+        for card in cards {
+            let points = if card.is_an_ace() { 1 } else { card.get_points().unwrap_or(10) };
+            shoe.counts[points as usize - 1] += 1;
+        }
+
+        shoe
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().map(|count| *count as u32).sum()
+    }
+
+    /// Every card that can come next: its points, the chance of it and the shoe without it.
+    fn draws(&self) -> Vec<(u32, f64, Shoe)> {
+        let total = self.total() as f64;
+
+        (0..self.counts.len()).
+            filter(|i| self.counts[*i] > 0).
+            map(|i| {
+                let mut rest = *self;
+                rest.counts[i] -= 1;
+                (i as u32 + 1, self.counts[i] as f64 / total, rest)
+            }).
+            collect()
+    }
+}
+
+/// Odds of standing now and of hitting now, then playing on the best way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WhatIf {
+    pub stand: HandOdds,
+    pub hit: HandOdds,
+}
+
+/// Where the dealer ends: the chance of each value after the handicap, a bust last.
+type DealerEnds = [f64; 23];
+const DEALER_BUST: usize = 22;
+
+/// The enumeration, remembering the hands it has been through: the same cards reach the same
+/// hand in many orders.
+struct Enumeration<'a> {
+    rules: Rules,
+    best: &'a BestPlay,
+    dealer: HashMap<(u32, bool, Shoe), DealerEnds>,
+    player: HashMap<(u32, bool, Shoe), HandOdds>,
+}
+
+impl<'a> Enumeration<'a> {
+    fn dealer_from(&mut self, hard: u32, has_ace: bool, shoe: Shoe) -> DealerEnds {
+        let value = probability::best_value(hard, has_ace);
+        // a hand that can't draw from an empty deck stops too, it never happens with 52 cards
+        if !game_engine::dealer_must_draw(value, self.rules) || shoe.total() == 0 {
+            let mut ends = [0.0; 23];
+            ends[(game_engine::effective_dealer_score(value, self.rules) as usize).min(DEALER_BUST)] = 1.0;
+            return ends;
+        }
+        if let Some(ends) = self.dealer.get(&(hard, has_ace, shoe)) {
+            return *ends;
+        }
+
+        let mut ends = [0.0; 23];
+        for (points, probability, rest) in shoe.draws() {
+            let next = self.dealer_from(hard + points, has_ace || points == 1, rest);
+            for (end, next_end) in ends.iter_mut().zip(next.iter()) {
+                *end += probability * next_end;
+            }
+        }
+
+        self.dealer.insert((hard, has_ace, shoe), ends);
+        ends
+    }
+
+    fn stand(&mut self, value: u32, shoe: Shoe) -> HandOdds {
+        let ends = self.dealer_from(0, false, shoe);
+        let mut odds = HandOdds { win: ends[DEALER_BUST], ..HandOdds::default() };
+
+        for (dealer_value, probability) in ends[..DEALER_BUST].iter().enumerate() {
+            let dealer_value = dealer_value as u32;
+
+            if value > dealer_value {
+                odds.win += probability;
+            } else if value == dealer_value {
+                odds.draw += probability;
+            } else {
+                odds.lose += probability;
+            }
+        }
+
+        odds
+    }
+
+    fn hit(&mut self, hard: u32, has_ace: bool, shoe: Shoe) -> HandOdds {
+        let mut odds = HandOdds::default();
+
+        for (points, probability, rest) in shoe.draws() {
+            let next = self.play_on(hard + points, has_ace || points == 1, rest);
+            odds.win += probability * next.win;
+            odds.draw += probability * next.draw;
+            odds.lose += probability * next.lose;
+        }
+
+        odds
+    }
+
+    /// The player's hand after a card, played on the best way.
+    fn play_on(&mut self, hard: u32, has_ace: bool, shoe: Shoe) -> HandOdds {
+        if hard > 21 {
+            return HandOdds { lose: 1.0, ..HandOdds::default() };
+        }
+        if let Some(odds) = self.player.get(&(hard, has_ace, shoe)) {
+            return *odds;
+        }
+
+        // the turn ends by itself at 21
+        let value = probability::best_value(hard, has_ace);
+        let odds = if value == 21 || !self.best.should_draw(hard, has_ace) || shoe.total() == 0 {
+            self.stand(value, shoe)
+        } else {
+            self.hit(hard, has_ace, shoe)
+        };
+
+        self.player.insert((hard, has_ace, shoe), odds);
+        odds
+    }
+}
+
+/// Odds of standing and of hitting on a hand of `hard` points with aces as 1, with `shoe` left
+/// in the deck.
+pub fn what_if(hard: u32, has_ace: bool, shoe: Shoe, rules: Rules, best: &BestPlay) -> WhatIf {
+    let mut enumeration = Enumeration { rules, best, dealer: HashMap::new(), player: HashMap::new() };
+
+    WhatIf {
+        stand: enumeration.stand(probability::best_value(hard, has_ace), shoe),
+        hit: enumeration.hit(hard, has_ace, shoe),
+    }
+}
+
+/// The odds of the decision being made, worked out again only when the hand, the deck or the
+/// rules change.
+#[derive(Debug, Default)]
+pub struct WhatIfPanel {
+    /// The hand with aces as 1, the deck and the rules the odds are for
+    decision: Option<((u32, bool), Shoe, Rules)>,
+    odds: Option<WhatIf>,
+    receiver: Option<Receiver<WhatIf>>,
+}
+
+impl WhatIfPanel {
+    /// Starts working out the odds of standing and hitting on `cards`, unless they are the
+    /// decision's already.
+    pub fn ask(&mut self, cards: &[Card], shoe: Shoe, rules: Rules) {
+        let (hard, has_ace) = review::hard_total(cards);
+        let decision = ((hard, has_ace), shoe, rules);
+        if self.decision == Some(decision) {
+            return;
+        }
+
+        // the odds of an earlier decision are sent nowhere once its receiver is dropped
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let best = probability::best_play(rules);
+            let _ = sender.send(what_if(hard, has_ace, shoe, rules, &best));
+        });
+        self.decision = Some(decision);
+        self.odds = None;
+        self.receiver = Some(receiver);
+    }
+
+    /// Picks up odds worked out in the background, call it every update.
+    pub fn update(&mut self) {
+        match self.receiver.as_ref().map(|receiver| receiver.try_recv()) {
+            Some(Ok(odds)) => self.odds = Some(odds),
+            Some(Err(TryRecvError::Empty)) | None => return,
+            Some(Err(TryRecvError::Disconnected)) => (),
+        }
+        self.receiver = None;
+    }
+
+    /// The odds of the last decision asked about, None while they are worked out.
+    pub fn odds(&self) -> Option<WhatIf> {
+        self.odds
+    }
+}
+
+/// "Stand: win 41.2%, push 8.0%, lose 50.8%" in `language`, `action` being WhatIfStand or WhatIfHit
+pub fn describe(language: Language, action: Label, odds: &HandOdds) -> String {
+    let tr = |label| i18n::tr(language, label);
+
+    format!("{}: {} {:.1}%, {} {:.1}%, {} {:.1}%",
+            tr(action),
+            tr(Label::WhatIfWin), odds.win * 100.0,
+            tr(Label::WhatIfPush), odds.draw * 100.0,
+            tr(Label::WhatIfLose), odds.lose * 100.0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(names: &[&str]) -> Vec<Card> {
+        names.iter().map(|name| Card::new(name)).collect()
+    }
+
+    /// A full deck without `dealt`
+    fn shoe_without(dealt: &[Card]) -> Shoe {
+        let left: Vec<Card> = crate::card::all().into_iter().
+            filter(|card| !dealt.iter().any(|dealt| dealt.name == card.name)).
+            collect();
+
+        Shoe::of(&left)
+    }
+
+    fn total(odds: &HandOdds) -> f64 {
+        odds.win + odds.draw + odds.lose
+    }
+
+    #[test]
+    fn shoe_counts_points() {
+        let shoe = Shoe::of(&crate::card::all());
+
+        assert_eq!(shoe.total(), 52);
+        assert_eq!(shoe.counts[0], 4);
+        assert_eq!(shoe.counts[9], 16);
+    }
+
+    #[test]
+    fn odds_add_up() {
+        let hand = cards(&["9_of_clubs", "5_of_hearts"]);
+        let best = probability::best_play(Rules::default());
+        let odds = what_if(14, false, shoe_without(&hand), Rules::default(), &best);
+
+        assert!((total(&odds.stand) - 1.0).abs() < 1e-9);
+        assert!((total(&odds.hit) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hitting_20_is_worse() {
+        let hand = cards(&["king_of_clubs", "queen_of_hearts"]);
+        let best = probability::best_play(Rules::default());
+        let odds = what_if(20, false, shoe_without(&hand), Rules::default(), &best);
+
+        assert!(odds.stand.expected_value() > odds.hit.expected_value());
+    }
+
+    #[test]
+    fn only_tens_left_bust_a_12() {
+        let best = probability::best_play(Rules::default());
+        let tens = Shoe::of(&cards(&["10_of_clubs", "jack_of_hearts", "queen_of_spades", "king_of_diamonds"]));
+
+        assert_eq!(what_if(12, false, tens, Rules::default(), &best).hit.lose, 1.0);
+    }
+
+    #[test]
+    fn panel_keeps_the_odds_of_a_decision() {
+        let mut panel = WhatIfPanel::default();
+        let hand = cards(&["8_of_clubs", "5_of_hearts"]);
+        let shoe = shoe_without(&hand);
+
+        let wait = |panel: &mut WhatIfPanel| {
+            while panel.odds().is_none() {
+                panel.update();
+                thread::sleep(std::time::Duration::from_millis(5));
+            }
+            panel.odds().unwrap()
+        };
+
+        panel.ask(&hand, shoe, Rules::default());
+        let odds = wait(&mut panel);
+        panel.ask(&hand, shoe, Rules::default());
+        assert_eq!(panel.odds(), Some(odds));
+
+        panel.ask(&hand, shoe, Rules { dealer_handicap: true });
+        assert_eq!(panel.odds(), None);
+        assert!(wait(&mut panel).stand.expected_value() > odds.stand.expected_value());
+    }
+
+    #[test]
+    fn odds_read_in_the_players_language() {
+        let odds = HandOdds { win: 0.412, draw: 0.08, lose: 0.508 };
+
+        assert_eq!(describe(Language::English, Label::WhatIfStand, &odds), "Stand: win 41.2%, push 8.0%, lose 50.8%");
+        assert_eq!(describe(Language::Bulgarian, Label::WhatIfHit, &odds), "Карта: печалба 41.2%, равенство 8.0%, загуба 50.8%");
+    }
+}
@@ -0,0 +1,156 @@
+//! The game's window: with or without a border, and where it was when the game last closed.
+//! The size, the position and the monitor are kept in config.txt and the window opens there
+//! again, as long as that monitor is still connected; a monitor that's gone opens it in the
+//! default place instead of somewhere off every screen.
+
+use crate::config::Config;
+
+use ggez::{conf::WindowMode, graphics, Context};
+use winit::dpi::PhysicalPosition;
+
+/// The size the layout is made for(see layout.rs)
+pub const DEFAULT_SIZE: (u32, u32) = (1900, 900);
+
+/// Smaller than this and the menu doesn't fit, even at the smallest UI scale
+pub const MIN_SIZE: (u32, u32) = (950, 450);
+
+/// A connected monitor: its name and where it is on the desktop, in physical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: String,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+}
+
+impl Monitor {
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.position.0 && x < self.position.0 + self.size.0 as i32 &&
+            y >= self.position.1 && y < self.position.1 + self.size.1 as i32
+    }
+}
+
+/// "1900x900", also the value in the config file
+pub fn size_name((width, height): (u32, u32)) -> String {
+    format!("{}x{}", width, height)
+}
+
+pub fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let expected = || format!("expected the width and height in pixels, like \"{}\"", size_name(DEFAULT_SIZE));
+    let (width, height) = value.split_once('x').ok_or_else(expected)?;
+    let size: (u32, u32) = (width.trim().parse().map_err(|_| expected())?, height.trim().parse().map_err(|_| expected())?);
+
+    if size.0 < MIN_SIZE.0 || size.1 < MIN_SIZE.1 {
+        return Err(format!("the window can't be smaller than {}", size_name(MIN_SIZE)));
+    }
+
+    Ok(size)
+}
+
+/// "120, 40", empty for the default place
+pub fn position_name(position: Option<(i32, i32)>) -> String {
+    match position {
+        Some((x, y)) => format!("{}, {}", x, y),
+        None => String::new(),
+    }
+}
+
+pub fn parse_position(value: &str) -> Result<Option<(i32, i32)>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let expected = || String::from("expected x and y in pixels, like \"120, 40\", or nothing");
+    let (x, y) = value.split_once(',').ok_or_else(expected)?;
+
+    Ok(Some((x.trim().parse().map_err(|_| expected())?, y.trim().parse().map_err(|_| expected())?)))
+}
+
+/// The window the game opens, `visible` unless it only renders a scene.
+pub fn window_mode(config: &Config, visible: bool) -> WindowMode {
+    WindowMode {
+        width: config.window_size.0 as f32,
+        height: config.window_size.1 as f32,
+        min_width: MIN_SIZE.0 as f32,
+        min_height: MIN_SIZE.1 as f32,
+        borderless: config.borderless,
+        resizable: true,
+        visible,
+        ..Default::default()
+    }
+}
+
+/// Where the window goes back to: the saved position, if it's on the monitor it was saved on
+/// and that monitor is among `monitors`. None leaves it to the system.
+pub fn placement(config: &Config, monitors: &[Monitor]) -> Option<(i32, i32)> {
+    let position = config.window_position?;
+
+    monitors.iter().
+        find(|monitor| monitor.name == config.window_monitor).
+        filter(|monitor| monitor.contains(position)).
+        map(|_| position)
+}
+
+fn monitors(ctx: &Context) -> Vec<Monitor> {
+    graphics::window(ctx).available_monitors().
+        map(|monitor| Monitor {
+            name: monitor.name().unwrap_or_default(),
+            position: (monitor.position().x, monitor.position().y),
+            size: (monitor.size().width, monitor.size().height),
+        }).
+        collect()
+}
+
+/// Moves the window to where it was last time, see placement.
+pub fn restore(ctx: &Context, config: &Config) {
+    if let Some((x, y)) = placement(config, &monitors(ctx)) {
+        graphics::window(ctx).set_outer_position(PhysicalPosition::new(x, y));
+    }
+}
+
+/// Puts the window's size, position and monitor into `config`. True if any of them changed.
+pub fn remember(ctx: &Context, config: &mut Config) -> bool {
+    let window = graphics::window(ctx);
+    let size = window.inner_size();
+    let before = (config.window_size, config.window_position, config.window_monitor.clone());
+
+    config.window_size = (size.width.max(MIN_SIZE.0), size.height.max(MIN_SIZE.1));
+    // some systems(Wayland) don't tell where windows are
+    config.window_position = window.outer_position().ok().map(|position| (position.x, position.y));
+    config.window_monitor = window.current_monitor().and_then(|monitor| monitor.name()).unwrap_or_default();
+
+    before != (config.window_size, config.window_position, config.window_monitor.clone())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32) -> Monitor {
+        Monitor { name: name.to_string(), position: (x, 0), size: (1920, 1080) }
+    }
+
+    #[test]
+    fn values_round_trip() {
+        assert_eq!(parse_size(&size_name((1280, 720))), Ok((1280, 720)));
+        assert!(parse_size("1280 by 720").is_err());
+        assert!(parse_size("640x480").unwrap_err().contains("950x450"));
+
+        assert_eq!(parse_position(&position_name(Some((-1800, 40)))), Ok(Some((-1800, 40))));
+        assert_eq!(parse_position(&position_name(None)), Ok(None));
+        assert!(parse_position("left").is_err());
+    }
+
+    #[test]
+    fn opens_where_it_was_on_a_connected_monitor() {
+        let config = Config { window_position: Some((2000, 100)), window_monitor: String::from("HDMI-1"), ..Config::default() };
+        let monitors = [monitor("eDP-1", 0), monitor("HDMI-1", 1920)];
+
+        assert_eq!(placement(&config, &monitors), Some((2000, 100)));
+        // unplugged
+        assert_eq!(placement(&config, &monitors[..1]), None);
+        // plugged in on the other side
+        assert_eq!(placement(&config, &[monitor("eDP-1", 0), monitor("HDMI-1", -1920)]), None);
+        assert_eq!(placement(&Config::default(), &monitors), None);
+    }
+}
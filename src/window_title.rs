@@ -0,0 +1,53 @@
+//! The window's title follows the game("Blackjack — 17 vs 10", "Blackjack — Menu"), so it
+//! can be read from the taskbar while alt-tabbed. The title is only set when it changes, and at
+//! most a few times a second while cards are counted up.
+
+pub const GAME_NAME: &str = "Blackjack";
+/// A change coming sooner waits for this long after the last one
+pub const SECONDS_BETWEEN_TITLES: f32 = 0.25;
+
+/// "Blackjack — `detail`"
+pub fn title(detail: &str) -> String {
+    format!("{} \u{2014} {}", GAME_NAME, detail)
+}
+
+/// The title last set, and how long until the next one can be.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowTitle {
+    shown: String,
+    cooldown: f32,
+}
+
+impl WindowTitle {
+    /// The title to set now, when `wanted` differs from the window's and the last change
+    /// isn't too recent. A change held back is returned by a later call.
+    pub fn update(&mut self, wanted: String, time_delta: f32) -> Option<String> {
+        self.cooldown = (self.cooldown - time_delta).max(0.0);
+        if wanted == self.shown || self.cooldown > 0.0 {
+            return None;
+        }
+
+        self.cooldown = SECONDS_BETWEEN_TITLES;
+        self.shown = wanted.clone();
+        Some(wanted)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_throttled() {
+        let mut window_title = WindowTitle::default();
+        let frame = 1.0 / 60.0;
+
+        assert_eq!(window_title.update(title("Menu"), frame), Some(String::from("Blackjack \u{2014} Menu")));
+        assert_eq!(window_title.update(title("Menu"), frame), None);
+
+        // too soon after the menu's, the title waits
+        assert_eq!(window_title.update(title("12 vs 10"), frame), None);
+        assert_eq!(window_title.update(title("13 vs 10"), SECONDS_BETWEEN_TITLES), Some(title("13 vs 10")));
+    }
+}
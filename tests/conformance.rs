@@ -0,0 +1,177 @@
+// Checks the engine against a small reference implementation of the rules, for every score
+// and situation a hand can be in. Written before any rule variants, so a change to the rules
+// shows up here as a list of the exact cases it changes.
+
+use blackjack::board::Turn;
+use blackjack::card::Card;
+use blackjack::game_engine::{self, GameEngine, Outcome};
+use blackjack::scenario::Scenario;
+
+const RANKS: [&str; 13] = ["ace", "2", "3", "4", "5", "6", "7", "8", "9", "10", "jack", "queen", "king"];
+
+/// The rules as the help screen states them.
+mod reference {
+    use blackjack::game_engine::Outcome;
+
+    pub const DEALER_STANDS_ON: u32 = 17;
+    pub const HANDICAP_POINTS: u32 = 1;
+
+    /// Best total: as many aces as fit count 11, the rest 1.
+    pub fn hand_value(points: &[u32]) -> u32 {
+        let aces = points.iter().filter(|points| **points == 11).count() as u32;
+        let mut total: u32 = points.iter().sum();
+
+        for _ in 0..aces {
+            if total > 21 {
+                total -= 10;
+            }
+        }
+
+        total
+    }
+
+    /// Whether the dealer is done drawing on `dealer`: at 17 or more once the handicap is off.
+    pub fn dealer_stands(dealer: u32, handicap: bool) -> bool {
+        let dealer = if handicap { dealer - HANDICAP_POINTS } else { dealer };
+        dealer >= DEALER_STANDS_ON
+    }
+
+    /// Outcome once the player stood on `player` and the dealer stopped drawing on `dealer`.
+    /// The handicap comes off the dealer's score before the bust check.
+    pub fn outcome(player: u32, dealer: u32, handicap: bool) -> Outcome {
+        let dealer = if handicap { dealer - HANDICAP_POINTS } else { dealer };
+
+        if player > 21 {
+            return Outcome::Lose;
+        }
+        if dealer > 21 {
+            return Outcome::Win;
+        }
+
+        match player.cmp(&dealer) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Less => Outcome::Lose,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        }
+    }
+}
+
+fn engine(player: u32, dealer: u32, handicap: bool) -> GameEngine {
+    GameEngine { player_score: player, dealer_score: dealer, dealer_handicap_active: handicap, ..GameEngine::new() }
+}
+
+fn rank_points(rank: &str) -> u32 {
+    Card::new(&format!("{}_of_spades", rank)).get_points().unwrap()
+}
+
+#[test]
+fn finished_dealer_against_every_player_total() {
+    let mut failures = Vec::new();
+
+    for handicap in [false, true] {
+        for player in 4..=21 {
+            for dealer in reference::DEALER_STANDS_ON..=22 {
+                if !reference::dealer_stands(dealer, handicap) {
+                    continue;
+                }
+
+                let mut engine = engine(player, dealer, handicap);
+                engine.check_outcome(&mut Turn::Dealer);
+
+                let expected = reference::outcome(player, dealer, handicap);
+                if !engine.game_over || engine.outcome != expected {
+                    failures.push(format!("player {} vs dealer {}, handicap {}: {:?} instead of {:?}",
+                                          player, dealer, handicap, engine.outcome, expected));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn busts_end_the_hand() {
+    for handicap in [false, true] {
+        for player in 22..=30 {
+            for mut turn in [Turn::Player, Turn::Dealer] {
+                let mut engine = engine(player, 0, handicap);
+                engine.check_outcome(&mut turn);
+
+                assert!(engine.game_over && engine.outcome == Outcome::Lose, "player bust on {}", player);
+            }
+        }
+
+        for player in 4..=21 {
+            for dealer in 22..=26 {
+                let mut engine = engine(player, dealer, handicap);
+                engine.check_outcome(&mut Turn::Dealer);
+
+                assert_eq!(engine.outcome, reference::outcome(player, dealer, handicap), "dealer bust on {}", dealer);
+                assert!(engine.game_over);
+            }
+        }
+    }
+}
+
+#[test]
+fn dealer_keeps_drawing_below_17() {
+    for handicap in [false, true] {
+        for player in 4..=21 {
+            for dealer in 2..=21 {
+                if reference::dealer_stands(dealer, handicap) {
+                    continue;
+                }
+
+                let mut engine = engine(player, dealer, handicap);
+                engine.check_outcome(&mut Turn::Dealer);
+
+                assert!(!engine.game_over && engine.dealer_must_draw(), "player {} vs dealer {} ended early", player, dealer);
+                assert_eq!(engine.outcome, Outcome::Undecided);
+            }
+        }
+    }
+}
+
+#[test]
+fn player_turn_only_ends_on_21_or_a_bust() {
+    for auto_stand_on_21 in [true, false] {
+        for player in 2..=21 {
+            let mut engine = GameEngine { auto_stand_on_21, ..engine(player, 0, false) };
+            let mut turn = Turn::Player;
+            engine.check_outcome(&mut turn);
+
+            let stands = player == 21 && auto_stand_on_21;
+            assert_eq!(matches!(turn, Turn::Dealer), stands, "player on {}", player);
+            assert!(!engine.game_over);
+        }
+    }
+}
+
+#[test]
+fn every_hand_of_up_to_four_cards_is_valued_like_the_reference() {
+    let mut hands: Vec<Vec<&str>> = vec![Vec::new()];
+
+    for _ in 0..4 {
+        hands = hands.iter().
+            flat_map(|hand| RANKS.iter().map(move |rank| [hand.clone(), vec![*rank]].concat())).
+            collect();
+
+        for hand in &hands {
+            let cards: Vec<Card> = hand.iter().map(|rank| Card::new(&format!("{}_of_hearts", rank))).collect();
+            let points: Vec<u32> = hand.iter().map(|rank| rank_points(rank)).collect();
+
+            assert_eq!(game_engine::hand_value(&cards), reference::hand_value(&points), "{:?}", hand);
+        }
+    }
+}
+
+#[test]
+fn naturals_have_no_bonus() {
+    let play = |deck: &str| Scenario::parse(&format!("deck = {}\nactions = hit, hit", deck)).unwrap().play().unwrap();
+
+    // a natural hands the turn over, and the dealer can still tie it
+    assert_eq!(play("ace_of_spades, king_of_hearts, 10_of_clubs, 9_of_diamonds"), Outcome::Win);
+    assert_eq!(play("ace_of_spades, king_of_hearts, ace_of_clubs, queen_of_diamonds"), Outcome::Draw);
+    assert_eq!(play("ace_of_spades, king_of_hearts, 10_of_clubs, 5_of_diamonds, 8_of_hearts"), Outcome::Win);
+}
@@ -0,0 +1,77 @@
+// Renders the key screens off-screen and compares them with the images in tests/goldens.
+// It needs a display(and a GPU driver), so it only runs when BLACKJACK_GOLDEN=1 is set:
+//
+//     BLACKJACK_GOLDEN=1 cargo test --test golden
+//
+// A missing golden fails the screen like a mismatch does. Write new goldens, or refresh them
+// after an intended visual change, with BLACKJACK_UPDATE_GOLDENS=1.
+
+use blackjack::golden::{self, Comparison, Frame};
+use blackjack::main_state::MainState;
+use blackjack::scene::SCENES;
+
+use ggez::{
+    ContextBuilder,
+    conf::{Conf, WindowMode},
+    filesystem,
+};
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+fn main() {
+    if env::var("BLACKJACK_GOLDEN").is_err() {
+        println!("golden-image tests skipped, set BLACKJACK_GOLDEN=1 to run them");
+        return;
+    }
+    let update = env::var("BLACKJACK_UPDATE_GOLDENS").is_ok();
+
+    let conf = Conf::new().
+        window_mode(WindowMode {
+            width: 1900.0,
+            height: 900.0,
+            visible: false,
+            ..Default::default()
+        });
+    let (mut ctx, _event_loop) = ContextBuilder::new("BlackJack", "Kris").
+        default_conf(conf).
+        build().
+        unwrap();
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    filesystem::mount(&mut ctx, &manifest_dir.join("resources"), true);
+
+    let mut failed = 0;
+    for scene in SCENES {
+        let mut state = MainState::for_scene(&mut ctx, *scene).unwrap();
+        let actual = state.render_frame(&mut ctx).unwrap().downsample(golden::DOWNSAMPLE);
+
+        let golden_file = manifest_dir.join("tests").join("goldens").join(format!("{}.png", scene.name()));
+        if update {
+            actual.save(&golden_file).unwrap();
+            println!("{}: golden written", scene.name());
+            continue;
+        }
+        if !golden_file.exists() {
+            println!("{}: no golden at {}, write it with BLACKJACK_UPDATE_GOLDENS=1", scene.name(), golden_file.display());
+            failed += 1;
+            continue;
+        }
+
+        match golden::compare(&actual, &Frame::load(&golden_file).unwrap()) {
+            Comparison::Match => println!("{}: ok", scene.name()),
+            comparison => {
+                let failure_file = manifest_dir.join("target").join("golden-failures").join(format!("{}.png", scene.name()));
+                actual.save(&failure_file).unwrap();
+
+                println!("{}: {:?}, actual image saved to {}", scene.name(), comparison, failure_file.display());
+                failed += 1;
+            },
+        }
+    }
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
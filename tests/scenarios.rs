@@ -0,0 +1,23 @@
+// Plays every scenario in tests/scenarios on the engine and checks the expected outcome.
+
+use blackjack::scenario::Scenario;
+
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn scenarios_end_as_expected() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("scenarios");
+    let mut played = 0;
+
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let scenario = Scenario::load(path.to_str().unwrap()).unwrap();
+        let expected = scenario.expected.clone().unwrap_or_else(|| panic!("{} has no expected outcome", path.display()));
+
+        assert_eq!(scenario.play().unwrap(), expected, "{}", path.display());
+        played += 1;
+    }
+
+    assert!(played > 0);
+}
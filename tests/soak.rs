@@ -0,0 +1,70 @@
+// Plays many thousands of hands on the engine alone, as fast as it goes, and checks what must
+// always hold: every hand is decided, the same cards and actions always end the same way, and
+// simulated bankrolls only ever move by the bet. Run it with `cargo test --features soak`.
+
+#![cfg(feature = "soak")]
+
+use blackjack::card;
+use blackjack::game_engine::Outcome;
+use blackjack::probability::Rules;
+use blackjack::scenario::{Action, Scenario};
+use blackjack::simulation::{self, BettingStrategy, STRATEGIES};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const HANDS: usize = 50_000;
+const SESSIONS: usize = 300;
+const SESSION_HANDS: usize = 2000;
+
+fn random_scenario<R: Rng>(rng: &mut R) -> Scenario {
+    let mut deck: Vec<String> = card::all().into_iter().map(|card| card.name).collect();
+    deck.shuffle(rng);
+
+    let mut actions: Vec<Action> = (0..rng.gen_range(0..6)).
+        map(|_| *[Action::Hit, Action::Hit, Action::Hint, Action::Handicap].choose(rng).unwrap()).
+        collect();
+    actions.push(Action::Stand);
+
+    Scenario { deck, actions, expected: None }
+}
+
+#[test]
+fn hands_are_decided_and_replay_the_same() {
+    let mut rng = StdRng::seed_from_u64(1704);
+
+    for hand in 0..HANDS {
+        let scenario = random_scenario(&mut rng);
+        let outcome = scenario.play().unwrap_or_else(|e| panic!("hand {}: {}", hand, e));
+
+        assert_ne!(outcome, Outcome::Undecided, "hand {}: {:?}", hand, scenario);
+        assert_eq!(scenario.play().unwrap(), outcome, "hand {} replayed differently: {:?}", hand, scenario);
+    }
+}
+
+#[test]
+fn bankrolls_only_move_by_the_bet() {
+    let mut rng = StdRng::seed_from_u64(1705);
+    let (base_bet, bankroll) = (10, 500);
+
+    for rules in [Rules::default(), Rules { dealer_handicap: true }] {
+        for strategy in STRATEGIES {
+            for _ in 0..SESSIONS {
+                let session = simulation::simulate_session(&mut rng, *strategy, base_bet, bankroll, SESSION_HANDS, rules, true);
+
+                // a point per hand and the starting bankroll, nothing kept beyond that
+                assert!(session.bankroll.len() <= SESSION_HANDS + 1);
+                assert_eq!(session.bankroll[0], bankroll);
+                assert_eq!(session.ruined_at.is_some(), session.final_bankroll() == 0, "{:?}", strategy);
+
+                for pair in session.bankroll.windows(2) {
+                    // nothing is won or lost beyond what was on the table
+                    let (before, after) = (pair[0], pair[1]);
+                    assert!(after <= before.saturating_mul(2), "{:?}: {} -> {}", strategy, before, after);
+                    if *strategy == BettingStrategy::Flat {
+                        assert!([before, before + base_bet, before - base_bet].contains(&after), "{} -> {}", before, after);
+                    }
+                }
+            }
+        }
+    }
+}